@@ -0,0 +1,208 @@
+//! A scope for each request served by actix-web
+//!
+//! `ScopeMiddleware` creates a new `Scoped` for each request, and handlers
+//! declare what they need from it as `Dep<T>` parameters:
+//!
+//! ```no_run
+//! # use std::rc::Rc;
+//! # use actix_web::web::{self, Json};
+//! # use actix_web::{App, HttpResponse, HttpServer};
+//! # use ioc::prelude::*;
+//! # use ioc_actix::{Dep, ScopeMiddleware};
+//! # #[derive(serde::Deserialize)]
+//! # struct Rename { id: u32, title: String }
+//! # struct RenameProduct;
+//! # impl RenameProduct {
+//! #     fn rename(&self, _: u32, _: &str) -> HttpResponse { HttpResponse::Ok().finish() }
+//! # }
+//! # impl Resolvable<Scoped> for RenameProduct {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { RenameProduct }
+//! # }
+//! # fn registry() -> Rc<Registry<Scoped>> { Rc::new(Registry::new()) }
+//! async fn rename(handler: Dep<RenameProduct>, body: Json<Rename>) -> HttpResponse {
+//!     handler.rename(body.id, &body.title)
+//! }
+//!
+//! HttpServer::new(|| {
+//!     App::new()
+//!         .wrap(ScopeMiddleware::new().with_registry(registry()))
+//!         .route("/products/rename", web::post().to(rename))
+//! })
+//! # ;
+//! ```
+//!
+//! Each worker runs its requests on a single thread, so the scope is the
+//! `Rc`-based `Scoped`. The registry isn't `Send` either, so it's built
+//! inside the closure that creates each worker's `App`.
+//!
+//! The scope's context is seeded with the `HttpRequest`, so anything
+//! resolved from it can depend on a `Context<HttpRequest>`. If the request
+//! has an `x-correlation-id` header then it's used as the scope's
+//! correlation id. A dependency that can't be resolved fails the request
+//! with a 500.
+
+extern crate actix_web;
+extern crate futures_util;
+extern crate ioc;
+
+use std::fmt;
+use std::future::{ready, Ready};
+use std::ops::Deref;
+use std::rc::{Rc, Weak};
+
+use actix_web::dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::ErrorInternalServerError;
+use actix_web::{Error, FromRequest, HttpMessage, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use ioc::{Container, ContextContainer, CorrelationId, Registry, Resolvable, ResolvableFromContainer, Scoped};
+
+/// The header a request's correlation id is read from.
+pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+
+/// The scope of the request being handled.
+///
+/// It's kept in the request's extensions, which are keyed by type. The
+/// scope's context holds the request, so the extensions only keep a weak
+/// reference to it. The strong one is owned by the middleware's future, so
+/// the scope ends when that future completes or is dropped.
+#[derive(Clone)]
+struct RequestScope(Weak<Scoped>);
+
+/// Create a scope for a request.
+fn request_scope(registry: Option<&Rc<Registry<Scoped>>>, req: &HttpRequest) -> Scoped {
+    let mut scope = Scoped::new();
+
+    if let Some(registry) = registry {
+        scope = scope.with_registry(registry.clone());
+    }
+
+    match req.headers().get(CORRELATION_ID_HEADER).and_then(|id| id.to_str().ok()) {
+        Some(id) => scope.with_correlation_id(CorrelationId::new(id)),
+        None => scope,
+    }
+}
+
+/// Middleware that handles each request in a new scope.
+#[derive(Default, Clone)]
+pub struct ScopeMiddleware {
+    registry: Option<Rc<Registry<Scoped>>>,
+}
+
+impl ScopeMiddleware {
+    pub fn new() -> Self {
+        ScopeMiddleware::default()
+    }
+
+    /// Use a registry of runtime bindings for each request's scope.
+    pub fn with_registry(mut self, registry: Rc<Registry<Scoped>>) -> Self {
+        self.registry = Some(registry);
+
+        self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ScopeMiddleware
+    where S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+          B: 'static
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = Scoping<S>;
+    type InitError = ();
+    type Future = Ready<Result<Scoping<S>, ()>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(Scoping {
+            service,
+            registry: self.registry.clone(),
+        }))
+    }
+}
+
+/// A service that calls an inner service in a new scope for each request.
+pub struct Scoping<S> {
+    service: S,
+    registry: Option<Rc<Registry<Scoped>>>,
+}
+
+impl<S, B> Service<ServiceRequest> for Scoping<S>
+    where S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+          B: 'static
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<ServiceResponse<B>, Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let scope = Rc::new(request_scope(self.registry.as_ref(), req.request()));
+
+        req.extensions_mut().insert(RequestScope(Rc::downgrade(&scope)));
+
+        // the request can't be cloned until it's been routed, so it's added
+        // to the scope's context when a `Dep` is first extracted instead
+        let res = self.service.call(req);
+
+        // the scope is owned by the future, so it's ended even if the future
+        // is dropped before it completes
+        Box::pin(async move {
+            let _scope = scope;
+
+            res.await
+        })
+    }
+}
+
+/// An extractor that resolves a `T` from the request's scope.
+pub struct Dep<T> {
+    value: T,
+}
+
+impl<T> Dep<T> {
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Dep<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> fmt::Debug for Dep<T>
+    where T: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Dep").field(&self.value).finish()
+    }
+}
+
+impl<T, D> FromRequest for Dep<T>
+    where T: Resolvable<Scoped, Dependency = D>,
+          D: ResolvableFromContainer<Scoped>
+{
+    type Error = Error;
+    type Future = Ready<Result<Self, Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let scope = req.extensions().get::<RequestScope>().and_then(|RequestScope(scope)| scope.upgrade());
+
+        let value = match scope {
+            Some(scope) => {
+                if scope.context::<HttpRequest>().is_none() {
+                    scope.set_context(req.clone());
+                }
+
+                scope.try_resolve::<D, T>().map_err(ErrorInternalServerError)
+            }
+            None => Err(ErrorInternalServerError("`Dep` needs the app to be wrapped in a `ScopeMiddleware`")),
+        };
+
+        ready(value.map(|value| Dep { value }))
+    }
+}
@@ -0,0 +1,137 @@
+extern crate actix_web;
+extern crate ioc;
+extern crate ioc_actix;
+
+use std::cell::Cell;
+use std::future::pending;
+use std::rc::Rc;
+
+use actix_web::dev::{fn_service, Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{test, web, App, Error, FromRequest, HttpRequest, HttpResponse};
+use ioc::{Context, CorrelationId, Resolvable, Scoped};
+use ioc_actix::{Dep, ScopeMiddleware, CORRELATION_ID_HEADER};
+
+thread_local!(static DROPPED: Cell<usize> = const { Cell::new(0) });
+
+/// A shared dependency that counts how many times it's been dropped.
+struct Connection;
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        DROPPED.with(|dropped| dropped.set(dropped.get() + 1));
+    }
+}
+
+impl<C> Resolvable<C> for Connection {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Connection
+    }
+}
+
+struct Handler {
+    connection: Rc<Connection>,
+    correlation_id: CorrelationId,
+}
+
+impl Resolvable<Scoped> for Handler {
+    type Dependency = (Rc<Connection>, CorrelationId);
+
+    fn resolve((connection, correlation_id): Self::Dependency) -> Self {
+        Handler { connection, correlation_id }
+    }
+}
+
+async fn handle(a: Dep<Handler>, b: Dep<Handler>) -> HttpResponse {
+    assert!(Rc::ptr_eq(&a.connection, &b.connection));
+
+    HttpResponse::Ok().body(a.correlation_id.to_string())
+}
+
+#[actix_web::test]
+async fn dependencies_are_shared_within_a_request() {
+    let app = test::init_service(App::new().wrap(ScopeMiddleware::new()).route("/", web::get().to(handle))).await;
+
+    let req = test::TestRequest::get()
+        .uri("/")
+        .insert_header((CORRELATION_ID_HEADER, "request-1"))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert!(res.status().is_success());
+    assert_eq!("request-1", test::read_body(res).await);
+}
+
+#[actix_web::test]
+async fn scope_ends_with_the_request() {
+    DROPPED.with(|dropped| dropped.set(0));
+
+    let app = test::init_service(App::new().wrap(ScopeMiddleware::new()).route("/", web::get().to(handle))).await;
+
+    for _ in 0..2 {
+        let res = test::call_service(&app, test::TestRequest::get().uri("/").to_request()).await;
+        assert!(res.status().is_success());
+    }
+
+    assert_eq!(2, DROPPED.with(Cell::get));
+}
+
+#[actix_web::test]
+async fn scope_ends_when_the_request_is_dropped() {
+    DROPPED.with(|dropped| dropped.set(0));
+
+    // the handler resolves from the scope, then never finishes, like a
+    // request whose client disconnects
+    let handler = fn_service(|req: ServiceRequest| {
+        let handler = Dep::<Handler>::from_request(req.request(), &mut Payload::None).into_inner();
+
+        async move {
+            let _handler = handler;
+            let _req = req;
+
+            pending::<Result<ServiceResponse, Error>>().await
+        }
+    });
+
+    let service = ScopeMiddleware::new().new_transform(handler).await.unwrap();
+
+    let res = service.call(test::TestRequest::default().to_srv_request());
+    assert_eq!(0, DROPPED.with(Cell::get));
+
+    drop(res);
+    assert_eq!(1, DROPPED.with(Cell::get));
+}
+
+struct RequestPath(String);
+
+impl Resolvable<Scoped> for RequestPath {
+    type Dependency = Context<HttpRequest>;
+
+    fn resolve(req: Self::Dependency) -> Self {
+        RequestPath(req.path().to_owned())
+    }
+}
+
+async fn path(path: Dep<RequestPath>) -> HttpResponse {
+    HttpResponse::Ok().body(path.0.clone())
+}
+
+#[actix_web::test]
+async fn request_is_the_scope_context() {
+    let app = test::init_service(App::new().wrap(ScopeMiddleware::new()).route("/products", web::get().to(path))).await;
+
+    let res = test::call_service(&app, test::TestRequest::get().uri("/products").to_request()).await;
+
+    assert!(res.status().is_success());
+    assert_eq!("/products", test::read_body(res).await);
+}
+
+#[actix_web::test]
+async fn dep_without_the_middleware_is_an_error() {
+    let app = test::init_service(App::new().route("/", web::get().to(handle))).await;
+
+    let res = test::call_service(&app, test::TestRequest::get().uri("/").to_request()).await;
+
+    assert!(res.status().is_server_error());
+}
@@ -1,10 +1,15 @@
 #![feature(test)]
 
+extern crate fnv;
 extern crate ioc;
 extern crate test;
 
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, BuildHasherDefault};
 use std::rc::Rc;
 use std::cell::RefCell;
+use fnv::FnvHasher;
 use ioc::*;
 use test::{Bencher,black_box};
 
@@ -51,7 +56,7 @@ impl<C> Resolvable<C> for BorrowY {
     fn resolve((x, y): Self::Dependency) -> Self {
         BorrowY {
             x: x.into_inner(),
-            y: y,
+            y,
         }
     }
 }
@@ -78,4 +83,192 @@ pub fn resolve_brwd_y_subsequent(b: &mut Bencher) {
 
 		black_box(y);
 	})
-}
\ No newline at end of file
+}
+
+#[bench]
+pub fn resolve_shared_y_repeated(b: &mut Bencher) {
+	let scope = Scoped::new();
+
+	b.iter(|| {
+		for _ in 0..100 {
+			black_box(scope.get_or_add::<Y, _>());
+		}
+	})
+}
+
+#[allow(dead_code)]
+struct RefY<'scope> {
+    x: X,
+    y: &'scope Y,
+}
+impl<'scope, C> Resolvable<C> for RefY<'scope> {
+    type Dependency = (RefCell<X>, B<'scope, Y>);
+
+    fn resolve((x, y): Self::Dependency) -> Self {
+        RefY {
+            x: x.into_inner(),
+            y: y.into_inner(),
+        }
+    }
+}
+
+#[bench]
+pub fn resolve_ref_y_first(b: &mut Bencher) {
+	let c = BasicContainer;
+
+	b.iter(|| {
+		c.borrow_scope(|scope| {
+			let y: RefY = scope.resolve();
+
+			black_box(y);
+		})
+	})
+}
+
+#[bench]
+pub fn resolve_ref_y_subsequent(b: &mut Bencher) {
+	let c = BasicContainer;
+
+	c.borrow_scope(|scope| {
+		b.iter(|| {
+			let y: RefY = scope.resolve();
+
+			black_box(y);
+		})
+	})
+}
+
+macro_rules! leaves {
+    ($($name:ident,)*) => {
+        $(
+            struct $name(usize);
+            impl<C> Resolvable<C> for $name {
+                type Dependency = ();
+
+                fn resolve(_: Self::Dependency) -> Self {
+                    $name(0)
+                }
+            }
+        )*
+    };
+}
+
+leaves! { L0, L1, L2, L3, L4, L5, }
+
+#[allow(dead_code)]
+struct RefMany<'scope> {
+    y: &'scope Y,
+    leaves: (&'scope L0, &'scope L1, &'scope L2, &'scope L3, &'scope L4, &'scope L5),
+}
+impl<'scope, C> Resolvable<C> for RefMany<'scope> {
+    type Dependency = (B<'scope, Y>, B<'scope, L0>, B<'scope, L1>, B<'scope, L2>, B<'scope, L3>, B<'scope, L4>, B<'scope, L5>);
+
+    fn resolve((y, l0, l1, l2, l3, l4, l5): Self::Dependency) -> Self {
+        RefMany {
+            y: y.into_inner(),
+            leaves: (l0.into_inner(), l1.into_inner(), l2.into_inner(), l3.into_inner(), l4.into_inner(), l5.into_inner()),
+        }
+    }
+}
+
+#[bench]
+pub fn resolve_ref_many_first(b: &mut Bencher) {
+	let c = BasicContainer;
+
+	b.iter(|| {
+		c.borrow_scope(|scope| {
+			let many: RefMany = scope.resolve();
+
+			black_box(many);
+		})
+	})
+}
+
+closed_scope! {
+    struct ClosedScope {
+        y: Y,
+    }
+}
+
+#[bench]
+pub fn resolve_closed_y_subsequent(b: &mut Bencher) {
+	let scope = ClosedScope::new();
+
+	b.iter(|| {
+		let y: BorrowY = scope.resolve();
+
+		black_box(y);
+	})
+}
+
+fn type_id_lookup<S>(b: &mut Bencher)
+    where S: BuildHasher + Default
+{
+	let mut map: HashMap<TypeId, usize, S> = HashMap::default();
+	map.insert(TypeId::of::<X>(), 0);
+	map.insert(TypeId::of::<Y>(), 1);
+	map.insert(TypeId::of::<BorrowY>(), 2);
+
+	let key = TypeId::of::<Y>();
+
+	b.iter(|| {
+		black_box(map.get(&black_box(key)));
+	})
+}
+
+#[bench]
+pub fn type_id_lookup_fnv(b: &mut Bencher) {
+	type_id_lookup::<BuildHasherDefault<FnvHasher>>(b);
+}
+
+#[bench]
+pub fn type_id_lookup_passthrough(b: &mut Bencher) {
+	type_id_lookup::<BuildTypeIdHasher>(b);
+}
+
+/// Run a bench and print how long was spent in each phase of resolving
+/// shared dependencies.
+///
+/// The timings cover every iteration the bencher ran, including warmup.
+#[cfg(feature = "timings")]
+fn bench_phases<F>(b: &mut Bencher, name: &str, mut f: F)
+    where F: FnMut()
+{
+	let mut iterations = 0u64;
+
+	reset_phase_timings();
+
+	b.iter(|| {
+		iterations += 1;
+
+		f()
+	});
+
+	eprintln!("\n{} ({} iterations)\n{}", name, iterations, phase_timings());
+}
+
+#[cfg(feature = "timings")]
+#[bench]
+pub fn phases_brwd_y_first(b: &mut Bencher) {
+	let c = BasicContainer;
+
+	bench_phases(b, "phases_brwd_y_first", || {
+		c.scope(|scope| {
+			let y: BorrowY = scope.resolve();
+
+			black_box(y);
+		})
+	})
+}
+
+#[cfg(feature = "timings")]
+#[bench]
+pub fn phases_brwd_y_subsequent(b: &mut Bencher) {
+	let scope = Scoped::new();
+
+	bench_phases(b, "phases_brwd_y_subsequent", || {
+		let y: BorrowY = scope.resolve();
+
+		black_box(y);
+	})
+}
@@ -0,0 +1,140 @@
+//! A diesel connection and transaction for each scope
+//!
+//! `DieselPool` wraps an r2d2 pool of diesel connections so it can be bound
+//! to a registry. Anything resolved from a scope can then share one
+//! connection with an open transaction by depending on an
+//! `Rc<InTransaction<DieselPool<C>>>`:
+//!
+//! ```no_run
+//! # use std::error::Error;
+//! # use std::rc::Rc;
+//! # use diesel::r2d2::{ConnectionManager, Pool};
+//! # use diesel::SqliteConnection;
+//! # use ioc::prelude::*;
+//! # use ioc::InTransaction;
+//! # use ioc_diesel::DieselPool;
+//! # struct RenameProduct { tx: Rc<InTransaction<DieselPool<SqliteConnection>>> }
+//! # impl RenameProduct {
+//! #     fn rename(&self, _: u32, _: &str) -> Result<(), Box<dyn Error>> { Ok(()) }
+//! # }
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! # let url = "products.db";
+//! # let mut registry = Registry::new();
+//! registry.bind_pool(DieselPool::new(Pool::new(ConnectionManager::<SqliteConnection>::new(url))?));
+//! # let scope = Scoped::new().with_registry(Rc::new(registry));
+//!
+//! impl Resolvable<Scoped> for RenameProduct {
+//!     type Dependency = Rc<InTransaction<DieselPool<SqliteConnection>>>;
+//!
+//!     fn resolve(tx: Self::Dependency) -> Self {
+//!         RenameProduct { tx }
+//!     }
+//! }
+//!
+//! scope.try_run(|scope| {
+//!     let handler: RenameProduct = scope.try_resolve()?;
+//!
+//!     handler.rename(1, "A new title")
+//! })?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The transaction begins when the connection is first resolved in the
+//! scope. It's committed when the scope ends if `try_run`'s closure
+//! returned `Ok`, and rolled back otherwise. See `ioc::InTransaction` for
+//! the details.
+
+extern crate diesel;
+extern crate ioc;
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use diesel::connection::{Connection, TransactionManager};
+use diesel::r2d2::{ConnectionManager, Pool, PooledConnection, R2D2Connection};
+use diesel::result::Error;
+
+/// An r2d2 pool of diesel connections.
+pub struct DieselPool<C>
+    where C: R2D2Connection + 'static
+{
+    pool: Pool<ConnectionManager<C>>,
+}
+
+impl<C> DieselPool<C>
+    where C: R2D2Connection + 'static
+{
+    pub fn new(pool: Pool<ConnectionManager<C>>) -> Self {
+        DieselPool { pool }
+    }
+}
+
+impl<C> ioc::Pool for DieselPool<C>
+    where C: R2D2Connection + 'static
+{
+    type Connection = DieselConnection<C>;
+    type Error = diesel::r2d2::PoolError;
+
+    fn check_out(&self) -> Result<DieselConnection<C>, Self::Error> {
+        Ok(DieselConnection {
+            connection: self.pool.get()?,
+        })
+    }
+
+    // r2d2 returns the connection to its pool when it's dropped
+    fn check_in(&self, connection: DieselConnection<C>) {
+        drop(connection);
+    }
+}
+
+impl<C> fmt::Debug for DieselPool<C>
+    where C: R2D2Connection + 'static
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DieselPool").field("state", &self.pool.state()).finish()
+    }
+}
+
+/// A diesel connection checked out of a `DieselPool`.
+pub struct DieselConnection<C>
+    where C: R2D2Connection + 'static
+{
+    connection: PooledConnection<ConnectionManager<C>>,
+}
+
+impl<C> Deref for DieselConnection<C>
+    where C: R2D2Connection + 'static
+{
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.connection
+    }
+}
+
+impl<C> DerefMut for DieselConnection<C>
+    where C: R2D2Connection + 'static
+{
+    fn deref_mut(&mut self) -> &mut C {
+        &mut self.connection
+    }
+}
+
+impl<C> ioc::Transactional for DieselConnection<C>
+    where C: R2D2Connection + 'static
+{
+    type Error = Error;
+
+    fn begin(&mut self) -> Result<(), Error> {
+        <C as Connection>::TransactionManager::begin_transaction(&mut *self.connection)
+    }
+
+    fn commit(&mut self) -> Result<(), Error> {
+        <C as Connection>::TransactionManager::commit_transaction(&mut *self.connection)
+    }
+
+    fn rollback(&mut self) -> Result<(), Error> {
+        <C as Connection>::TransactionManager::rollback_transaction(&mut *self.connection)
+    }
+}
@@ -0,0 +1,107 @@
+extern crate diesel;
+extern crate ioc;
+extern crate ioc_diesel;
+
+use std::rc::Rc;
+
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::result::Error;
+use diesel::sql_query;
+use diesel::sql_types::BigInt;
+use ioc::prelude::*;
+use ioc::InTransaction;
+use ioc_diesel::DieselPool;
+
+type Tx = InTransaction<DieselPool<SqliteConnection>>;
+
+#[derive(QueryableByName)]
+struct Count {
+    #[diesel(sql_type = BigInt)]
+    count: i64,
+}
+
+/// A pool with a single in-memory database.
+///
+/// Each connection to `:memory:` is a separate database, so the pool only
+/// ever has one connection.
+fn pool() -> Pool<ConnectionManager<SqliteConnection>> {
+    let pool = Pool::builder()
+        .max_size(1)
+        .build(ConnectionManager::<SqliteConnection>::new(":memory:"))
+        .unwrap();
+
+    sql_query("CREATE TABLE products (title TEXT NOT NULL)")
+        .execute(&mut pool.get().unwrap())
+        .unwrap();
+
+    pool
+}
+
+fn registry(pool: &Pool<ConnectionManager<SqliteConnection>>) -> Rc<Registry<Scoped>> {
+    let mut registry = Registry::new();
+    registry.bind_pool(DieselPool::new(pool.clone()));
+
+    Rc::new(registry)
+}
+
+fn products(pool: &Pool<ConnectionManager<SqliteConnection>>) -> i64 {
+    sql_query("SELECT COUNT(*) AS count FROM products")
+        .get_result::<Count>(&mut pool.get().unwrap())
+        .unwrap()
+        .count
+}
+
+fn insert(scope: &Scoped) -> Result<(), Error> {
+    let tx = scope.get_or_add::<Tx, _>();
+    let mut connection = tx.connection();
+
+    sql_query("INSERT INTO products (title) VALUES ('A product')").execute(&mut **connection)?;
+
+    Ok(())
+}
+
+#[test]
+fn transaction_is_committed_when_the_scope_succeeds() {
+    let pool = pool();
+
+    Scoped::new().with_registry(registry(&pool)).try_run(insert).unwrap();
+
+    assert_eq!(1, products(&pool));
+}
+
+#[test]
+fn transaction_is_rolled_back_when_the_scope_fails() {
+    let pool = pool();
+
+    let result = Scoped::new().with_registry(registry(&pool)).try_run(|scope| {
+        insert(scope)?;
+
+        Err::<(), _>(Error::RollbackTransaction)
+    });
+
+    assert!(result.is_err());
+    assert_eq!(0, products(&pool));
+}
+
+#[test]
+fn transaction_is_rolled_back_when_the_outcome_isnt_recorded() {
+    let pool = pool();
+
+    {
+        let scope = Scoped::new().with_registry(registry(&pool));
+        insert(&scope).unwrap();
+    }
+
+    assert_eq!(0, products(&pool));
+}
+
+#[test]
+fn connection_is_returned_to_the_pool_when_the_scope_ends() {
+    let pool = pool();
+
+    Scoped::new().with_registry(registry(&pool)).try_run(insert).unwrap();
+
+    // the pool only has one connection, so this would time out otherwise
+    assert_eq!(1, pool.state().idle_connections);
+}
@@ -0,0 +1,414 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::resolver::{self, Resolver};
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+// Types that just store their dependencies can be declared without
+// writing out a `Resolvable` impl.
+resolvable! {
+    #[derive(Debug, Clone)]
+    struct X;
+}
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+struct Y {
+    x: X,
+    i: i32
+}
+impl<C> Resolvable<C> for Y {
+    type Dependency = RefCell<X>;
+
+    fn resolve(x: Self::Dependency) -> Self {
+        Y { x: x.into_inner(), i: 1 }
+    }
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct Z {
+    x: X,
+    y: Y,
+}
+impl<C> Resolvable<C> for Z {
+    type Dependency = (RefCell<X>, RefCell<Y>);
+
+    fn resolve((x, y): Self::Dependency) -> Self {
+        Z {
+            x: x.into_inner(),
+            y: y.into_inner(),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[allow(dead_code, clippy::upper_case_acronyms)]
+struct XYZ {
+    x: X,
+    y: Y,
+    z: Z,
+}
+impl<C> Resolvable<C> for XYZ {
+    // NOTE: `(RefCell<X>, (RefCell<Y>, RefCell<Z>))` would also work
+    type Dependency = (RefCell<X>, RefCell<Y>, RefCell<Z>);
+
+    fn resolve((x, y, z): Self::Dependency) -> Self {
+        XYZ {
+            x: x.into_inner(),
+            y: y.into_inner(),
+            z: z.into_inner(),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct XorY<T> {
+    t: T,
+}
+impl<C, T> Resolvable<C> for XorY<T> {
+    type Dependency = RefCell<T>;
+
+    fn resolve(t: Self::Dependency) -> Self {
+        XorY { t: t.into_inner() }
+    }
+}
+
+// A generic service that's bound once for any type argument.
+trait Named<T> {
+    fn name(&self) -> String;
+}
+
+#[derive(Debug)]
+struct DebugNamed<T> {
+    t: T,
+}
+impl<C, T, D> Resolvable<C> for DebugNamed<T>
+    where C: Container,
+          T: Resolvable<C, Dependency = D>,
+          D: ResolvableFromContainer<C>
+{
+    type Dependency = Transient<T>;
+
+    fn resolve(t: Self::Dependency) -> Self {
+        DebugNamed { t: t.into_inner() }
+    }
+}
+impl<T> Named<T> for DebugNamed<T>
+    where T: std::fmt::Debug
+{
+    fn name(&self) -> String {
+        format!("{:?}", self.t)
+    }
+}
+
+open_binding! {
+    impl<T> dyn Named<T> => DebugNamed<T> where T: std::fmt::Debug + 'static;
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct BorrowY {
+    x: X,
+    y: Rc<Y>,
+    k: &'static str
+}
+impl<C> Resolvable<C> for BorrowY {
+    type Dependency = (RefCell<X>, Rc<Y>);
+
+    fn resolve((x, y): Self::Dependency) -> Self {
+        BorrowY {
+            x: x.into_inner(),
+            y,
+            k: "some string value"
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RefY<'scope> {
+    y: &'scope Y,
+}
+impl<'scope, C> Resolvable<C> for RefY<'scope> {
+    type Dependency = B<'scope, Y>;
+
+    fn resolve(y: Self::Dependency) -> Self {
+        RefY { y: y.into_inner() }
+    }
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct YForUser {
+    y: Rc<Y>,
+    user_id: u32,
+}
+impl<C> ResolvableWith<C, u32> for YForUser {
+    type Dependency = Rc<Y>;
+
+    fn resolve_with(y: Self::Dependency, user_id: u32) -> Self {
+        YForUser { y, user_id }
+    }
+}
+
+resolvable! {
+    #[derive(Debug)]
+    struct BorrowMoreY {
+        y: Rc<BorrowY>,
+    }
+
+    #[derive(Debug)]
+    struct BorrowAndMutateY {
+        y: SharedMut<Y>,
+    }
+
+    #[derive(Debug)]
+    struct LazyZ {
+        z: Lazy<Z>,
+    }
+
+    #[derive(Debug)]
+    struct MakeBorrowY {
+        factory: Factory<BorrowY>,
+    }
+}
+
+trait Handler {
+    fn handle(&self) -> String;
+}
+
+resolvable! {
+    #[derive(Debug)]
+    struct XHandler;
+
+    #[derive(Debug)]
+    struct YHandler {
+        y: Rc<Y>,
+    }
+}
+
+impl Handler for XHandler {
+    fn handle(&self) -> String {
+        "x".into()
+    }
+}
+
+impl Handler for YHandler {
+    fn handle(&self) -> String {
+        format!("y with i: {}", self.y.i)
+    }
+}
+
+trait KeyStore {
+    fn name(&self) -> &'static str;
+}
+
+struct KeychainStore;
+impl KeyStore for KeychainStore {
+    fn name(&self) -> &'static str {
+        "keychain"
+    }
+}
+
+struct FileStore;
+impl KeyStore for FileStore {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+}
+
+resolvable! {
+    struct Handlers {
+        all: All<dyn Handler>,
+    }
+}
+
+// Fail to compile if the dependencies of `XYZ` grow too deep.
+max_depth!(BasicContainer, XYZ, 8);
+
+// Fail to compile if `XYZ` can't be resolved from a `BasicContainer`.
+assert_resolvable!(XYZ, BasicContainer);
+
+// A scoped container for a fixed set of shared dependencies.
+closed_scope! {
+    struct ClosedScope {
+        y: Y,
+        borrow_y: BorrowY,
+    }
+}
+
+fn main() {
+    // A basic container for only owned resources.
+    let c = BasicContainer;
+
+    let x: X = c.resolve();
+    let xy: (X, Y) = c.resolve();
+    let z: Z = c.resolve();
+    let xyz: XYZ = c.resolve();
+
+    let xory_x: XorY<X> = c.resolve();
+    let xory_y: XorY<Y> = c.resolve();
+
+    println!("{:?}", x);
+    println!("{:?}", xy);
+    println!("{:?}", z);
+    println!("{:?}", xyz);
+    println!("{:?}", xory_x);
+    println!("{:?}", xory_y);
+
+    // Open bindings resolve generic services for any type argument.
+    let named_x: Open<dyn Named<X>> = c.resolve();
+
+    println!("{}", named_x.name());
+
+    // Lazy dependencies aren't resolved until they're accessed.
+    let lazy_z: LazyZ = c.resolve();
+
+    println!("{:?}", lazy_z);
+    println!("{:?}", lazy_z.z.get());
+
+    // Create a scope that can be used to resolve references.
+    // Each B<'a, T> dependency will be the same instance for the lifetime of the scope.
+    c.scope(|scope| {
+        let z: Z = scope.resolve();
+
+        {
+            let y: BorrowMoreY = scope.resolve();
+
+            println!("y count: {}", Rc::strong_count(&y.y));
+        }
+        {
+            let y: BorrowMoreY = scope.resolve();
+
+            println!("{:?}", y);
+            println!("y count: {}", Rc::strong_count(&y.y));
+        }
+        {
+            let y: BorrowAndMutateY = scope.resolve();
+
+            let mut iy = y.y.borrow_mut();
+
+            iy.i += 1;
+
+            println!("{:?}", y);
+            println!("y.i: {}", iy.i);
+        }
+        {
+            let y: BorrowAndMutateY = scope.resolve();
+
+            let mut iy = y.y.borrow_mut();
+
+            iy.i += 1;
+
+            println!("y.i: {}", iy.i);
+        }
+
+        {
+            // Each BorrowY is new, but they share the same Rc<Y>.
+            let make: MakeBorrowY = scope.resolve();
+
+            let a = make.factory.create();
+            let b = make.factory.create();
+
+            println!("same y: {}", Rc::ptr_eq(&a.y, &b.y));
+        }
+
+        {
+            // Pass a runtime value along with the resolved dependencies.
+            let y: YForUser = scope.resolve_with(42);
+
+            println!("{:?}", y);
+        }
+
+        // Pinned dependencies are shared, but never move until the scope ends.
+        scope.invoke(|a: PinnedRc<Y>, b: PinnedRc<Y>| {
+            println!("same pinned y: {}", PinnedRc::ptr_eq(&a, &b));
+        });
+
+        // Call a closure with its arguments resolved from the scope.
+        scope.invoke(|x: RefCell<X>, y: Rc<Y>| {
+            println!("invoked with {:?} and {:?}", x, y);
+        });
+
+        // Build up a value from resolvers instead of implementing `Resolvable`.
+        let describe_y = resolver::resolve::<Rc<Y>>()
+            .zip(resolver::of("y.i"))
+            .map(|(y, name)| format!("{}: {}", name, y.i));
+
+        println!("{}", describe_y.resolve(&scope));
+
+        // Clone shared dependencies out of the scope so they can be used independently.
+        let (y,): (Y,) = scope.detach();
+
+        std::thread::spawn(move || println!("detached y.i: {}", y.i)).join().unwrap();
+
+        // Lines logged within the scope are written out together when it ends.
+        scope.invoke(|log: Rc<ScopeLog>| {
+            log.log(format_args!("resolved {:?}", z));
+        });
+
+        println!("correlation id: {}", scope.correlation_id());
+    });
+
+    // Create a scope that hands out references instead of Rcs.
+    // Each B<'scope, T> dependency will be the same instance for the lifetime of the scope.
+    c.borrow_scope(|scope| {
+        let a: RefY = scope.resolve();
+        let b: RefY = scope.resolve();
+
+        println!("{:?}", a);
+        println!("same y: {}", std::ptr::eq(a.y, b.y));
+    });
+
+    // Seed a scope with an existing value before resolving from it.
+    // Each Rc<Y> dependency will point to the seeded instance.
+    c.scope_with(|scope| scope.insert(Y { x: X, i: 42 }).unwrap(), |scope| {
+        let y: BorrowY = scope.resolve();
+
+        println!("seeded y.i: {}", y.y.i);
+    });
+
+    // Register several implementations of the same trait and resolve them all.
+    let mut registry = Registry::<Scoped>::new();
+    registry
+        .bind_all_to::<dyn Handler, XHandler, _, _>(|handler| Rc::new(handler))
+        .bind_all_to::<dyn Handler, YHandler, _, _>(|handler| Rc::new(handler));
+
+    // Bind a different implementation of a trait depending on the platform.
+    registry
+        .bind_platform::<dyn KeyStore>()
+        .on_os("macos", |_| Rc::new(KeychainStore))
+        .otherwise(|_| Rc::new(FileStore));
+
+    let scope = Scoped::new().with_registry(Rc::new(registry));
+
+    // Check the bindings can all be resolved before resolving anything.
+    if let Err(errors) = scope.verify::<_, Handlers>() {
+        for error in errors {
+            println!("{}", error);
+        }
+    }
+
+    // Write out the dependencies of a type for Graphviz.
+    println!("{}", scope.dependency_graph::<_, Handlers>().to_dot());
+
+    let handlers: Handlers = scope.resolve();
+
+    for handler in &handlers.all {
+        println!("handled by: {}", handler.handle());
+    }
+
+    scope.invoke(|store: Bound<dyn KeyStore>| {
+        println!("key store: {}", store.name());
+    });
+
+    // Resolve shared dependencies from a closed set of types without a map.
+    let scope = ClosedScope::new();
+    let y: BorrowMoreY = scope.resolve();
+
+    println!("{:?}", y);
+}
@@ -0,0 +1,164 @@
+// Dependency injection with queries and commands.
+//
+// Queries and commands are closures returned from functions that take
+// their dependencies, so the same logic can be run with different
+// implementations. A registry binds those functions, and the container
+// resolves a `QueryOf` or `CommandOf` like any other dependency. Run it
+// with:
+//
+// cargo run --example factories
+
+#[macro_use]
+extern crate ioc;
+
+mod unit_of_work;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ioc::prelude::*;
+use ioc::factories::{CommandBus, Dispatch, DispatchError, Events, Handles, Next};
+use ioc::Veto;
+use unit_of_work::{CompleteUnitOfWork, Transaction, UnitOfWork};
+
+struct Product {
+    id: i32,
+    title: String,
+}
+
+#[derive(Clone, Default)]
+struct DbConnection {}
+
+resolvable! {
+    default DbConnection;
+}
+
+impl DbConnection {
+    fn do_stuff(&self) {}
+}
+
+struct GetProduct {
+    id: i32,
+}
+
+query! {
+    // Return a closure to get a product
+    fn get_product(conn: DbConnection) -> impl GetProductQuery(action: GetProduct) -> Result<Product, String> {
+        conn.do_stuff();
+
+        Ok(Product {
+            id: action.id,
+            title: "Some product".into(),
+        })
+    }
+}
+
+#[derive(Clone)]
+struct SetProductTitle {
+    id: i32,
+    title: String,
+}
+
+// Published once a product's title has been set
+struct ProductRenamed {
+    id: i32,
+    title: String,
+}
+
+// Subscribes to renamed products to keep the search index up to date
+struct ReindexProduct;
+
+impl<C> Resolvable<C> for ReindexProduct {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        ReindexProduct
+    }
+}
+
+impl Handles<ProductRenamed> for ReindexProduct {
+    fn handle(&self, event: &ProductRenamed) {
+        println!("reindex product {} as '{}'", event.id, event.title);
+    }
+}
+
+command! {
+    // Return a closure to set a product's title
+    // Changes are made in the transaction shared by the scope's unit of work,
+    // and subscribers hear about them when the scope ends
+    fn set_product_title<Q: GetProductQuery>(conn: DbConnection, transaction: Rc<RefCell<Transaction>>, events: Rc<Events<ProductRenamed>>, get_product: Q) -> impl SetProductTitleCommand(action: SetProductTitle) -> Result<(), String> {
+        let mut product = get_product.query(GetProduct { id: action.id })?;
+        product.title = action.title;
+
+        conn.do_stuff();
+        transaction.borrow_mut().execute(format!("update product {} set title = '{}'", product.id, product.title));
+        events.defer(ProductRenamed { id: product.id, title: product.title });
+
+        Ok(())
+    }
+}
+
+// The `GetProductQuery` bound in the registry
+type BoundGetProductQuery = QueryOf<GetProduct, Result<Product, String>>;
+
+// The dependencies of the bound `SetProductTitleCommand`
+type SetProductTitleDependencies = (Transient<DbConnection>, Rc<RefCell<Transaction>>, Rc<Events<ProductRenamed>>, BoundGetProductQuery);
+
+fn main() {
+    let conn = DbConnection {};
+
+    // Separate construction from action
+    let query = get_product(conn.clone());
+
+    // Execute the action with the inputs
+    query.query(GetProduct { id: 1 }).unwrap();
+
+    // Bind the functions so the container can construct them
+    let mut registry = Registry::new();
+    registry
+        .bind_query(|conn: Transient<DbConnection>| get_product(conn.into_inner()))
+        .bind_command(|(conn, transaction, events, get_product): SetProductTitleDependencies| {
+            set_product_title(conn.into_inner(), transaction, events, get_product)
+        })
+        .subscribe::<ProductRenamed, ReindexProduct, _>();
+
+    let registry = Rc::new(registry);
+
+    // The bus resolves a handler for each command from the scope it's
+    // dispatched on, validating and logging it along the way. Successful
+    // commands complete the scope's unit of work
+    let mut bus = CommandBus::new();
+    bus.handle::<SetProductTitle, CommandOf<SetProductTitle, String>, _>()
+        .validate(|action: &SetProductTitle| match action.title.is_empty() {
+            true => Err(Veto::new("product titles can't be empty")),
+            false => Ok(()),
+        })
+        .with(|dispatch: &Dispatch, next: Next| {
+            let result = next.run();
+            println!("dispatched {}: {:?}", dispatch.command, result);
+
+            result
+        })
+        .behavior::<CompleteUnitOfWork, _>();
+
+    // Commands within a scope share its unit of work, which commits or
+    // rolls back when the scope ends
+    for title in &["A new title", ""] {
+        let scope = Scoped::new().with_registry(registry.clone());
+
+        let _: Result<(), DispatchError<String>> = bus.dispatch(&scope, SetProductTitle { id: 1, title: title.to_string() });
+    }
+
+    // An alternative implementation of `GetProductQuery` that doesn't use a db connection
+    {
+        let scope = Scoped::new().with_registry(registry);
+
+        scope.invoke(|transaction: Rc<RefCell<Transaction>>, unit_of_work: Rc<UnitOfWork>, events: Rc<Events<ProductRenamed>>| {
+            let command = set_product_title(conn.clone(), transaction, events, |_: GetProduct| Err::<Product, _>("not found".to_owned()));
+
+            if command.execute(SetProductTitle { id: 1, title: "Stuff".into() }).is_ok() {
+                unit_of_work.complete();
+            }
+        });
+    }
+}
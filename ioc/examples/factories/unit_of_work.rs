@@ -0,0 +1,98 @@
+// A unit of work shared by the commands resolved within a scope.
+//
+// Each command is handed the same `Rc<RefCell<Transaction>>`, so their
+// changes are either all committed or all rolled back. That's decided
+// by the `UnitOfWork` when the scope ends, instead of by the commands.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use ioc::{Resolvable, ScopedContainer};
+use ioc::factories::{Behavior, Dispatch, Failure, Next};
+
+pub struct Transaction {
+    statements: Vec<String>,
+}
+
+impl Transaction {
+    pub fn execute<S>(&mut self, statement: S)
+        where S: Into<String>
+    {
+        self.statements.push(statement.into());
+    }
+}
+
+// Each scope gets a new transaction when it's first resolved
+impl<C> Resolvable<C> for Transaction {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Transaction { statements: Vec::new() }
+    }
+}
+
+pub struct UnitOfWork {
+    transaction: Rc<RefCell<Transaction>>,
+    completed: Cell<bool>,
+}
+
+impl UnitOfWork {
+    // Commit the transaction when the scope ends.
+    //
+    // If the unit of work isn't completed, say because a command failed
+    // part way through, then it's rolled back.
+    pub fn complete(&self) {
+        self.completed.set(true);
+    }
+}
+
+impl<C> Resolvable<C> for UnitOfWork
+    where C: ScopedContainer
+{
+    type Dependency = Rc<RefCell<Transaction>>;
+
+    fn resolve(transaction: Self::Dependency) -> Self {
+        UnitOfWork {
+            transaction,
+            completed: Cell::new(false),
+        }
+    }
+}
+
+// The unit of work is resolved after its transaction, so the scope
+// drops it first, while the transaction is still around
+impl Drop for UnitOfWork {
+    fn drop(&mut self) {
+        let statements = self.transaction.borrow_mut().statements.split_off(0);
+
+        if self.completed.get() {
+            println!("commit {} statements", statements.len());
+        } else {
+            println!("rollback {} statements", statements.len());
+        }
+    }
+}
+
+// Completes the scope's unit of work when a command succeeds
+pub struct CompleteUnitOfWork {
+    unit_of_work: Rc<UnitOfWork>,
+}
+
+impl<C> Resolvable<C> for CompleteUnitOfWork
+    where C: ScopedContainer
+{
+    type Dependency = Rc<UnitOfWork>;
+
+    fn resolve(unit_of_work: Self::Dependency) -> Self {
+        CompleteUnitOfWork { unit_of_work }
+    }
+}
+
+impl Behavior for CompleteUnitOfWork {
+    fn handle(&self, _: &Dispatch, next: Next) -> Result<(), Failure> {
+        next.run()?;
+        self.unit_of_work.complete();
+
+        Ok(())
+    }
+}
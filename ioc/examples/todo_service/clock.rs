@@ -0,0 +1,35 @@
+//! The time todos are updated at.
+
+use ioc::prelude::*;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A source of timestamps.
+///
+/// Requests use the system clock, but tests can seed their scope with a
+/// fixed one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Clock {
+    System,
+    // tests override the clock with a fixed one
+    #[cfg_attr(not(test), allow(dead_code))]
+    Fixed(u64),
+}
+
+impl<C> Resolvable<C> for Clock {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Clock::System
+    }
+}
+
+impl Clock {
+    /// Seconds since the Unix epoch.
+    pub fn now(&self) -> u64 {
+        match *self {
+            Clock::System => SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs()).unwrap_or_default(),
+            Clock::Fixed(now) => now,
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Request handlers.
+//!
+//! Each kind of request has its own handler. The `Dispatcher` creates the
+//! handler for a request through a `Factory`, so only the handler that's
+//! needed is resolved.
+
+use ioc::prelude::*;
+
+use std::fmt;
+use std::rc::Rc;
+
+use store::{Todo, TodoRepository, Transaction};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Request {
+    List,
+    Create { title: String },
+    Complete { id: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Response {
+    Todos(Vec<Todo>),
+    Created(Todo),
+    Completed(Todo),
+    NotFound,
+}
+
+impl Response {
+    fn is_success(&self) -> bool {
+        *self != Response::NotFound
+    }
+}
+
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Response::Todos(ref todos) => {
+                for todo in todos {
+                    writeln!(f, "[{}] {}: {}", if todo.done { "x" } else { " " }, todo.id, todo.title)?;
+                }
+
+                Ok(())
+            }
+            Response::Created(ref todo) => writeln!(f, "created {}: {}", todo.id, todo.title),
+            Response::Completed(ref todo) => writeln!(f, "completed {}: {}", todo.id, todo.title),
+            Response::NotFound => writeln!(f, "not found"),
+        }
+    }
+}
+
+pub trait Handler {
+    fn handle(&self, request: &Request) -> Response;
+}
+
+pub struct ListTodos {
+    todos: Bound<dyn TodoRepository>,
+}
+
+impl Resolvable<Scoped> for ListTodos {
+    type Dependency = Bound<dyn TodoRepository>;
+
+    fn resolve(todos: Self::Dependency) -> Self {
+        ListTodos { todos }
+    }
+}
+
+impl Handler for ListTodos {
+    fn handle(&self, _: &Request) -> Response {
+        Response::Todos(self.todos.all())
+    }
+}
+
+pub struct CreateTodo {
+    todos: Bound<dyn TodoRepository>,
+    log: Rc<ScopeLog>,
+}
+
+impl Resolvable<Scoped> for CreateTodo {
+    type Dependency = (Bound<dyn TodoRepository>, Rc<ScopeLog>);
+
+    fn resolve((todos, log): Self::Dependency) -> Self {
+        CreateTodo { todos, log }
+    }
+}
+
+impl Handler for CreateTodo {
+    fn handle(&self, request: &Request) -> Response {
+        match *request {
+            Request::Create { ref title } => {
+                let todo = self.todos.add(title);
+
+                self.log.log(format_args!("created todo {}", todo.id));
+
+                Response::Created(todo)
+            }
+            _ => Response::NotFound,
+        }
+    }
+}
+
+pub struct CompleteTodo {
+    todos: Bound<dyn TodoRepository>,
+    log: Rc<ScopeLog>,
+}
+
+impl Resolvable<Scoped> for CompleteTodo {
+    type Dependency = (Bound<dyn TodoRepository>, Rc<ScopeLog>);
+
+    fn resolve((todos, log): Self::Dependency) -> Self {
+        CompleteTodo { todos, log }
+    }
+}
+
+impl Handler for CompleteTodo {
+    fn handle(&self, request: &Request) -> Response {
+        match *request {
+            Request::Complete { id } => match self.todos.complete(id) {
+                Some(todo) => Response::Completed(todo),
+                None => {
+                    self.log.log(format_args!("todo {} doesn't exist", id));
+
+                    Response::NotFound
+                }
+            },
+            _ => Response::NotFound,
+        }
+    }
+}
+
+/// Routes a request to its handler and commits the request's transaction
+/// if it succeeds.
+pub struct Dispatcher {
+    list: Factory<ListTodos>,
+    create: Factory<CreateTodo>,
+    complete: Factory<CompleteTodo>,
+    tx: Rc<Transaction>,
+    log: Rc<ScopeLog>,
+}
+
+impl Resolvable<Scoped> for Dispatcher {
+    type Dependency = (Factory<ListTodos>, Factory<CreateTodo>, Factory<CompleteTodo>, Rc<Transaction>, Rc<ScopeLog>);
+
+    fn resolve((list, create, complete, tx, log): Self::Dependency) -> Self {
+        Dispatcher { list, create, complete, tx, log }
+    }
+}
+
+impl Dispatcher {
+    pub fn dispatch(&self, request: &Request) -> Response {
+        self.log.log(format_args!("dispatching {:?}", request));
+
+        let response = match *request {
+            Request::List => self.list.create().handle(request),
+            Request::Create { .. } => self.create.create().handle(request),
+            Request::Complete { .. } => self.complete.create().handle(request),
+        };
+
+        if response.is_success() {
+            self.tx.commit();
+        }
+
+        response
+    }
+}
@@ -0,0 +1,173 @@
+//! A todo service wired up with the container.
+//!
+//! Each request gets its own scope, with its own correlation id, log and
+//! database transaction. The bindings shared by every request live in a
+//! single registry. Run the tests with:
+//!
+//! ```text
+//! cargo test --example todo_service
+//! ```
+
+extern crate ioc;
+
+mod clock;
+mod handlers;
+mod store;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+
+use handlers::{Dispatcher, Request, Response};
+use store::{Database, TodoModule};
+
+/// The service, which handles each request in a new scope.
+pub struct App {
+    registry: Rc<Registry<Scoped>>,
+}
+
+impl App {
+    pub fn new(db: Rc<Database>) -> Self {
+        let mut registry = Registry::new();
+        registry.add_module(TodoModule::new(db));
+
+        App { registry: Rc::new(registry) }
+    }
+
+    fn scope(&self) -> Scoped {
+        Scoped::new().with_registry(self.registry.clone())
+    }
+
+    /// Check every binding the service needs can be resolved.
+    pub fn verify(&self) -> Result<(), Vec<VerifyError>> {
+        let scope = self.scope();
+
+        scope.verify_all()?;
+        scope.verify::<_, Dispatcher>()
+    }
+
+    pub fn handle(&self, request: Request) -> Response {
+        let scope = self.scope();
+        let dispatcher: Dispatcher = scope.resolve();
+
+        dispatcher.dispatch(&request)
+    }
+}
+
+fn main() {
+    let app = App::new(Rc::new(Database::default()));
+
+    if let Err(errors) = app.verify() {
+        for error in errors {
+            eprintln!("{}", error);
+        }
+
+        return;
+    }
+
+    let requests = vec![
+        Request::Create { title: "write the example".into() },
+        Request::Create { title: "run the example".into() },
+        Request::Complete { id: 1 },
+        Request::Complete { id: 42 },
+        Request::List,
+    ];
+
+    for request in requests {
+        print!("{}", app.handle(request));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ioc::testing::TestScope;
+
+    use clock::Clock;
+    use store::{Todo, TodoRepository};
+
+    fn todo(id: u32, title: &str, done: bool, updated_at: u64) -> Todo {
+        Todo { id, title: title.into(), done, updated_at }
+    }
+
+    #[ioc_test(modules(TodoModule::new(Rc::new(Database::default()))), overrides(Clock::Fixed(42)))]
+    fn created_todos_use_the_scope_clock(dispatcher: Rc<Dispatcher>) {
+        let created = dispatcher.dispatch(&Request::Create { title: "a".into() });
+
+        assert_eq!(Response::Created(todo(1, "a", false, 42)), created);
+    }
+
+    #[ioc_test(modules(TodoModule::new(Rc::new(Database::default()))), overrides(Clock::Fixed(42)))]
+    fn changes_are_visible_to_their_transaction_before_commit(todos: Bound<dyn TodoRepository>, db: Bound<Database>) {
+        todos.add("a");
+
+        assert_eq!(vec![todo(1, "a", false, 42)], todos.all());
+        assert_eq!(Vec::<Todo>::new(), db.todos());
+    }
+
+    #[test]
+    fn committed_changes_are_shared_between_requests() {
+        let app = App::new(Rc::new(Database::default()));
+
+        app.handle(Request::Create { title: "a".into() });
+        app.handle(Request::Complete { id: 1 });
+
+        match app.handle(Request::List) {
+            Response::Todos(ref todos) => {
+                assert_eq!(1, todos.len());
+                assert!(todos[0].done);
+            }
+            response => panic!("unexpected response {:?}", response),
+        }
+    }
+
+    #[test]
+    fn uncommitted_changes_are_rolled_back() {
+        let db = Rc::new(Database::default());
+        let app = App::new(db.clone());
+
+        app.scope().invoke(|todos: Bound<dyn TodoRepository>| {
+            todos.add("a");
+        });
+
+        assert_eq!(Response::NotFound, app.handle(Request::Complete { id: 1 }));
+        assert_eq!(Vec::<Todo>::new(), db.todos());
+    }
+
+    struct FakeTodos;
+
+    impl TodoRepository for FakeTodos {
+        fn all(&self) -> Vec<Todo> {
+            vec![todo(7, "fake", true, 1)]
+        }
+
+        fn add(&self, _: &str) -> Todo {
+            unimplemented!()
+        }
+
+        fn complete(&self, _: u32) -> Option<Todo> {
+            None
+        }
+    }
+
+    #[test]
+    fn repositories_can_be_replaced_with_fakes() {
+        let scope = TestScope::new()
+            .with_module(TodoModule::new(Rc::new(Database::default())))
+            .with_override::<dyn TodoRepository>(Rc::new(FakeTodos))
+            .build();
+
+        let dispatcher: Dispatcher = scope.resolve();
+
+        assert_eq!(Response::Todos(vec![todo(7, "fake", true, 1)]), dispatcher.dispatch(&Request::List));
+        assert_eq!(Response::NotFound, dispatcher.dispatch(&Request::Complete { id: 7 }));
+    }
+
+    #[test]
+    fn bindings_are_verified() {
+        let app = App::new(Rc::new(Database::default()));
+
+        assert!(app.verify().is_ok());
+    }
+}
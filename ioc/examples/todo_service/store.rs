@@ -0,0 +1,215 @@
+//! Storage for todos.
+//!
+//! The `Database` is shared by every request. Each request makes its
+//! changes through its own `Transaction`, which is cached in the request's
+//! scope so everything that handles the request sees the same changes.
+
+use ioc::prelude::*;
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use clock::Clock;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Todo {
+    pub id: u32,
+    pub title: String,
+    pub done: bool,
+    pub updated_at: u64,
+}
+
+/// The todos shared by every request.
+#[derive(Debug, Default)]
+pub struct Database {
+    todos: RefCell<Vec<Todo>>,
+    next_id: Cell<u32>,
+}
+
+impl Database {
+    pub fn todos(&self) -> Vec<Todo> {
+        self.todos.borrow().clone()
+    }
+
+    fn next_id(&self) -> u32 {
+        let id = self.next_id.get() + 1;
+        self.next_id.set(id);
+
+        id
+    }
+
+    fn apply(&self, change: Change) {
+        let mut todos = self.todos.borrow_mut();
+
+        match change {
+            Change::Insert(todo) => todos.push(todo),
+            Change::Update(todo) => {
+                if let Some(existing) = todos.iter_mut().find(|existing| existing.id == todo.id) {
+                    *existing = todo;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Change {
+    Insert(Todo),
+    Update(Todo),
+}
+
+/// The changes made to the database by a single request.
+///
+/// Changes are only written to the database when the transaction is
+/// committed. Anything left uncommitted when the request's scope ends is
+/// rolled back.
+pub struct Transaction {
+    db: Rc<Database>,
+    pending: RefCell<Vec<Change>>,
+    log: Rc<ScopeLog>,
+}
+
+impl Resolvable<Scoped> for Transaction {
+    type Dependency = (Bound<Database>, Rc<ScopeLog>);
+
+    fn resolve((db, log): Self::Dependency) -> Self {
+        Transaction {
+            db: db.into_inner(),
+            pending: RefCell::new(Vec::new()),
+            log,
+        }
+    }
+}
+
+impl Transaction {
+    /// The todos in the database, including changes made in this transaction.
+    pub fn todos(&self) -> Vec<Todo> {
+        let mut todos = self.db.todos();
+
+        for change in self.pending.borrow().iter() {
+            match *change {
+                Change::Insert(ref todo) => todos.push(todo.clone()),
+                Change::Update(ref todo) => {
+                    if let Some(existing) = todos.iter_mut().find(|existing| existing.id == todo.id) {
+                        *existing = todo.clone();
+                    }
+                }
+            }
+        }
+
+        todos
+    }
+
+    pub fn insert(&self, title: &str, now: u64) -> Todo {
+        let todo = Todo {
+            id: self.db.next_id(),
+            title: title.into(),
+            done: false,
+            updated_at: now,
+        };
+
+        self.pending.borrow_mut().push(Change::Insert(todo.clone()));
+
+        todo
+    }
+
+    pub fn update(&self, todo: Todo) {
+        self.pending.borrow_mut().push(Change::Update(todo));
+    }
+
+    /// Write the changes made in this transaction to the database.
+    pub fn commit(&self) {
+        let pending: Vec<_> = self.pending.borrow_mut().drain(..).collect();
+
+        if pending.is_empty() {
+            return;
+        }
+
+        self.log.log(format_args!("committed {} change(s)", pending.len()));
+
+        for change in pending {
+            self.db.apply(change);
+        }
+    }
+}
+
+impl Disposable for Transaction {
+    fn dispose(&mut self) {
+        let pending = self.pending.get_mut();
+
+        if !pending.is_empty() {
+            self.log.log(format_args!("rolled back {} uncommitted change(s)", pending.len()));
+
+            pending.clear();
+        }
+    }
+}
+
+/// Todos as seen by handlers.
+///
+/// This is bound in the registry, so handlers don't depend on the
+/// transaction directly.
+pub trait TodoRepository {
+    fn all(&self) -> Vec<Todo>;
+
+    fn add(&self, title: &str) -> Todo;
+
+    fn complete(&self, id: u32) -> Option<Todo>;
+}
+
+/// A repository that makes its changes in the request's transaction.
+pub struct TransactionalTodos {
+    tx: Rc<Transaction>,
+    clock: Rc<Clock>,
+}
+
+impl Resolvable<Scoped> for TransactionalTodos {
+    type Dependency = (Rc<Transaction>, Rc<Clock>);
+
+    fn resolve((tx, clock): Self::Dependency) -> Self {
+        TransactionalTodos { tx, clock }
+    }
+}
+
+impl TodoRepository for TransactionalTodos {
+    fn all(&self) -> Vec<Todo> {
+        self.tx.todos()
+    }
+
+    fn add(&self, title: &str) -> Todo {
+        self.tx.insert(title, self.clock.now())
+    }
+
+    fn complete(&self, id: u32) -> Option<Todo> {
+        let mut todo = self.all().into_iter().find(|todo| todo.id == id)?;
+
+        todo.done = true;
+        todo.updated_at = self.clock.now();
+
+        self.tx.update(todo.clone());
+
+        Some(todo)
+    }
+}
+
+/// The bindings for storing todos in a shared database.
+pub struct TodoModule {
+    db: Rc<Database>,
+}
+
+impl TodoModule {
+    pub fn new(db: Rc<Database>) -> Self {
+        TodoModule { db }
+    }
+}
+
+impl Module<Scoped> for TodoModule {
+    fn register(self, registry: &mut Registry<Scoped>) {
+        let db = self.db;
+
+        registry
+            .bind::<Database, _>(move |_| db.clone())
+            .bind_to::<dyn TodoRepository, TransactionalTodos, _, _>(|todos| Rc::new(todos))
+            .disposable::<Transaction>();
+    }
+}
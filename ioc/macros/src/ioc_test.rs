@@ -0,0 +1,92 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{Error, Expr, FnArg, ItemFn, Meta, Pat, Token};
+
+/// The arguments to `#[ioc_test]`.
+#[derive(Default)]
+struct Args {
+    modules: Vec<Expr>,
+    overrides: Vec<Expr>,
+}
+
+impl Args {
+    fn parse(args: TokenStream) -> syn::Result<Self> {
+        let mut parsed = Args::default();
+
+        let metas = Punctuated::<Meta, Token![,]>::parse_terminated.parse2(args)?;
+
+        for meta in metas {
+            let list = match meta {
+                Meta::List(list) => list,
+                meta => return Err(Error::new_spanned(meta, "expected `modules(..)` or `overrides(..)`")),
+            };
+
+            let exprs = list.parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated)?;
+
+            if list.path.is_ident("modules") {
+                parsed.modules.extend(exprs);
+            } else if list.path.is_ident("overrides") {
+                parsed.overrides.extend(exprs);
+            } else {
+                return Err(Error::new_spanned(list.path, "expected `modules` or `overrides`"));
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+pub fn expand(args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let args = Args::parse(args)?;
+    let mut item: ItemFn = syn::parse2(item)?;
+
+    let inputs = std::mem::take(&mut item.sig.inputs);
+
+    let mut resolve = Vec::new();
+    for input in inputs {
+        match input {
+            FnArg::Typed(arg) => {
+                let (pat, ty) = (&arg.pat, &arg.ty);
+
+                if let Pat::Ident(ref ident) = **pat {
+                    if ident.by_ref.is_some() {
+                        return Err(Error::new_spanned(pat, "test parameters can't be bound by reference"));
+                    }
+                }
+
+                resolve.push(quote! {
+                    let #pat: #ty = ::ioc::ResolvableFromContainer::resolve_from_container(&__ioc_scope);
+                });
+            }
+            FnArg::Receiver(receiver) => {
+                return Err(Error::new_spanned(receiver, "`#[ioc_test]` can't be used on methods"));
+            }
+        }
+    }
+
+    let modules = &args.modules;
+    let overrides = &args.overrides;
+
+    let attrs = &item.attrs;
+    let vis = &item.vis;
+    let sig = &item.sig;
+    let body = &item.block;
+
+    Ok(quote! {
+        #[test]
+        #(#attrs)*
+        #vis #sig {
+            let mut __ioc_registry = ::ioc::Registry::<::ioc::Scoped>::new();
+            #(__ioc_registry.add_module(#modules);)*
+
+            let __ioc_scope = ::ioc::Scoped::new().with_registry(::std::rc::Rc::new(__ioc_registry));
+            #(__ioc_scope.replace(#overrides);)*
+
+            #(#resolve)*
+
+            #body
+        }
+    })
+}
@@ -0,0 +1,102 @@
+//! Procedural macros for the `ioc` crate.
+//!
+//! These are re-exported from `ioc`, so depend on that instead of this
+//! crate directly.
+
+extern crate proc_macro;
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+mod ioc_test;
+mod resolvable;
+
+use proc_macro::TokenStream;
+use quote::quote_spanned;
+
+// `syn::Error::to_compile_error` expands to `::core::compile_error!`, which
+// isn't in scope for 2015 edition crates, so use the prelude's instead.
+fn compile_error(err: syn::Error) -> proc_macro2::TokenStream {
+    err.into_iter()
+        .map(|err| {
+            let message = err.to_string();
+            quote_spanned!(err.span()=> compile_error!(#message);)
+        })
+        .collect()
+}
+
+/// Run a test with its parameters resolved from a new scope.
+///
+/// The scope is built from any `modules(..)`, and seeded with any
+/// `overrides(..)` so they're used instead of constructing new instances.
+/// Parameters are resolved the same way as a `Resolvable::Dependency`, so
+/// shared dependencies are taken as `Rc<T>`:
+///
+/// ```
+/// # extern crate ioc;
+/// # use ioc::prelude::*;
+/// # use std::rc::Rc;
+/// # #[derive(Default)]
+/// # struct FakeClock;
+/// # impl<C> Resolvable<C> for FakeClock {
+/// #     type Dependency = ();
+/// #     fn resolve(_: ()) -> Self { FakeClock }
+/// # }
+/// # struct OrderService { clock: Rc<FakeClock> }
+/// # impl Resolvable<Scoped> for OrderService {
+/// #     type Dependency = Rc<FakeClock>;
+/// #     fn resolve(clock: Self::Dependency) -> Self { OrderService { clock } }
+/// # }
+/// # struct AppModule;
+/// # impl Module<Scoped> for AppModule {
+/// #     fn register(self, _: &mut Registry<Scoped>) {}
+/// # }
+/// #[ioc_test(modules(AppModule), overrides(FakeClock::default()))]
+/// fn order_is_timestamped(svc: Rc<OrderService>, clock: Rc<FakeClock>) {
+///     assert!(Rc::ptr_eq(&svc.clock, &clock));
+/// }
+/// # fn main() {}
+/// ```
+#[proc_macro_attribute]
+pub fn ioc_test(args: TokenStream, item: TokenStream) -> TokenStream {
+    ioc_test::expand(args.into(), item.into())
+        .unwrap_or_else(compile_error)
+        .into()
+}
+
+/// Derive `Resolvable` for a struct from the dependencies of its fields.
+///
+/// Each field is resolved as a root dependency, like `Rc<T>` or `Bound<T>`.
+/// Fields marked `#[inject(optional)]` are collaborators the struct can do
+/// without. They're `Option<Rc<T>>`s that start out `None`, and are set
+/// after the rest of the struct is constructed if there's a binding for `T`:
+///
+/// ```
+/// # extern crate ioc;
+/// # use ioc::prelude::*;
+/// # use std::rc::Rc;
+/// # trait AuditLog {}
+/// # struct Orders;
+/// # impl<C> Resolvable<C> for Orders {
+/// #     type Dependency = ();
+/// #     fn resolve(_: ()) -> Self { Orders }
+/// # }
+/// #[derive(Resolvable)]
+/// struct Checkout {
+///     orders: Rc<Orders>,
+///     #[inject(optional)]
+///     audit: Option<Rc<dyn AuditLog>>,
+/// }
+/// # fn main() {
+/// #     let checkout: Checkout = Scoped::new().resolve();
+/// #     assert!(checkout.audit.is_none());
+/// # }
+/// ```
+///
+/// A missing binding for an optional field isn't reported by `verify`.
+#[proc_macro_derive(Resolvable, attributes(inject))]
+pub fn derive_resolvable(item: TokenStream) -> TokenStream {
+    resolvable::expand(item.into())
+        .unwrap_or_else(compile_error)
+        .into()
+}
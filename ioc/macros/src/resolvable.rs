@@ -0,0 +1,132 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{parse_quote, Data, DeriveInput, Error, Fields, GenericArgument, Ident, Index, Member, PathArguments, Type};
+
+/// A field that's resolved along with the rest of its type.
+struct Required {
+    member: Member,
+    ty: Type,
+}
+
+/// A `#[inject(optional)]` field that's set after its type is constructed.
+struct Optional {
+    member: Member,
+    bound: Type,
+}
+
+/// Whether a field is marked `#[inject(optional)]`.
+fn is_optional(field: &syn::Field) -> syn::Result<bool> {
+    let mut optional = false;
+
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("inject")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("optional") {
+                optional = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `optional`"))
+            }
+        })?;
+    }
+
+    Ok(optional)
+}
+
+/// The `T` in an `Option<Rc<T>>`.
+fn optional_bound(ty: &Type) -> Option<&Type> {
+    fn only_arg<'a>(ty: &'a Type, ident: &str) -> Option<&'a Type> {
+        let segment = match ty {
+            Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+            _ => return None,
+        };
+
+        if segment.ident != ident {
+            return None;
+        }
+
+        match segment.arguments {
+            PathArguments::AngleBracketed(ref args) if args.args.len() == 1 => match args.args[0] {
+                GenericArgument::Type(ref ty) => Some(ty),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    only_arg(ty, "Option").and_then(|ty| only_arg(ty, "Rc"))
+}
+
+pub fn expand(item: TokenStream) -> syn::Result<TokenStream> {
+    let input: DeriveInput = syn::parse2(item)?;
+
+    let fields = match input.data {
+        Data::Struct(ref data) => &data.fields,
+        _ => return Err(Error::new_spanned(&input.ident, "`Resolvable` can only be derived for structs")),
+    };
+
+    let mut required = Vec::new();
+    let mut optional = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let member = match field.ident {
+            Some(ref ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(i)),
+        };
+
+        if is_optional(field)? {
+            let bound = optional_bound(&field.ty)
+                .ok_or_else(|| Error::new_spanned(&field.ty, "`#[inject(optional)]` fields need to be an `Option<Rc<T>>`"))?;
+
+            optional.push(Optional { member, bound: bound.clone() });
+        } else {
+            required.push(Required { member, ty: field.ty.clone() });
+        }
+    }
+
+    let ident = &input.ident;
+    let container = Ident::new("__IocContainer", Span::call_site());
+
+    let mut generics = input.generics.clone();
+    generics.params.push(parse_quote!(#container));
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let required_tys: Vec<_> = required.iter().map(|field| &field.ty).collect();
+    let optional_tys: Vec<_> = optional.iter().map(|field| &field.bound).collect();
+
+    let mut where_clause = where_clause.cloned().unwrap_or_else(|| parse_quote!(where));
+    where_clause.predicates.push(parse_quote!((#(#required_tys,)*): ::ioc::ResolvableFromContainer<#container>));
+    if optional.is_empty() {
+        where_clause.predicates.push(parse_quote!(#container: ::ioc::Container));
+    } else {
+        where_clause.predicates.push(parse_quote!(#container: ::ioc::RegisteredContainer + 'static));
+    }
+
+    let required_vars: Vec<_> = (0..required.len()).map(|i| format_ident!("__ioc_required_{}", i)).collect();
+    let optional_vars: Vec<_> = (0..optional.len()).map(|i| format_ident!("__ioc_optional_{}", i)).collect();
+
+    let required_members = required.iter().map(|field| &field.member);
+    let optional_members: Vec<_> = optional.iter().map(|field| &field.member).collect();
+
+    let construct = match fields {
+        Fields::Unit => quote!(#ident),
+        _ => quote! {
+            #ident {
+                #(#required_members: #required_vars,)*
+                #(#optional_members: ::std::option::Option::None,)*
+            }
+        },
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::ioc::Resolvable<#container> for #ident #ty_generics #where_clause {
+            type Dependency = ((#(#required_tys,)*), (#(::std::option::Option<::ioc::Bound<#optional_tys>>,)*));
+
+            #[allow(unused_mut)]
+            fn resolve(((#(#required_vars,)*), (#(#optional_vars,)*)): Self::Dependency) -> Self {
+                let mut resolved = #construct;
+                #(resolved.#optional_members = #optional_vars.map(::ioc::Bound::into_inner);)*
+                resolved
+            }
+        }
+    })
+}
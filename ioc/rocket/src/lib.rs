@@ -0,0 +1,170 @@
+//! A scope for each request served by Rocket
+//!
+//! `IocFairing` owns the application's singletons and gives each request a
+//! new `SyncScoped`. Handlers then take `Injected<T>` parameters that are
+//! resolved from the request's scope:
+//!
+//! ```
+//! # #[macro_use] extern crate rocket;
+//! # use std::sync::Arc;
+//! # use ioc::{ArcContainer, Resolvable, SyncScoped};
+//! # use ioc_rocket::{Injected, IocFairing};
+//! # struct OrderHandler;
+//! # impl OrderHandler {
+//! #     fn get(&self, id: u64) -> String { id.to_string() }
+//! # }
+//! # impl Resolvable<SyncScoped> for OrderHandler {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { OrderHandler }
+//! # }
+//! #[get("/orders/<id>")]
+//! fn order(id: u64, handler: Injected<OrderHandler>) -> String {
+//!     handler.get(id)
+//! }
+//!
+//! rocket::build()
+//!     .attach(IocFairing::new(ArcContainer::new()))
+//!     .mount("/", routes![order])
+//! # ;
+//! # fn main() {}
+//! ```
+//!
+//! Rocket keeps request-local state in a `Send + Sync` cache and needs
+//! handler futures to be `Send`, so the request's scope is a `SyncScoped`
+//! rather than a `Scoped`. Its shared dependencies are `Arc<T>`s. The
+//! application's `ArcContainer` is managed state, so handlers can take a
+//! `&State<ArcContainer>` to resolve singletons.
+//!
+//! `Injected<T>` only resolves from that `SyncScoped`, so it can't inject
+//! the ordinary `Rc`-based `Scoped` services other integrations hand out.
+//! `T` needs to be `Resolvable<SyncScoped>`, and the shared dependencies it
+//! asks for need to be `Arc<T>`s rather than `Rc<T>`s. A type that's only
+//! `Resolvable<Scoped>` isn't a request guard:
+//!
+//! ```compile_fail
+//! # #[macro_use] extern crate rocket;
+//! # use std::rc::Rc;
+//! # use ioc::{Resolvable, Scoped};
+//! # use ioc_rocket::Injected;
+//! # struct Connection;
+//! # impl<C> Resolvable<C> for Connection {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { Connection }
+//! # }
+//! # struct OrderHandler { connection: Rc<Connection> }
+//! impl Resolvable<Scoped> for OrderHandler {
+//!     type Dependency = Rc<Connection>;
+//!
+//!     fn resolve(connection: Self::Dependency) -> Self {
+//!         OrderHandler { connection }
+//!     }
+//! }
+//!
+//! // doesn't compile: `OrderHandler` can't be resolved from a `SyncScoped`
+//! #[get("/orders/<id>")]
+//! fn order(id: u64, handler: Injected<OrderHandler>) -> String {
+//!     id.to_string()
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! A dependency that can't be resolved fails the request with a 500,
+//! carrying the `ioc::Error` that says why.
+
+extern crate ioc;
+extern crate rocket;
+
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use ioc::{ArcContainer, Container, Error, Resolvable, ResolvableFromContainer, SyncScoped};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::{Build, Data, Rocket};
+
+/// The scope of the request being handled.
+///
+/// It's kept in the request's local cache, which is keyed by type.
+struct RequestScope(Arc<SyncScoped>);
+
+/// Get the scope for a request, creating it if it hasn't been already.
+fn request_scope<'r>(req: &'r Request<'_>) -> &'r Arc<SyncScoped> {
+    &req.local_cache(|| RequestScope(Arc::new(SyncScoped::new()))).0
+}
+
+/// A fairing that creates a scope for each request.
+pub struct IocFairing {
+    container: ArcContainer,
+}
+
+impl IocFairing {
+    /// Create a fairing that shares `container` with every request.
+    pub fn new(container: ArcContainer) -> Self {
+        IocFairing { container }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for IocFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "ioc",
+            kind: Kind::Ignite | Kind::Request,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> rocket::fairing::Result {
+        Ok(rocket.manage(self.container.clone()))
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data<'_>) {
+        request_scope(req);
+    }
+}
+
+/// A request guard that resolves a `T` from the request's scope.
+///
+/// The scope is a `SyncScoped`, so `T`'s shared dependencies are `Arc<T>`s.
+/// Types that are only resolvable from a `Scoped` can't be injected.
+pub struct Injected<T> {
+    value: T,
+}
+
+impl<T> Injected<T> {
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Injected<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> fmt::Debug for Injected<T>
+    where T: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Injected").field(&self.value).finish()
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, T, D> FromRequest<'r> for Injected<T>
+    where T: Resolvable<SyncScoped, Dependency = D> + Send,
+          D: ResolvableFromContainer<SyncScoped>
+{
+    type Error = Error;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Error> {
+        match request_scope(req).try_resolve::<D, T>() {
+            Ok(value) => Outcome::Success(Injected { value }),
+            Err(e) => Outcome::Error((Status::InternalServerError, e)),
+        }
+    }
+}
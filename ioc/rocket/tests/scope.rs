@@ -0,0 +1,106 @@
+#[macro_use]
+extern crate rocket;
+extern crate ioc;
+extern crate ioc_rocket;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use ioc::{ArcContainer, Resolvable, SyncScoped};
+use ioc_rocket::{Injected, IocFairing};
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+
+struct Connection;
+
+impl<C> Resolvable<C> for Connection {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Connection
+    }
+}
+
+struct OrderHandler {
+    connection: Arc<Connection>,
+}
+
+impl Resolvable<SyncScoped> for OrderHandler {
+    type Dependency = Arc<Connection>;
+
+    fn resolve(connection: Self::Dependency) -> Self {
+        OrderHandler { connection }
+    }
+}
+
+#[get("/shared")]
+fn shared(a: Injected<OrderHandler>, b: Injected<OrderHandler>) -> String {
+    Arc::ptr_eq(&a.connection, &b.connection).to_string()
+}
+
+static SESSIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// A shared dependency that counts how many times it's been created.
+struct Session;
+
+impl<C> Resolvable<C> for Session {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        SESSIONS.fetch_add(1, Ordering::SeqCst);
+
+        Session
+    }
+}
+
+struct SessionHandler {
+    _session: Arc<Session>,
+}
+
+impl Resolvable<SyncScoped> for SessionHandler {
+    type Dependency = Arc<Session>;
+
+    fn resolve(session: Self::Dependency) -> Self {
+        SessionHandler { _session: session }
+    }
+}
+
+#[get("/session")]
+fn session(_a: Injected<SessionHandler>, _b: Injected<SessionHandler>) {}
+
+fn client() -> Client {
+    let rocket = rocket::build()
+        .attach(IocFairing::new(ArcContainer::new()))
+        .mount("/", routes![shared, session]);
+
+    Client::tracked(rocket).unwrap()
+}
+
+#[test]
+fn dependencies_are_shared_within_a_request() {
+    let client = client();
+
+    let res = client.get("/shared").dispatch();
+
+    assert_eq!(Status::Ok, res.status());
+    assert_eq!("true", res.into_string().unwrap());
+}
+
+#[test]
+fn each_request_has_its_own_scope() {
+    let client = client();
+
+    assert_eq!(Status::Ok, client.get("/session").dispatch().status());
+    assert_eq!(Status::Ok, client.get("/session").dispatch().status());
+
+    // each request shares one session between its guards
+    assert_eq!(2, SESSIONS.load(Ordering::SeqCst));
+}
+
+#[test]
+fn container_is_managed_state() {
+    let rocket = rocket::build().attach(IocFairing::new(ArcContainer::new()));
+    let client = Client::tracked(rocket).unwrap();
+
+    assert!(client.rocket().state::<ArcContainer>().is_some());
+}
@@ -0,0 +1,154 @@
+//! Command-line arguments
+//!
+//! Command-line apps can resolve their parsed arguments like any other
+//! dependency. The arguments are parsed into a type that implements
+//! `FromArgs`, and a handler depends on an `Args<T>`:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use std::rc::Rc;
+//! # struct Db;
+//! # impl<C> Resolvable<C> for Db {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { Db }
+//! # }
+//! # struct ImportArgs { path: String }
+//! # struct ImportCommand { path: String, db: Rc<Db> }
+//! impl FromArgs for ImportArgs {
+//!     fn from_args(args: &[String]) -> Result<Self, String> {
+//!         let path = args.first().ok_or("missing the file to import")?;
+//!
+//!         Ok(ImportArgs { path: path.into() })
+//!     }
+//! }
+//!
+//! impl Resolvable<Scoped> for ImportCommand {
+//!     type Dependency = (Args<ImportArgs>, Rc<Db>);
+//!
+//!     fn resolve((args, db): Self::Dependency) -> Self {
+//!         ImportCommand { path: args.into_inner().path, db }
+//!     }
+//! }
+//! #
+//! # let scope = Scoped::new();
+//! # scope.override_with::<dyn ioc::ArgsProvider>(Rc::new(ioc::MemoryArgs::new(vec!["orders.csv"])));
+//! # let command: ImportCommand = scope.resolve();
+//! # assert_eq!("orders.csv", command.path);
+//! ```
+//!
+//! Types parsed with a library like clap can implement `FromArgs` by
+//! putting the program name back in front of the arguments:
+//!
+//! ```
+//! # mod clap {
+//! #     pub trait Parser: Sized {
+//! #         fn try_parse_from<I, T>(args: I) -> Result<Self, String>
+//! #             where I: IntoIterator<Item = T>, T: Into<std::ffi::OsString> + Clone;
+//! #     }
+//! # }
+//! # use ioc::prelude::*;
+//! # use clap::Parser;
+//! # use std::iter;
+//! # struct Cli;
+//! # impl Parser for Cli {
+//! #     fn try_parse_from<I, T>(_: I) -> Result<Self, String>
+//! #         where I: IntoIterator<Item = T>, T: Into<std::ffi::OsString> + Clone { Ok(Cli) }
+//! # }
+//! impl FromArgs for Cli {
+//!     fn from_args(args: &[String]) -> Result<Self, String> {
+//!         Cli::try_parse_from(iter::once("app").chain(args.iter().map(String::as_str))).map_err(|e| e.to_string())
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Arguments that can't be parsed panic. `try_resolve` returns these as
+//! `Error::InvalidArgs`.
+//!
+//! Arguments are read from the process, unless there's a binding for
+//! `dyn ArgsProvider`. Tests can bind a `MemoryArgs` instead.
+
+use std::env;
+use std::fmt;
+use std::ops::Deref;
+
+/// A type that's parsed from command-line arguments.
+pub trait FromArgs: Sized {
+    /// Parse the arguments, not including the program name.
+    fn from_args(args: &[String]) -> Result<Self, String>;
+}
+
+/// The arguments themselves, without any parsing.
+impl FromArgs for Vec<String> {
+    fn from_args(args: &[String]) -> Result<Self, String> {
+        Ok(args.to_vec())
+    }
+}
+
+/// A source of command-line arguments.
+pub trait ArgsProvider {
+    /// The arguments, not including the program name.
+    fn args(&self) -> Vec<String>;
+}
+
+/// The arguments the current process was started with.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ProcessArgs;
+
+impl ArgsProvider for ProcessArgs {
+    fn args(&self) -> Vec<String> {
+        env::args().skip(1).collect()
+    }
+}
+
+/// Command-line arguments held in memory, for tests.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryArgs {
+    args: Vec<String>,
+}
+
+impl MemoryArgs {
+    pub fn new<I, S>(args: I) -> Self
+        where I: IntoIterator<Item = S>,
+              S: Into<String>
+    {
+        MemoryArgs { args: args.into_iter().map(Into::into).collect() }
+    }
+}
+
+impl ArgsProvider for MemoryArgs {
+    fn args(&self) -> Vec<String> {
+        self.args.clone()
+    }
+}
+
+/// Command-line arguments parsed as a `T`.
+pub struct Args<T> {
+    value: T,
+}
+
+impl<T> Args<T> {
+    pub fn new(value: T) -> Self {
+        Args { value }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Args<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> fmt::Debug for Args<T>
+    where T: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Args").field(&self.value).finish()
+    }
+}
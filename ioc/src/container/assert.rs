@@ -0,0 +1,50 @@
+//! Compile-time resolvability checks
+//!
+//! A type that can't be resolved from a container is only an error where
+//! something tries to resolve it, which might be a long way from the change
+//! that broke it. `assert_resolvable!` makes the same check anywhere, like a
+//! test module, so broken wiring fails to compile in one obvious place.
+//!
+//! Only the static graph is checked. Runtime bindings like `Bound<T>` can't
+//! be known until the registry is built, so check those with `verify`.
+
+use super::*;
+
+/// Fail to compile unless `R` and all of its dependencies can be resolved
+/// from `C`.
+///
+/// This does nothing at runtime. It's used by `assert_resolvable!`, which
+/// makes the check in a constant.
+pub const fn assert_resolvable<C, R, D>()
+    where C: Container,
+          R: Resolvable<C, Dependency = D>,
+          D: ResolvableFromContainer<C>
+{
+}
+
+/// Fail to compile if a type can't be resolved from a container.
+///
+/// The check is made for the given container, since the dependencies of a
+/// type can depend on the container it's resolved from:
+///
+/// ```
+/// # #[macro_use] extern crate ioc;
+/// # use ioc::prelude::*;
+/// # use std::rc::Rc;
+/// # resolvable! {
+/// #     struct XYZ;
+/// #     struct App { xyz: Rc<XYZ> }
+/// # }
+/// assert_resolvable!(XYZ, BasicContainer);
+/// assert_resolvable!(App, Scoped);
+/// # fn main() {}
+/// ```
+///
+/// If the type can't be resolved then the error points at the
+/// `assert_resolvable!` call and names the dependency that's missing.
+#[macro_export]
+macro_rules! assert_resolvable {
+    ($t:ty, $container:ty) => {
+        const _: () = $crate::assert_resolvable::<$container, $t, _>();
+    };
+}
@@ -0,0 +1,360 @@
+//! Asynchronous queries and commands
+//!
+//! Queries and commands backed by a database or another service shouldn't
+//! block while they wait on it. An `AsyncQuery` or `AsyncCommand` returns a
+//! future instead, and any closure that returns a future is one:
+//!
+//! ```edition2018
+//! # use ioc::prelude::*;
+//! # use ioc::factories::AsyncQuery;
+//! # #[derive(Clone)]
+//! # struct Db;
+//! # impl Db {
+//! #     async fn product(&self, id: u32) -> Result<Product, String> { Ok(Product { id }) }
+//! # }
+//! # impl<C> Resolvable<C> for Db {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { Db }
+//! # }
+//! # struct GetProduct { id: u32 }
+//! # struct Product { id: u32 }
+//! # let mut registry = Registry::<Scoped>::new();
+//! fn get_product(db: Db) -> impl AsyncQuery<GetProduct, Result<Product, String>> {
+//!     move |action: GetProduct| {
+//!         let db = db.clone();
+//!
+//!         async move { db.product(action.id).await }
+//!     }
+//! }
+//!
+//! registry.bind_async_query(|db: Transient<Db>| get_product(db.into_inner()));
+//! ```
+//!
+//! Sync queries and commands can be used where async ones are expected with
+//! `into_async`, and async ones can be used where sync ones are expected with
+//! `blocking`.
+//!
+//! Resolving an `AsyncQueryOf` or `AsyncCommandOf` is still synchronous.
+//! Only executing them is async, so their futures can't borrow from the
+//! scope they were resolved from.
+
+use std::any::type_name;
+use std::fmt;
+use std::future::{self, Future};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use super::*;
+use super::factories::{build, verify_builder, verify_dependencies, Builder, Command, Query};
+use super::registry::VerifyBinding;
+
+/// A future that's boxed so queries and commands with different futures
+/// can be resolved as the same type.
+pub type BoxFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+
+/// A query that returns a future of a `Res` for each `Req`.
+///
+/// Any `Fn(Req) -> impl Future<Output = Res>` is an async query.
+pub trait AsyncQuery<Req, Res> {
+    type Future: Future<Output = Res>;
+
+    fn query(&self, request: Req) -> Self::Future;
+}
+
+impl<F, Req, Fut> AsyncQuery<Req, Fut::Output> for F
+    where F: Fn(Req) -> Fut,
+          Fut: Future
+{
+    type Future = Fut;
+
+    fn query(&self, request: Req) -> Fut {
+        self(request)
+    }
+}
+
+/// A command that's executed once with a `Req`, completing in the future.
+///
+/// Any `FnOnce(Req) -> impl Future<Output = Result<(), E>>` is an async
+/// command.
+pub trait AsyncCommand<Req> {
+    type Error;
+    type Future: Future<Output = Result<(), Self::Error>>;
+
+    fn execute(self, request: Req) -> Self::Future;
+}
+
+impl<F, Req, Fut, E> AsyncCommand<Req> for F
+    where F: FnOnce(Req) -> Fut,
+          Fut: Future<Output = Result<(), E>>
+{
+    type Error = E;
+    type Future = Fut;
+
+    fn execute(self, request: Req) -> Fut {
+        self(request)
+    }
+}
+
+/// An async query bound in a container's registry.
+pub struct AsyncQueryOf<Req, Res> {
+    query: Rc<dyn Fn(Req) -> BoxFuture<Res>>,
+}
+
+impl<Req, Res> AsyncQueryOf<Req, Res> {
+    pub fn new<Q>(query: Q) -> Self
+        where Q: AsyncQuery<Req, Res> + 'static,
+              Q::Future: 'static
+    {
+        AsyncQueryOf { query: Rc::new(move |request| Box::pin(query.query(request)) as BoxFuture<Res>) }
+    }
+}
+
+impl<Req, Res> Clone for AsyncQueryOf<Req, Res> {
+    fn clone(&self) -> Self {
+        AsyncQueryOf { query: self.query.clone() }
+    }
+}
+
+impl<Req, Res> AsyncQuery<Req, Res> for AsyncQueryOf<Req, Res> {
+    type Future = BoxFuture<Res>;
+
+    fn query(&self, request: Req) -> BoxFuture<Res> {
+        (self.query)(request)
+    }
+}
+
+impl<Req, Res> fmt::Debug for AsyncQueryOf<Req, Res> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(type_name::<Self>())
+    }
+}
+
+/// An async command bound in a container's registry.
+pub struct AsyncCommandOf<Req, E> {
+    command: Box<dyn FnOnce(Req) -> BoxFuture<Result<(), E>>>,
+}
+
+impl<Req, E> AsyncCommandOf<Req, E> {
+    pub fn new<K>(command: K) -> Self
+        where K: AsyncCommand<Req, Error = E> + 'static,
+              K::Future: 'static
+    {
+        AsyncCommandOf { command: Box::new(move |request| Box::pin(command.execute(request)) as BoxFuture<Result<(), E>>) }
+    }
+}
+
+impl<Req, E> AsyncCommand<Req> for AsyncCommandOf<Req, E> {
+    type Error = E;
+    type Future = BoxFuture<Result<(), E>>;
+
+    fn execute(self, request: Req) -> BoxFuture<Result<(), E>> {
+        (self.command)(request)
+    }
+}
+
+impl<Req, E> fmt::Debug for AsyncCommandOf<Req, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(type_name::<Self>())
+    }
+}
+
+impl<C, Req, Res> Resolvable<C> for AsyncQueryOf<Req, Res>
+    where C: RegisteredContainer + 'static,
+          Req: 'static,
+          Res: 'static
+{
+    type Dependency = Self;
+
+    fn resolve(query: Self::Dependency) -> Self {
+        query
+    }
+}
+
+impl<C, Req, E> Resolvable<C> for AsyncCommandOf<Req, E>
+    where C: RegisteredContainer + 'static,
+          Req: 'static,
+          E: 'static
+{
+    type Dependency = Self;
+
+    fn resolve(command: Self::Dependency) -> Self {
+        command
+    }
+}
+
+// Async queries and commands are built by the function bound in the
+// container's registry, the same as sync ones.
+impl<C, Req, Res> ResolvableFromContainer<C> for AsyncQueryOf<Req, Res>
+    where C: RegisteredContainer + 'static,
+          Req: 'static,
+          Res: 'static
+{
+    fn resolve_from_container(container: &C) -> Self {
+        build::<C, Self, dyn AsyncQuery<Req, Res, Future = BoxFuture<Res>>>(container)
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        verify_builder::<C, Self, dyn AsyncQuery<Req, Res, Future = BoxFuture<Res>>>(container, verification)
+    }
+}
+
+impl<C, Req, E> ResolvableFromContainer<C> for AsyncCommandOf<Req, E>
+    where C: RegisteredContainer + 'static,
+          Req: 'static,
+          E: 'static
+{
+    fn resolve_from_container(container: &C) -> Self {
+        build::<C, Self, dyn AsyncCommand<Req, Error = E, Future = BoxFuture<Result<(), E>>>>(container)
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        verify_builder::<C, Self, dyn AsyncCommand<Req, Error = E, Future = BoxFuture<Result<(), E>>>>(container, verification)
+    }
+}
+
+impl<C> Registry<C>
+    where C: Container + 'static
+{
+    /// Build each `AsyncQueryOf<Req, Res>` from its dependencies with `f`,
+    /// replacing any previous binding.
+    pub fn bind_async_query<Req, Res, D, Q, F>(&mut self, f: F) -> &mut Self
+        where Req: 'static,
+              Res: 'static,
+              D: ResolvableFromContainer<C>,
+              Q: AsyncQuery<Req, Res> + 'static,
+              Q::Future: 'static,
+              F: Fn(D) -> Q + 'static
+    {
+        let builder = Rc::new(Builder { build: Box::new(move |container: &C| AsyncQueryOf::new(f(D::resolve_from_container(container)))) });
+        let verify: VerifyBinding<C> = verify_dependencies::<C, F, D>;
+
+        self.bind_implementation::<Builder<C, AsyncQueryOf<Req, Res>>>(Box::new(move |_| builder.clone()), Implementation::of::<Q>(Lifetime::Deferred), Some(verify))
+    }
+
+    /// Build each `AsyncCommandOf<Req, E>` from its dependencies with `f`,
+    /// replacing any previous binding.
+    pub fn bind_async_command<Req, E, D, K, F>(&mut self, f: F) -> &mut Self
+        where Req: 'static,
+              E: 'static,
+              D: ResolvableFromContainer<C>,
+              K: AsyncCommand<Req, Error = E> + 'static,
+              K::Future: 'static,
+              F: Fn(D) -> K + 'static
+    {
+        let builder = Rc::new(Builder { build: Box::new(move |container: &C| AsyncCommandOf::new(f(D::resolve_from_container(container)))) });
+        let verify: VerifyBinding<C> = verify_dependencies::<C, F, D>;
+
+        self.bind_implementation::<Builder<C, AsyncCommandOf<Req, E>>>(Box::new(move |_| builder.clone()), Implementation::of::<K>(Lifetime::Deferred), Some(verify))
+    }
+}
+
+/// Use a sync query or command where an async one is expected.
+///
+/// The sync one runs when the async one is called, and its future is
+/// already complete.
+pub fn into_async<T>(inner: T) -> IntoAsync<T> {
+    IntoAsync { inner }
+}
+
+/// Use an async query or command where a sync one is expected.
+///
+/// Each future is polled on the current thread until it completes,
+/// parking the thread while it waits. Futures that need a particular
+/// runtime, like ones that do IO on tokio, should be awaited on that
+/// runtime instead.
+pub fn blocking<T>(inner: T) -> Blocking<T> {
+    Blocking { inner }
+}
+
+/// A sync query or command used as an async one.
+#[derive(Debug, Clone)]
+pub struct IntoAsync<T> {
+    inner: T,
+}
+
+impl<T> IntoAsync<T> {
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T, Req, Res> AsyncQuery<Req, Res> for IntoAsync<T>
+    where T: Query<Req, Res>
+{
+    type Future = future::Ready<Res>;
+
+    fn query(&self, request: Req) -> future::Ready<Res> {
+        future::ready(self.inner.query(request))
+    }
+}
+
+impl<T, Req> AsyncCommand<Req> for IntoAsync<T>
+    where T: Command<Req>
+{
+    type Error = T::Error;
+    type Future = future::Ready<Result<(), T::Error>>;
+
+    fn execute(self, request: Req) -> Self::Future {
+        future::ready(self.inner.execute(request))
+    }
+}
+
+/// An async query or command used as a sync one.
+#[derive(Debug, Clone)]
+pub struct Blocking<T> {
+    inner: T,
+}
+
+impl<T> Blocking<T> {
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T, Req, Res> Query<Req, Res> for Blocking<T>
+    where T: AsyncQuery<Req, Res>
+{
+    fn query(&self, request: Req) -> Res {
+        block_on(self.inner.query(request))
+    }
+}
+
+impl<T, Req> Command<Req> for Blocking<T>
+    where T: AsyncCommand<Req>
+{
+    type Error = T::Error;
+
+    fn execute(self, request: Req) -> Result<(), T::Error> {
+        block_on(self.inner.execute(request))
+    }
+}
+
+/// Wakes a thread that's parked waiting on a future.
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Poll a future on the current thread until it completes.
+fn block_on<F>(future: F) -> F::Output
+    where F: Future
+{
+    let mut future = Box::pin(future);
+
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            // a wake that's already happened leaves the thread unparked
+            Poll::Pending => thread::park(),
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! Branded scoped dependencies
+//!
+//! `Scoped` hands out shared dependencies as `Rc<T>`, which can be kept
+//! after the scope ends or handed to another scope with
+//! `Scoped::override_with`. Usually that's fine, but sometimes a shared
+//! value needs to stay with the scope it came from, like a connection that
+//! belongs to a request.
+//!
+//! `Scoped::brand` uses a scope within a closure as a `BrandedScope<'scope>`,
+//! which hands out shared dependencies as `Shared<'scope, T>`. The `'scope`
+//! lifetime is introduced for the duration of the closure and is invariant,
+//! so shared values can't escape the closure or be mixed with values from
+//! another branded scope, even one nested inside it:
+//!
+//! ```
+//! use ioc::prelude::*;
+//!
+//! struct Connection;
+//!
+//! impl<C> Resolvable<C> for Connection {
+//!     type Dependency = ();
+//!
+//!     fn resolve(_: ()) -> Self {
+//!         Connection
+//!     }
+//! }
+//!
+//! struct Handler<'scope> {
+//!     connection: Shared<'scope, Connection>,
+//! }
+//!
+//! impl<'scope> Resolvable<BrandedScope<'scope>> for Handler<'scope> {
+//!     type Dependency = Shared<'scope, Connection>;
+//!
+//!     fn resolve(connection: Self::Dependency) -> Self {
+//!         Handler { connection }
+//!     }
+//! }
+//!
+//! Scoped::new().brand(|scope| {
+//!     let handler = scope.resolve::<_, Handler>();
+//!     let connection = Shared::<Connection>::resolve_from_container(&scope);
+//!
+//!     assert!(Shared::ptr_eq(&handler.connection, &connection));
+//! });
+//! ```
+//!
+//! A `Shared<'scope, T>` can't be turned back into an `Rc<T>`, so the only
+//! way to get one is to resolve it from its scope. Like `B<'scope, T>`, the
+//! `T` itself needs to be `'static`, so a type that depends on a
+//! `Shared<'scope, T>` can be resolved as a `Transient<T>`, but not shared.
+
+use super::*;
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+/// A scoped container that hands out shared dependencies branded with its
+/// scope.
+pub struct BrandedScope<'scope, S = TypeMap>
+    where S: ScopeStorage
+{
+    scope: &'scope Scoped<S>,
+    // `'scope` is invariant so it can't be shortened or extended
+    _scope: PhantomData<fn(&'scope ()) -> &'scope ()>,
+}
+
+impl<'scope, S> Clone for BrandedScope<'scope, S>
+    where S: ScopeStorage
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'scope, S> Copy for BrandedScope<'scope, S> where S: ScopeStorage {}
+
+impl<'scope, S> Container for BrandedScope<'scope, S> where S: ScopeStorage {}
+
+/// A scoped container that can resolve branded shared dependencies.
+pub trait BrandedContainer<'scope>
+    where Self: Container
+{
+    fn get_or_add_shared<T, D>(&self) -> Shared<'scope, T>
+        where T: Resolvable<Self, Dependency = D> + 'static,
+              D: ResolvableFromContainer<Self>;
+}
+
+impl<'scope, S> BrandedContainer<'scope> for BrandedScope<'scope, S>
+    where S: ScopeStorage
+{
+    fn get_or_add_shared<T, D>(&self) -> Shared<'scope, T>
+        where T: Resolvable<Self, Dependency = D> + 'static,
+              D: ResolvableFromContainer<Self>
+    {
+        let value = self.scope.get_or_insert_with(|| T::resolve(D::resolve_from_container(self)));
+
+        Shared::new(value)
+    }
+}
+
+impl<S> Scoped<S>
+    where S: ScopeStorage
+{
+    /// Use the scope within a closure, branding the shared dependencies it
+    /// resolves with the closure's lifetime.
+    ///
+    /// The scope is consumed, so everything it shares is dropped when the
+    /// closure returns. Shared dependencies can't outlive the closure:
+    ///
+    /// ```compile_fail
+    /// use ioc::prelude::*;
+    ///
+    /// struct X;
+    ///
+    /// impl<C> Resolvable<C> for X {
+    ///     type Dependency = ();
+    ///
+    ///     fn resolve(_: ()) -> Self {
+    ///         X
+    ///     }
+    /// }
+    ///
+    /// let x = Scoped::new().brand(|scope| Shared::<X>::resolve_from_container(&scope));
+    /// ```
+    pub fn brand<F, T>(self, f: F) -> T
+        where F: for<'scope> FnOnce(BrandedScope<'scope, S>) -> T
+    {
+        f(BrandedScope {
+            scope: &self,
+            _scope: PhantomData,
+        })
+    }
+}
+
+/// A branded shared dependency.
+///
+/// Each `Shared<'scope, T>` resolved from the same scope points to the same
+/// `T`. `'scope` brands the value with the scope it came from. Like the
+/// scope's own lifetime it's invariant, so values from different scopes
+/// can't be mixed, even when one scope is nested inside the other:
+///
+/// ```compile_fail
+/// use ioc::prelude::*;
+///
+/// struct X;
+///
+/// impl<C> Resolvable<C> for X {
+///     type Dependency = ();
+///
+///     fn resolve(_: ()) -> Self {
+///         X
+///     }
+/// }
+///
+/// fn same_scope<'scope>(_: Shared<'scope, X>, _: Shared<'scope, X>) {}
+///
+/// Scoped::new().brand(|outer| {
+///     let a = Shared::<X>::resolve_from_container(&outer);
+///
+///     Scoped::new().brand(|inner| {
+///         let b = Shared::<X>::resolve_from_container(&inner);
+///
+///         same_scope(a, b);
+///     });
+/// });
+/// ```
+pub struct Shared<'scope, T> {
+    value: Rc<T>,
+    // `'scope` is invariant so it can't be shortened to another scope's
+    _scope: PhantomData<fn(&'scope ()) -> &'scope ()>,
+}
+
+impl<'scope, T> Shared<'scope, T> {
+    pub(crate) fn new(value: Rc<T>) -> Self {
+        Shared {
+            value,
+            _scope: PhantomData,
+        }
+    }
+
+    /// Whether two values are the same instance.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Rc::ptr_eq(&this.value, &other.value)
+    }
+}
+
+impl<'scope, T> Clone for Shared<'scope, T> {
+    fn clone(&self) -> Self {
+        Shared::new(self.value.clone())
+    }
+}
+
+impl<'scope, T> Deref for Shared<'scope, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<'scope, T> AsRef<T> for Shared<'scope, T> {
+    fn as_ref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<'scope, T> fmt::Debug for Shared<'scope, T>
+    where T: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
@@ -1,127 +1,843 @@
 use super::*;
+use super::dispose::Dispose;
+use super::error::Resolving;
+use super::intercept::Hooks;
+use super::stats::Holdings;
+use super::timings::{timed, Phase};
+use super::trace::Traced;
 
-use std::any::{Any, TypeId};
-use std::cell::RefCell;
-use std::collections::HashMap as StdHashMap;
-use std::hash::BuildHasherDefault;
-use fnv::FnvHasher;
+use std::any::{type_name, Any, TypeId};
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::ptr;
+use std::rc::Weak;
+use std::time::{Duration, Instant};
+#[cfg(debug_assertions)]
+use std::thread::{self, ThreadId};
 
-type HashMap<K, V> = StdHashMap<K, V, BuildHasherDefault<FnvHasher>>;
-type DropHandle = Box<Fn(*mut Any) -> ()>;
+/// The details of a shared dependency with more outstanding references
+/// than its scope allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrongCountExceeded {
+    /// The name of the shared dependency type.
+    pub type_name: &'static str,
+    /// The number of outstanding references, not counting the scope's own.
+    pub strong_count: usize,
+    /// The threshold configured on the scope.
+    pub threshold: usize,
+}
+
+/// What to do when a value is inserted into a scope that already has one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplacePolicy {
+    /// Replace the existing value, disposing of it if nothing else
+    /// shares it.
+    #[default]
+    Replace,
+    /// Keep the existing value and drop the new one.
+    KeepExisting,
+    /// Keep the existing value and return an error.
+    Error,
+}
+
+/// A value couldn't be inserted because the scope already has one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlreadyInserted {
+    /// The name of the dependency type.
+    pub type_name: &'static str,
+}
+
+impl fmt::Display for AlreadyInserted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the scope already has a value for `{}`", self.type_name)
+    }
+}
+
+impl Error for AlreadyInserted {}
+
+struct StrongCountThreshold {
+    threshold: usize,
+    exceeded: Box<dyn Fn(&StrongCountExceeded)>,
+}
+
+impl StrongCountThreshold {
+    fn check<T>(&self, rc: &Rc<T>) {
+        // the scope holds a reference of its own, which doesn't count
+        let strong_count = Rc::strong_count(rc) - 1;
+
+        if strong_count > self.threshold {
+            (self.exceeded)(&StrongCountExceeded {
+                type_name: type_name::<T>(),
+                strong_count,
+                threshold: self.threshold,
+            });
+        }
+    }
+}
+
+struct ScopedState<S>
+    where S: ScopeStorage
+{
+    map: RefCell<S>,
+    /// The last shared value found in `map`, which is checked first.
+    ///
+    /// Hot paths tend to resolve the same type over and over, and comparing
+    /// one `TypeId` is cheaper than any map lookup. It's cleared whenever a
+    /// value that's already in `map` could be replaced or removed.
+    last: Cell<Option<(TypeId, *const ())>>,
+    /// The innermost shared value that's being constructed.
+    constructing: Cell<*const ConstructingFrame>,
+    registry: Option<Rc<Registry<Scoped<S>>>>,
+    /// Values resolved in place of a dependency, each an `Rc<T>` keyed by `T`.
+    overrides: RefCell<HashMap<TypeId, Box<dyn Any>, BuildTypeIdHasher>>,
+    /// Context set on the scope, each an `Rc<T>` keyed by `T`.
+    contexts: RefCell<HashMap<TypeId, Rc<dyn Any>, BuildTypeIdHasher>>,
+    observed: RefCell<ObservedGraph>,
+    holdings: RefCell<Holdings>,
+    metrics: Option<Rc<Metrics>>,
+    correlation_id: OnceCell<CorrelationId>,
+    correlation_ids: Option<Rc<dyn CorrelationIdProvider>>,
+    strong_count_threshold: Option<StrongCountThreshold>,
+    hooks: Hooks,
+    replace_policy: ReplacePolicy,
+    #[cfg(debug_assertions)]
+    thread: ThreadId,
+}
+
+/// A shared value that's being constructed.
+///
+/// Frames live on the stack of `get_or_add` and link to the frame of the
+/// value that's being constructed around them, so a `Weak<T>` resolved
+/// along the way can find its `T` without allocating.
+struct ConstructingFrame {
+    key: TypeId,
+    type_name: &'static str,
+    /// A `Weak<T>` for the value, where `T` is the type with `key`.
+    weak: *const (),
+    /// The time spent constructing shared values nested inside this one.
+    nested: Cell<Duration>,
+    parent: *const ConstructingFrame,
+}
+
+/// Keeps a frame at the top of the constructing list until it's dropped.
+struct Constructing<'a> {
+    constructing: &'a Cell<*const ConstructingFrame>,
+    parent: *const ConstructingFrame,
+}
+
+impl<'a> Constructing<'a> {
+    fn push(constructing: &'a Cell<*const ConstructingFrame>, frame: &'a ConstructingFrame) -> Self {
+        constructing.set(frame);
+
+        Constructing {
+            constructing,
+            parent: frame.parent,
+        }
+    }
+}
+
+impl Drop for Constructing<'_> {
+    fn drop(&mut self) {
+        self.constructing.set(self.parent);
+    }
+}
+
+impl<S> Drop for ScopedState<S>
+    where S: ScopeStorage
+{
+    fn drop(&mut self) {
+        // the weak references held for stats would stop values being disposed
+        self.holdings.get_mut().clear();
+        self.map.get_mut().drain();
+    }
+}
 
-struct TypeMap {
-    refs: HashMap<TypeId, (*mut Any, DropHandle)>,
+/// A basic implementation of a scoped container.
+///
+/// The state of the scope lives behind an `Rc` so dependencies like
+/// `Lazy<T>` can capture a weak handle to it and resolve later.
+/// Shared dependencies are kept in a `ScopeStorage`, which is a `TypeMap`
+/// by default.
+pub struct Scoped<S = TypeMap>
+    where S: ScopeStorage
+{
+    state: Rc<ScopedState<S>>,
+}
+
+/// A weak handle to a `Scoped` container.
+pub struct ScopedHandle<S = TypeMap>
+    where S: ScopeStorage
+{
+    state: Weak<ScopedState<S>>,
+}
+
+impl<S> Clone for ScopedHandle<S>
+    where S: ScopeStorage
+{
+    fn clone(&self) -> Self {
+        ScopedHandle { state: self.state.clone() }
+    }
+}
+
+impl<S> Default for Scoped<S>
+    where S: ScopeStorage + Default
+{
+    fn default() -> Self {
+        Self::with_storage(S::default())
+    }
 }
 
-impl TypeMap {
+impl Scoped {
     pub fn new() -> Self {
-        TypeMap { refs: HashMap::default() }
+        Self::with_storage(TypeMap::new())
     }
+}
 
-    fn key<T>() -> TypeId
-        where T: 'static
+impl<S> Scoped<S>
+    where S: ScopeStorage
+{
+    /// Create a scope that keeps its shared dependencies in `storage`.
+    pub fn with_storage(storage: S) -> Self {
+        Scoped {
+            state: Rc::new(ScopedState {
+                map: RefCell::new(storage),
+                last: Cell::new(None),
+                constructing: Cell::new(ptr::null()),
+                registry: None,
+                overrides: RefCell::default(),
+                contexts: RefCell::default(),
+                observed: RefCell::default(),
+                holdings: RefCell::default(),
+                metrics: None,
+                correlation_id: OnceCell::new(),
+                correlation_ids: None,
+                strong_count_threshold: None,
+                hooks: Hooks::default(),
+                replace_policy: ReplacePolicy::default(),
+                #[cfg(debug_assertions)]
+                thread: thread::current().id(),
+            }),
+        }
+    }
+
+    fn state_mut(&mut self) -> &mut ScopedState<S> {
+        Rc::get_mut(&mut self.state).expect("a scope can't be configured after it's been captured")
+    }
+
+    /// Use a registry of runtime bindings for this scope.
+    ///
+    /// The registry is shared, so it can be cheaply given to each new scope.
+    pub fn with_registry(mut self, registry: Rc<Registry<Scoped<S>>>) -> Self {
+        self.state_mut().registry = Some(registry);
+
+        self
+    }
+
+    /// Use a specific correlation id for this scope.
+    ///
+    /// This is useful when the work the scope is created for already has
+    /// an id, like an incoming request with a correlation header.
+    pub fn with_correlation_id(self, id: CorrelationId) -> Self {
+        let _ = self.state.correlation_id.set(id);
+
+        self
+    }
+
+    /// Generate this scope's correlation id using the given provider.
+    ///
+    /// The provider is shared, so it can be cheaply given to each new scope.
+    /// By default ids are generated by `RandomCorrelationIds`.
+    pub fn with_correlation_ids(mut self, provider: Rc<dyn CorrelationIdProvider>) -> Self {
+        self.state_mut().correlation_ids = Some(provider);
+
+        self
+    }
+
+    /// Record metrics for the shared dependencies this scope resolves.
+    ///
+    /// The collector is shared, so it can be cheaply given to each new scope.
+    pub fn with_metrics(mut self, metrics: Rc<Metrics>) -> Self {
+        self.state_mut().metrics = Some(metrics);
+
+        self
+    }
+
+    /// Call `exceeded` whenever a shared dependency is handed out while
+    /// more than `threshold` references to it are outstanding.
+    ///
+    /// This is a diagnostic for catching shared handles that are cloned
+    /// into unbounded collections. The callback runs on every resolution
+    /// above the threshold, not just the first one.
+    pub fn with_strong_count_threshold<F>(mut self, threshold: usize, exceeded: F) -> Self
+        where F: Fn(&StrongCountExceeded) + 'static
     {
-        TypeId::of::<T>()
+        self.state_mut().strong_count_threshold = Some(StrongCountThreshold {
+            threshold,
+            exceeded: Box::new(exceeded),
+        });
+
+        self
     }
 
+    /// Call `hook` before each shared dependency is resolved from the scope.
+    ///
+    /// The hook runs whether or not the scope already has a value, and can
+    /// refuse to let it be resolved by returning a `Veto`. See `Resolution`
+    /// for what the hook is given.
+    pub fn on_resolving<F>(mut self, hook: F) -> Self
+        where F: Fn(&Resolution) -> Result<(), Veto> + 'static
+    {
+        self.state_mut().hooks.add_resolving(Box::new(hook));
+
+        self
+    }
+
+    /// Call `hook` after each shared dependency is resolved from the scope.
+    pub fn on_resolved<F>(mut self, hook: F) -> Self
+        where F: Fn(&Resolution) + 'static
+    {
+        self.state_mut().hooks.add_resolved(Box::new(hook));
+
+        self
+    }
+
+    /// Decide what `insert` does when the scope already has a value.
+    ///
+    /// By default the existing value is replaced.
+    pub fn with_replace_policy(mut self, policy: ReplacePolicy) -> Self {
+        self.state_mut().replace_policy = policy;
+
+        self
+    }
+
+    /// Check that the scope is being used on the thread that created it.
+    ///
+    /// `Scoped` isn't `Send`, but this gives a clear message if unsafe code
+    /// moves one across threads anyway. It's only checked in debug builds.
+    #[inline]
+    fn assert_affinity(&self) {
+        #[cfg(debug_assertions)]
+        {
+            let current = thread::current();
+
+            assert!(current.id() == self.state.thread,
+                    "a scope created on thread {:?} was used on thread {:?} ({})",
+                    self.state.thread,
+                    current.id(),
+                    current.name().unwrap_or("<unnamed>"));
+        }
+    }
+
+    #[inline]
     fn exists<T>(&self) -> bool
         where T: 'static
     {
-        self.refs.get(&Self::key::<T>()).is_some()
+        self.state.map.borrow().contains::<T>()
     }
 
-    /// Get a shared reference to a dependency.
-    /// 
-    /// This will increment the reference count.
-    /// It will panic if the dependency doesn't already exist so
-    /// call `exists` first, and `insert` if it's not found.
-    unsafe fn get<T>(&self) -> Rc<T>
+    #[inline]
+    fn get<T>(&self) -> Option<Rc<T>>
         where T: 'static
     {
-        let &(ptr, _) = self.refs.get(&Self::key::<T>()).unwrap();
+        let key = TypeId::of::<T>();
+
+        if let Some((last, ptr)) = self.state.last.get() {
+            if last == key {
+                // the map keeps its own reference, so the value is still alive
+                return Some(unsafe {
+                    Rc::increment_strong_count(ptr as *const T);
+
+                    Rc::from_raw(ptr as *const T)
+                });
+            }
+        }
 
-        let rc = Rc::from_raw(ptr as *mut T);
-        let rc_clone = rc.clone();
+        let t = self.state.map.borrow().get::<T>();
 
-        // forget this Rc again (don't decrement count)
-        Rc::into_raw(rc);
+        if let Some(ref t) = t {
+            self.state.last.set(Some((key, Rc::as_ptr(t) as *const ())));
+        }
 
-        rc_clone
+        t
     }
 
-    /// Insert a dependency into the map.
-    fn insert<T>(&mut self, t: T)
+    /// Put a value in the storage, handing back any value it replaced.
+    ///
+    /// The replaced value is returned rather than dropped, so it isn't
+    /// dropped while the storage is borrowed.
+    #[inline]
+    fn store<T>(&self, t: Rc<T>, dispose: Option<Dispose<T>>) -> Option<Rc<T>>
         where T: 'static
     {
-        let ptr = Rc::into_raw(Rc::new(t)) as *mut T;
+        // `last` may point at the value being replaced, which could be freed
+        self.state.last.set(None);
 
-        // a function to drop this Rc
-        let drop = Box::new(|ptr| unsafe {
-            Rc::from_raw(ptr as *mut T);
-        });
+        self.state.map.borrow_mut().insert(t, dispose)
+    }
 
-        // add the dependency, dropping any previous value
-        match self.refs.insert(Self::key::<T>(), (ptr, drop)) {
-            Some((ptr, drop)) => drop(ptr),
-            _ => ()
+    /// Insert an already constructed dependency into the scope.
+    ///
+    /// Any `Rc<T>` resolved from the scope afterwards will point to this
+    /// value instead of constructing a new one. If the scope already holds
+    /// a `T` then what happens depends on its `ReplacePolicy`.
+    pub fn insert<T>(&self, t: T) -> Result<(), AlreadyInserted>
+        where T: 'static
+    {
+        self.assert_affinity();
+
+        if self.exists::<T>() {
+            match self.state.replace_policy {
+                ReplacePolicy::Replace => (),
+                ReplacePolicy::KeepExisting => return Ok(()),
+                ReplacePolicy::Error => return Err(AlreadyInserted { type_name: type_name::<T>() }),
+            }
         }
+
+        let t = Rc::new(t);
+        self.held(&t, Duration::default());
+
+        if let Some(replaced) = self.store(t, self.disposer()) {
+            self.discard(replaced);
+        }
+
+        Ok(())
     }
-}
 
-impl Drop for TypeMap {
-    fn drop(&mut self) {
-        for (_, (ptr, drop)) in self.refs.drain() {
-            drop(ptr);
+    /// Replace the dependency in the scope, returning the previous one.
+    ///
+    /// This ignores the scope's `ReplacePolicy`, so it can be used to
+    /// deliberately swap an instance part way through a scope. Anything
+    /// that already resolved the previous value keeps it.
+    pub fn replace<T>(&self, t: T) -> Option<Rc<T>>
+        where T: 'static
+    {
+        self.assert_affinity();
+
+        let t = Rc::new(t);
+        self.held(&t, Duration::default());
+
+        self.store(t, self.disposer())
+    }
+
+    /// Remove the dependency from the scope, returning it.
+    ///
+    /// Anything that already resolved the value keeps it, and the next
+    /// `Rc<T>` resolved from the scope will construct a new one. The
+    /// removed value isn't disposed by the scope.
+    pub fn remove<T>(&self) -> Option<Rc<T>>
+        where T: 'static
+    {
+        self.assert_affinity();
+
+        self.state.last.set(None);
+        let t = self.state.map.borrow_mut().remove::<T>();
+
+        if t.is_some() {
+            self.state.holdings.borrow_mut().removed::<T>();
         }
+
+        t
     }
-}
 
-/// A basic implementation of a scoped container.
-pub struct Scoped {
-    map: RefCell<TypeMap>,
-}
+    /// Drop the dependency cached in the scope, so it's rebuilt the next
+    /// time it's resolved.
+    ///
+    /// This is for long-lived scopes that need to refresh a service, like
+    /// after its configuration changes. The value is disposed if it's
+    /// `Disposable` and nothing else still shares it. Returns whether there
+    /// was a value to drop.
+    pub fn invalidate<T>(&self) -> bool
+        where T: 'static
+    {
+        match self.remove::<T>() {
+            Some(t) => {
+                self.discard(t);
 
-impl Scoped {
-    pub fn new() -> Self {
-        Scoped { map: RefCell::new(TypeMap::new()) }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop every dependency cached in the scope, so they're all rebuilt
+    /// the next time they're resolved.
+    ///
+    /// Values are dropped and disposed the same way as when the scope
+    /// ends, so they can't resolve anything from the scope while they're
+    /// being dropped.
+    pub fn clear(&self) {
+        self.assert_affinity();
+
+        self.state.holdings.borrow_mut().clear();
+        self.state.last.set(None);
+        self.state.map.borrow_mut().drain();
+    }
+
+    /// Move the dependency out of the scope.
+    ///
+    /// This is for values the scope builds up as a result, like a unit of
+    /// work or a response. The value is only taken if the scope holds the
+    /// only reference to it. Otherwise it's left in the scope and this
+    /// returns `None`.
+    pub fn take<T>(&self) -> Option<T>
+        where T: 'static
+    {
+        self.assert_affinity();
+
+        // the scope's own reference and the one just taken from it
+        let unique = self.get::<T>().is_some_and(|t| Rc::strong_count(&t) == 2);
+
+        if !unique {
+            return None;
+        }
+
+        self.remove::<T>().map(|t| Rc::try_unwrap(t).ok().expect("the value was uniquely owned by the scope"))
+    }
+
+    /// Resolve `value` in place of any `Rc<T>` or `Bound<T>` dependency.
+    ///
+    /// This is for replacing a dependency with a fake in tests, while
+    /// everything else resolves normally. `T` can be a trait object:
+    ///
+    /// ```
+    /// # use ioc::prelude::*;
+    /// # use std::rc::Rc;
+    /// # trait Clock { fn now(&self) -> u64; }
+    /// # struct FakeClock;
+    /// # impl Clock for FakeClock { fn now(&self) -> u64 { 42 } }
+    /// # let scope = Scoped::new();
+    /// scope.override_with::<dyn Clock>(Rc::new(FakeClock));
+    /// # assert_eq!(42, Bound::<dyn Clock>::resolve_from_container(&scope).now());
+    /// ```
+    ///
+    /// Overrides are checked before constructing a shared dependency, so
+    /// they need to be added before `T` is first resolved from the scope.
+    pub fn override_with<T>(&self, value: Rc<T>) -> &Self
+        where T: ?Sized + 'static
+    {
+        self.assert_affinity();
+
+        self.state.overrides.borrow_mut().insert(TypeId::of::<T>(), Box::new(value));
+
+        self
+    }
+
+    /// Set context of type `T` on the scope, returning any it replaced.
+    ///
+    /// Context is resolved as a `Context<T>`. It's for data that comes with
+    /// the work the scope is for, like the id of a request, and isn't cached
+    /// alongside the scope's shared dependencies.
+    pub fn set_context<T>(&self, value: T) -> Option<Rc<T>>
+        where T: 'static
+    {
+        self.assert_affinity();
+
+        self.state.contexts
+            .borrow_mut()
+            .insert(TypeId::of::<T>(), Rc::new(value))
+            .map(|value| value.downcast().expect("contexts are keyed by their type"))
+    }
+
+    /// Set context of type `T` on the scope.
+    pub fn with_context<T>(self, value: T) -> Self
+        where T: 'static
+    {
+        self.set_context(value);
+
+        self
     }
 
+    /// Take a snapshot of the shared dependencies the scope holds.
+    ///
+    /// Each entry has the number of outstanding references to the value
+    /// and how long it took to construct.
+    pub fn stats(&self) -> ScopeStats {
+        self.state.holdings.borrow().stats()
+    }
+
+    /// Get the cached `T`, or construct one with `f` and cache it.
+    ///
+    /// This skips the hooks and metrics `get_or_add` runs, for containers
+    /// that resolve their own dependencies but cache them in the scope.
+    pub(super) fn get_or_insert_with<T, F>(&self, f: F) -> Rc<T>
+        where T: 'static,
+              F: FnOnce() -> T
+    {
+        self.assert_affinity();
+
+        if let Some(t) = self.get::<T>() {
+            return t;
+        }
+
+        let started = Instant::now();
+        let t = Rc::new(f());
+
+        self.held(&t, started.elapsed());
+
+        if let Some(replaced) = self.store(t.clone(), self.disposer()) {
+            self.discard(replaced);
+        }
+
+        t
+    }
+
+    /// Record a value that's being added to the scope.
     #[inline]
-    fn exists<T>(&self) -> bool
+    fn held<T>(&self, t: &Rc<T>, construction: Duration)
         where T: 'static
     {
-        self.map.borrow().exists::<T>()
+        self.state.holdings.borrow_mut().added(type_name::<T>(), Rc::downgrade(t), construction);
+    }
+
+    /// Dispose of a value the scope no longer holds, then drop it.
+    ///
+    /// The value is only disposed if nothing else still shares it.
+    fn discard<T>(&self, t: Rc<T>)
+        where T: 'static
+    {
+        if let (Some(dispose), Some(mut t)) = (self.disposer::<T>(), Rc::into_inner(t)) {
+            dispose(&mut t);
+        }
     }
 
     #[inline]
-    unsafe fn get<T>(&self) -> Rc<T>
+    fn disposer<T>(&self) -> Option<Dispose<T>>
         where T: 'static
     {
-        self.map.borrow().get::<T>()
+        self.state.registry.as_ref().and_then(|registry| registry.disposer())
     }
 
     #[inline]
-    fn add<T>(&self, t: T)
+    fn initializer<T>(&self) -> Option<Initializer<Self, T>>
         where T: 'static
     {
-        self.map.borrow_mut().insert::<T>(t);
+        self.state.registry.as_ref().and_then(|registry| registry.initializer())
+    }
+}
+
+impl<S> Container for Scoped<S> where S: ScopeStorage {}
+
+impl<S> fmt::Debug for Scoped<S>
+    where S: ScopeStorage
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Scoped").field("entries", &self.stats().entries()).finish()
     }
 }
 
-impl Container for Scoped {}
+impl<S> CaptureContainer for Scoped<S>
+    where S: ScopeStorage
+{
+    type Captured = ScopedHandle<S>;
+
+    fn capture(&self) -> ScopedHandle<S> {
+        ScopedHandle { state: Rc::downgrade(&self.state) }
+    }
+
+    fn with_captured<F, T>(captured: &ScopedHandle<S>, f: F) -> T
+        where F: FnOnce(&Self) -> T
+    {
+        let state = captured.state.upgrade().expect("the captured scope has already ended");
+
+        f(&Scoped { state })
+    }
+}
 
-impl ScopedContainer for Scoped {
+impl<S> CorrelatedContainer for Scoped<S>
+    where S: ScopeStorage
+{
+    fn correlation_id(&self) -> CorrelationId {
+        self.assert_affinity();
+
+        let state = &self.state;
+
+        state.correlation_id
+            .get_or_init(|| match state.correlation_ids {
+                Some(ref provider) => provider.next_id(),
+                None => RandomCorrelationIds.next_id(),
+            })
+            .clone()
+    }
+}
+
+impl<S> ContextContainer for Scoped<S>
+    where S: ScopeStorage
+{
+    fn context<T>(&self) -> Option<Rc<T>>
+        where T: 'static
+    {
+        self.assert_affinity();
+
+        self.state.contexts
+            .borrow()
+            .get(&TypeId::of::<T>())
+            .map(|value| value.clone().downcast().expect("contexts are keyed by their type"))
+    }
+}
+
+impl<S> RegisteredContainer for Scoped<S>
+    where S: ScopeStorage
+{
+    fn registry(&self) -> Option<&Registry<Self>> {
+        self.state.registry.as_deref()
+    }
+
+    #[inline]
+    fn overridden<T>(&self) -> Option<Rc<T>>
+        where T: ?Sized + 'static
+    {
+        let overrides = self.state.overrides.borrow();
+
+        if overrides.is_empty() {
+            return None;
+        }
+
+        overrides
+            .get(&TypeId::of::<T>())
+            .map(|value| value.downcast_ref::<Rc<T>>().expect("overrides are keyed by their type").clone())
+    }
+}
+
+impl<S> ScopedContainer for Scoped<S>
+    where S: ScopeStorage
+{
     fn get_or_add<T, D>(&self) -> Rc<T>
         where T: Resolvable<Self, Dependency = D> + 'static,
               D: ResolvableFromContainer<Self>
     {
-        if !self.exists::<T>() {
-            let d = D::resolve_from_container(self);
-            let t = T::resolve(d);
+        self.assert_affinity();
+
+        let traced = Traced::get_or_add(type_name::<T>());
 
-            self.add(t);
+        // frames are only in the list while they're on the stack below this call
+        let dependent = unsafe { self.state.constructing.get().as_ref() }.map(|frame| frame.type_name);
+
+        let cached = timed(Phase::Lookup, || self.get::<T>());
+        let resolution = self.state.hooks.resolving::<T>(type_name::<T>(), dependent, cached.is_some());
+
+        if let Some(ref metrics) = self.state.metrics {
+            metrics.resolved::<T>(type_name::<T>(), cached.is_some());
         }
 
-        unsafe { self.get() }
+        let t = match cached {
+            Some(t) => {
+                traced.cached(true);
+
+                t
+            }
+            None => {
+                traced.cached(false);
+
+                match self.overridden::<T>() {
+                    // the override is cached so the lookup finds it next time
+                    Some(t) => {
+                        self.held(&t, Duration::default());
+                        self.store(t.clone(), None);
+                        self.state.observed.borrow_mut().constructed(TypeId::of::<T>(), type_name::<T>());
+
+                        t
+                    }
+                    None => {
+                        let _resolving = Resolving::enter::<_, T>(self);
+
+                        let mut construction = Duration::default();
+                        let t = Rc::new_cyclic(|weak| {
+                            // a `Weak<T>` resolved along the way refers to this value
+                            let frame = ConstructingFrame {
+                                key: TypeId::of::<T>(),
+                                type_name: type_name::<T>(),
+                                weak: weak as *const Weak<T> as *const (),
+                                nested: Cell::new(Duration::default()),
+                                parent: self.state.constructing.get(),
+                            };
+                            let _constructing = Constructing::push(&self.state.constructing, &frame);
+                            let started = Instant::now();
+
+                            let d = D::resolve_from_container(self);
+                            let mut t = timed(Phase::Construct, || T::resolve(d));
+
+                            // the frame is still pushed, so the initializer can
+                            // hand out a `Weak<T>` to the value it's initializing
+                            if let Some(initialize) = self.initializer::<T>() {
+                                timed(Phase::Construct, || initialize(&mut t, self));
+                            }
+
+                            // nested shared values have their own entries, so
+                            // their time is only counted once
+                            let elapsed = started.elapsed();
+                            construction = elapsed.saturating_sub(frame.nested.get());
+
+                            if let Some(parent) = unsafe { frame.parent.as_ref() } {
+                                parent.nested.set(parent.nested.get() + elapsed);
+                            }
+
+                            t
+                        });
+                        let scope_t = timed(Phase::Clone, || t.clone());
+
+                        self.held(&t, construction);
+
+                        if let Some(ref metrics) = self.state.metrics {
+                            metrics.constructed::<T>(type_name::<T>(), construction);
+                        }
+
+                        // a binding may have inserted a `T` while this one was
+                        // being constructed, which this one replaces
+                        if let Some(replaced) = timed(Phase::Insert, || self.store(scope_t, self.disposer())) {
+                            self.discard(replaced);
+                        }
+                        self.state.observed.borrow_mut().constructed(TypeId::of::<T>(), type_name::<T>());
+
+                        t
+                    }
+                }
+            }
+        };
+
+        // only record the edge once `T` has been resolved, so a panic
+        // doesn't leave half of it behind
+        if let Some(dependent) = dependent {
+            self.state.observed.borrow_mut().depended_on(dependent, type_name::<T>());
+        }
+
+        if let Some(resolution) = resolution {
+            self.state.hooks.resolved(&resolution);
+        }
+
+        if let Some(ref threshold) = self.state.strong_count_threshold {
+            threshold.check(&t);
+        }
+
+        t
+    }
+
+    fn get_weak<T>(&self) -> Weak<T>
+        where T: 'static
+    {
+        self.assert_affinity();
+
+        if let Some(t) = self.get::<T>() {
+            return Rc::downgrade(&t);
+        }
+
+        let key = TypeId::of::<T>();
+        let mut frame = self.state.constructing.get();
+
+        // frames are only in the list while they're on the stack below this call
+        while let Some(current) = unsafe { frame.as_ref() } {
+            if current.key == key {
+                return unsafe { &*(current.weak as *const Weak<T>) }.clone();
+            }
+
+            frame = current.parent;
+        }
+
+        Weak::new()
+    }
+
+    fn observed_graph(&self) -> ObservedGraph {
+        self.state.observed.borrow().clone()
     }
 }
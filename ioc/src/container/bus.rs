@@ -0,0 +1,438 @@
+//! Command bus
+//!
+//! Wiring each command up by hand in `main` doesn't scale past a few of
+//! them. A `CommandBus` maps each command struct to the handler type that
+//! executes it. Handlers are resolved from the container each command is
+//! dispatched on, so they share that scope's dependencies:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use ioc::{CommandBus, Dispatch, DispatchError, Next, Retry, Veto};
+//! # use std::rc::Rc;
+//! # #[derive(Clone)]
+//! # struct SetProductTitle { id: u32, title: String }
+//! # let mut registry = Registry::new();
+//! # registry.bind_command(|_: ()| |_: SetProductTitle| Ok::<(), String>(()));
+//! # let scope = Scoped::new().with_registry(Rc::new(registry));
+//! let mut bus = CommandBus::new();
+//! bus.handle::<SetProductTitle, CommandOf<SetProductTitle, String>, _>()
+//!     .validate(|command: &SetProductTitle| match command.title.is_empty() {
+//!         true => Err(Veto::new("titles can't be empty")),
+//!         false => Ok(()),
+//!     })
+//!     .with(|dispatch: &Dispatch, next: Next| {
+//!         let result = next.run();
+//!         println!("{}: {:?}", dispatch.command, result);
+//!
+//!         result
+//!     })
+//!     .with(Retry::new(3));
+//!
+//! let result: Result<(), DispatchError<String>> = bus.dispatch(&scope, SetProductTitle { id: 1, title: "A new title".into() });
+//! # assert!(result.is_ok());
+//! ```
+//!
+//! Middleware runs in the order it was added, each one around the rest.
+//! Behaviors are middleware that's resolved from the container, so they
+//! can depend on the scope's services, like its transaction.
+//! Commands are cloned each time they're handled, so middleware can
+//! handle one more than once.
+
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::marker::PhantomData;
+
+use super::*;
+use super::factories::Command;
+
+type Handle<C, Req, E> = Box<dyn Fn(&C, Req) -> Result<(), E>>;
+
+/// The handler for a `Req` whose errors are `E`.
+struct Handler<C, Req, E> {
+    type_name: &'static str,
+    handle: Handle<C, Req, E>,
+}
+
+/// A command that's being dispatched.
+#[derive(Clone, Copy)]
+pub struct Dispatch<'a> {
+    pub type_id: TypeId,
+    /// The name of the command type.
+    pub command: &'static str,
+    /// The name of the handler type.
+    pub handler: &'static str,
+    request: &'a dyn Any,
+}
+
+impl<'a> Dispatch<'a> {
+    /// The command, if it's a `T`.
+    pub fn request<T>(&self) -> Option<&'a T>
+        where T: 'static
+    {
+        self.request.downcast_ref()
+    }
+}
+
+impl<'a> fmt::Debug for Dispatch<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Dispatch")
+            .field("command", &self.command)
+            .field("handler", &self.handler)
+            .finish()
+    }
+}
+
+type ResolveBehavior<C> = Box<dyn Fn(&C) -> Box<dyn Behavior>>;
+
+/// A layer of the pipeline around each command.
+enum Layer<C> {
+    Middleware(Box<dyn Middleware>),
+    /// A behavior that's resolved from the container each command is
+    /// dispatched on.
+    Behavior(ResolveBehavior<C>),
+}
+
+/// A layer of the pipeline around one command.
+#[derive(Clone, Copy)]
+enum Link<'a> {
+    Middleware(&'a dyn Middleware),
+    Behavior(&'a dyn Fn() -> Box<dyn Behavior>),
+}
+
+/// The rest of the pipeline, then the handler.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    pipeline: &'a [Link<'a>],
+    dispatch: &'a Dispatch<'a>,
+    handle: &'a dyn Fn() -> Result<(), Failure>,
+}
+
+impl<'a> Next<'a> {
+    /// Handle the command.
+    ///
+    /// Each run resolves new behaviors and a new handler from the
+    /// container, and gives the handler a clone of the command.
+    pub fn run(&self) -> Result<(), Failure> {
+        match self.pipeline.split_first() {
+            Some((&Link::Middleware(middleware), rest)) => middleware.dispatch(self.dispatch, Next { pipeline: rest, ..*self }),
+            Some((&Link::Behavior(resolve), rest)) => resolve().handle(self.dispatch, Next { pipeline: rest, ..*self }),
+            None => (self.handle)(),
+        }
+    }
+}
+
+/// A hook that runs around each command.
+///
+/// Middleware can inspect the command, reject it, or run the rest of the
+/// chain as many times as it likes. Any `Fn(&Dispatch, Next) -> Result<(), Failure>`
+/// is middleware, but closures need their arguments annotated.
+pub trait Middleware {
+    fn dispatch(&self, dispatch: &Dispatch, next: Next) -> Result<(), Failure>;
+}
+
+impl<F> Middleware for F
+    where F: Fn(&Dispatch, Next) -> Result<(), Failure>
+{
+    fn dispatch(&self, dispatch: &Dispatch, next: Next) -> Result<(), Failure> {
+        self(dispatch, next)
+    }
+}
+
+/// A step in the pipeline around each command that's resolved from the
+/// container the command is dispatched on.
+///
+/// Behaviors are the same as middleware, except they're `Resolvable`, so
+/// they can depend on the scope's services. A behavior that completes a
+/// unit of work can depend on the same `Rc<UnitOfWork>` as the handler:
+///
+/// ```
+/// # use ioc::prelude::*;
+/// # use ioc::{Behavior, Dispatch, Failure, Next};
+/// # use std::cell::Cell;
+/// # use std::rc::Rc;
+/// # #[derive(Default)]
+/// # struct UnitOfWork { completed: Cell<bool> }
+/// # impl UnitOfWork {
+/// #     fn complete(&self) { self.completed.set(true) }
+/// # }
+/// # struct CompleteUnitOfWork { unit_of_work: Rc<UnitOfWork> }
+/// impl Behavior for CompleteUnitOfWork {
+///     fn handle(&self, _: &Dispatch, next: Next) -> Result<(), Failure> {
+///         next.run()?;
+///         self.unit_of_work.complete();
+///
+///         Ok(())
+///     }
+/// }
+/// ```
+///
+/// A new behavior is resolved each time the pipeline reaches it, so one
+/// that's behind a command's rejection is never resolved at all.
+pub trait Behavior {
+    fn handle(&self, dispatch: &Dispatch, next: Next) -> Result<(), Failure>;
+}
+
+/// Middleware that handles a command again when its handler fails.
+///
+/// A new handler is resolved for each attempt, but it's resolved from the
+/// same container, so shared dependencies like a transaction are the same
+/// ones the failed attempt used. Rejected commands aren't retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Retry {
+    attempts: usize,
+}
+
+impl Retry {
+    /// Make up to `attempts` attempts at handling each command, including
+    /// the first.
+    pub fn new(attempts: usize) -> Self {
+        Retry { attempts: attempts.max(1) }
+    }
+}
+
+impl Middleware for Retry {
+    fn dispatch(&self, _: &Dispatch, next: Next) -> Result<(), Failure> {
+        let mut attempt = 1;
+
+        loop {
+            match next.run() {
+                Err(ref failure) if !failure.is_rejected() && attempt < self.attempts => attempt += 1,
+                result => return result,
+            }
+        }
+    }
+}
+
+enum FailureKind {
+    Rejected(Veto),
+    Failed {
+        error: Box<dyn Any>,
+        debug: String,
+    },
+}
+
+/// A command that wasn't handled successfully, as middleware sees it.
+///
+/// The handler's error is only available from `DispatchError` once the
+/// command has finished dispatching.
+pub struct Failure {
+    kind: FailureKind,
+}
+
+impl Failure {
+    /// Refuse to handle a command.
+    pub fn rejected(veto: Veto) -> Self {
+        Failure { kind: FailureKind::Rejected(veto) }
+    }
+
+    fn failed<E>(error: E) -> Self
+        where E: fmt::Debug + 'static
+    {
+        Failure {
+            kind: FailureKind::Failed {
+                debug: format!("{:?}", error),
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Whether middleware refused to handle the command.
+    pub fn is_rejected(&self) -> bool {
+        match self.kind {
+            FailureKind::Rejected(_) => true,
+            FailureKind::Failed { .. } => false,
+        }
+    }
+
+    fn into_dispatch_error<E>(self, command: &'static str) -> DispatchError<E>
+        where E: 'static
+    {
+        match self.kind {
+            FailureKind::Rejected(veto) => DispatchError::Rejected { command, veto },
+            FailureKind::Failed { error, .. } => DispatchError::Failed {
+                command,
+                // only `Failure::failed` boxes errors, and only with the handler's error
+                error: *error.downcast().expect("the failure is the handler's error"),
+            },
+        }
+    }
+}
+
+impl fmt::Debug for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            FailureKind::Rejected(ref veto) => f.debug_tuple("Rejected").field(veto).finish(),
+            FailureKind::Failed { ref debug, .. } => f.debug_tuple("Failed").field(&format_args!("{}", debug)).finish(),
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            FailureKind::Rejected(ref veto) => write!(f, "rejected: {}", veto),
+            FailureKind::Failed { ref debug, .. } => write!(f, "failed: {}", debug),
+        }
+    }
+}
+
+/// A command couldn't be dispatched.
+#[derive(Debug)]
+pub enum DispatchError<E> {
+    /// There's no handler for the command.
+    Unhandled { command: &'static str },
+    /// Middleware refused to handle the command.
+    Rejected { command: &'static str, veto: Veto },
+    /// The handler returned an error.
+    Failed { command: &'static str, error: E },
+}
+
+impl<E> fmt::Display for DispatchError<E>
+    where E: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DispatchError::Unhandled { command } => write!(f, "there's no handler for `{}`", command),
+            DispatchError::Rejected { command, ref veto } => write!(f, "`{}` was rejected: {}", command, veto),
+            DispatchError::Failed { command, ref error } => write!(f, "`{}` failed: {:?}", command, error),
+        }
+    }
+}
+
+impl<E> error::Error for DispatchError<E> where E: fmt::Debug {}
+
+/// Dispatches commands to handlers resolved from a container.
+pub struct CommandBus<C> {
+    handlers: HashMap<TypeId, (&'static str, Box<dyn Any>)>,
+    pipeline: Vec<Layer<C>>,
+    _container: PhantomData<fn(&C)>,
+}
+
+impl<C> Default for CommandBus<C>
+    where C: Container + 'static
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> CommandBus<C>
+    where C: Container + 'static
+{
+    pub fn new() -> Self {
+        CommandBus {
+            handlers: HashMap::new(),
+            pipeline: Vec::new(),
+            _container: PhantomData,
+        }
+    }
+
+    /// Handle each `Req` with an `H` resolved from the container it's
+    /// dispatched on, replacing any previous handler.
+    pub fn handle<Req, H, D>(&mut self) -> &mut Self
+        where Req: Clone + 'static,
+              H: Resolvable<C, Dependency = D> + Command<Req>,
+              H::Error: fmt::Debug + 'static,
+              D: ResolvableFromContainer<C>
+    {
+        let handler: Handler<C, Req, H::Error> = Handler {
+            type_name: type_name::<H>(),
+            handle: Box::new(|container: &C, request| container.resolve::<D, H>().execute(request)),
+        };
+
+        self.handlers.insert(TypeId::of::<Req>(), (type_name::<H::Error>(), Box::new(handler)));
+        self
+    }
+
+    /// Whether there's a handler for `Req`.
+    pub fn handles<Req>(&self) -> bool
+        where Req: 'static
+    {
+        self.handlers.contains_key(&TypeId::of::<Req>())
+    }
+
+    /// Run `middleware` around each command, inside any middleware or
+    /// behaviors that were added before it.
+    pub fn with<M>(&mut self, middleware: M) -> &mut Self
+        where M: Middleware + 'static
+    {
+        self.pipeline.push(Layer::Middleware(Box::new(middleware)));
+        self
+    }
+
+    /// Run a `B` resolved from the container around each command, inside
+    /// any middleware or behaviors that were added before it.
+    pub fn behavior<B, D>(&mut self) -> &mut Self
+        where B: Resolvable<C, Dependency = D> + Behavior + 'static,
+              D: ResolvableFromContainer<C>
+    {
+        self.pipeline.push(Layer::Behavior(Box::new(|container: &C| Box::new(container.resolve::<D, B>()))));
+        self
+    }
+
+    /// Reject any `Req` that `validate` refuses.
+    pub fn validate<Req, F>(&mut self, validate: F) -> &mut Self
+        where Req: 'static,
+              F: Fn(&Req) -> Result<(), Veto> + 'static
+    {
+        self.with(move |dispatch: &Dispatch, next: Next| match dispatch.request::<Req>() {
+            Some(request) => validate(request).map_err(Failure::rejected).and_then(|_| next.run()),
+            None => next.run(),
+        })
+    }
+
+    /// Handle a command with a handler resolved from `container`.
+    ///
+    /// This panics if the handler for `Req` doesn't return `E` errors, or
+    /// if the handler can't be resolved.
+    pub fn dispatch<Req, E>(&self, container: &C, request: Req) -> Result<(), DispatchError<E>>
+        where Req: Clone + 'static,
+              E: fmt::Debug + 'static
+    {
+        let command = type_name::<Req>();
+
+        let handler = match self.handlers.get(&TypeId::of::<Req>()) {
+            Some(&(error, ref handler)) => handler
+                .downcast_ref::<Handler<C, Req, E>>()
+                .unwrap_or_else(|| panic!("the handler for `{}` returns `{}` errors, not `{}`", command, error, type_name::<E>())),
+            None => return Err(DispatchError::Unhandled { command }),
+        };
+
+        let dispatch = Dispatch {
+            type_id: TypeId::of::<Req>(),
+            command,
+            handler: handler.type_name,
+            request: &request,
+        };
+
+        let handle = || (handler.handle)(container, request.clone()).map_err(Failure::failed);
+
+        // behaviors are resolved from this container when they're reached
+        let resolvers: Vec<_> = self.pipeline
+            .iter()
+            .filter_map(|layer| match *layer {
+                Layer::Behavior(ref resolve) => Some(move || resolve(container)),
+                Layer::Middleware(_) => None,
+            })
+            .collect();
+
+        let mut resolvers = resolvers.iter();
+        let pipeline: Vec<Link> = self.pipeline
+            .iter()
+            .map(|layer| match *layer {
+                Layer::Middleware(ref middleware) => Link::Middleware(&**middleware),
+                Layer::Behavior(_) => Link::Behavior(resolvers.next().expect("there's a resolver for each behavior")),
+            })
+            .collect();
+
+        let next = Next {
+            pipeline: &pipeline,
+            dispatch: &dispatch,
+            handle: &handle,
+        };
+
+        next.run().map_err(|failure| failure.into_dispatch_error(command))
+    }
+}
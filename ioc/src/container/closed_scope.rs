@@ -0,0 +1,190 @@
+//! Closed-world scoped containers
+//!
+//! `Scoped` can cache any `'static` type, so it needs a `TypeMap` of boxed
+//! values keyed by `TypeId`. When the set of shared dependencies is known
+//! upfront we can do better: the `closed_scope!` macro generates a scoped
+//! container with a dedicated slot for each type.
+//!
+//! Each set gets an enum with a variant per slot, and `get_or_add` matches
+//! on the variant for the type it's resolving. Picking the variant only
+//! compares constant `TypeId`s, so it's folded away when `get_or_add` is
+//! monomorphised, and there's no hashing, boxing or `dyn Any` at runtime.
+
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+type Slot<T> = RefCell<Option<Rc<T>>>;
+
+/// Get a weak reference to the value in a closed scope slot.
+///
+/// This is used by the `closed_scope!` macro and isn't meant to be called
+/// directly.
+#[doc(hidden)]
+#[inline]
+pub fn get_weak_slot<T>(slot: &Slot<T>) -> Weak<T> {
+    slot.borrow().as_ref().map(Rc::downgrade).unwrap_or_default()
+}
+
+/// Whether `T` is the type stored in a closed scope slot for `U`.
+///
+/// This is used by the `closed_scope!` macro and isn't meant to be called
+/// directly.
+#[doc(hidden)]
+#[inline]
+pub fn is_slot_type<T, U>() -> bool
+    where T: 'static,
+          U: 'static
+{
+    TypeId::of::<T>() == TypeId::of::<U>()
+}
+
+/// Treat a closed scope slot for `U` as a slot for `T`.
+///
+/// This is used by the `closed_scope!` macro and isn't meant to be called
+/// directly.
+///
+/// # Panics
+///
+/// Panics if `T` and `U` aren't the same type.
+#[doc(hidden)]
+#[inline]
+pub fn cast_slot<T, U>(slot: &Slot<U>) -> &Slot<T>
+    where T: 'static,
+          U: 'static
+{
+    assert!(is_slot_type::<T, U>(), "mismatched closed scope slot");
+
+    // SAFETY: `T` and `U` are the same type
+    unsafe { &*(slot as *const Slot<U> as *const Slot<T>) }
+}
+
+/// Get the value in a closed scope slot, or add one if it's empty.
+///
+/// This is used by the `closed_scope!` macro and isn't meant to be called
+/// directly.
+#[doc(hidden)]
+#[inline]
+pub fn get_or_add_slot<T, F>(slot: &Slot<T>, f: F) -> Rc<T>
+    where F: FnOnce() -> T
+{
+    if let Some(ref t) = *slot.borrow() {
+        return t.clone();
+    }
+
+    // the slot isn't borrowed while `f` runs so it can resolve other slots
+    let t = Rc::new(f());
+    *slot.borrow_mut() = Some(t.clone());
+
+    t
+}
+
+/// Declare a scoped container for a closed set of shared dependencies.
+///
+/// Each field names a slot for a single type. Resolving an `Rc<T>` for a
+/// type that doesn't have a slot will panic. A `Weak<T>` only refers to a
+/// value once it's in its slot, not while it's being constructed.
+///
+/// ```
+/// # #[macro_use] extern crate ioc;
+/// # use ioc::prelude::*;
+/// # use std::rc::Rc;
+/// # resolvable! {
+/// #     struct Y;
+/// #     struct BorrowY { y: Rc<Y> }
+/// #     struct BorrowMoreY { y: Rc<Y>, borrow_y: Rc<BorrowY> }
+/// # }
+/// # fn main() {
+/// closed_scope! {
+///     pub struct RequestScope {
+///         y: Y,
+///         borrow_y: BorrowY,
+///     }
+/// }
+///
+/// let scope = RequestScope::new();
+/// let y: BorrowMoreY = scope.resolve();
+/// # assert!(Rc::ptr_eq(&y.y, &y.borrow_y.y));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! closed_scope {
+    ($(#[$attr:meta])* $vis:vis struct $name:ident { $($field:ident: $t:ty),* $(,)* }) => {
+        $(#[$attr])*
+        $vis struct $name {
+            $($field: ::std::cell::RefCell<::std::option::Option<::std::rc::Rc<$t>>>,)*
+        }
+
+        impl $name {
+            #[allow(dead_code)]
+            $vis fn new() -> Self {
+                $name {
+                    $($field: ::std::cell::RefCell::new(::std::option::Option::None),)*
+                }
+            }
+        }
+
+        impl ::std::default::Default for $name {
+            fn default() -> Self {
+                $name::new()
+            }
+        }
+
+        impl $crate::Container for $name {}
+
+        const _: () = {
+            /// The slots of the closed scope.
+            #[allow(non_camel_case_types)]
+            #[derive(Clone, Copy)]
+            enum Slot {
+                $($field,)*
+            }
+
+            impl Slot {
+                #[inline]
+                fn of<T>() -> ::std::option::Option<Slot>
+                    where T: 'static
+                {
+                    $(
+                        if $crate::is_slot_type::<T, $t>() {
+                            return ::std::option::Option::Some(Slot::$field);
+                        }
+                    )*
+
+                    ::std::option::Option::None
+                }
+            }
+
+            impl $crate::ScopedContainer for $name {
+                fn get_or_add<T, D>(&self) -> ::std::rc::Rc<T>
+                    where T: $crate::Resolvable<Self, Dependency = D> + 'static,
+                          D: $crate::ResolvableFromContainer<Self>
+                {
+                    match Slot::of::<T>() {
+                        $(
+                            ::std::option::Option::Some(Slot::$field) => {
+                                $crate::get_or_add_slot($crate::cast_slot::<T, $t>(&self.$field), || T::resolve(D::resolve_from_container(self)))
+                            }
+                        )*
+                        ::std::option::Option::None => {
+                            panic!("`{}` isn't part of the closed scope `{}`",
+                                   ::std::any::type_name::<T>(),
+                                   stringify!($name))
+                        }
+                    }
+                }
+
+                fn get_weak<T>(&self) -> ::std::rc::Weak<T>
+                    where T: 'static
+                {
+                    match Slot::of::<T>() {
+                        $(
+                            ::std::option::Option::Some(Slot::$field) => $crate::get_weak_slot($crate::cast_slot::<T, $t>(&self.$field)),
+                        )*
+                        ::std::option::Option::None => ::std::rc::Weak::new(),
+                    }
+                }
+            }
+        };
+    };
+}
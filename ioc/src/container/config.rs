@@ -0,0 +1,362 @@
+//! Settings from configuration
+//!
+//! With the `config` feature, services can depend on their settings instead
+//! of being constructed with literals. Settings are deserialized from one
+//! section of the configuration:
+//!
+//! ```
+//! # #[macro_use] extern crate serde;
+//! # extern crate ioc;
+//! # use ioc::prelude::*;
+//! # use ioc::{Config, ConfigSection};
+//! # struct Db;
+//! # impl Db {
+//! #     fn connect(_: &str, _: u16) -> Self { Db }
+//! # }
+//! #[derive(Deserialize)]
+//! struct DbSettings {
+//!     host: String,
+//!     port: u16,
+//! }
+//!
+//! impl ConfigSection for DbSettings {
+//!     const SECTION: &'static str = "db";
+//! }
+//!
+//! impl Resolvable<Scoped> for Db {
+//!     type Dependency = Config<DbSettings>;
+//!
+//!     fn resolve(settings: Self::Dependency) -> Self {
+//!         Db::connect(&settings.host, settings.port)
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! The configuration comes from sources added to the registry. Later
+//! sources override the values of earlier ones, so defaults can be
+//! overridden by a file, and the file by the environment:
+//!
+//! ```no_run
+//! # use ioc::prelude::*;
+//! # use ioc::{ConfigError, EnvSource, MemorySource, TomlSource};
+//! # fn main() -> Result<(), ConfigError> {
+//! # let mut registry = Registry::<Scoped>::new();
+//! registry
+//!     .add_config_source(MemorySource::new().with("db.port", 5432))
+//!     .add_config_source(TomlSource::from_file("app.toml")?)
+//!     .add_config_source(EnvSource::new("APP_"));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::Deref;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use toml::{Table, Value};
+
+use super::*;
+
+/// Settings that are read from a section of the configuration.
+pub trait ConfigSection: DeserializeOwned {
+    /// The dotted path to the section, like `"db"` or `"db.primary"`.
+    ///
+    /// An empty path is the whole configuration.
+    const SECTION: &'static str;
+}
+
+/// A source of configuration values.
+pub trait ConfigSource {
+    /// The values from this source.
+    fn table(&self) -> Table;
+}
+
+/// Configuration parsed from TOML.
+#[derive(Debug, Clone)]
+pub struct TomlSource {
+    table: Table,
+}
+
+impl TomlSource {
+    pub fn parse(toml: &str) -> Result<Self, ConfigError> {
+        Ok(TomlSource { table: toml.parse()? })
+    }
+
+    pub fn from_file<P>(path: P) -> Result<Self, ConfigError>
+        where P: AsRef<Path>
+    {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+}
+
+impl ConfigSource for TomlSource {
+    fn table(&self) -> Table {
+        self.table.clone()
+    }
+}
+
+/// Configuration read from environment variables.
+///
+/// Variables that start with the prefix are included. The rest of the name
+/// is the lowercased path to the value, with `__` between sections, so
+/// with a prefix of `APP_` the variable `APP_DB__HOST` is the value of
+/// `db.host`. Values that look like TOML, like numbers or `true`, are
+/// parsed as TOML. Anything else is a string.
+#[derive(Debug, Clone)]
+pub struct EnvSource {
+    table: Table,
+}
+
+impl EnvSource {
+    /// Read the current environment variables that start with `prefix`.
+    pub fn new(prefix: &str) -> Self {
+        Self::from_vars(prefix, env::vars())
+    }
+
+    /// Read the given variables that start with `prefix`.
+    pub fn from_vars<I, K, V>(prefix: &str, vars: I) -> Self
+        where I: IntoIterator<Item = (K, V)>,
+              K: AsRef<str>,
+              V: AsRef<str>
+    {
+        let mut table = Table::new();
+
+        for (key, value) in vars {
+            if let Some(key) = key.as_ref().strip_prefix(prefix) {
+                let path = key.to_lowercase().replace("__", ".");
+
+                insert(&mut table, &path, parse_value(value.as_ref()));
+            }
+        }
+
+        EnvSource { table }
+    }
+}
+
+impl ConfigSource for EnvSource {
+    fn table(&self) -> Table {
+        self.table.clone()
+    }
+}
+
+/// Configuration held in memory, like defaults or values for tests.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySource {
+    table: Table,
+}
+
+impl MemorySource {
+    pub fn new() -> Self {
+        MemorySource::default()
+    }
+
+    /// Set the value at a dotted path, like `"db.port"`.
+    pub fn with<V>(mut self, path: &str, value: V) -> Self
+        where V: Into<Value>
+    {
+        insert(&mut self.table, path, value.into());
+
+        self
+    }
+}
+
+impl ConfigSource for MemorySource {
+    fn table(&self) -> Table {
+        self.table.clone()
+    }
+}
+
+/// Configuration couldn't be read.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// A source couldn't be read.
+    Io(io::Error),
+    /// A source isn't valid TOML, or a section doesn't match its settings.
+    Toml(toml::de::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConfigError::Io(ref err) => write!(f, "the config couldn't be read: {}", err),
+            ConfigError::Toml(ref err) => write!(f, "the config is invalid: {}", err),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            ConfigError::Io(ref err) => Some(err),
+            ConfigError::Toml(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(err: io::Error) -> Self {
+        ConfigError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        ConfigError::Toml(err)
+    }
+}
+
+/// Settings deserialized from the configuration.
+pub struct Config<T> {
+    value: T,
+}
+
+impl<T> Config<T> {
+    pub fn new(value: T) -> Self {
+        Config { value }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Config<T>
+    where T: ConfigSection
+{
+    /// Read the settings from the merged values of `sources`.
+    pub fn load<'a, I>(sources: I) -> Result<Self, ConfigError>
+        where I: IntoIterator<Item = &'a Rc<dyn ConfigSource>>
+    {
+        let mut table = Table::new();
+
+        for source in sources {
+            merge(&mut table, source.table());
+        }
+
+        // a missing section is empty, so settings with defaults still work
+        let section = T::SECTION
+            .split('.')
+            .filter(|key| !key.is_empty())
+            .try_fold(Value::Table(table), |value, key| match value {
+                Value::Table(mut table) => table.remove(key),
+                _ => None,
+            })
+            .unwrap_or_else(|| Value::Table(Table::new()));
+
+        Ok(Config::new(section.try_into()?))
+    }
+
+    fn resolve_from_sources(sources: All<dyn ConfigSource>) -> Self {
+        Self::load(&*sources).unwrap_or_else(|err| {
+            panic!("the `{}` config section couldn't be read into `{}`: {}", T::SECTION, type_name::<T>(), err)
+        })
+    }
+}
+
+impl<T> Deref for Config<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> fmt::Debug for Config<T>
+    where T: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Config").field(&self.value).finish()
+    }
+}
+
+// `Config`s are read from the sources in the registry each time they're
+// resolved. Depending on an `Rc<Config<T>>` reads them once per scope.
+impl<C, T> Resolvable<C> for Config<T>
+    where C: RegisteredContainer + 'static,
+          T: ConfigSection
+{
+    type Dependency = All<dyn ConfigSource>;
+
+    fn resolve(sources: Self::Dependency) -> Self {
+        Config::resolve_from_sources(sources)
+    }
+}
+
+impl<C, T> ResolvableFromContainer<C> for Config<T>
+    where C: RegisteredContainer + 'static,
+          T: ConfigSection
+{
+    fn resolve_from_container(container: &C) -> Self {
+        Config::resolve_from_sources(All::resolve_from_container(container))
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::Config, type_name::<Self>(), |verification| {
+            All::<dyn ConfigSource>::verify(container, verification)
+        });
+    }
+}
+
+impl<C> Registry<C>
+    where C: 'static
+{
+    /// Add a source of configuration values for `Config<T>` dependencies.
+    ///
+    /// Values from this source override the same values from any sources
+    /// added before it.
+    pub fn add_config_source<S>(&mut self, source: S) -> &mut Self
+        where S: ConfigSource + 'static
+    {
+        let source: Rc<dyn ConfigSource> = Rc::new(source);
+
+        self.bind_all::<dyn ConfigSource, _>(move |_| source.clone())
+    }
+}
+
+/// Insert a value at a dotted path, creating any tables along the way.
+fn insert(table: &mut Table, path: &str, value: Value) {
+    let mut keys = path.split('.').peekable();
+    let mut table = table;
+
+    while let Some(key) = keys.next() {
+        if keys.peek().is_none() {
+            table.insert(key.to_owned(), value);
+            return;
+        }
+
+        let entry = table.entry(key.to_owned()).or_insert_with(|| Value::Table(Table::new()));
+
+        if !entry.is_table() {
+            *entry = Value::Table(Table::new());
+        }
+
+        table = entry.as_table_mut().expect("the entry was just made a table");
+    }
+}
+
+/// Merge `from` into `into`, replacing any values that are in both.
+fn merge(into: &mut Table, from: Table) {
+    for (key, value) in from {
+        match (into.get_mut(&key), value) {
+            (Some(&mut Value::Table(ref mut into)), Value::Table(from)) => merge(into, from),
+            (_, value) => {
+                into.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Parse a value that might be TOML, or might just be a string.
+fn parse_value(value: &str) -> Value {
+    format!("value = {}", value)
+        .parse::<Table>()
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(value.to_owned()))
+}
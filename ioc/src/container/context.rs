@@ -0,0 +1,88 @@
+//! Typed context for a scope
+//!
+//! Scopes are often created for a piece of work that comes with data of its
+//! own, like the id or user of an incoming request. That data can be set on
+//! the scope as context when it's created:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use std::rc::Rc;
+//! # struct Db;
+//! # impl<C> Resolvable<C> for Db {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { Db }
+//! # }
+//! # struct RequestId(u64);
+//! # struct AuditLog { request_id: Rc<RequestId>, db: Rc<Db> }
+//! # let registry = Rc::new(Registry::new());
+//! # let request_id = 7;
+//! let scope = Scoped::new().with_registry(registry.clone());
+//! scope.set_context(RequestId(request_id));
+//!
+//! impl Resolvable<Scoped> for AuditLog {
+//!     type Dependency = (Context<RequestId>, Rc<Db>);
+//!
+//!     fn resolve((request_id, db): Self::Dependency) -> Self {
+//!         AuditLog { request_id: request_id.into_inner(), db }
+//!     }
+//! }
+//! # let log: AuditLog = scope.resolve();
+//! # assert_eq!(7, log.request_id.0);
+//! ```
+//!
+//! Context is kept apart from the scope's shared dependencies, so it can't
+//! be replaced by resolving `T`, and doesn't show up in the scope's stats.
+//! Resolving a `Context<T>` that was never set panics. `try_resolve`
+//! returns it as `Error::MissingContext`.
+
+use std::fmt;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use super::*;
+
+/// A container that can hold typed context for the work it's resolving for.
+pub trait ContextContainer
+    where Self: Container
+{
+    /// The context of type `T`, if it's been set.
+    fn context<T>(&self) -> Option<Rc<T>>
+        where T: 'static;
+}
+
+/// Context of type `T` that was set on the scope it's resolved from.
+pub struct Context<T> {
+    value: Rc<T>,
+}
+
+impl<T> Context<T> {
+    pub fn new(value: Rc<T>) -> Self {
+        Context { value }
+    }
+
+    pub fn into_inner(self) -> Rc<T> {
+        self.value
+    }
+}
+
+impl<T> Clone for Context<T> {
+    fn clone(&self) -> Self {
+        Context { value: self.value.clone() }
+    }
+}
+
+impl<T> Deref for Context<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> fmt::Debug for Context<T>
+    where T: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Context").field(&self.value).finish()
+    }
+}
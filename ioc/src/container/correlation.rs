@@ -0,0 +1,86 @@
+//! Correlation ids
+//!
+//! Each scope has a `CorrelationId` that's generated the first time it's
+//! requested. Services can depend on it to tie their work back to the
+//! request or job the scope was created for.
+
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// An id that's shared by everything resolved within a single scope.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CorrelationId(Rc<str>);
+
+impl CorrelationId {
+    pub fn new<S>(id: S) -> Self
+        where S: Into<String>
+    {
+        CorrelationId(id.into().into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A source of new correlation ids.
+///
+/// Any `Fn() -> CorrelationId` is a provider, so ids from crates like
+/// `uuid` or `ulid` can be used without a wrapper type.
+pub trait CorrelationIdProvider {
+    fn next_id(&self) -> CorrelationId;
+}
+
+impl<F> CorrelationIdProvider for F
+    where F: Fn() -> CorrelationId
+{
+    fn next_id(&self) -> CorrelationId {
+        self()
+    }
+}
+
+/// The default provider, which generates random ids formatted like a v4 UUID.
+///
+/// Randomness comes from the standard library's hasher seeds so it's
+/// unpredictable enough to avoid collisions, but isn't cryptographically
+/// secure.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RandomCorrelationIds;
+
+impl RandomCorrelationIds {
+    fn next_u64() -> u64 {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_usize(COUNTER.fetch_add(1, Ordering::Relaxed));
+
+        hasher.finish()
+    }
+}
+
+impl CorrelationIdProvider for RandomCorrelationIds {
+    fn next_id(&self) -> CorrelationId {
+        let hi = Self::next_u64();
+        let lo = Self::next_u64();
+
+        // set the version (4) and variant (10) bits
+        let hi = (hi & 0xffff_ffff_ffff_0fff) | 0x0000_0000_0000_4000;
+        let lo = (lo & 0x3fff_ffff_ffff_ffff) | 0x8000_0000_0000_0000;
+
+        CorrelationId::new(format!("{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+                                   hi >> 32,
+                                   (hi >> 16) & 0xffff,
+                                   hi & 0xffff,
+                                   lo >> 48,
+                                   lo & 0xffff_ffff_ffff))
+    }
+}
@@ -0,0 +1,199 @@
+//! Declaring resolvable types
+//!
+//! Most `Resolvable` impls just move each of their dependencies into a
+//! field. The `resolvable!` macro writes those impls, so a type only needs
+//! to declare its fields:
+//!
+//! ```
+//! # #[macro_use] extern crate ioc;
+//! # use ioc::prelude::*;
+//! # use std::rc::Rc;
+//! # resolvable! {
+//! #     default Y;
+//! # }
+//! # #[derive(Default)]
+//! # struct Y;
+//! resolvable! {
+//!     struct X;
+//!
+//!     struct BorrowY {
+//!         x: Transient<X>,
+//!         y: Rc<Y>,
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Leaf types that are just `Default` can be declared as `default` to
+//! get a `Resolvable` impl that uses their `Default` value instead:
+//!
+//! ```
+//! # #[macro_use] extern crate ioc;
+//! # use ioc::prelude::*;
+//! #[derive(Default)]
+//! struct Settings {
+//!     retries: usize,
+//! }
+//!
+//! resolvable! {
+//!     default Settings;
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Types that already have a constructor function can use it as their
+//! `Resolvable` impl with the `provider!` macro, which restates the
+//! function's signature:
+//!
+//! ```
+//! # #[macro_use] extern crate ioc;
+//! # use ioc::prelude::*;
+//! # use std::rc::Rc;
+//! # struct DatabaseUrl;
+//! # impl EnvVar for DatabaseUrl {
+//! #     const NAME: &'static str = "DATABASE_URL";
+//! #     type Value = String;
+//! # }
+//! # struct Db;
+//! fn connect(url: Env<DatabaseUrl>, log: Rc<ScopeLog>) -> Db {
+//!     // ...
+//! #   let _ = (url, log);
+//! #   Db
+//! }
+//!
+//! provider! {
+//!     fn connect(url: Env<DatabaseUrl>, log: Rc<ScopeLog>) -> Db;
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Types that transform their dependencies, or are generic, still need a
+//! `Resolvable` impl written by hand.
+
+/// Declare structs whose fields are their dependencies.
+///
+/// Each field is resolved as its own type, so fields need to be root
+/// dependencies, like `Rc<T>`, `Transient<T>` or `Bound<T>`. A unit struct
+/// depends on `()`. Structs can have up to 16 fields, the same as tuple
+/// dependencies.
+///
+/// Types that are declared elsewhere and are resolved as their `Default`
+/// value can be listed as `default T;`.
+///
+/// Optional collaborators can be declared as `Option<Bound<T>>` fields,
+/// which are `None` when there's no binding for `T`:
+///
+/// ```
+/// # #[macro_use] extern crate ioc;
+/// # use ioc::prelude::*;
+/// # use std::rc::Rc;
+/// # trait AuditLog {}
+/// # resolvable! {
+/// #     default Orders;
+/// # }
+/// # #[derive(Default)]
+/// # struct Orders;
+/// resolvable! {
+///     struct Checkout {
+///         orders: Rc<Orders>,
+///         audit: Option<Bound<dyn AuditLog>>,
+///     }
+/// }
+/// # fn main() {
+/// #     let checkout: Checkout = Scoped::new().resolve();
+/// #     assert!(checkout.audit.is_none());
+/// # }
+/// ```
+#[macro_export]
+macro_rules! resolvable {
+    () => {};
+    (default $t:ty; $($rest:tt)*) => {
+        impl<C> $crate::Resolvable<C> for $t {
+            type Dependency = ();
+
+            fn resolve(_: Self::Dependency) -> Self {
+                <$t as ::std::default::Default>::default()
+            }
+        }
+
+        $crate::resolvable! { $($rest)* }
+    };
+    ($(#[$attr:meta])* $vis:vis struct $name:ident; $($rest:tt)*) => {
+        $(#[$attr])*
+        $vis struct $name;
+
+        impl<C> $crate::Resolvable<C> for $name {
+            type Dependency = ();
+
+            fn resolve(_: Self::Dependency) -> Self {
+                $name
+            }
+        }
+
+        $crate::resolvable! { $($rest)* }
+    };
+    ($(#[$attr:meta])* $vis:vis struct $name:ident { $($(#[$field_attr:meta])* $field_vis:vis $field:ident: $t:ty),* $(,)* } $($rest:tt)*) => {
+        $(#[$attr])*
+        $vis struct $name {
+            $($(#[$field_attr])* $field_vis $field: $t,)*
+        }
+
+        impl<C> $crate::Resolvable<C> for $name {
+            type Dependency = ($($t,)*);
+
+            fn resolve(($($field,)*): Self::Dependency) -> Self {
+                $name { $($field,)* }
+            }
+        }
+
+        $crate::resolvable! { $($rest)* }
+    };
+}
+
+/// Use existing functions as the `Resolvable` impls of the types they return.
+///
+/// Each function's signature is restated without its body. Arguments are
+/// resolved as their own types, so they need to be root dependencies, like
+/// `Rc<T>`, `Transient<T>` or `Bound<T>`. A function with no arguments
+/// depends on `()`. Functions can have up to 16 arguments, the same as
+/// tuple dependencies, and can be paths to functions in other modules:
+///
+/// ```
+/// # #[macro_use] extern crate ioc;
+/// # use ioc::prelude::*;
+/// # struct DatabaseUrl;
+/// # impl EnvVar for DatabaseUrl {
+/// #     const NAME: &'static str = "DATABASE_URL";
+/// #     type Value = String;
+/// # }
+/// # resolvable! {
+/// #     struct X;
+/// # }
+/// # struct Y;
+/// # fn make_y(_: Transient<X>) -> Y { Y }
+/// # struct Db;
+/// # mod db {
+/// #     use ioc::Env;
+/// #     pub fn connect(_: Env<::DatabaseUrl>) -> ::Db { ::Db }
+/// # }
+/// provider! {
+///     fn make_y(x: Transient<X>) -> Y;
+///     fn db::connect(url: Env<DatabaseUrl>) -> Db;
+/// }
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! provider {
+    () => {};
+    (fn $($f:ident)::+ ($($arg:ident: $t:ty),* $(,)*) -> $ret:ty; $($rest:tt)*) => {
+        impl<C> $crate::Resolvable<C> for $ret {
+            type Dependency = ($($t,)*);
+
+            fn resolve(($($arg,)*): Self::Dependency) -> Self {
+                $($f)::+($($arg),*)
+            }
+        }
+
+        $crate::provider! { $($rest)* }
+    };
+}
@@ -0,0 +1,240 @@
+//! Decorators for queries and commands
+//!
+//! Cross-cutting concerns like logging, retries and caching can be layered
+//! onto a query or command without touching its body. Each decorator wraps
+//! one and is a query or command itself, so they compose:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use ioc::factories::{with_cache, with_logging};
+//! # use std::time::Duration;
+//! # struct DbConnection;
+//! # impl<C> Resolvable<C> for DbConnection {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { DbConnection }
+//! # }
+//! # #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+//! # struct GetProduct { id: u32 }
+//! # #[derive(Debug, Clone)]
+//! # struct Product { id: u32 }
+//! # fn get_product(_: DbConnection) -> impl Query<GetProduct, Product> {
+//! #     |action: GetProduct| Product { id: action.id }
+//! # }
+//! # let mut registry = Registry::<Scoped>::new();
+//! registry.bind_query(|conn: Transient<DbConnection>| {
+//!     with_logging(with_cache(Duration::from_secs(60), get_product(conn.into_inner())))
+//! });
+//! ```
+
+use std::any::type_name;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use super::factories::{Command, Query};
+
+/// Log each request a query or command handles.
+///
+/// Lines are written to standard error unless they're sent somewhere else
+/// with `Logged::to`.
+pub fn with_logging<T>(inner: T) -> Logged<T> {
+    Logged {
+        inner,
+        sink: Rc::new(|line: &str| {
+            let _ = writeln!(io::stderr(), "{}", line);
+        }),
+    }
+}
+
+/// Retry a command, or a query that returns a `Result`, until it succeeds
+/// or has been tried `attempts` times.
+///
+/// Each attempt is handled by a clone of `inner` with a clone of the
+/// request, so both need to be `Clone`. A `CommandOf` can't be cloned, so
+/// use the command bus's `Retry` for those instead.
+pub fn with_retry<T>(attempts: usize, inner: T) -> Retried<T> {
+    Retried {
+        inner,
+        attempts: attempts.max(1),
+    }
+}
+
+/// Cache the responses of a query for `ttl`.
+///
+/// Every response is cached, including errors. The cache belongs to the
+/// `Cached` query, so it only lasts as long as that does.
+pub fn with_cache<Q, Req, Res>(ttl: Duration, inner: Q) -> Cached<Q, Req, Res>
+    where Q: Query<Req, Res>
+{
+    Cached {
+        inner,
+        ttl,
+        responses: RefCell::new(HashMap::new()),
+    }
+}
+
+/// A query or command that logs each request it handles.
+pub struct Logged<T> {
+    inner: T,
+    sink: Rc<dyn Fn(&str)>,
+}
+
+impl<T> Logged<T> {
+    /// Send log lines to `sink` instead of standard error.
+    pub fn to<F>(self, sink: F) -> Self
+        where F: Fn(&str) + 'static
+    {
+        Logged {
+            inner: self.inner,
+            sink: Rc::new(sink),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> Clone for Logged<T>
+    where T: Clone
+{
+    fn clone(&self) -> Self {
+        Logged {
+            inner: self.inner.clone(),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<T, Req, Res> Query<Req, Res> for Logged<T>
+    where T: Query<Req, Res>,
+          Res: fmt::Debug
+{
+    fn query(&self, request: Req) -> Res {
+        let start = Instant::now();
+        let response = self.inner.query(request);
+
+        (self.sink)(&format!("query {} returned {:?} in {:?}", type_name::<Req>(), response, start.elapsed()));
+
+        response
+    }
+}
+
+impl<T, Req> Command<Req> for Logged<T>
+    where T: Command<Req>,
+          T::Error: fmt::Debug
+{
+    type Error = T::Error;
+
+    fn execute(self, request: Req) -> Result<(), T::Error> {
+        let start = Instant::now();
+        let result = self.inner.execute(request);
+
+        (self.sink)(&format!("command {} returned {:?} in {:?}", type_name::<Req>(), result, start.elapsed()));
+
+        result
+    }
+}
+
+/// A query or command that's tried again when it fails.
+#[derive(Debug, Clone)]
+pub struct Retried<T> {
+    inner: T,
+    attempts: usize,
+}
+
+impl<T> Retried<T> {
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Call `attempt` until it succeeds or has been called `attempts` times.
+    fn retry<R, E, F>(&self, mut attempt: F) -> Result<R, E>
+        where F: FnMut() -> Result<R, E>
+    {
+        let mut attempts = 1;
+
+        loop {
+            match attempt() {
+                Err(_) if attempts < self.attempts => attempts += 1,
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<T, Req, Res, E> Query<Req, Result<Res, E>> for Retried<T>
+    where T: Query<Req, Result<Res, E>>,
+          Req: Clone
+{
+    fn query(&self, request: Req) -> Result<Res, E> {
+        self.retry(|| self.inner.query(request.clone()))
+    }
+}
+
+impl<T, Req> Command<Req> for Retried<T>
+    where T: Command<Req> + Clone,
+          Req: Clone
+{
+    type Error = T::Error;
+
+    fn execute(self, request: Req) -> Result<(), T::Error> {
+        self.retry(|| self.inner.clone().execute(request.clone()))
+    }
+}
+
+/// A query whose responses are cached.
+pub struct Cached<Q, Req, Res> {
+    inner: Q,
+    ttl: Duration,
+    responses: RefCell<HashMap<Req, (Instant, Res)>>,
+}
+
+impl<Q, Req, Res> Cached<Q, Req, Res> {
+    /// Forget every cached response.
+    pub fn clear(&self) {
+        self.responses.borrow_mut().clear();
+    }
+
+    pub fn into_inner(self) -> Q {
+        self.inner
+    }
+}
+
+impl<Q, Req, Res> Query<Req, Res> for Cached<Q, Req, Res>
+    where Q: Query<Req, Res>,
+          Req: Hash + Eq + Clone,
+          Res: Clone
+{
+    fn query(&self, request: Req) -> Res {
+        let now = Instant::now();
+
+        if let Some(&(cached, ref response)) = self.responses.borrow().get(&request) {
+            if now.duration_since(cached) < self.ttl {
+                return response.clone();
+            }
+        }
+
+        // the cache isn't borrowed while the query runs, in case it calls itself
+        let response = self.inner.query(request.clone());
+
+        let mut responses = self.responses.borrow_mut();
+        responses.retain(|_, &mut (cached, _)| now.duration_since(cached) < self.ttl);
+        responses.insert(request, (now, response.clone()));
+
+        response
+    }
+}
+
+impl<Q, Req, Res> fmt::Debug for Cached<Q, Req, Res> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Cached")
+            .field("ttl", &self.ttl)
+            .field("responses", &self.responses.borrow().len())
+            .finish()
+    }
+}
@@ -0,0 +1,57 @@
+//! Dependency depth limits
+//!
+//! Every level of a dependency graph is another set of generic impls for
+//! the compiler to work through, so very deep graphs are slow to build.
+//! Each root dependency knows how deeply nested its own dependencies are,
+//! so a limit can be checked at compile time with `max_depth!`.
+//!
+//! The same check catches graphs that are infinitely deep, like a type that
+//! depends on a `RefCell` of itself. The depth of those can't be counted,
+//! so instead of the limit's own message the compiler reports an overflow
+//! (`E0275`) at the `max_depth!` call, naming the dependency that recurses.
+//! Raising the recursion limit like the error suggests won't help; the
+//! recursion needs to be broken with a dependency that doesn't resolve its
+//! type's own dependencies, like a `Weak<T>` or a `Bound<T>`. Lowering the
+//! limit with `#![recursion_limit]` makes these fail sooner.
+
+use super::*;
+
+/// How deeply nested the dependencies resolved for `R` are.
+///
+/// A type that only depends on `()` has a depth of `1`.
+pub const fn depth<C, R, D>() -> usize
+    where C: Container,
+          R: Resolvable<C, Dependency = D>,
+          D: ResolvableFromContainer<C>
+{
+    D::DEPTH + 1
+}
+
+/// Fail to compile if the dependencies of a type are nested too deeply.
+///
+/// The check is made for the given container, since the dependencies of a
+/// type can depend on the container it's resolved from:
+///
+/// ```
+/// # #[macro_use] extern crate ioc;
+/// # use ioc::prelude::*;
+/// # use std::rc::Rc;
+/// # resolvable! {
+/// #     struct Db;
+/// #     struct App { db: Rc<Db> }
+/// # }
+/// max_depth!(Scoped, App, 16);
+/// # fn main() {}
+/// ```
+///
+/// If the limit is exceeded then the error points at the `max_depth!` call
+/// and names the type that's too deep. If the dependencies are infinitely
+/// deep then the error is an overflow at the same call.
+#[macro_export]
+macro_rules! max_depth {
+    ($container:ty, $t:ty, $max:expr) => {
+        const _: () = assert!($crate::depth::<$container, $t, _>() <= $max,
+                              concat!("the dependencies of `", stringify!($t), "` are nested more than ",
+                                      stringify!($max), " levels deep"));
+    };
+}
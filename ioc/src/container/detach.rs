@@ -0,0 +1,76 @@
+//! Detaching shared dependencies from a scope
+//!
+//! An `Rc<T>` from a scope keeps the scope's instance of `T` alive, but it
+//! still can't be sent anywhere else and any changes through it are seen
+//! by the rest of the scope. Detaching clones the scope's instances into
+//! plain owned values instead, so it's clear they're no longer managed by
+//! the scope:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use std::thread;
+//! # #[derive(Clone, Default)]
+//! # struct Config { url: String }
+//! # #[derive(Clone, Default)]
+//! # struct Client;
+//! # impl Client {
+//! #     fn send(&self, _: &Config) {}
+//! # }
+//! # impl<C> Resolvable<C> for Config {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { Config::default() }
+//! # }
+//! # impl<C> Resolvable<C> for Client {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { Client }
+//! # }
+//! # let scope = Scoped::new();
+//! let (config, client) = scope.detach::<(Config, Client)>();
+//!
+//! thread::spawn(move || client.send(&config));
+//! ```
+
+use super::*;
+
+/// A tuple of shared dependencies that can be cloned out of a scope.
+///
+/// Each member is resolved as if it were an `Rc<T>`, so it's the same
+/// instance that's shared within the scope, and then cloned.
+/// A single dependency can be detached as a 1-tuple, like `(T,)`.
+pub trait Detach<C>
+    where C: ScopedContainer
+{
+    fn detach(container: &C) -> Self;
+}
+
+macro_rules! detach_tuple {
+    ($(($T:ident,$D:ident))*) => (
+        impl <C $(,$T,$D)*> Detach<C> for ($($T,)*)
+            where $($T: Resolvable<C, Dependency = $D> + Clone + 'static, $D: ResolvableFromContainer<C>,)*
+                  C: ScopedContainer
+        {
+            fn detach(container: &C) -> Self {
+                (
+                    $(<$T as Clone>::clone(&container.get_or_add::<$T, $D>()),)*
+                )
+            }
+        }
+    )
+}
+
+detach_tuple!((T1, D1));
+detach_tuple!((T1, D1)(T2, D2));
+detach_tuple!((T1, D1)(T2, D2)(T3, D3));
+detach_tuple!((T1, D1)(T2, D2)(T3, D3)(T4, D4));
+detach_tuple!((T1, D1)(T2, D2)(T3, D3)(T4, D4)(T5, D5));
+detach_tuple!((T1, D1)(T2, D2)(T3, D3)(T4, D4)(T5, D5)(T6, D6));
+detach_tuple!((T1, D1)(T2, D2)(T3, D3)(T4, D4)(T5, D5)(T6, D6)(T7, D7));
+detach_tuple!((T1, D1)(T2, D2)(T3, D3)(T4, D4)(T5, D5)(T6, D6)(T7, D7)(T8, D8));
+detach_tuple!((T1, D1)(T2, D2)(T3, D3)(T4, D4)(T5, D5)(T6, D6)(T7, D7)(T8, D8)(T9, D9));
+detach_tuple!((T1, D1)(T2, D2)(T3, D3)(T4, D4)(T5, D5)(T6, D6)(T7, D7)(T8, D8)(T9, D9)(T10, D10));
+detach_tuple!((T1, D1)(T2, D2)(T3, D3)(T4, D4)(T5, D5)(T6, D6)(T7, D7)(T8, D8)(T9, D9)(T10, D10)(T11, D11));
+detach_tuple!((T1, D1)(T2, D2)(T3, D3)(T4, D4)(T5, D5)(T6, D6)(T7, D7)(T8, D8)(T9, D9)(T10, D10)(T11, D11)(T12, D12));
+detach_tuple!((T1, D1)(T2, D2)(T3, D3)(T4, D4)(T5, D5)(T6, D6)(T7, D7)(T8, D8)(T9, D9)(T10, D10)(T11, D11)(T12, D12)(T13, D13));
+detach_tuple!((T1, D1)(T2, D2)(T3, D3)(T4, D4)(T5, D5)(T6, D6)(T7, D7)(T8, D8)(T9, D9)(T10, D10)(T11, D11)(T12, D12)(T13, D13)(T14, D14));
+detach_tuple!((T1, D1)(T2, D2)(T3, D3)(T4, D4)(T5, D5)(T6, D6)(T7, D7)(T8, D8)(T9, D9)(T10, D10)(T11, D11)(T12, D12)(T13, D13)(T14, D14)(T15, D15));
+detach_tuple!((T1, D1)(T2, D2)(T3, D3)(T4, D4)(T5, D5)(T6, D6)(T7, D7)(T8, D8)(T9, D9)(T10, D10)(T11, D11)(T12, D12)(T13, D13)(T14, D14)(T15, D15)(T16, D16));
@@ -0,0 +1,29 @@
+//! Disposable dependencies
+//!
+//! Dropping a scope drops everything it cached, but `Drop` can't fail or
+//! be given anything to work with, and there's no way to tell it apart from
+//! a value being dropped for some other reason. A `Disposable` dependency
+//! gets a chance to clean up deliberately when its scope ends instead, like
+//! returning a connection to a pool or flushing a buffer.
+//!
+//! Scopes don't know which of the values they cache are disposable, so
+//! they're registered on the registry given to the scope:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # struct Connection;
+//! # impl Disposable for Connection {
+//! #     fn dispose(&mut self) {}
+//! # }
+//! # let mut registry = Registry::<Scoped>::new();
+//! registry.disposable::<Connection>();
+//! ```
+
+/// A dependency that's disposed of when the scope that cached it ends.
+pub trait Disposable {
+    /// Clean up the value before it's dropped.
+    fn dispose(&mut self);
+}
+
+/// A function that disposes of a `T`.
+pub type Dispose<T> = fn(&mut T);
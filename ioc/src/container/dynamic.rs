@@ -0,0 +1,550 @@
+//! Dynamic containers
+//!
+//! Static dependencies are decided when the program is compiled. Sometimes
+//! the bindings can only be decided at runtime, like when they depend on a
+//! feature flag or on which plugins are loaded. A `DynamicContainer` holds
+//! a factory for each type, keyed by its `TypeId`:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # trait Search {}
+//! # struct NewSearch;
+//! # struct OldSearch;
+//! # impl NewSearch { fn new() -> Self { NewSearch } }
+//! # impl OldSearch { fn new() -> Self { OldSearch } }
+//! # impl Search for NewSearch {}
+//! # impl Search for OldSearch {}
+//! # struct Flags { new_search: bool }
+//! # let flags = Flags { new_search: true };
+//! let mut container = DynamicContainer::new();
+//!
+//! if flags.new_search {
+//!     container.register::<Box<dyn Search>, _>(|_| Box::new(NewSearch::new()));
+//! } else {
+//!     container.register::<Box<dyn Search>, _>(|_| Box::new(OldSearch::new()));
+//! }
+//!
+//! let search: Box<dyn Search> = container.get().expect("search is registered");
+//! ```
+//!
+//! It's still a `Container`, so `Resolvable` types can be resolved from it,
+//! or registered with `register_resolvable`. They depend on a registered
+//! type using `Dyn<T>`:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # trait Search {}
+//! # struct SearchHandler { search: Box<dyn Search> }
+//! impl Resolvable<DynamicContainer> for SearchHandler {
+//!     type Dependency = Dyn<Box<dyn Search>>;
+//!
+//!     fn resolve(search: Self::Dependency) -> Self {
+//!         SearchHandler { search: search.into_inner() }
+//!     }
+//! }
+//! ```
+//!
+//! A `Dyn<T>` that isn't registered panics, and `try_resolve` returns it as
+//! `Error::MissingBinding`. Each request for a type calls its factory again.
+//!
+//! Factories can also be tagged with a `Profile`, so the fakes used by
+//! integration tests can live alongside the real wiring. The profile is
+//! picked when the container is built, and its factories replace any that
+//! aren't tagged:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use ioc::Profile;
+//! # trait Mailer {}
+//! # struct SmtpMailer;
+//! # struct FakeMailer;
+//! # impl SmtpMailer { fn new() -> Self { SmtpMailer } }
+//! # impl FakeMailer { fn new() -> Self { FakeMailer } }
+//! # impl Mailer for SmtpMailer {}
+//! # impl Mailer for FakeMailer {}
+//! let mut builder = DynamicContainer::builder();
+//!
+//! builder
+//!     .register::<Box<dyn Mailer>, _>(|_| Box::new(SmtpMailer::new()))
+//!     .register_for::<Box<dyn Mailer>, _>(Profile::Test, |_| Box::new(FakeMailer::new()));
+//!
+//! let container = builder.build(Profile::Test);
+//! # assert!(container.is_registered::<Box<dyn Mailer>>());
+//! ```
+//!
+//! For anything a profile can't express, a factory can be registered with
+//! a condition that's checked when the container is built:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # trait Queue {}
+//! # struct SqsQueue;
+//! # impl SqsQueue { fn new() -> Self { SqsQueue } }
+//! # impl Queue for SqsQueue {}
+//! # let mut builder = DynamicContainer::builder();
+//! builder.register_if::<Box<dyn Queue>, _, _>(
+//!     |ctx| ctx.var("APP_ENV").as_deref() == Some("staging"),
+//!     |_| Box::new(SqsQueue::new()),
+//! );
+//! ```
+//!
+//! When more than one factory is registered for a type, the container
+//! records which one was used. See `DynamicContainer::contested`.
+
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap as StdHashMap;
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use super::*;
+use super::error::{missing_binding, Resolving};
+
+// `TypeId`s are already hashes, so they're used as-is
+type HashMap<K, V> = StdHashMap<K, V, BuildTypeIdHasher>;
+
+/// A factory for a `T`, boxed as `Box<dyn Any>`.
+type DynFactory = Box<dyn Fn(&DynamicContainer) -> Box<dyn Any>>;
+
+/// A factory in the container.
+struct Entry {
+    type_name: &'static str,
+    factory: DynFactory,
+}
+
+impl Entry {
+    fn new<T, F>(f: F) -> Self
+        where T: 'static,
+              F: Fn(&DynamicContainer) -> T + 'static
+    {
+        Entry {
+            type_name: type_name::<T>(),
+            factory: Box::new(move |container| Box::new(f(container))),
+        }
+    }
+}
+
+/// A set of factories for some environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Profile {
+    Dev,
+    Test,
+    Prod,
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Profile::Dev => "dev",
+            Profile::Test => "test",
+            Profile::Prod => "prod",
+        })
+    }
+}
+
+/// Parse a profile from its name, like `"test"`.
+///
+/// This makes it easy to pick the profile from an environment variable.
+impl FromStr for Profile {
+    type Err = UnknownProfile;
+
+    fn from_str(s: &str) -> Result<Self, UnknownProfile> {
+        match s {
+            "dev" => Ok(Profile::Dev),
+            "test" => Ok(Profile::Test),
+            "prod" => Ok(Profile::Prod),
+            _ => Err(UnknownProfile { name: s.to_owned() }),
+        }
+    }
+}
+
+/// A profile name that isn't `dev`, `test` or `prod`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownProfile {
+    pub name: String,
+}
+
+impl fmt::Display for UnknownProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}` isn't a profile, expected `dev`, `test` or `prod`", self.name)
+    }
+}
+
+impl Error for UnknownProfile {}
+
+/// What a `DynamicBuilder` knows about the container it's building.
+pub struct BuildContext {
+    profile: Profile,
+    env: Box<dyn EnvProvider>,
+}
+
+impl BuildContext {
+    /// A context for `profile` that reads the process environment.
+    pub fn new(profile: Profile) -> Self {
+        BuildContext {
+            profile,
+            env: Box::new(ProcessEnv),
+        }
+    }
+
+    /// Read environment variables from `env` instead.
+    pub fn with_env<E>(mut self, env: E) -> Self
+        where E: EnvProvider + 'static
+    {
+        self.env = Box::new(env);
+
+        self
+    }
+
+    /// The profile the container is being built for.
+    pub fn profile(&self) -> Profile {
+        self.profile
+    }
+
+    /// Read an environment variable.
+    pub fn var(&self, name: &str) -> Option<String> {
+        self.env.var(name)
+    }
+}
+
+/// When a factory in a `DynamicBuilder` is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    /// The factory is used in every profile.
+    Always,
+    /// The factory is only used in a profile.
+    Profile(Profile),
+    /// The factory is only used if a predicate holds. This is the name of
+    /// the predicate's type.
+    If(&'static str),
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Condition::Always => f.write_str("always"),
+            Condition::Profile(profile) => write!(f, "in the `{}` profile", profile),
+            Condition::If(predicate) => write!(f, "if `{}`", predicate),
+        }
+    }
+}
+
+type Predicate = Box<dyn Fn(&BuildContext) -> bool>;
+
+/// A factory registered on a builder.
+struct Registration {
+    condition: Condition,
+    predicate: Option<Predicate>,
+    key: TypeId,
+    entry: Entry,
+}
+
+impl Registration {
+    fn holds(&self, context: &BuildContext) -> bool {
+        match (self.condition, &self.predicate) {
+            (Condition::Profile(profile), _) => profile == context.profile,
+            (_, Some(predicate)) => predicate(context),
+            _ => true,
+        }
+    }
+}
+
+/// A type with more than one factory registered for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contested {
+    pub type_name: &'static str,
+    /// The condition of each factory, in the order they were registered,
+    /// and whether it held.
+    pub candidates: Vec<(Condition, bool)>,
+    /// The index of the candidate that was used, if any of them held.
+    pub winner: Option<usize>,
+}
+
+impl fmt::Display for Contested {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}` has {} factories", self.type_name, self.candidates.len())?;
+
+        if self.winner.is_none() {
+            f.write_str(", but none of their conditions held")?;
+        }
+
+        for (i, &(condition, held)) in self.candidates.iter().enumerate() {
+            let outcome = match (Some(i) == self.winner, held) {
+                (true, _) => "used",
+                (false, true) => "replaced",
+                (false, false) => "didn't hold",
+            };
+
+            write!(f, "\n  {}: {}", condition, outcome)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Factories for a `DynamicContainer`, some of which are only used under
+/// some condition.
+#[derive(Default)]
+pub struct DynamicBuilder {
+    registrations: Vec<Registration>,
+}
+
+impl DynamicBuilder {
+    pub fn new() -> Self {
+        DynamicBuilder::default()
+    }
+
+    fn add<T, F>(&mut self, condition: Condition, predicate: Option<Predicate>, f: F) -> &mut Self
+        where T: 'static,
+              F: Fn(&DynamicContainer) -> T + 'static
+    {
+        self.registrations.push(Registration {
+            condition,
+            predicate,
+            key: TypeId::of::<T>(),
+            entry: Entry::new(f),
+        });
+
+        self
+    }
+
+    /// Register a factory for `T` in every profile.
+    pub fn register<T, F>(&mut self, f: F) -> &mut Self
+        where T: 'static,
+              F: Fn(&DynamicContainer) -> T + 'static
+    {
+        self.add(Condition::Always, None, f)
+    }
+
+    /// Register a factory for `T` that's only used in `profile`.
+    ///
+    /// It replaces any factory for `T` that's always used.
+    pub fn register_for<T, F>(&mut self, profile: Profile, f: F) -> &mut Self
+        where T: 'static,
+              F: Fn(&DynamicContainer) -> T + 'static
+    {
+        self.add(Condition::Profile(profile), None, f)
+    }
+
+    /// Register a factory for `T` that's only used if `predicate` holds
+    /// when the container is built.
+    ///
+    /// It replaces any factory for `T` that's always used.
+    pub fn register_if<T, P, F>(&mut self, predicate: P, f: F) -> &mut Self
+        where T: 'static,
+              P: Fn(&BuildContext) -> bool + 'static,
+              F: Fn(&DynamicContainer) -> T + 'static
+    {
+        self.add(Condition::If(type_name::<P>()), Some(Box::new(predicate)), f)
+    }
+
+    /// Register a `Resolvable` type in every profile.
+    pub fn register_resolvable<T, D>(&mut self) -> &mut Self
+        where T: Resolvable<DynamicContainer, Dependency = D> + 'static,
+              D: ResolvableFromContainer<DynamicContainer>
+    {
+        self.register::<T, _>(|container| container.resolve::<D, T>())
+    }
+
+    /// Register a `Resolvable` type that's only used in `profile`.
+    pub fn register_resolvable_for<T, D>(&mut self, profile: Profile) -> &mut Self
+        where T: Resolvable<DynamicContainer, Dependency = D> + 'static,
+              D: ResolvableFromContainer<DynamicContainer>
+    {
+        self.register_for::<T, _>(profile, |container| container.resolve::<D, T>())
+    }
+
+    /// Build a container for `profile`, reading the process environment.
+    pub fn build(self, profile: Profile) -> DynamicContainer {
+        self.build_in(BuildContext::new(profile))
+    }
+
+    /// Build a container with the factories whose conditions hold in `context`.
+    ///
+    /// Factories with a condition replace any that are always used.
+    /// Otherwise, later factories for a type replace earlier ones.
+    pub fn build_in(self, context: BuildContext) -> DynamicContainer {
+        let mut container = DynamicContainer::new();
+        container.profile = Some(context.profile);
+
+        let mut order = Vec::new();
+        let mut candidates: HashMap<TypeId, Vec<(Registration, bool)>> = HashMap::default();
+
+        for registration in self.registrations {
+            let held = registration.holds(&context);
+
+            candidates
+                .entry(registration.key)
+                .or_insert_with(|| {
+                    order.push(registration.key);
+
+                    Vec::new()
+                })
+                .push((registration, held));
+        }
+
+        for key in order {
+            let candidates = candidates.remove(&key).expect("every key has candidates");
+
+            let winner = {
+                let held = |conditional: bool| {
+                    candidates.iter().rposition(|&(ref registration, held)| {
+                        held && (registration.condition != Condition::Always) == conditional
+                    })
+                };
+
+                held(true).or_else(|| held(false))
+            };
+
+            if candidates.len() > 1 {
+                container.contested.push(Contested {
+                    type_name: candidates[0].0.entry.type_name,
+                    candidates: candidates.iter().map(|&(ref registration, held)| (registration.condition, held)).collect(),
+                    winner,
+                });
+            }
+
+            if let Some(winner) = winner {
+                let (registration, _) = candidates.into_iter().nth(winner).expect("the winner is a candidate");
+
+                container.factories.insert(key, registration.entry);
+            }
+        }
+
+        container
+    }
+}
+
+/// A container with bindings decided at runtime.
+#[derive(Default)]
+pub struct DynamicContainer {
+    factories: HashMap<TypeId, Entry>,
+    profile: Option<Profile>,
+    contested: Vec<Contested>,
+}
+
+impl DynamicContainer {
+    pub fn new() -> Self {
+        DynamicContainer::default()
+    }
+
+    /// Collect factories, some of which are only for a `Profile`, before
+    /// building a container.
+    pub fn builder() -> DynamicBuilder {
+        DynamicBuilder::new()
+    }
+
+    /// The profile the container was built for, if it was built with one.
+    pub fn profile(&self) -> Option<Profile> {
+        self.profile
+    }
+
+    /// The types that had more than one factory when the container was
+    /// built, and which factory each one used.
+    pub fn contested(&self) -> &[Contested] {
+        &self.contested
+    }
+
+    /// Register a factory for `T`, replacing any previous one.
+    pub fn register<T, F>(&mut self, f: F) -> &mut Self
+        where T: 'static,
+              F: Fn(&DynamicContainer) -> T + 'static
+    {
+        self.factories.insert(TypeId::of::<T>(), Entry::new(f));
+
+        self
+    }
+
+    /// Register a `Resolvable` type, replacing any previous factory for it.
+    ///
+    /// Its dependencies are resolved from this container each time it's
+    /// requested.
+    pub fn register_resolvable<T, D>(&mut self) -> &mut Self
+        where T: Resolvable<Self, Dependency = D> + 'static,
+              D: ResolvableFromContainer<Self>
+    {
+        self.register::<T, _>(|container| container.resolve::<D, T>())
+    }
+
+    /// Whether or not there's a factory for `T`.
+    pub fn is_registered<T>(&self) -> bool
+        where T: 'static
+    {
+        self.factories.contains_key(&TypeId::of::<T>())
+    }
+
+    /// The names of the registered types.
+    pub fn type_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.factories.values().map(|entry| entry.type_name)
+    }
+
+    /// Create a `T` using its factory, if there is one.
+    pub fn get<T>(&self) -> Option<T>
+        where T: 'static
+    {
+        let entry = self.factories.get(&TypeId::of::<T>())?;
+
+        let _resolving = Resolving::enter::<_, T>(self);
+
+        let t = (entry.factory)(self)
+            .downcast::<T>()
+            .expect("factories are keyed by their type");
+
+        Some(*t)
+    }
+}
+
+impl Container for DynamicContainer {}
+
+impl fmt::Debug for DynamicContainer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.type_names()).finish()
+    }
+}
+
+/// A `T` created by a `DynamicContainer`'s factory.
+pub struct Dyn<T> {
+    value: T,
+}
+
+impl<T> Dyn<T> {
+    pub fn new(value: T) -> Self {
+        Dyn { value }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Dyn<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> ResolvableFromContainer<DynamicContainer> for Dyn<T>
+    where T: 'static
+{
+    fn resolve_from_container(container: &DynamicContainer) -> Self {
+        match container.get() {
+            Some(value) => Dyn::new(value),
+            None => {
+                let _resolving = Resolving::enter::<_, Self>(container);
+
+                missing_binding(type_name::<T>())
+            }
+        }
+    }
+
+    fn verify(container: &DynamicContainer, verification: &mut Verification) {
+        verification.visit(DependencyKind::Dynamic, type_name::<Self>(), |verification| {
+            if !container.is_registered::<T>() {
+                verification.missing_binding(type_name::<T>());
+            }
+        });
+    }
+}
@@ -0,0 +1,137 @@
+//! Environment variables
+//!
+//! Simple values, like a connection string or a port, can be resolved
+//! straight from an environment variable. Each variable is named by a
+//! marker type, so a service depends on `Env<DatabaseUrl>` rather than a
+//! bare `String`:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # struct Db;
+//! # impl Db {
+//! #     fn connect(_: &str, _: usize) -> Self { Db }
+//! # }
+//! # struct PoolSize;
+//! # impl EnvVar for PoolSize {
+//! #     const NAME: &'static str = "POOL_SIZE";
+//! #     type Value = usize;
+//! # }
+//! struct DatabaseUrl;
+//!
+//! impl EnvVar for DatabaseUrl {
+//!     const NAME: &'static str = "DATABASE_URL";
+//!     type Value = String;
+//! }
+//!
+//! impl Resolvable<Scoped> for Db {
+//!     type Dependency = (Env<DatabaseUrl>, Env<PoolSize>);
+//!
+//!     fn resolve((url, pool_size): Self::Dependency) -> Self {
+//!         Db::connect(&url, *pool_size)
+//!     }
+//! }
+//! ```
+//!
+//! A variable that isn't set, or can't be parsed, panics. `try_resolve`
+//! returns these as `Error::MissingEnvVar` and `Error::InvalidEnvVar`.
+//!
+//! Variables are read from the process environment, unless there's a
+//! binding for `dyn EnvProvider`. Tests can bind a `MemoryEnv` instead.
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+/// A marker type for an environment variable.
+pub trait EnvVar {
+    /// The name of the variable.
+    const NAME: &'static str;
+
+    /// The value to use if the variable isn't set.
+    const DEFAULT: Option<&'static str> = None;
+
+    /// The type the variable is parsed as.
+    type Value: FromStr;
+}
+
+/// A source of environment variables.
+pub trait EnvProvider {
+    fn var(&self, name: &str) -> Option<String>;
+}
+
+/// The environment variables of the current process.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ProcessEnv;
+
+impl EnvProvider for ProcessEnv {
+    fn var(&self, name: &str) -> Option<String> {
+        env::var(name).ok()
+    }
+}
+
+/// Environment variables held in memory, for tests.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryEnv {
+    vars: HashMap<String, String>,
+}
+
+impl MemoryEnv {
+    pub fn new() -> Self {
+        MemoryEnv::default()
+    }
+
+    pub fn with(mut self, name: &str, value: &str) -> Self {
+        self.vars.insert(name.to_owned(), value.to_owned());
+
+        self
+    }
+}
+
+impl EnvProvider for MemoryEnv {
+    fn var(&self, name: &str) -> Option<String> {
+        self.vars.get(name).cloned()
+    }
+}
+
+/// The value of the environment variable `K`.
+pub struct Env<K>
+    where K: EnvVar
+{
+    value: K::Value,
+}
+
+impl<K> Env<K>
+    where K: EnvVar
+{
+    pub fn new(value: K::Value) -> Self {
+        Env { value }
+    }
+
+    pub fn into_inner(self) -> K::Value {
+        self.value
+    }
+}
+
+impl<K> Deref for Env<K>
+    where K: EnvVar
+{
+    type Target = K::Value;
+
+    fn deref(&self) -> &K::Value {
+        &self.value
+    }
+}
+
+impl<K> fmt::Debug for Env<K>
+    where K: EnvVar,
+          K::Value: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Env")
+            .field("name", &K::NAME)
+            .field("value", &self.value)
+            .finish()
+    }
+}
@@ -0,0 +1,293 @@
+//! Errors raised while resolving
+//!
+//! Resolution isn't fallible on its own: a missing binding or a cycle
+//! panics like any other failure in user code. The chain of types being
+//! resolved is tracked as dependencies are constructed, so these panics say
+//! what was being resolved when they happened, and `Container::try_resolve`
+//! can return them as an `Error` instead of unwinding.
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::error;
+use std::fmt;
+use std::thread;
+
+use super::{ResolveError, TypeName};
+use super::names::{short_name, short_path};
+
+/// A dependency couldn't be resolved.
+#[derive(Debug)]
+pub enum Error {
+    /// A `Bound<T>` dependency with no binding for `T`.
+    MissingBinding {
+        type_name: &'static str,
+        /// The chain of dependencies that led to the missing binding.
+        path: Vec<&'static str>,
+    },
+    /// A dependency that depends on itself.
+    Cycle {
+        /// The chain of dependencies, ending with the type that was
+        /// already being resolved.
+        path: Vec<&'static str>,
+    },
+    /// An `Env<K>` dependency whose environment variable isn't set.
+    MissingEnvVar {
+        name: &'static str,
+        /// The chain of dependencies that needed the variable.
+        path: Vec<&'static str>,
+    },
+    /// An `Env<K>` dependency whose environment variable couldn't be parsed.
+    InvalidEnvVar {
+        name: &'static str,
+        value: String,
+        /// Why the value couldn't be parsed.
+        message: String,
+        /// The chain of dependencies that needed the variable.
+        path: Vec<&'static str>,
+    },
+    /// An `Args<T>` dependency whose command-line arguments couldn't be parsed.
+    InvalidArgs {
+        type_name: &'static str,
+        /// Why the arguments couldn't be parsed.
+        message: String,
+        /// The chain of dependencies that needed the arguments.
+        path: Vec<&'static str>,
+    },
+    /// A `Context<T>` dependency whose context wasn't set on the scope.
+    MissingContext {
+        type_name: &'static str,
+        /// The chain of dependencies that needed the context.
+        path: Vec<&'static str>,
+    },
+    /// A dependency that an `on_resolving` hook refused to resolve.
+    Vetoed {
+        type_name: &'static str,
+        /// Why the hook refused it.
+        reason: String,
+        /// The chain of dependencies, ending with the vetoed type.
+        path: Vec<&'static str>,
+    },
+    /// Constructing a dependency panicked.
+    ConstructionFailed {
+        /// The chain of dependencies that were being resolved when it panicked.
+        path: Vec<&'static str>,
+        source: ResolveError,
+    },
+}
+
+impl Error {
+    /// The chain of dependencies that were being resolved.
+    pub fn path(&self) -> &[&'static str] {
+        match *self {
+            Error::MissingBinding { ref path, .. } => path,
+            Error::Cycle { ref path } => path,
+            Error::MissingEnvVar { ref path, .. } => path,
+            Error::InvalidEnvVar { ref path, .. } => path,
+            Error::InvalidArgs { ref path, .. } => path,
+            Error::MissingContext { ref path, .. } => path,
+            Error::Vetoed { ref path, .. } => path,
+            Error::ConstructionFailed { ref path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::MissingBinding { type_name, ref path } => {
+                write!(f, "there's no binding for `{}` (required by {})", short_name(type_name), short_path(path))
+            }
+            Error::Cycle { ref path } => write!(f, "there's a dependency cycle: {}", short_path(path)),
+            Error::MissingEnvVar { name, ref path } => {
+                write!(f, "the environment variable `{}` isn't set (required by {})", name, short_path(path))
+            }
+            Error::InvalidEnvVar { name, ref value, ref message, ref path } => {
+                write!(f, "the environment variable `{}` has an invalid value `{}`: {} (required by {})",
+                       name, value, message, short_path(path))
+            }
+            Error::InvalidArgs { type_name, ref message, ref path } => {
+                write!(f, "the command-line arguments couldn't be parsed as `{}`: {} (required by {})",
+                       short_name(type_name), message, short_path(path))
+            }
+            Error::MissingContext { type_name, ref path } => {
+                write!(f, "the scope has no `{}` context (required by {})", short_name(type_name), short_path(path))
+            }
+            Error::Vetoed { type_name, ref reason, ref path } => {
+                write!(f, "resolving `{}` was vetoed: {} (required by {})", short_name(type_name), reason, short_path(path))
+            }
+            Error::ConstructionFailed { ref path, .. } => write!(f, "constructing {} failed", short_path(path)),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::ConstructionFailed { ref source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// A type that's being resolved in a particular container.
+struct Frame {
+    // the address of the container and the type, or `None` if the type
+    // is only tracked for its name
+    key: Option<(usize, TypeId)>,
+    name: TypeName,
+}
+
+#[derive(Default)]
+struct State {
+    // the types currently being resolved, outermost first
+    path: Vec<Frame>,
+    // the path when the current panic started unwinding
+    unwound: Option<Vec<&'static str>>,
+    // the error the current panic was raised for
+    failed: Option<Error>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+/// A type that's being resolved.
+///
+/// The type is part of the path until the guard is dropped.
+pub(super) struct Resolving(());
+
+impl Resolving {
+    /// Start resolving `T` in `container`.
+    ///
+    /// If `T` is already being resolved in the same container then there's
+    /// a cycle, which panics instead of recursing until the stack overflows.
+    /// Other containers, like a child scope created while resolving, can
+    /// resolve `T` again.
+    pub(super) fn enter<C, T>(container: &C) -> Self
+        where T: ?Sized + 'static
+    {
+        let key = (container as *const C as usize, TypeId::of::<T>());
+
+        Resolving::push(Frame { key: Some(key), name: TypeName::of::<T>() })
+    }
+
+    /// Start resolving `type_name` without checking for a cycle.
+    ///
+    /// This is for types that are resolved anew each time, so they're only
+    /// part of the path for its name.
+    pub(super) fn enter_named(type_name: &'static str) -> Self {
+        Resolving::push(Frame { key: None, name: TypeName::from_static(type_name) })
+    }
+
+    fn push(frame: Frame) -> Self {
+        let cycle = STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            let cycle = frame.key.is_some() && state.path.iter().any(|resolving| resolving.key == frame.key);
+
+            state.path.push(frame);
+
+            cycle
+        });
+
+        // the guard pops `type_name` again while unwinding
+        let resolving = Resolving(());
+
+        if cycle {
+            fail(Error::Cycle { path: path() });
+        }
+
+        resolving
+    }
+}
+
+impl Drop for Resolving {
+    fn drop(&mut self) {
+        STATE.with(|state| {
+            let mut state = state.borrow_mut();
+
+            // the innermost guard sees the full path
+            if thread::panicking() && state.unwound.is_none() {
+                state.unwound = Some(state.path.iter().map(|frame| frame.name.full()).collect());
+            }
+
+            state.path.pop();
+        });
+    }
+}
+
+fn path() -> Vec<&'static str> {
+    STATE.with(|state| state.borrow().path.iter().map(|frame| frame.name.full()).collect())
+}
+
+fn fail(error: Error) -> ! {
+    let message = error.to_string();
+
+    STATE.with(|state| state.borrow_mut().failed = Some(error));
+
+    panic!("{}", message)
+}
+
+/// Panic because there's no binding for `type_name`.
+pub(super) fn missing_binding(type_name: &'static str) -> ! {
+    fail(Error::MissingBinding { type_name, path: path() })
+}
+
+/// Panic because the environment variable `name` isn't set.
+pub(super) fn missing_env_var(name: &'static str) -> ! {
+    fail(Error::MissingEnvVar { name, path: path() })
+}
+
+/// Panic because the environment variable `name` couldn't be parsed.
+pub(super) fn invalid_env_var(name: &'static str, value: String, message: String) -> ! {
+    fail(Error::InvalidEnvVar { name, value, message, path: path() })
+}
+
+/// Panic because the command-line arguments couldn't be parsed as `type_name`.
+pub(super) fn invalid_args(type_name: &'static str, message: String) -> ! {
+    fail(Error::InvalidArgs { type_name, message, path: path() })
+}
+
+/// Panic because the scope has no context of type `type_name`.
+pub(super) fn missing_context(type_name: &'static str) -> ! {
+    fail(Error::MissingContext { type_name, path: path() })
+}
+
+/// Panic because a hook vetoed `type_name`.
+pub(super) fn vetoed(type_name: &'static str, reason: String) -> ! {
+    let mut path = path();
+    path.push(type_name);
+
+    fail(Error::Vetoed { type_name, reason, path })
+}
+
+/// Forget about any previous panic before resolving.
+pub(super) fn reset() {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        state.unwound = None;
+        state.failed = None;
+    });
+}
+
+/// The error for a panic caught while resolving `type_name`.
+pub(super) fn caught(type_name: &'static str, payload: Box<dyn Any + Send>) -> Error {
+    let (unwound, failed) = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        (state.unwound.take(), state.failed.take())
+    });
+
+    match failed {
+        Some(error) => error,
+        None => {
+            let path = unwound.unwrap_or_else(|| vec![type_name]);
+            let type_name = path.last().cloned().unwrap_or(type_name);
+
+            Error::ConstructionFailed {
+                path,
+                source: ResolveError::Panicked { type_name, payload },
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Events
+//!
+//! Commands have a single handler, but events can have any number of
+//! subscribers, or none. Subscribers implement `Handles<E>` and are bound
+//! in the registry alongside each other:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use ioc::factories::Handles;
+//! # struct ProductRenamed { id: u32 }
+//! # struct ReindexProduct;
+//! # struct NotifyWatchers;
+//! # impl<C> Resolvable<C> for ReindexProduct {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { ReindexProduct }
+//! # }
+//! # impl<C> Resolvable<C> for NotifyWatchers {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { NotifyWatchers }
+//! # }
+//! # impl Handles<ProductRenamed> for ReindexProduct {
+//! #     fn handle(&self, _: &ProductRenamed) {}
+//! # }
+//! # impl Handles<ProductRenamed> for NotifyWatchers {
+//! #     fn handle(&self, _: &ProductRenamed) {}
+//! # }
+//! # let mut registry = Registry::<Scoped>::new();
+//! registry
+//!     .subscribe::<ProductRenamed, ReindexProduct, _>()
+//!     .subscribe::<ProductRenamed, NotifyWatchers, _>();
+//! ```
+//!
+//! Services publish events through an `Rc<Events<E>>`, which resolves every
+//! subscriber from the same scope:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use ioc::factories::Events;
+//! # use std::rc::Rc;
+//! # struct ProductRenamed { id: u32 }
+//! # let events: Rc<Events<ProductRenamed>> = Scoped::new().with_registry(Rc::new(Registry::new())).get_or_add();
+//! events.publish(ProductRenamed { id: 1 });
+//! events.defer(ProductRenamed { id: 1 });
+//! ```
+//!
+//! Published events are handled straight away. Deferred ones are queued
+//! until the `Events` is dropped, which is when its scope ends if it's
+//! shared, so subscribers only see them once everything else in the scope
+//! has been done. Deferred events are discarded if the scope ends because
+//! of a panic.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::mem;
+use std::rc::Rc;
+use std::thread;
+
+use super::*;
+
+/// A subscriber to `E` events.
+pub trait Handles<E> {
+    fn handle(&self, event: &E);
+}
+
+/// Publishes `E` events to each of their subscribers.
+pub struct Events<E>
+    where E: 'static
+{
+    subscribers: All<dyn Handles<E>>,
+    deferred: RefCell<Vec<E>>,
+}
+
+impl<E> Events<E>
+    where E: 'static
+{
+    pub fn new(subscribers: All<dyn Handles<E>>) -> Self {
+        Events {
+            subscribers,
+            deferred: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Handle an event with each subscriber, in the order they were bound.
+    pub fn publish(&self, event: E) {
+        for subscriber in &self.subscribers {
+            subscriber.handle(&event);
+        }
+    }
+
+    /// Queue an event to be published when the `Events` is dropped.
+    pub fn defer(&self, event: E) {
+        self.deferred.borrow_mut().push(event);
+    }
+
+    /// Publish each deferred event now, in the order they were deferred.
+    ///
+    /// Events deferred by subscribers while this runs are published too.
+    pub fn flush(&self) {
+        loop {
+            // subscribers can defer more events while these are published
+            let deferred = mem::take(&mut *self.deferred.borrow_mut());
+
+            if deferred.is_empty() {
+                return;
+            }
+
+            for event in deferred {
+                self.publish(event);
+            }
+        }
+    }
+
+    /// The number of subscribers.
+    pub fn subscribers(&self) -> usize {
+        self.subscribers.len()
+    }
+
+    /// The number of events waiting to be published.
+    pub fn deferred(&self) -> usize {
+        self.deferred.borrow().len()
+    }
+}
+
+impl<E> Drop for Events<E>
+    where E: 'static
+{
+    fn drop(&mut self) {
+        // the scope may be half-finished, so don't claim anything happened
+        if !thread::panicking() {
+            self.flush();
+        }
+    }
+}
+
+impl<E> fmt::Debug for Events<E>
+    where E: 'static
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Events")
+            .field("subscribers", &self.subscribers())
+            .field("deferred", &self.deferred())
+            .finish()
+    }
+}
+
+impl<C, E> Resolvable<C> for Events<E>
+    where C: RegisteredContainer + 'static,
+          E: 'static
+{
+    type Dependency = All<dyn Handles<E>>;
+
+    fn resolve(subscribers: Self::Dependency) -> Self {
+        Events::new(subscribers)
+    }
+}
+
+impl<C> Registry<C>
+    where C: Container + 'static
+{
+    /// Subscribe an `H` resolved from the container to `E` events,
+    /// alongside any other subscribers.
+    pub fn subscribe<E, H, D>(&mut self) -> &mut Self
+        where E: 'static,
+              H: Resolvable<C, Dependency = D> + Handles<E> + 'static,
+              D: ResolvableFromContainer<C>
+    {
+        self.bind_all_to::<dyn Handles<E>, H, D, _>(|handler: H| Rc::new(handler) as Rc<dyn Handles<E>>)
+    }
+}
@@ -0,0 +1,164 @@
+//! Third-party dependencies
+//!
+//! Orphan rules mean `Resolvable` can't be implemented for a type from
+//! another crate, like a connection pool or a clock. Instead, the registry
+//! can hold a factory for it, and services depend on an `External<T>`:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use std::rc::Rc;
+//! # struct DatabaseUrl;
+//! # impl EnvVar for DatabaseUrl {
+//! #     const NAME: &'static str = "DATABASE_URL";
+//! #     type Value = String;
+//! # }
+//! # struct Manager;
+//! # impl Manager { fn new(_: &str) -> Self { Manager } }
+//! # struct Pool<M>(M);
+//! # impl<M> Pool<M> { fn new(manager: M) -> Self { Pool(manager) } }
+//! # struct Repository { pool: Rc<External<Pool<Manager>>> }
+//! # let mut registry = Registry::<Scoped>::new();
+//! registry.bind_external_with::<Pool<Manager>, _, _>(|url: Env<DatabaseUrl>| {
+//!     Pool::new(Manager::new(&url))
+//! });
+//!
+//! impl Resolvable<Scoped> for Repository {
+//!     type Dependency = Rc<External<Pool<Manager>>>;
+//!
+//!     fn resolve(pool: Self::Dependency) -> Self {
+//!         Repository { pool }
+//!     }
+//! }
+//! ```
+//!
+//! An `External<T>` is a new `T` each time it's resolved, but it's also
+//! `Resolvable` itself, so an `Rc<External<T>>` is shared for the scope like
+//! any other dependency.
+
+use std::any::type_name;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use super::*;
+use super::error::{missing_binding, Resolving};
+use super::registry::VerifyBinding;
+
+/// The factory for an external `T`, bound in the registry.
+struct ExternalFactory<C, T> {
+    factory: Box<dyn Fn(&C) -> T>,
+}
+
+/// A value of a third-party type `T`, created by a factory in the registry.
+pub struct External<T> {
+    value: T,
+}
+
+impl<T> External<T> {
+    pub fn new(value: T) -> Self {
+        External { value }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for External<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for External<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T> fmt::Debug for External<T>
+    where T: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("External").field(&self.value).finish()
+    }
+}
+
+impl<C, T> Resolvable<C> for External<T>
+    where C: RegisteredContainer + 'static,
+          T: 'static
+{
+    type Dependency = Self;
+
+    fn resolve(external: Self::Dependency) -> Self {
+        external
+    }
+}
+
+// `External`s are created by their factory in the container's registry,
+// and panic if there isn't one.
+impl<C, T> ResolvableFromContainer<C> for External<T>
+    where C: RegisteredContainer + 'static,
+          T: 'static
+{
+    fn resolve_from_container(container: &C) -> Self {
+        let _resolving = Resolving::enter::<_, T>(container);
+
+        let factory = container
+            .registry()
+            .and_then(|registry| registry.resolve::<ExternalFactory<C, T>>(container))
+            .unwrap_or_else(|| missing_binding(type_name::<T>()));
+
+        External::new((factory.factory)(container))
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::External, type_name::<T>(), |verification| match container.registry() {
+            Some(registry) if registry.is_bound::<ExternalFactory<C, T>>() => {
+                registry.verify::<ExternalFactory<C, T>>(container, verification)
+            }
+            _ => verification.missing_binding(type_name::<T>()),
+        });
+    }
+}
+
+impl<C> Registry<C>
+    where C: Container + 'static
+{
+    /// Create each `External<T>` with `f`, replacing any previous factory.
+    #[track_caller]
+    pub fn bind_external<T, F>(&mut self, f: F) -> &mut Self
+        where T: 'static,
+              F: Fn(&C) -> T + 'static
+    {
+        let factory = Rc::new(ExternalFactory { factory: Box::new(f) });
+
+        self.bind_implementation::<ExternalFactory<C, T>>(Box::new(move |_| factory.clone()), Implementation::closure::<F>(Lifetime::Transient), None)
+    }
+
+    /// Create each `External<T>` from its dependencies with `f`, replacing
+    /// any previous factory.
+    ///
+    /// This is like `bind_external`, but because the dependencies are known
+    /// they can be verified.
+    #[track_caller]
+    pub fn bind_external_with<T, D, F>(&mut self, f: F) -> &mut Self
+        where T: 'static,
+              D: ResolvableFromContainer<C>,
+              F: Fn(D) -> T + 'static
+    {
+        let factory = Rc::new(ExternalFactory { factory: Box::new(move |container: &C| f(D::resolve_from_container(container))) });
+        let verify: VerifyBinding<C> = verify_external::<C, F, D>;
+
+        self.bind_implementation::<ExternalFactory<C, T>>(Box::new(move |_| factory.clone()), Implementation::closure::<F>(Lifetime::Transient), Some(verify))
+    }
+}
+
+/// Verify the dependencies of the factory `F` for an external type.
+fn verify_external<C, F, D>(container: &C, verification: &mut Verification)
+    where C: Container,
+          D: ResolvableFromContainer<C>
+{
+    verification.visit(DependencyKind::Implementation, type_name::<F>(), |verification| D::verify(container, verification));
+}
@@ -0,0 +1,395 @@
+//! Queries and commands
+//!
+//! A query or command separates the dependencies it's constructed from
+//! from the inputs of the action it performs. Any closure can be one, so
+//! they're usually returned from functions that capture their
+//! dependencies:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # struct DbConnection;
+//! # impl DbConnection {
+//! #     fn query(&self, id: u32) -> Result<Product, String> { Ok(Product { id }) }
+//! #     fn update(&self, _: u32, _: String) -> Result<(), String> { Ok(()) }
+//! # }
+//! # impl<C> Resolvable<C> for DbConnection {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { DbConnection }
+//! # }
+//! # #[derive(Debug, PartialEq)]
+//! # struct Product { id: u32 }
+//! # struct GetProduct { id: u32 }
+//! fn get_product(conn: DbConnection) -> impl Query<GetProduct, Result<Product, String>> {
+//!     move |action: GetProduct| conn.query(action.id)
+//! }
+//! ```
+//!
+//! Those functions can be bound in a registry, and services depend on a
+//! `QueryOf<Req, Res>` or `CommandOf<Req, E>` without knowing how it's
+//! constructed:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use std::rc::Rc;
+//! # struct DbConnection;
+//! # impl DbConnection {
+//! #     fn query(&self, id: u32) -> Result<Product, String> { Ok(Product { id }) }
+//! #     fn update(&self, _: u32, _: String) -> Result<(), String> { Ok(()) }
+//! # }
+//! # impl<C> Resolvable<C> for DbConnection {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { DbConnection }
+//! # }
+//! # #[derive(Debug, PartialEq)]
+//! # struct Product { id: u32 }
+//! # struct GetProduct { id: u32 }
+//! # fn get_product(conn: DbConnection) -> impl Query<GetProduct, Result<Product, String>> {
+//! #     move |action: GetProduct| conn.query(action.id)
+//! # }
+//! # let mut registry = Registry::new();
+//! registry.bind_query(|conn: Transient<DbConnection>| get_product(conn.into_inner()));
+//! # let scope = Scoped::new().with_registry(Rc::new(registry));
+//!
+//! let product = scope.invoke(|get_product: QueryOf<GetProduct, Result<Product, String>>| {
+//!     get_product.query(GetProduct { id: 1 })
+//! });
+//! # assert_eq!(Ok(Product { id: 1 }), product);
+//! ```
+//!
+//! A new query or command is constructed from its dependencies each time
+//! it's resolved. Logging, retries and caching can be layered onto one
+//! with `with_logging`, `with_retry` and `with_cache`.
+//!
+//! Queries and commands that shouldn't block can be `AsyncQuery`s and
+//! `AsyncCommand`s instead, which are bound with `bind_async_query` and
+//! `bind_async_command`.
+//!
+//! Events are published to any number of subscribers through `Events<E>`,
+//! which resolves each one bound with `Registry::subscribe`.
+//!
+//! Giving each action its own trait, like a `GetProductQuery`, documents
+//! what it does better than the full `Query<Req, Res>`. The `query!` and
+//! `command!` macros write that trait, its blanket impl, and the function
+//! that constructs one from a single signature:
+//!
+//! ```
+//! # #[macro_use] extern crate ioc;
+//! # use ioc::prelude::*;
+//! # struct DbConnection;
+//! # impl DbConnection {
+//! #     fn query(&self, id: u32) -> Result<Product, String> { Ok(Product { id }) }
+//! #     fn update(&self, _: u32, _: String) -> Result<(), String> { Ok(()) }
+//! # }
+//! # struct Product { id: u32 }
+//! # struct GetProduct { id: u32 }
+//! # struct SetProductTitle { id: u32, title: String }
+//! query! {
+//!     pub fn get_product(conn: DbConnection) -> impl GetProductQuery(action: GetProduct) -> Result<Product, String> {
+//!         conn.query(action.id)
+//!     }
+//! }
+//!
+//! command! {
+//!     pub fn set_product_title<Q: GetProductQuery>(conn: DbConnection, get_product: Q) -> impl SetProductTitleCommand(action: SetProductTitle) -> Result<(), String> {
+//!         let product = get_product.query(GetProduct { id: action.id })?;
+//!
+//!         conn.update(product.id, action.title)
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+
+use std::any::type_name;
+use std::fmt;
+use std::rc::Rc;
+
+use super::*;
+use super::error::{missing_binding, Resolving};
+use super::registry::VerifyBinding;
+
+pub use super::bus::{CommandBus, Behavior, Dispatch, DispatchError, Failure, Middleware, Next, Retry};
+pub use super::async_factories::{AsyncQuery, AsyncCommand, AsyncQueryOf, AsyncCommandOf, BoxFuture, into_async, blocking, IntoAsync, Blocking};
+pub use super::events::{Handles, Events};
+pub use super::decorators::{with_logging, with_retry, with_cache, Logged, Retried, Cached};
+
+/// A query that returns a `Res` for each `Req`.
+///
+/// Any `Fn(Req) -> Res` is a query.
+pub trait Query<Req, Res> {
+    fn query(&self, request: Req) -> Res;
+}
+
+impl<F, Req, Res> Query<Req, Res> for F
+    where F: Fn(Req) -> Res
+{
+    fn query(&self, request: Req) -> Res {
+        self(request)
+    }
+}
+
+/// A command that's executed once with a `Req`.
+///
+/// Any `FnOnce(Req) -> Result<(), E>` is a command.
+pub trait Command<Req> {
+    type Error;
+
+    fn execute(self, request: Req) -> Result<(), Self::Error>;
+}
+
+impl<F, Req, E> Command<Req> for F
+    where F: FnOnce(Req) -> Result<(), E>
+{
+    type Error = E;
+
+    fn execute(self, request: Req) -> Result<(), E> {
+        self(request)
+    }
+}
+
+/// A query bound in a container's registry.
+pub struct QueryOf<Req, Res> {
+    query: Rc<dyn Query<Req, Res>>,
+}
+
+impl<Req, Res> QueryOf<Req, Res> {
+    pub fn new<Q>(query: Q) -> Self
+        where Q: Query<Req, Res> + 'static
+    {
+        QueryOf { query: Rc::new(query) }
+    }
+}
+
+impl<Req, Res> Clone for QueryOf<Req, Res> {
+    fn clone(&self) -> Self {
+        QueryOf { query: self.query.clone() }
+    }
+}
+
+impl<Req, Res> Query<Req, Res> for QueryOf<Req, Res> {
+    fn query(&self, request: Req) -> Res {
+        self.query.query(request)
+    }
+}
+
+impl<Req, Res> fmt::Debug for QueryOf<Req, Res> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(type_name::<Self>())
+    }
+}
+
+/// A command bound in a container's registry.
+pub struct CommandOf<Req, E> {
+    command: Box<dyn FnOnce(Req) -> Result<(), E>>,
+}
+
+impl<Req, E> CommandOf<Req, E> {
+    pub fn new<K>(command: K) -> Self
+        where K: Command<Req, Error = E> + 'static
+    {
+        CommandOf { command: Box::new(move |request| command.execute(request)) }
+    }
+}
+
+impl<Req, E> Command<Req> for CommandOf<Req, E> {
+    type Error = E;
+
+    fn execute(self, request: Req) -> Result<(), E> {
+        (self.command)(request)
+    }
+}
+
+impl<Req, E> fmt::Debug for CommandOf<Req, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(type_name::<Self>())
+    }
+}
+
+/// The function that builds a `T` from a container, bound in its registry.
+pub(super) struct Builder<C, T> {
+    pub(super) build: Box<dyn Fn(&C) -> T>,
+}
+
+/// Build a `T` with the builder in the container's registry.
+///
+/// The binding is named after the trait object `N` a `T` is built for, so
+/// it's distinct from the `T` that depends on it.
+pub(super) fn build<C, T, N>(container: &C) -> T
+    where C: RegisteredContainer + 'static,
+          T: 'static,
+          N: ?Sized + 'static
+{
+    let _resolving = Resolving::enter::<_, N>(container);
+
+    let builder = container
+        .registry()
+        .and_then(|registry| registry.resolve::<Builder<C, T>>(container))
+        .unwrap_or_else(|| missing_binding(type_name::<N>()));
+
+    (builder.build)(container)
+}
+
+pub(super) fn verify_builder<C, T, N>(container: &C, verification: &mut Verification)
+    where C: RegisteredContainer + 'static,
+          T: 'static,
+          N: ?Sized
+{
+    verification.visit(DependencyKind::Bound, type_name::<N>(), |verification| match container.registry() {
+        Some(registry) if registry.is_bound::<Builder<C, T>>() => registry.verify::<Builder<C, T>>(container, verification),
+        _ => verification.missing_binding(type_name::<N>()),
+    });
+}
+
+/// Verify the dependencies of the function `F` that builds a query or command.
+pub(super) fn verify_dependencies<C, F, D>(container: &C, verification: &mut Verification)
+    where C: Container,
+          D: ResolvableFromContainer<C>
+{
+    verification.visit(DependencyKind::Implementation, type_name::<F>(), |verification| D::verify(container, verification));
+}
+
+impl<C, Req, Res> Resolvable<C> for QueryOf<Req, Res>
+    where C: RegisteredContainer + 'static,
+          Req: 'static,
+          Res: 'static
+{
+    type Dependency = Self;
+
+    fn resolve(query: Self::Dependency) -> Self {
+        query
+    }
+}
+
+impl<C, Req, E> Resolvable<C> for CommandOf<Req, E>
+    where C: RegisteredContainer + 'static,
+          Req: 'static,
+          E: 'static
+{
+    type Dependency = Self;
+
+    fn resolve(command: Self::Dependency) -> Self {
+        command
+    }
+}
+
+// Queries and commands are built by the function bound in the container's
+// registry, and panic if there isn't one.
+impl<C, Req, Res> ResolvableFromContainer<C> for QueryOf<Req, Res>
+    where C: RegisteredContainer + 'static,
+          Req: 'static,
+          Res: 'static
+{
+    fn resolve_from_container(container: &C) -> Self {
+        build::<C, Self, dyn Query<Req, Res>>(container)
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        verify_builder::<C, Self, dyn Query<Req, Res>>(container, verification)
+    }
+}
+
+impl<C, Req, E> ResolvableFromContainer<C> for CommandOf<Req, E>
+    where C: RegisteredContainer + 'static,
+          Req: 'static,
+          E: 'static
+{
+    fn resolve_from_container(container: &C) -> Self {
+        build::<C, Self, dyn Command<Req, Error = E>>(container)
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        verify_builder::<C, Self, dyn Command<Req, Error = E>>(container, verification)
+    }
+}
+
+impl<C> Registry<C>
+    where C: Container + 'static
+{
+    /// Build each `QueryOf<Req, Res>` from its dependencies with `f`,
+    /// replacing any previous binding.
+    pub fn bind_query<Req, Res, D, Q, F>(&mut self, f: F) -> &mut Self
+        where Req: 'static,
+              Res: 'static,
+              D: ResolvableFromContainer<C>,
+              Q: Query<Req, Res> + 'static,
+              F: Fn(D) -> Q + 'static
+    {
+        let builder = Rc::new(Builder { build: Box::new(move |container: &C| QueryOf::new(f(D::resolve_from_container(container)))) });
+        let verify: VerifyBinding<C> = verify_dependencies::<C, F, D>;
+
+        self.bind_implementation::<Builder<C, QueryOf<Req, Res>>>(Box::new(move |_| builder.clone()), Implementation::of::<Q>(Lifetime::Deferred), Some(verify))
+    }
+
+    /// Build each `CommandOf<Req, E>` from its dependencies with `f`,
+    /// replacing any previous binding.
+    pub fn bind_command<Req, E, D, K, F>(&mut self, f: F) -> &mut Self
+        where Req: 'static,
+              E: 'static,
+              D: ResolvableFromContainer<C>,
+              K: Command<Req, Error = E> + 'static,
+              F: Fn(D) -> K + 'static
+    {
+        let builder = Rc::new(Builder { build: Box::new(move |container: &C| CommandOf::new(f(D::resolve_from_container(container)))) });
+        let verify: VerifyBinding<C> = verify_dependencies::<C, F, D>;
+
+        self.bind_implementation::<Builder<C, CommandOf<Req, E>>>(Box::new(move |_| builder.clone()), Implementation::of::<K>(Lifetime::Deferred), Some(verify))
+    }
+}
+
+/// Declare queries with their own traits.
+///
+/// Each function is declared with the dependencies the query is
+/// constructed from, then the name of its trait with the request it
+/// takes and the response it returns. The body is the query itself, and
+/// is moved into a closure with the function's arguments. The trait is
+/// implemented for every `Query` with the same request and response,
+/// including closures and `QueryOf`.
+///
+/// Functions can have generic parameters with bounds, like
+/// `<Q: GetProductQuery>`, but not `where` clauses.
+#[macro_export]
+macro_rules! query {
+    () => {};
+    ($(#[$attr:meta])* $vis:vis fn $name:ident $(<$($g:ident: $bound:path),* $(,)*>)* ($($arg:ident: $t:ty),* $(,)*) -> impl $query:ident ($req:ident: $req_t:ty) -> $res:ty { $($body:tt)* } $($rest:tt)*) => {
+        #[doc = concat!("The query returned by `", stringify!($name), "`.")]
+        $vis trait $query: $crate::factories::Query<$req_t, $res> {}
+
+        impl<F> $query for F
+            where F: $crate::factories::Query<$req_t, $res>
+        {
+        }
+
+        $(#[$attr])*
+        $vis fn $name $(<$($g: $bound),*>)* ($($arg: $t),*) -> impl $query {
+            move |$req: $req_t| -> $res { $($body)* }
+        }
+
+        $crate::query! { $($rest)* }
+    };
+}
+
+/// Declare commands with their own traits.
+///
+/// This is the same as `query!`, except the body is only executed once,
+/// so it can consume the function's arguments, and it returns a
+/// `Result<(), E>`. The trait is implemented for every `Command` with
+/// the same request and error, including closures and `CommandOf`.
+#[macro_export]
+macro_rules! command {
+    () => {};
+    ($(#[$attr:meta])* $vis:vis fn $name:ident $(<$($g:ident: $bound:path),* $(,)*>)* ($($arg:ident: $t:ty),* $(,)*) -> impl $command:ident ($req:ident: $req_t:ty) -> Result<(), $err:ty> { $($body:tt)* } $($rest:tt)*) => {
+        #[doc = concat!("The command returned by `", stringify!($name), "`.")]
+        $vis trait $command: $crate::factories::Command<$req_t, Error = $err> {}
+
+        impl<F> $command for F
+            where F: $crate::factories::Command<$req_t, Error = $err>
+        {
+        }
+
+        $(#[$attr])*
+        $vis fn $name $(<$($g: $bound),*>)* ($($arg: $t),*) -> impl $command {
+            move |$req: $req_t| -> Result<(), $err> { $($body)* }
+        }
+
+        $crate::command! { $($rest)* }
+    };
+}
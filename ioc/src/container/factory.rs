@@ -0,0 +1,41 @@
+//! Factory dependencies
+//!
+//! A `Factory<T>` captures its container so it can resolve new instances of
+//! `T` whenever it's asked to, rather than receiving a single instance upfront.
+
+use std::fmt;
+use std::rc::Rc;
+
+/// A dependency that resolves a new `T` each time `create` is called.
+///
+/// Shared dependencies of `T` still come from the container the factory
+/// was resolved from, so they're the same between instances.
+pub struct Factory<T> {
+    create: Rc<dyn Fn() -> T>,
+}
+
+impl<T> Factory<T> {
+    /// Create a factory that calls `create` for each new instance.
+    pub fn new<F>(create: F) -> Self
+        where F: Fn() -> T + 'static
+    {
+        Factory { create: Rc::new(create) }
+    }
+
+    /// Resolve a new instance of `T`.
+    pub fn create(&self) -> T {
+        (self.create)()
+    }
+}
+
+impl<T> Clone for Factory<T> {
+    fn clone(&self) -> Self {
+        Factory { create: self.create.clone() }
+    }
+}
+
+impl<T> fmt::Debug for Factory<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Factory")
+    }
+}
@@ -0,0 +1,153 @@
+//! Wiring fingerprints
+//!
+//! Several binaries can share the same modules but wire them up slightly
+//! differently. A fingerprint is a stable summary of a registry's bindings
+//! so tests can check those binaries only differ where they're meant to.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::hash::Hasher;
+use std::str::FromStr;
+use fnv::FnvHasher;
+
+/// A stable summary of the bindings and modules in a registry.
+///
+/// Each binding is identified by its kind and the name of the type it
+/// produces, like `bind dyn app::KeyStore`. It's summarised by a hash of
+/// its implementations and how long the values they resolve live for.
+/// Bindings to a resolvable implementation, like `bind_to` or `alias`, use
+/// the name of that implementation. Bindings added with a closure use the
+/// name of the closure and the file, line, and column it was bound on,
+/// because every closure in a function has the same name. Those are only
+/// stable while the call that binds them stays put.
+///
+/// Keyed bindings are also identified by the type of their keys, like
+/// `bind_keyed app::Format => dyn app::Exporter`, and hash each key with
+/// the implementation bound under it. Keys are hashed with their `Hash`
+/// impl.
+///
+/// Fingerprints are built from type names rather than `TypeId`s, so they
+/// can be compared between binaries built with the same compiler. The
+/// `Display` and `FromStr` impls can be used to save a fingerprint from one
+/// binary and check it in another:
+///
+/// ```no_run
+/// # use ioc::prelude::*;
+/// # use std::error::Error;
+/// # use std::fs;
+/// # mod server {
+/// #     use ioc::prelude::*;
+/// #     pub fn registry() -> Registry<Scoped> { Registry::new() }
+/// # }
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let cli: Fingerprint = fs::read_to_string("cli.fingerprint")?.parse()?;
+/// let server = server::registry().fingerprint();
+///
+/// assert_eq!(vec!["bind dyn app::KeyStore"], cli.diff(&server));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Platform-specific bindings are fingerprinted using the implementation
+/// picked for the registry's platform.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Fingerprint {
+    entries: BTreeMap<String, u64>,
+}
+
+impl Fingerprint {
+    pub fn new() -> Self {
+        Fingerprint::default()
+    }
+
+    pub(super) fn add(&mut self, kind: &str, type_name: &str, implementations: &[String]) {
+        let mut hasher = FnvHasher::default();
+
+        for implementation in implementations {
+            hasher.write(implementation.as_bytes());
+            hasher.write_u8(0xff);
+        }
+
+        self.entries.insert(format!("{} {}", kind, type_name), hasher.finish());
+    }
+
+    /// A single hash of the whole fingerprint.
+    pub fn hash(&self) -> u64 {
+        let mut hasher = FnvHasher::default();
+
+        for (entry, hash) in &self.entries {
+            hasher.write(entry.as_bytes());
+            hasher.write_u8(0xff);
+            hasher.write_u64(*hash);
+        }
+
+        hasher.finish()
+    }
+
+    /// The entries that are different between two fingerprints.
+    ///
+    /// This includes entries that are only in one of them.
+    pub fn diff<'a>(&'a self, other: &'a Fingerprint) -> Vec<&'a str> {
+        let mut diff: Vec<&str> = self.entries
+            .iter()
+            .filter(|&(entry, hash)| other.entries.get(entry) != Some(hash))
+            .map(|(entry, _)| &**entry)
+            .chain(other.entries.keys().filter(|entry| !self.entries.contains_key(*entry)).map(|entry| &**entry))
+            .collect();
+
+        diff.sort_unstable();
+        diff
+    }
+
+    /// The entries in the fingerprint.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.entries.iter().map(|(entry, hash)| (&**entry, *hash))
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    /// Format the fingerprint with one entry per line.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (entry, hash) in &self.entries {
+            writeln!(f, "{:016x} {}", hash, entry)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Fingerprint {
+    type Err = ParseFingerprintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fingerprint = Fingerprint::new();
+
+        for (index, line) in s.lines().enumerate().filter(|&(_, line)| !line.trim().is_empty()) {
+            let err = || ParseFingerprintError { line: index + 1 };
+
+            let mut parts = line.splitn(2, ' ');
+            let hash = parts.next().and_then(|hash| u64::from_str_radix(hash, 16).ok()).ok_or_else(err)?;
+            let entry = parts.next().ok_or_else(err)?;
+
+            fingerprint.entries.insert(entry.to_owned(), hash);
+        }
+
+        Ok(fingerprint)
+    }
+}
+
+/// A fingerprint couldn't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFingerprintError {
+    /// The line the error is on, starting from 1.
+    pub line: usize,
+}
+
+impl fmt::Display for ParseFingerprintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid fingerprint entry on line {}", self.line)
+    }
+}
+
+impl Error for ParseFingerprintError {}
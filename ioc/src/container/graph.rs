@@ -0,0 +1,224 @@
+//! Dependency graph introspection
+//!
+//! Verifying a dependency walks the same graph that resolving it would, so
+//! the walk is also recorded as a tree of the dependencies it visited. The
+//! tree can be inspected directly, or written out in the DOT format for
+//! Graphviz:
+//!
+//! ```no_run
+//! # use ioc::prelude::*;
+//! # use std::fs;
+//! # use std::io;
+//! # struct App;
+//! # impl<C> Resolvable<C> for App {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { App }
+//! # }
+//! # fn main() -> io::Result<()> {
+//! # let scope = Scoped::new();
+//! let graph = scope.dependency_graph::<_, App>();
+//!
+//! fs::write("app.dot", graph.to_dot())?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt::{self, Write};
+
+use super::TypeName;
+
+/// The way a dependency is resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyKind {
+    /// The type a graph was built for.
+    Root,
+    /// An owned value, like `RefCell<T>`, `Transient<T>` or `Mutex<T>`.
+    Owned,
+    /// A shared value, like `Rc<T>` or `Arc<T>`.
+    Shared,
+    /// A pinned shared value, like `PinnedRc<T>`.
+    Pinned,
+    /// A borrowed shared value, like `B<'scope, T>`.
+    Borrowed,
+    /// A weak reference to a shared value, like `Weak<T>`.
+    Weak,
+    /// A runtime binding, like `Bound<T>`.
+    Bound,
+    /// Every runtime binding for a type, like `All<T>` or `KeyedAll<K, T>`.
+    All,
+    /// A value that's resolved the first time it's used, like `Lazy<T>`.
+    Lazy,
+    /// A factory for new values, like `Factory<T>`.
+    Factory,
+    /// The correlation id of the container.
+    CorrelationId,
+    /// A binding in a registry.
+    Binding,
+    /// The implementation of a binding.
+    Implementation,
+    /// The implementation of an open binding, like `Open<T>`.
+    Open,
+    /// Settings read from configuration, like `Config<T>`.
+    Config,
+    /// An environment variable, like `Env<K>`.
+    Env,
+    /// Command-line arguments, like `Args<T>`.
+    Args,
+    /// Context set on the scope, like `Context<T>`.
+    Context,
+    /// A factory in a dynamic container, like `Dyn<T>`.
+    Dynamic,
+    /// A third-party type created by a factory in a registry, like `External<T>`.
+    External,
+}
+
+/// How long a dependency lives for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lifetime {
+    /// A new value is resolved each time.
+    Transient,
+    /// The same value is shared for the life of the scope.
+    Scoped,
+    /// A new value is resolved later, when it's asked for.
+    Deferred,
+    /// The binding decides.
+    Bound,
+}
+
+impl DependencyKind {
+    /// How long dependencies of this kind live for.
+    pub fn lifetime(&self) -> Lifetime {
+        match *self {
+            DependencyKind::Root |
+            DependencyKind::Owned |
+            DependencyKind::Implementation |
+            DependencyKind::Open |
+            DependencyKind::Config |
+            DependencyKind::Env => Lifetime::Transient,
+            DependencyKind::Args => Lifetime::Transient,
+            DependencyKind::Shared |
+            DependencyKind::Pinned |
+            DependencyKind::Borrowed |
+            DependencyKind::Weak |
+            DependencyKind::CorrelationId |
+            DependencyKind::Context => Lifetime::Scoped,
+            DependencyKind::Lazy |
+            DependencyKind::Factory => Lifetime::Deferred,
+            DependencyKind::Bound |
+            DependencyKind::All |
+            DependencyKind::Binding |
+            DependencyKind::Dynamic |
+            DependencyKind::External => Lifetime::Bound,
+        }
+    }
+}
+
+/// A dependency in a graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    /// The name of the dependency type.
+    pub type_name: &'static str,
+    /// The way the dependency is resolved.
+    pub kind: DependencyKind,
+    /// The index of the node that depends on this one.
+    pub parent: Option<usize>,
+    /// Whether the dependency refers back to one of its parents.
+    ///
+    /// The dependencies of a cycle aren't visited again.
+    pub cycle: bool,
+    /// Whether the dependency needs a runtime binding that doesn't exist.
+    pub missing_binding: bool,
+}
+
+/// A tree of the dependencies visited while verifying a type.
+///
+/// A dependency that's needed in several places appears in the tree once
+/// for each of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependencyGraph {
+    nodes: Vec<Node>,
+}
+
+impl DependencyGraph {
+    pub fn new() -> Self {
+        DependencyGraph::default()
+    }
+
+    pub(super) fn add(&mut self, node: Node) -> usize {
+        self.nodes.push(node);
+
+        self.nodes.len() - 1
+    }
+
+    pub(super) fn node_mut(&mut self, index: usize) -> &mut Node {
+        &mut self.nodes[index]
+    }
+
+    /// The nodes in the graph, in the order they were visited.
+    ///
+    /// Parents are always visited before their dependencies.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// The direct dependencies of the node at `index`.
+    pub fn dependencies(&self, index: usize) -> impl Iterator<Item = (usize, &Node)> {
+        self.nodes.iter().enumerate().filter(move |&(_, node)| node.parent == Some(index))
+    }
+
+    /// Write the graph in the DOT format for Graphviz.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+
+        self.write_dot(&mut dot).expect("writing to a string can't fail");
+
+        dot
+    }
+
+    fn write_dot<W>(&self, w: &mut W) -> fmt::Result
+        where W: Write
+    {
+        writeln!(w, "digraph dependencies {{")?;
+        writeln!(w, "    node [shape=box];")?;
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let type_name = TypeName::from_static(node.type_name);
+
+            write!(w,
+                   "    n{} [label=\"{}\\n{:?} ({:?})\", tooltip=\"{}\"",
+                   index,
+                   Escape(type_name.short()),
+                   node.kind,
+                   node.kind.lifetime(),
+                   Escape(type_name.full()))?;
+
+            if node.cycle || node.missing_binding {
+                write!(w, ", color=red")?;
+            }
+
+            writeln!(w, "];")?;
+
+            if let Some(parent) = node.parent {
+                writeln!(w, "    n{} -> n{};", parent, index)?;
+            }
+        }
+
+        writeln!(w, "}}")
+    }
+}
+
+/// Escape a string for a quoted DOT label.
+struct Escape<'a>(&'a str);
+
+impl<'a> fmt::Display for Escape<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '"' | '\\' => write!(f, "\\{}", c)?,
+                c => f.write_char(c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
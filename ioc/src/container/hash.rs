@@ -0,0 +1,43 @@
+//! Hashing for `TypeId` keys
+//!
+//! A `TypeId` is already a hash of its type, so hashing it again with a
+//! general purpose hasher like FNV just costs time. `TypeIdHasher` passes
+//! it through unchanged instead.
+
+use std::hash::{BuildHasherDefault, Hasher};
+
+/// A hasher that uses a `TypeId` as its own hash.
+///
+/// It's only meant for `TypeId`s, which hash themselves with a single
+/// integer write. Anything else is still hashed, just poorly.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TypeIdHasher {
+    hash: u64,
+}
+
+impl Hasher for TypeIdHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.hash = self.hash.rotate_left(8) ^ u64::from(byte);
+        }
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.hash ^= i;
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.hash ^= i as u64 ^ (i >> 64) as u64;
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// A `BuildHasher` for `TypeIdHasher`s.
+pub type BuildTypeIdHasher = BuildHasherDefault<TypeIdHasher>;
@@ -3,24 +3,58 @@
 //! Root dependencies include:
 //! 
 //! - `()` the only _true_ root dependency that can be used for types
-//! that can be materialised from nothing.
+//!   that can be materialised from nothing.
 //! - `Rc<T>` a shared instance of `T`.
+//! - `Weak<T>` a weak reference to the shared instance of `T`.
+//! - `SharedMut<T>` the shared `Rc<RefCell<T>>` instance of `T`.
 //! - `RefCell<T>` a unique instance of `T`.
+//! - `Transient<T>` a unique instance of `T`, even within a scope.
+//! - `Arc<T>` a thread-safe shared instance of `T`.
+//! - `Mutex<T>` and `RwLock<T>` unique, thread-safe instances of `T`.
+//! - `PinnedRc<T>` a shared instance of `T` that won't move.
+//! - `B<'scope, T>` a borrowed shared instance of `T`.
+//! - `Shared<'scope, T>` a shared instance of `T` branded with its scope.
+//! - `Bound<T>` the binding of `T` in the container's registry.
+//! - `Option<Bound<T>>` the binding of `T`, if there is one.
+//! - `All<T>` every binding of `T` in the container's registry.
+//! - `ResolveIter<T>` every binding of `T`, resolved as it's iterated.
+//! - `KeyedAll<K, T>` every binding of `T` in the container's registry, by key.
+//! - `Open<T>` the implementation of `T`'s open binding.
+//! - `Lazy<T>` an instance of `T` that's resolved on first access.
+//! - `Factory<T>` a way to resolve new instances of `T` on demand.
+//! - `Locator<C>` a way to resolve anything from the container on demand.
+//! - `CorrelationId` the id of the scope being resolved from.
+//! - `Env<K>` the value of an environment variable.
+//! - `Args<T>` the command-line arguments, parsed as `T`.
+//! - `Context<T>` context set on the scope being resolved from.
 //! 
-//! These can be combined in various ways, like `Rc<RefCell<T>>`.
-//! They can also be combined in pointless ways, like `Rc<()>`.
+//! These can be combined in various ways, like `Rc<RefCell<T>>` or
+//! `Arc<Mutex<T>>`.
+//! Only `RefCell<T>`, `Mutex<T>` and `RwLock<T>` can be wrapped by another
+//! root dependency, so pointless combinations like `Rc<Transient<T>>` or
+//! `Rc<()>` don't compile.
 
-use std::rc::Rc;
+use std::any::type_name;
+use std::rc::{Rc, Weak};
 use std::cell::RefCell;
+use std::sync::{Arc, Mutex, RwLock};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::str::FromStr;
 use super::*;
+use super::pinned::Pinned;
+use super::error::{invalid_args, invalid_env_var, missing_binding, missing_context, missing_env_var, Resolving};
 
 /// `()` is a root dependency that has no dependencies of its own.
 impl<C> ResolvableFromContainer<C> for ()
     where C: Container
 {
-    fn resolve_from_container(_: &C) -> Self {
-        ()
-    }
+    fn resolve_from_container(_: &C) -> Self {}
+}
+
+pub(super) const fn max_depth(a: usize, b: usize) -> usize {
+    if a > b { a } else { b }
 }
 
 /// Tuples are root dependencies that are constructed from the dependencies
@@ -31,11 +65,21 @@ macro_rules! resolve_tuple {
             where $($T: ResolvableFromContainer<C>,)*
                   C: Container
         {
+            const DEPTH: usize = {
+                let mut depth = 0;
+                $(depth = max_depth(depth, $T::DEPTH);)*
+                depth
+            };
+
             fn resolve_from_container(container: &C) -> Self {
                 (
                     $($T::resolve_from_container(container),)*
                 )
             }
+
+            fn verify(container: &C, verification: &mut Verification) {
+                $($T::verify(container, verification);)*
+            }
         }
 
         impl <C $(,$T,$D)*> Resolvable<C> for ($($T,)*)
@@ -53,10 +97,22 @@ macro_rules! resolve_tuple {
     )
 }
 
+resolve_tuple!((T1, D1, d1));
 resolve_tuple!((T1, D1, d1)(T2, D2, d2));
 resolve_tuple!((T1, D1, d1)(T2, D2, d2)(T3, D3, d3));
 resolve_tuple!((T1, D1, d1)(T2, D2, d2)(T3, D3, d3)(T4, D4, d4));
 resolve_tuple!((T1, D1, d1)(T2, D2, d2)(T3, D3, d3)(T4, D4, d4)(T5, D5, d5));
+resolve_tuple!((T1, D1, d1)(T2, D2, d2)(T3, D3, d3)(T4, D4, d4)(T5, D5, d5)(T6, D6, d6));
+resolve_tuple!((T1, D1, d1)(T2, D2, d2)(T3, D3, d3)(T4, D4, d4)(T5, D5, d5)(T6, D6, d6)(T7, D7, d7));
+resolve_tuple!((T1, D1, d1)(T2, D2, d2)(T3, D3, d3)(T4, D4, d4)(T5, D5, d5)(T6, D6, d6)(T7, D7, d7)(T8, D8, d8));
+resolve_tuple!((T1, D1, d1)(T2, D2, d2)(T3, D3, d3)(T4, D4, d4)(T5, D5, d5)(T6, D6, d6)(T7, D7, d7)(T8, D8, d8)(T9, D9, d9));
+resolve_tuple!((T1, D1, d1)(T2, D2, d2)(T3, D3, d3)(T4, D4, d4)(T5, D5, d5)(T6, D6, d6)(T7, D7, d7)(T8, D8, d8)(T9, D9, d9)(T10, D10, d10));
+resolve_tuple!((T1, D1, d1)(T2, D2, d2)(T3, D3, d3)(T4, D4, d4)(T5, D5, d5)(T6, D6, d6)(T7, D7, d7)(T8, D8, d8)(T9, D9, d9)(T10, D10, d10)(T11, D11, d11));
+resolve_tuple!((T1, D1, d1)(T2, D2, d2)(T3, D3, d3)(T4, D4, d4)(T5, D5, d5)(T6, D6, d6)(T7, D7, d7)(T8, D8, d8)(T9, D9, d9)(T10, D10, d10)(T11, D11, d11)(T12, D12, d12));
+resolve_tuple!((T1, D1, d1)(T2, D2, d2)(T3, D3, d3)(T4, D4, d4)(T5, D5, d5)(T6, D6, d6)(T7, D7, d7)(T8, D8, d8)(T9, D9, d9)(T10, D10, d10)(T11, D11, d11)(T12, D12, d12)(T13, D13, d13));
+resolve_tuple!((T1, D1, d1)(T2, D2, d2)(T3, D3, d3)(T4, D4, d4)(T5, D5, d5)(T6, D6, d6)(T7, D7, d7)(T8, D8, d8)(T9, D9, d9)(T10, D10, d10)(T11, D11, d11)(T12, D12, d12)(T13, D13, d13)(T14, D14, d14));
+resolve_tuple!((T1, D1, d1)(T2, D2, d2)(T3, D3, d3)(T4, D4, d4)(T5, D5, d5)(T6, D6, d6)(T7, D7, d7)(T8, D8, d8)(T9, D9, d9)(T10, D10, d10)(T11, D11, d11)(T12, D12, d12)(T13, D13, d13)(T14, D14, d14)(T15, D15, d15));
+resolve_tuple!((T1, D1, d1)(T2, D2, d2)(T3, D3, d3)(T4, D4, d4)(T5, D5, d5)(T6, D6, d6)(T7, D7, d7)(T8, D8, d8)(T9, D9, d9)(T10, D10, d10)(T11, D11, d11)(T12, D12, d12)(T13, D13, d13)(T14, D14, d14)(T15, D15, d15)(T16, D16, d16));
 
 // `RefCell`s are unique dependencies. Each request will return a new instance.
 // For sharing, use an `Rc<T>` or `Rc<RefCell<T>>`.
@@ -77,11 +133,39 @@ impl<C, T, D> ResolvableFromContainer<C> for RefCell<T>
           T: Resolvable<C, Dependency = D>,
           D: ResolvableFromContainer<C>
 {
+    const DEPTH: usize = D::DEPTH + 1;
+
     fn resolve_from_container(container: &C) -> Self {
         let d = D::resolve_from_container(container);
         
         RefCell::new(T::resolve(d))
     }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::Owned, type_name::<Self>(), |verification| D::verify(container, verification));
+    }
+}
+
+// `Transient`s are unique dependencies, like `RefCell`s, but without
+// interior mutability. Each request will return a new instance, even from
+// a scope that caches shared instances of the same type.
+// `Transient` isn't `Resolvable` itself, so it can't be shared or nested.
+impl<C, T, D> ResolvableFromContainer<C> for Transient<T>
+    where C: Container,
+          T: Resolvable<C, Dependency = D>,
+          D: ResolvableFromContainer<C>
+{
+    const DEPTH: usize = D::DEPTH + 1;
+
+    fn resolve_from_container(container: &C) -> Self {
+        let d = D::resolve_from_container(container);
+
+        Transient::new(T::resolve(d))
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::Owned, type_name::<Self>(), |verification| D::verify(container, verification));
+    }
 }
 
 // `Rc`s are shared dependencies. Each request will return a pointer to the
@@ -92,7 +176,466 @@ impl<C, T, D> ResolvableFromContainer<C> for Rc<T>
           T: Resolvable<C, Dependency = D> + 'static,
           D: ResolvableFromContainer<C>
 {
+    const DEPTH: usize = D::DEPTH + 1;
+
     fn resolve_from_container(container: &C) -> Self {
         container.get_or_add()
     }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::Shared, type_name::<Self>(), |verification| D::verify(container, verification));
+    }
+}
+
+// `SharedMut`s are the same shared dependencies as `Rc<RefCell<T>>`s.
+// `SharedMut` isn't `Resolvable` itself, so it can't be nested.
+impl<C, T, D> ResolvableFromContainer<C> for SharedMut<T>
+    where C: ScopedContainer,
+          T: Resolvable<C, Dependency = D> + 'static,
+          D: ResolvableFromContainer<C>
+{
+    const DEPTH: usize = D::DEPTH + 1;
+
+    fn resolve_from_container(container: &C) -> Self {
+        SharedMut::new(container.get_or_add::<RefCell<T>, D>())
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::Shared, type_name::<Self>(), |verification| D::verify(container, verification));
+    }
+}
+
+// `Arc`s are thread-safe shared dependencies. Each request will return a
+// pointer to the same instance, which is separate from the one shared by `Rc`s.
+// For shared, mutable dependencies use `Arc<Mutex<T>>` or `Arc<RwLock<T>>`.
+impl<C, T, D> ResolvableFromContainer<C> for Arc<T>
+    where C: SyncScopedContainer,
+          T: Resolvable<C, Dependency = D> + Send + Sync + 'static,
+          D: ResolvableFromContainer<C>
+{
+    const DEPTH: usize = D::DEPTH + 1;
+
+    fn resolve_from_container(container: &C) -> Self {
+        container.get_or_add_sync()
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::Shared, type_name::<Self>(), |verification| D::verify(container, verification));
+    }
+}
+
+// `Mutex`s and `RwLock`s are unique dependencies, like `RefCell`s, that can
+// be shared between threads once they're wrapped in an `Arc`.
+macro_rules! resolve_lock {
+    ($($L:ident)*) => ($(
+        impl<C, T, D> Resolvable<C> for $L<T>
+            where C: Container,
+                  T: Resolvable<C, Dependency = D>,
+                  D: ResolvableFromContainer<C>
+        {
+            type Dependency = D;
+
+            fn resolve(dependency: D) -> Self {
+                $L::new(T::resolve(dependency))
+            }
+        }
+
+        impl<C, T, D> ResolvableFromContainer<C> for $L<T>
+            where C: Container,
+                  T: Resolvable<C, Dependency = D>,
+                  D: ResolvableFromContainer<C>
+        {
+            const DEPTH: usize = D::DEPTH + 1;
+
+            fn resolve_from_container(container: &C) -> Self {
+                let d = D::resolve_from_container(container);
+
+                $L::new(T::resolve(d))
+            }
+
+            fn verify(container: &C, verification: &mut Verification) {
+                verification.visit(DependencyKind::Owned, type_name::<Self>(), |verification| D::verify(container, verification));
+            }
+        }
+    )*)
+}
+
+resolve_lock!(Mutex RwLock);
+
+// `Weak`s are weak references to shared dependencies. They point to the
+// same instance as an `Rc<T>`, but don't keep it alive or construct it.
+// Constructing `T` here would make any cycle through the `Weak` a cycle in
+// the types too, which the compiler rejects, so `T` needs to be resolved
+// by something else first. A `Weak<T>` resolved while `T` is still being
+// constructed refers to the instance under construction.
+impl<C, T> ResolvableFromContainer<C> for Weak<T>
+    where C: ScopedContainer,
+          T: 'static
+{
+    fn resolve_from_container(container: &C) -> Self {
+        container.get_weak()
+    }
+
+    fn verify(_: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::Weak, type_name::<Self>(), |_| ());
+    }
+}
+
+// `PinnedRc`s are pinned shared dependencies. Each request will return a
+// pointer to the same instance, which is separate from the one shared by `Rc`s.
+impl<C, T, D> ResolvableFromContainer<C> for PinnedRc<T>
+    where C: ScopedContainer,
+          T: Resolvable<C, Dependency = D> + 'static,
+          D: ResolvableFromContainer<C>
+{
+    const DEPTH: usize = D::DEPTH + 1;
+
+    fn resolve_from_container(container: &C) -> Self {
+        PinnedRc::new(container.get_or_add::<Pinned<T>, D>())
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::Pinned, type_name::<Self>(), |verification| D::verify(container, verification));
+    }
+}
+
+// `B`s are borrowed shared dependencies. Each request will return a
+// reference to the same instance.
+// They're like `Rc<T>`, but avoid reference counting.
+impl<'scope, C, T, D> ResolvableFromContainer<C> for B<'scope, T>
+    where C: BorrowedContainer<'scope>,
+          T: Resolvable<C, Dependency = D> + 'static,
+          D: ResolvableFromContainer<C>
+{
+    const DEPTH: usize = D::DEPTH + 1;
+
+    fn resolve_from_container(container: &C) -> Self {
+        B::new(container.get_or_add_ref())
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::Borrowed, type_name::<Self>(), |verification| D::verify(container, verification));
+    }
+}
+
+// `Shared`s are branded shared dependencies. Each request will return the
+// same instance for the lifetime of the scope.
+// They're like `Rc<T>`, but can't escape their scope.
+impl<'scope, C, T, D> ResolvableFromContainer<C> for Shared<'scope, T>
+    where C: BrandedContainer<'scope>,
+          T: Resolvable<C, Dependency = D> + 'static,
+          D: ResolvableFromContainer<C>
+{
+    const DEPTH: usize = D::DEPTH + 1;
+
+    fn resolve_from_container(container: &C) -> Self {
+        container.get_or_add_shared()
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::Shared, type_name::<Self>(), |verification| D::verify(container, verification));
+    }
+}
+
+// `Bound`s are runtime bindings. Each request will call the binding for `T`
+// in the registry, and panic if there isn't one. A container can override
+// the binding, like a scope in a test replacing it with a fake.
+// Bindings aren't checked by the compiler, so they're tracked to find cycles.
+impl<C, T> ResolvableFromContainer<C> for Bound<T>
+    where C: RegisteredContainer + 'static,
+          T: ?Sized + 'static
+{
+    fn resolve_from_container(container: &C) -> Self {
+        let _resolving = Resolving::enter::<_, Self>(container);
+
+        let bound = container
+            .overridden()
+            .or_else(|| container.registry().and_then(|registry| registry.resolve(container)))
+            .unwrap_or_else(|| missing_binding(type_name::<T>()));
+
+        Bound::new(bound)
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::Bound, type_name::<Self>(), |verification| match container.registry() {
+            _ if container.overridden::<T>().is_some() => (),
+            Some(registry) => registry.verify::<T>(container, verification),
+            None => verification.missing_binding(type_name::<T>()),
+        });
+    }
+}
+
+// Optional `Bound`s are collaborators that a type can do without. They're
+// `None` instead of panicking when there's no binding for `T`.
+impl<C, T> ResolvableFromContainer<C> for Option<Bound<T>>
+    where C: RegisteredContainer + 'static,
+          T: ?Sized + 'static
+{
+    fn resolve_from_container(container: &C) -> Self {
+        let _resolving = Resolving::enter::<_, Self>(container);
+
+        container
+            .overridden()
+            .or_else(|| container.registry().and_then(|registry| registry.resolve(container)))
+            .map(Bound::new)
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::Bound, type_name::<Self>(), |verification| match container.registry() {
+            _ if container.overridden::<T>().is_some() => (),
+            Some(registry) if registry.is_bound::<T>() => registry.verify::<T>(container, verification),
+            _ => (),
+        });
+    }
+}
+
+// `Open`s are static bindings of generic services. Each request will
+// resolve a new implementation for the service's type arguments.
+impl<C, T, D> Resolvable<C> for Open<T>
+    where C: Container,
+          T: ?Sized + OpenBinding<C>,
+          T::Implementation: Resolvable<C, Dependency = D>,
+          D: ResolvableFromContainer<C>
+{
+    type Dependency = D;
+
+    fn resolve(dependency: D) -> Self {
+        Open::new(T::bind(T::Implementation::resolve(dependency)))
+    }
+}
+
+impl<C, T, D> ResolvableFromContainer<C> for Open<T>
+    where C: Container,
+          T: ?Sized + OpenBinding<C>,
+          T::Implementation: Resolvable<C, Dependency = D>,
+          D: ResolvableFromContainer<C>
+{
+    const DEPTH: usize = D::DEPTH + 1;
+
+    fn resolve_from_container(container: &C) -> Self {
+        let d = D::resolve_from_container(container);
+
+        Open::new(T::bind(T::Implementation::resolve(d)))
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::Open, type_name::<Self>(), |verification| D::verify(container, verification));
+    }
+}
+
+// `All`s are multi-bindings. Each request will call every binding for `T`
+// in the order they were added to the registry.
+// A container without a registry has no bindings.
+impl<C, T> ResolvableFromContainer<C> for All<T>
+    where C: RegisteredContainer + 'static,
+          T: ?Sized + 'static
+{
+    fn resolve_from_container(container: &C) -> Self {
+        let all = match container.registry() {
+            Some(registry) => registry.resolve_all(container),
+            None => Vec::new(),
+        };
+
+        All::new(all)
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        if let Some(registry) = container.registry() {
+            verification.visit(DependencyKind::All, type_name::<Self>(), |verification| registry.verify_each::<T>(container, verification));
+        }
+    }
+}
+
+// `ResolveIter`s are deferred multi-bindings. They capture the container
+// and only call each binding for `T` when the iterator reaches it.
+impl<C, T> ResolvableFromContainer<C> for ResolveIter<T>
+    where C: CaptureContainer + RegisteredContainer + 'static,
+          T: ?Sized + 'static
+{
+    fn resolve_from_container(container: &C) -> Self {
+        let len = container.registry().map_or(0, Registry::count_all::<T>);
+        let captured = container.capture();
+
+        ResolveIter::new(len, move |index| C::with_captured(&captured, |container| {
+            container.registry().and_then(|registry| registry.resolve_nth(container, index))
+        }))
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        if let Some(registry) = container.registry() {
+            // deferred dependencies can refer back to themselves without a cycle
+            verification.visit_deferred(DependencyKind::All, type_name::<Self>(), |verification| registry.verify_each::<T>(container, verification));
+        }
+    }
+}
+
+// `KeyedAll`s are multi-bindings too, collected into a map by their key.
+impl<C, K, T> ResolvableFromContainer<C> for KeyedAll<K, T>
+    where C: RegisteredContainer + 'static,
+          K: Eq + Hash + Clone + 'static,
+          T: ?Sized + 'static
+{
+    fn resolve_from_container(container: &C) -> Self {
+        let all = match container.registry() {
+            Some(registry) => registry.resolve_keyed(container),
+            None => HashMap::new(),
+        };
+
+        KeyedAll::new(all)
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        if let Some(registry) = container.registry() {
+            verification.visit(DependencyKind::All, type_name::<Self>(), |verification| registry.verify_keyed::<K, T>(container, verification));
+        }
+    }
+}
+
+// `Lazy`s are deferred dependencies. They capture the container and resolve
+// `T` the first time they're accessed.
+impl<C, T, D> ResolvableFromContainer<C> for Lazy<T>
+    where C: CaptureContainer,
+          T: Resolvable<C, Dependency = D> + 'static,
+          D: ResolvableFromContainer<C>
+{
+    const DEPTH: usize = D::DEPTH + 1;
+
+    fn resolve_from_container(container: &C) -> Self {
+        let captured = container.capture();
+
+        Lazy::new(move || C::with_captured(&captured, |container| container.resolve()))
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        // deferred dependencies can refer back to themselves without a cycle
+        verification.visit_deferred(DependencyKind::Lazy, type_name::<Self>(), |verification| D::verify(container, verification));
+    }
+}
+
+// `Factory`s are transient dependencies. They capture the container and
+// resolve a new `T` each time they're called.
+impl<C, T, D> ResolvableFromContainer<C> for Factory<T>
+    where C: CaptureContainer,
+          T: Resolvable<C, Dependency = D> + 'static,
+          D: ResolvableFromContainer<C>
+{
+    const DEPTH: usize = D::DEPTH + 1;
+
+    fn resolve_from_container(container: &C) -> Self {
+        let captured = container.capture();
+
+        Factory::new(move || C::with_captured(&captured, |container| container.resolve()))
+    }
+
+    fn verify(container: &C, verification: &mut Verification) {
+        // deferred dependencies can refer back to themselves without a cycle
+        verification.visit_deferred(DependencyKind::Factory, type_name::<Self>(), |verification| D::verify(container, verification));
+    }
+}
+
+// `Locator`s capture their container so they can resolve any dependency later.
+// They're only meant for code that's being migrated to declared dependencies.
+impl<C> ResolvableFromContainer<C> for Locator<C>
+    where C: CaptureContainer
+{
+    fn resolve_from_container(container: &C) -> Self {
+        Locator::new(container)
+    }
+}
+
+// `CorrelationId`s are shared by everything resolved from the same container.
+impl<C> ResolvableFromContainer<C> for CorrelationId
+    where C: CorrelatedContainer
+{
+    fn resolve_from_container(container: &C) -> Self {
+        container.correlation_id()
+    }
+
+    fn verify(_: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::CorrelationId, type_name::<Self>(), |_| ());
+    }
+}
+
+// `Env`s are read from the container's binding for `dyn EnvProvider`, or
+// the process environment if there isn't one. Each request will read and
+// parse the variable again.
+impl<C, K> ResolvableFromContainer<C> for Env<K>
+    where C: RegisteredContainer + 'static,
+          K: EnvVar + 'static,
+          <K::Value as FromStr>::Err: fmt::Display
+{
+    fn resolve_from_container(container: &C) -> Self {
+        let _resolving = Resolving::enter::<_, Self>(container);
+
+        let provider = container
+            .overridden::<dyn EnvProvider>()
+            .or_else(|| container.registry().and_then(|registry| registry.resolve::<dyn EnvProvider>(container)));
+
+        let value = match provider {
+            Some(provider) => provider.var(K::NAME),
+            None => ProcessEnv.var(K::NAME),
+        };
+
+        let value = value
+            .or_else(|| K::DEFAULT.map(String::from))
+            .unwrap_or_else(|| missing_env_var(K::NAME));
+
+        match value.parse() {
+            Ok(parsed) => Env::new(parsed),
+            Err(err) => invalid_env_var(K::NAME, value, err.to_string()),
+        }
+    }
+
+    fn verify(_: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::Env, type_name::<Self>(), |_| ());
+    }
+}
+
+// `Args` are parsed from the command-line arguments each time they're
+// resolved, so they can't go out of sync with a bound `ArgsProvider`.
+impl<C, T> ResolvableFromContainer<C> for Args<T>
+    where C: RegisteredContainer + 'static,
+          T: FromArgs + 'static
+{
+    fn resolve_from_container(container: &C) -> Self {
+        let _resolving = Resolving::enter::<_, Self>(container);
+
+        let provider = container
+            .overridden::<dyn ArgsProvider>()
+            .or_else(|| container.registry().and_then(|registry| registry.resolve::<dyn ArgsProvider>(container)));
+
+        let args = match provider {
+            Some(provider) => provider.args(),
+            None => ProcessArgs.args(),
+        };
+
+        match T::from_args(&args) {
+            Ok(parsed) => Args::new(parsed),
+            Err(message) => invalid_args(type_name::<T>(), message),
+        }
+    }
+
+    fn verify(_: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::Args, type_name::<Self>(), |_| ());
+    }
+}
+
+// `Context`s are set on the scope before anything is resolved from it, so
+// there's nothing to construct if they're missing.
+impl<C, T> ResolvableFromContainer<C> for Context<T>
+    where C: ContextContainer,
+          T: 'static
+{
+    fn resolve_from_container(container: &C) -> Self {
+        let _resolving = Resolving::enter::<_, Self>(container);
+
+        match container.context::<T>() {
+            Some(value) => Context::new(value),
+            None => missing_context(type_name::<T>()),
+        }
+    }
+
+    fn verify(_: &C, verification: &mut Verification) {
+        verification.visit(DependencyKind::Context, type_name::<Self>(), |_| ());
+    }
 }
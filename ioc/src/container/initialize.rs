@@ -0,0 +1,61 @@
+//! Two-phase initialization
+//!
+//! Most dependencies can be fully constructed from what they declare in
+//! `Resolvable::Dependency`. Some need a second step once they exist, like
+//! registering a callback with another service that would otherwise form a
+//! cycle. An `Initialize` dependency is given the container right after it's
+//! resolved, before the scope caches it:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use std::cell::RefCell;
+//! # use std::rc::Weak;
+//! # struct AuditLog { subscribed: bool }
+//! # #[derive(Default)]
+//! # struct EventBus { subscribers: RefCell<Vec<Weak<AuditLog>>> }
+//! # impl EventBus {
+//! #     fn subscribe(&self, subscriber: Weak<AuditLog>) { self.subscribers.borrow_mut().push(subscriber) }
+//! # }
+//! # impl<C> Resolvable<C> for EventBus {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { EventBus::default() }
+//! # }
+//! impl Initialize<Scoped> for AuditLog {
+//!     fn initialize(&mut self, container: &Scoped) {
+//!         let bus = container.get_or_add::<EventBus, _>();
+//!
+//!         // the value isn't shared yet, but its `Weak` already refers to it
+//!         bus.subscribe(container.get_weak::<AuditLog>());
+//!         self.subscribed = true;
+//!     }
+//! }
+//! ```
+//!
+//! Scopes don't know which of the values they cache need initializing, so
+//! they're registered on the registry given to the scope:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # struct AuditLog;
+//! # impl Initialize<Scoped> for AuditLog {
+//! #     fn initialize(&mut self, _: &Scoped) {}
+//! # }
+//! # let mut registry = Registry::<Scoped>::new();
+//! registry.initializable::<AuditLog>();
+//! ```
+//!
+//! The value is still being constructed while it's initialized, so
+//! resolving an `Rc<T>` of it from the initializer is a cycle. A `Weak<T>`
+//! can't be upgraded until the initializer returns. If initializing panics
+//! then nothing is cached. Only shared values constructed by a scope are
+//! initialized. Values used in place of a dependency with
+//! `Scoped::override_with` aren't.
+
+/// A shared dependency that's initialized after it's resolved.
+pub trait Initialize<C> {
+    /// Finish setting up the value before the scope caches it.
+    fn initialize(&mut self, container: &C);
+}
+
+/// A function that initializes a `T`.
+pub type Initializer<C, T> = fn(&mut T, &C);
@@ -0,0 +1,128 @@
+//! Interception hooks
+//!
+//! A `Scoped` container can run hooks around each shared dependency it
+//! resolves. Hooks see the type being resolved and the shared dependency
+//! that's being constructed around it, so they can be used for metrics, for
+//! auditing which services a request touched, or for enforcing layering
+//! rules:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use ioc::Veto;
+//! # use std::cell::RefCell;
+//! # use std::collections::HashMap;
+//! # use std::rc::Rc;
+//! # fn is_web(type_name: &str) -> bool { type_name.contains("::web::") }
+//! # fn is_db(type_name: &str) -> bool { type_name.contains("::db::") }
+//! # #[derive(Default)]
+//! # struct Counts(RefCell<HashMap<&'static str, usize>>);
+//! # impl Counts {
+//! #     fn count(&self, type_name: &'static str) { *self.0.borrow_mut().entry(type_name).or_insert(0) += 1 }
+//! # }
+//! # let metrics = Rc::new(Counts::default());
+//! let scope = Scoped::new()
+//!     .on_resolving(|resolution| match resolution.dependent {
+//!         Some(dependent) if is_web(dependent) && is_db(resolution.type_name) => {
+//!             Err(Veto::new("web handlers can't use the database directly"))
+//!         }
+//!         _ => Ok(()),
+//!     })
+//!     .on_resolved(move |resolution| metrics.count(resolution.type_name));
+//! ```
+//!
+//! A vetoed dependency panics. `try_resolve` returns it as `Error::Vetoed`.
+
+use std::any::TypeId;
+use std::fmt;
+
+use super::error::vetoed;
+
+/// A shared dependency that's being resolved from a scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resolution {
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+    /// The shared dependency that's being constructed around this one, if any.
+    pub dependent: Option<&'static str>,
+    /// Whether the scope already had a value, so nothing is constructed.
+    pub cached: bool,
+}
+
+/// The reason a hook refused to let a dependency be resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Veto {
+    reason: String,
+}
+
+impl Veto {
+    pub fn new<R>(reason: R) -> Self
+        where R: Into<String>
+    {
+        Veto { reason: reason.into() }
+    }
+
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl fmt::Display for Veto {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.reason)
+    }
+}
+
+type ResolvingHook = Box<dyn Fn(&Resolution) -> Result<(), Veto>>;
+type ResolvedHook = Box<dyn Fn(&Resolution)>;
+
+/// The hooks registered on a scope.
+#[derive(Default)]
+pub(super) struct Hooks {
+    resolving: Vec<ResolvingHook>,
+    resolved: Vec<ResolvedHook>,
+}
+
+impl Hooks {
+    pub(super) fn add_resolving(&mut self, hook: ResolvingHook) {
+        self.resolving.push(hook);
+    }
+
+    pub(super) fn add_resolved(&mut self, hook: ResolvedHook) {
+        self.resolved.push(hook);
+    }
+
+    /// Run the `on_resolving` hooks for `T`.
+    ///
+    /// The resolution is only built if there are hooks to see it. If any
+    /// of them vetoes `T` this panics.
+    #[inline]
+    pub(super) fn resolving<T>(&self, type_name: &'static str, dependent: Option<&'static str>, cached: bool) -> Option<Resolution>
+        where T: 'static
+    {
+        if self.resolving.is_empty() && self.resolved.is_empty() {
+            return None;
+        }
+
+        let resolution = Resolution {
+            type_id: TypeId::of::<T>(),
+            type_name,
+            dependent,
+            cached,
+        };
+
+        for hook in &self.resolving {
+            if let Err(veto) = hook(&resolution) {
+                vetoed(type_name, veto.reason);
+            }
+        }
+
+        Some(resolution)
+    }
+
+    /// Run the `on_resolved` hooks.
+    pub(super) fn resolved(&self, resolution: &Resolution) {
+        for hook in &self.resolved {
+            hook(resolution);
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! Function-call injection
+//!
+//! Any function or closure whose arguments are all root dependencies can be
+//! called by a container, which resolves each argument first.
+
+use super::*;
+
+/// A function that can be called with arguments resolved from a container.
+///
+/// `Args` is the tuple of the function's argument types. It's only there
+/// to tell the implementations for different arities apart, so it doesn't
+/// need to be named.
+pub trait Injectable<C, Args> {
+    type Output;
+
+    fn invoke(self, container: &C) -> Self::Output;
+}
+
+impl<C, F, R> Injectable<C, ()> for F
+    where F: FnOnce() -> R
+{
+    type Output = R;
+
+    fn invoke(self, _: &C) -> R {
+        self()
+    }
+}
+
+/// Functions are injectable if each of their arguments can be resolved
+/// from the container.
+macro_rules! invoke_fn {
+    ($($A:ident)*) => (
+        impl <C, F, R $(,$A)*> Injectable<C, ($($A,)*)> for F
+            where F: FnOnce($($A),*) -> R,
+                  $($A: ResolvableFromContainer<C>,)*
+                  C: Container
+        {
+            type Output = R;
+
+            fn invoke(self, container: &C) -> R {
+                self($($A::resolve_from_container(container)),*)
+            }
+        }
+    )
+}
+
+invoke_fn!(A1);
+invoke_fn!(A1 A2);
+invoke_fn!(A1 A2 A3);
+invoke_fn!(A1 A2 A3 A4);
+invoke_fn!(A1 A2 A3 A4 A5);
+invoke_fn!(A1 A2 A3 A4 A5 A6);
+invoke_fn!(A1 A2 A3 A4 A5 A6 A7);
+invoke_fn!(A1 A2 A3 A4 A5 A6 A7 A8);
+invoke_fn!(A1 A2 A3 A4 A5 A6 A7 A8 A9);
+invoke_fn!(A1 A2 A3 A4 A5 A6 A7 A8 A9 A10);
+invoke_fn!(A1 A2 A3 A4 A5 A6 A7 A8 A9 A10 A11);
+invoke_fn!(A1 A2 A3 A4 A5 A6 A7 A8 A9 A10 A11 A12);
+invoke_fn!(A1 A2 A3 A4 A5 A6 A7 A8 A9 A10 A11 A12 A13);
+invoke_fn!(A1 A2 A3 A4 A5 A6 A7 A8 A9 A10 A11 A12 A13 A14);
+invoke_fn!(A1 A2 A3 A4 A5 A6 A7 A8 A9 A10 A11 A12 A13 A14 A15);
+invoke_fn!(A1 A2 A3 A4 A5 A6 A7 A8 A9 A10 A11 A12 A13 A14 A15 A16);
@@ -0,0 +1,59 @@
+//! Deferred dependencies
+//!
+//! A `Lazy<T>` captures its container instead of resolving `T` upfront, so
+//! expensive dependencies are only constructed on code paths that use them.
+
+use std::cell::{Cell, OnceCell};
+use std::fmt;
+
+/// A dependency that's resolved the first time it's accessed.
+pub struct Lazy<T> {
+    value: OnceCell<T>,
+    resolve: Cell<Option<Box<dyn FnOnce() -> T>>>,
+}
+
+impl<T> Lazy<T> {
+    /// Create a lazy value that calls `resolve` on first access.
+    pub fn new<F>(resolve: F) -> Self
+        where F: FnOnce() -> T + 'static
+    {
+        Lazy {
+            value: OnceCell::new(),
+            resolve: Cell::new(Some(Box::new(resolve))),
+        }
+    }
+
+    /// Get the value, resolving it if this is the first access.
+    ///
+    /// If resolving the value panics then so will any future accesses.
+    pub fn get(&self) -> &T {
+        self.value.get_or_init(|| {
+            let resolve = self.resolve.take().expect("a lazy dependency panicked while resolving");
+
+            resolve()
+        })
+    }
+
+    /// Get the value, resolving it if it hasn't been accessed yet.
+    pub fn into_inner(self) -> T {
+        self.get();
+
+        self.value.into_inner().expect("the lazy value was just resolved")
+    }
+
+    /// Whether or not the value has been resolved yet.
+    pub fn is_resolved(&self) -> bool {
+        self.value.get().is_some()
+    }
+}
+
+impl<T> fmt::Debug for Lazy<T>
+    where T: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value.get() {
+            Some(value) => f.debug_tuple("Lazy").field(value).finish(),
+            None => f.write_str("Lazy(<unresolved>)"),
+        }
+    }
+}
@@ -0,0 +1,254 @@
+//! Starting and stopping services
+//!
+//! Some shared dependencies do work in the background for as long as the
+//! application runs, like a connection pool or a queue consumer. These are
+//! `Startable`, and are registered on the registry so they can be started
+//! together when the application starts:
+//!
+//! ```
+//! # #[macro_use] extern crate ioc;
+//! # use ioc::prelude::*;
+//! # use std::rc::Rc;
+//! # resolvable! {
+//! #     struct ConnectionPool;
+//! #     struct OrderConsumer { pool: Rc<ConnectionPool> }
+//! # }
+//! # impl Startable for ConnectionPool {
+//! #     fn start(&self) {}
+//! # }
+//! # impl Startable for OrderConsumer {
+//! #     fn start(&self) {}
+//! # }
+//! # fn main() {
+//! # let mut registry = Registry::new();
+//! registry.startable::<ConnectionPool, _>().startable::<OrderConsumer, _>();
+//! # let scope = Scoped::new().with_registry(Rc::new(registry));
+//!
+//! let started = scope.start_all();
+//!
+//! // run the application
+//!
+//! started.shutdown();
+//! # }
+//! ```
+//!
+//! Services are started in dependency order, so a service is started
+//! after any startable services it depends on, and stopped in the reverse
+//! order. The order is the one the scope observed constructing them in,
+//! which includes dependencies through runtime bindings. Containers that
+//! don't observe their construction order fall back to each service's
+//! static dependencies. If a service panics while starting, the services
+//! that were already started are stopped.
+//!
+//! Each service can be given a timeout for stopping. Stopping is
+//! synchronous, so a service that takes longer isn't interrupted, but the
+//! `ShutdownReport` says which services went over:
+//!
+//! ```
+//! # #[macro_use] extern crate ioc;
+//! # use ioc::prelude::*;
+//! # use std::rc::Rc;
+//! # resolvable! {
+//! #     struct ConnectionPool;
+//! #     struct OrderConsumer { pool: Rc<ConnectionPool> }
+//! # }
+//! # impl Startable for ConnectionPool {
+//! #     fn start(&self) {}
+//! # }
+//! # impl Startable for OrderConsumer {
+//! #     fn start(&self) {}
+//! # }
+//! # use std::time::Duration;
+//! # fn main() {
+//! # let mut registry = Registry::new();
+//! # registry.startable::<ConnectionPool, _>().startable::<OrderConsumer, _>();
+//! # let scope = Scoped::new().with_registry(Rc::new(registry));
+//! # let started = scope.start_all();
+//! let report = started
+//!     .with_timeout(Duration::from_secs(5))
+//!     .with_timeout_for::<OrderConsumer>(Duration::from_secs(30))
+//!     .shutdown();
+//!
+//! for stopped in report.timed_out() {
+//!     eprintln!("{}", stopped);
+//! }
+//! # }
+//! ```
+//!
+//! Stopping a service doesn't dispose of it. Services are shared, so a
+//! `Disposable` service is disposed of when its scope ends, after anything
+//! that depends on it.
+
+use std::any::TypeId;
+use std::fmt;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use super::Verification;
+
+/// A shared dependency that needs to be started before it's used.
+pub trait Startable {
+    /// Start the service.
+    fn start(&self);
+
+    /// Stop the service.
+    ///
+    /// The default implementation does nothing.
+    fn stop(&self) {}
+}
+
+/// A startable service in a registry.
+pub(super) struct Startup<C> {
+    pub(super) key: TypeId,
+    pub(super) type_name: &'static str,
+    /// How deeply nested the service's dependencies are.
+    pub(super) depth: usize,
+    pub(super) resolve: fn(&C) -> Rc<dyn Startable>,
+    pub(super) verify: fn(&C, &mut Verification),
+}
+
+struct Service {
+    key: TypeId,
+    type_name: &'static str,
+    service: Rc<dyn Startable>,
+}
+
+/// The services started by `RegisteredContainer::start_all`.
+///
+/// Shutting down or dropping this stops the services in the reverse order
+/// they were started.
+#[derive(Default)]
+pub struct ShutdownHandle {
+    services: Vec<Service>,
+    timeout: Option<Duration>,
+    timeouts: Vec<(TypeId, Duration)>,
+}
+
+impl ShutdownHandle {
+    pub(super) fn start(&mut self, key: TypeId, type_name: &'static str, service: Rc<dyn Startable>) {
+        service.start();
+
+        self.services.push(Service { key, type_name, service });
+    }
+
+    /// The names of the started services, in the order they were started.
+    pub fn type_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.services.iter().map(|service| service.type_name)
+    }
+
+    /// Report any service that takes longer than `timeout` to stop.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+
+        self
+    }
+
+    /// Report `T` if it takes longer than `timeout` to stop.
+    ///
+    /// This takes precedence over the timeout given to `with_timeout`.
+    pub fn with_timeout_for<T>(mut self, timeout: Duration) -> Self
+        where T: ?Sized + 'static
+    {
+        self.timeouts.push((TypeId::of::<T>(), timeout));
+
+        self
+    }
+
+    fn timeout(&self, key: TypeId) -> Option<Duration> {
+        self.timeouts
+            .iter()
+            .rev()
+            .find(|&&(timeout_key, _)| timeout_key == key)
+            .map(|&(_, timeout)| timeout)
+            .or(self.timeout)
+    }
+
+    /// Stop the services in the reverse order they were started.
+    pub fn shutdown(mut self) -> ShutdownReport {
+        let mut report = ShutdownReport::default();
+
+        while let Some(service) = self.services.pop() {
+            let start = Instant::now();
+
+            service.service.stop();
+
+            report.stopped.push(Stopped {
+                type_name: service.type_name,
+                elapsed: start.elapsed(),
+                timeout: self.timeout(service.key),
+            });
+        }
+
+        report
+    }
+}
+
+impl Drop for ShutdownHandle {
+    fn drop(&mut self) {
+        while let Some(service) = self.services.pop() {
+            service.service.stop();
+        }
+    }
+}
+
+impl fmt::Debug for ShutdownHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.type_names()).finish()
+    }
+}
+
+/// A service that was stopped by `ShutdownHandle::shutdown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stopped {
+    pub type_name: &'static str,
+    /// How long the service took to stop.
+    pub elapsed: Duration,
+    /// How long the service was given to stop, if it had a timeout.
+    pub timeout: Option<Duration>,
+}
+
+impl Stopped {
+    /// Whether the service took longer to stop than its timeout.
+    pub fn timed_out(&self) -> bool {
+        self.timeout.is_some_and(|timeout| self.elapsed > timeout)
+    }
+}
+
+impl fmt::Display for Stopped {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}` stopped in {:?}", self.type_name, self.elapsed)?;
+
+        match self.timeout {
+            Some(timeout) if self.timed_out() => write!(f, ", over its timeout of {:?}", timeout),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The services stopped by `ShutdownHandle::shutdown`, in the order they
+/// were stopped.
+#[derive(Debug, Default, Clone)]
+pub struct ShutdownReport {
+    stopped: Vec<Stopped>,
+}
+
+impl ShutdownReport {
+    pub fn stopped(&self) -> &[Stopped] {
+        &self.stopped
+    }
+
+    /// The services that took longer to stop than their timeout.
+    pub fn timed_out(&self) -> impl Iterator<Item = &Stopped> + '_ {
+        self.stopped.iter().filter(|stopped| stopped.timed_out())
+    }
+}
+
+impl fmt::Display for ShutdownReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for stopped in &self.stopped {
+            writeln!(f, "{}", stopped)?;
+        }
+
+        Ok(())
+    }
+}
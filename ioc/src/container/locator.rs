@@ -0,0 +1,160 @@
+//! A service locator for migrating to declared dependencies
+//!
+//! Code written against a service locator resolves whatever it needs,
+//! wherever it needs it, so it can't be moved onto declared `Dependency`
+//! types in one go. A `Locator` gives that code somewhere to resolve from
+//! in the meantime:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use ioc::Locator;
+//! # use std::rc::Rc;
+//! # struct Db;
+//! # impl<C> Resolvable<C> for Db {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { Db }
+//! # }
+//! # struct LegacyHandler { locator: Locator<Scoped> }
+//! impl Resolvable<Scoped> for LegacyHandler {
+//!     type Dependency = Locator<Scoped>;
+//!
+//!     fn resolve(locator: Self::Dependency) -> Self {
+//!         LegacyHandler { locator }
+//!     }
+//! }
+//!
+//! # let scope = Scoped::new();
+//! # let handler: LegacyHandler = scope.resolve();
+//! let db: Rc<Db> = handler.locator.get();
+//! ```
+//!
+//! Every resolution is recorded along with where it was made, and
+//! `locator_report` lists them, so the remaining uses can be found and
+//! replaced. Once the report is empty the locator can be removed.
+//!
+//! The report is process-wide rather than per container, since it's
+//! describing code rather than any one scope. `Locator` isn't in the
+//! prelude so its uses are easy to find too.
+
+use std::any::type_name;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::panic::Location;
+use std::sync::{Mutex, PoisonError};
+
+use super::*;
+
+/// Resolution sites, keyed by the type resolved and where it was resolved from.
+static USES: Mutex<BTreeMap<(&'static str, &'static Location<'static>), usize>> = Mutex::new(BTreeMap::new());
+
+/// A handle for resolving any dependency from a container on demand.
+///
+/// Prefer declaring dependencies with `Resolvable::Dependency`. This is
+/// only meant for code that hasn't been migrated yet.
+pub struct Locator<C>
+    where C: CaptureContainer
+{
+    captured: C::Captured,
+}
+
+impl<C> Locator<C>
+    where C: CaptureContainer
+{
+    /// Create a locator for a container.
+    pub fn new(container: &C) -> Self {
+        Locator { captured: container.capture() }
+    }
+
+    /// Resolve a dependency, recording where it was resolved from.
+    #[track_caller]
+    pub fn resolve<D, R>(&self) -> R
+        where R: Resolvable<C, Dependency = D>,
+              D: ResolvableFromContainer<C>
+    {
+        record::<R>(Location::caller());
+
+        C::with_captured(&self.captured, |container| container.resolve())
+    }
+
+    /// Resolve a root dependency, like an `Rc<T>`, recording where it was
+    /// resolved from.
+    #[track_caller]
+    pub fn get<D>(&self) -> D
+        where D: ResolvableFromContainer<C>
+    {
+        record::<D>(Location::caller());
+
+        C::with_captured(&self.captured, |container| D::resolve_from_container(container))
+    }
+}
+
+impl<C> fmt::Debug for Locator<C>
+    where C: CaptureContainer
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Locator")
+    }
+}
+
+fn record<T>(location: &'static Location<'static>) {
+    let mut uses = USES.lock().unwrap_or_else(PoisonError::into_inner);
+
+    *uses.entry((type_name::<T>(), location)).or_insert(0) += 1;
+}
+
+/// A place a type was resolved from through a `Locator`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocatorUse {
+    /// The name of the type that was resolved.
+    pub type_name: &'static str,
+    /// The place it was resolved from.
+    pub location: &'static Location<'static>,
+    /// The number of times it was resolved from there.
+    pub count: usize,
+}
+
+/// Every type resolved through a `Locator` and where it was resolved from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocatorReport {
+    uses: Vec<LocatorUse>,
+}
+
+impl LocatorReport {
+    /// Whether nothing has been resolved through a locator.
+    pub fn is_empty(&self) -> bool {
+        self.uses.is_empty()
+    }
+
+    /// The uses, ordered by type name and then location.
+    pub fn uses(&self) -> &[LocatorUse] {
+        &self.uses
+    }
+}
+
+impl fmt::Display for LocatorReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for use_ in &self.uses {
+            writeln!(f, "`{}` resolved {} time(s) at {}",
+                     TypeName::from_static(use_.type_name).short(), use_.count, use_.location)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Get a report of everything resolved through a `Locator` so far.
+pub fn locator_report() -> LocatorReport {
+    let uses = USES.lock().unwrap_or_else(PoisonError::into_inner);
+
+    LocatorReport {
+        uses: uses
+            .iter()
+            .map(|(&(type_name, location), &count)| LocatorUse { type_name, location, count })
+            .collect(),
+    }
+}
+
+/// Clear the report of everything resolved through a `Locator`.
+pub fn reset_locator_report() {
+    USES.lock().unwrap_or_else(PoisonError::into_inner).clear();
+}
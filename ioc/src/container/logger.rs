@@ -0,0 +1,276 @@
+//! Structured logging with scope context
+//!
+//! Log records are more useful when they say which request or user they
+//! were logged for, but passing those fields down to every service that
+//! logs is tedious. Instead, they're set on the scope as a `ScopeContext`,
+//! and the `dyn Logger` resolved from the scope adds them to each record:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use ioc::{Logger, ScopeContext, StderrLogger};
+//! # use std::rc::Rc;
+//! # struct Handler { log: Bound<dyn Logger> }
+//! # let mut registry = Registry::new();
+//! # let (request_id, id) = (7, 1);
+//! registry.bind_logger(StderrLogger);
+//! # let registry = Rc::new(registry);
+//!
+//! let scope = Scoped::new().with_registry(registry.clone());
+//! scope.set_context(ScopeContext::new().with("request_id", request_id));
+//!
+//! impl Resolvable<Scoped> for Handler {
+//!     type Dependency = Bound<dyn Logger>;
+//!
+//!     fn resolve(log: Self::Dependency) -> Self {
+//!         Handler { log }
+//!     }
+//! }
+//!
+//! # let handler: Handler = scope.resolve();
+//! handler.log.info(format_args!("renamed product {}", id));
+//! ```
+//!
+//! Fields can also be added part way through a scope, like once a request
+//! has been authenticated, by resolving the `Context<ScopeContext>` and
+//! setting them. Records logged afterwards include them. A scope without a
+//! `ScopeContext` logs records with only their own fields.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use super::*;
+
+/// How severe a log record is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match *self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        })
+    }
+}
+
+/// A structured log record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub level: Level,
+    pub message: String,
+    /// The record's fields, in the order they were added.
+    pub fields: Vec<(&'static str, String)>,
+}
+
+impl Record {
+    pub fn new<M>(level: Level, message: M) -> Self
+        where M: fmt::Display
+    {
+        Record {
+            level,
+            message: message.to_string(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Add a field to the record.
+    pub fn with<V>(mut self, key: &'static str, value: V) -> Self
+        where V: fmt::Display
+    {
+        self.fields.push((key, value.to_string()));
+
+        self
+    }
+
+    /// The value of the first field called `key`.
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields.iter().find(|&&(k, _)| k == key).map(|(_, v)| &**v)
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.level, self.message)?;
+
+        for (key, value) in &self.fields {
+            write!(f, " {}={}", key, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Somewhere log records are written.
+pub trait Logger {
+    fn log(&self, record: Record);
+}
+
+impl dyn Logger {
+    pub fn error<M>(&self, message: M)
+        where M: fmt::Display
+    {
+        self.log(Record::new(Level::Error, message));
+    }
+
+    pub fn warn<M>(&self, message: M)
+        where M: fmt::Display
+    {
+        self.log(Record::new(Level::Warn, message));
+    }
+
+    pub fn info<M>(&self, message: M)
+        where M: fmt::Display
+    {
+        self.log(Record::new(Level::Info, message));
+    }
+
+    pub fn debug<M>(&self, message: M)
+        where M: fmt::Display
+    {
+        self.log(Record::new(Level::Debug, message));
+    }
+
+    pub fn trace<M>(&self, message: M)
+        where M: fmt::Display
+    {
+        self.log(Record::new(Level::Trace, message));
+    }
+}
+
+/// The fields every record logged in a scope is given.
+#[derive(Debug, Default)]
+pub struct ScopeContext {
+    fields: RefCell<Vec<(&'static str, String)>>,
+}
+
+impl ScopeContext {
+    pub fn new() -> Self {
+        ScopeContext::default()
+    }
+
+    /// Add a field, replacing any existing value for `key`.
+    pub fn with<V>(self, key: &'static str, value: V) -> Self
+        where V: fmt::Display
+    {
+        self.set(key, value);
+
+        self
+    }
+
+    /// Set a field, replacing any existing value for `key`.
+    pub fn set<V>(&self, key: &'static str, value: V)
+        where V: fmt::Display
+    {
+        let value = value.to_string();
+        let mut fields = self.fields.borrow_mut();
+
+        match fields.iter_mut().find(|&&mut (k, _)| k == key) {
+            Some(field) => field.1 = value,
+            None => fields.push((key, value)),
+        }
+    }
+
+    /// The value of the field called `key`.
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.fields.borrow().iter().find(|&&(k, _)| k == key).map(|(_, v)| v.clone())
+    }
+
+    /// Every field, in the order they were first set.
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        self.fields.borrow().clone()
+    }
+}
+
+/// A logger that adds the fields of a scope's context to each record.
+///
+/// The record's own fields come first.
+pub struct ContextLogger {
+    inner: Rc<dyn Logger>,
+    context: Rc<ScopeContext>,
+}
+
+impl ContextLogger {
+    pub fn new(inner: Rc<dyn Logger>, context: Rc<ScopeContext>) -> Self {
+        ContextLogger { inner, context }
+    }
+}
+
+impl Logger for ContextLogger {
+    fn log(&self, mut record: Record) {
+        record.fields.extend(self.context.fields());
+
+        self.inner.log(record);
+    }
+}
+
+impl fmt::Debug for ContextLogger {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ContextLogger")
+            .field("context", &self.context)
+            .finish()
+    }
+}
+
+/// A logger that writes each record to standard error.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StderrLogger;
+
+impl Logger for StderrLogger {
+    fn log(&self, record: Record) {
+        // there's nowhere to report a failure to write to stderr
+        let _ = writeln!(io::stderr(), "{}", record);
+    }
+}
+
+/// A logger that keeps records in memory, for tests.
+///
+/// Clones share the same records, so a test can keep one to check what
+/// was logged through the other.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryLogger {
+    records: Rc<RefCell<Vec<Record>>>,
+}
+
+impl MemoryLogger {
+    pub fn new() -> Self {
+        MemoryLogger::default()
+    }
+
+    /// Every record logged so far.
+    pub fn records(&self) -> Vec<Record> {
+        self.records.borrow().clone()
+    }
+}
+
+impl Logger for MemoryLogger {
+    fn log(&self, record: Record) {
+        self.records.borrow_mut().push(record);
+    }
+}
+
+impl<C> Registry<C>
+    where C: ContextContainer + 'static
+{
+    /// Bind `dyn Logger` to `logger`, adding the fields of the
+    /// `ScopeContext` set on the scope it's resolved from to each record.
+    pub fn bind_logger<L>(&mut self, logger: L) -> &mut Self
+        where L: Logger + 'static
+    {
+        let logger: Rc<dyn Logger> = Rc::new(logger);
+
+        self.bind::<dyn Logger, _>(move |container: &C| {
+            Rc::new(ContextLogger::new(logger.clone(), container.context::<ScopeContext>().unwrap_or_default()))
+        })
+    }
+}
@@ -0,0 +1,225 @@
+//! Resolution metrics
+//!
+//! A `Metrics` collector counts the shared dependencies resolved by every
+//! scope it's given to, how often they were already cached, and how long
+//! they took to construct. It's shared, so one collector can be given to
+//! each new scope:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use std::rc::Rc;
+//! # macro_rules! gauge {
+//! #     ($name:expr, $value:expr, $($label:expr => $label_value:expr),*) => { let _ = ($name, $value, $($label, $label_value),*); };
+//! # }
+//! let metrics = Rc::new(Metrics::new());
+//!
+//! let scope = Scoped::new().with_metrics(metrics.clone());
+//!
+//! for ty in metrics.snapshot().types() {
+//!     gauge!("ioc_cache_hit_ratio", ty.hit_ratio(), "type" => ty.type_name);
+//! }
+//! ```
+//!
+//! Snapshots are plain data, so they can be bridged into whatever the host
+//! app uses for metrics or logging.
+
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::collections::HashMap as StdHashMap;
+use std::fmt;
+use std::time::Duration;
+
+use super::*;
+
+// `TypeId`s are already hashes, so they're used as-is
+type HashMap<K, V> = StdHashMap<K, V, BuildTypeIdHasher>;
+
+/// The upper bounds of the construction latency buckets.
+///
+/// Anything slower than the last bound goes in an overflow bucket.
+const BUCKETS: [Duration; 7] = [
+    Duration::from_micros(1),
+    Duration::from_micros(10),
+    Duration::from_micros(100),
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+];
+
+/// A collector for resolution metrics.
+#[derive(Default)]
+pub struct Metrics {
+    types: RefCell<HashMap<TypeId, TypeMetrics>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a shared dependency resolved from a scope.
+    pub(super) fn resolved<T>(&self, type_name: &'static str, cached: bool)
+        where T: 'static
+    {
+        let mut types = self.types.borrow_mut();
+        let metrics = types.entry(TypeId::of::<T>()).or_insert_with(|| TypeMetrics::new(type_name));
+
+        metrics.resolutions += 1;
+
+        if cached {
+            metrics.hits += 1;
+        }
+    }
+
+    /// Record a shared dependency constructed by a scope.
+    pub(super) fn constructed<T>(&self, type_name: &'static str, construction: Duration)
+        where T: 'static
+    {
+        let mut types = self.types.borrow_mut();
+        let metrics = types.entry(TypeId::of::<T>()).or_insert_with(|| TypeMetrics::new(type_name));
+
+        metrics.construction.record(construction);
+    }
+
+    /// Take a snapshot of the metrics collected so far.
+    ///
+    /// Types are sorted by name so snapshots are stable.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let mut types: Vec<_> = self.types.borrow().values().cloned().collect();
+        types.sort_by_key(|metrics| metrics.type_name);
+
+        MetricsSnapshot { types }
+    }
+
+    /// Clear the metrics collected so far.
+    pub fn reset(&self) {
+        self.types.borrow_mut().clear();
+    }
+}
+
+impl fmt::Debug for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Metrics").field("types", &self.snapshot().types).finish()
+    }
+}
+
+/// The metrics for a single shared dependency type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeMetrics {
+    /// The name of the dependency type.
+    pub type_name: &'static str,
+    /// The number of times the type was resolved.
+    pub resolutions: u64,
+    /// The number of resolutions that found a value already in the scope.
+    pub hits: u64,
+    /// How long each constructed value took to construct.
+    ///
+    /// This doesn't include the shared dependencies it resolved, which
+    /// have their own metrics.
+    pub construction: Histogram,
+}
+
+impl TypeMetrics {
+    fn new(type_name: &'static str) -> Self {
+        TypeMetrics {
+            type_name,
+            resolutions: 0,
+            hits: 0,
+            construction: Histogram::default(),
+        }
+    }
+
+    /// The fraction of resolutions that found a value already in the scope.
+    pub fn hit_ratio(&self) -> f64 {
+        if self.resolutions == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.resolutions as f64
+        }
+    }
+}
+
+/// A histogram of durations.
+///
+/// Buckets are cumulative, like Prometheus histograms, so each one counts
+/// every duration up to and including its bound.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    buckets: [u64; BUCKETS.len()],
+    count: u64,
+    sum: Duration,
+}
+
+impl Histogram {
+    fn record(&mut self, duration: Duration) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(BUCKETS.iter()) {
+            if duration <= *bound {
+                *bucket += 1;
+            }
+        }
+
+        self.count += 1;
+        self.sum += duration;
+    }
+
+    /// Each bucket's upper bound, and the number of durations up to it.
+    ///
+    /// Durations above the last bound are only included in `count`.
+    pub fn buckets(&self) -> impl Iterator<Item = (Duration, u64)> + '_ {
+        BUCKETS.iter().cloned().zip(self.buckets.iter().cloned())
+    }
+
+    /// The number of durations recorded.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The total of the durations recorded.
+    pub fn sum(&self) -> Duration {
+        self.sum
+    }
+
+    /// The average duration recorded.
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            Duration::default()
+        } else {
+            Duration::from_nanos((self.sum.as_nanos() / u128::from(self.count)) as u64)
+        }
+    }
+}
+
+/// A snapshot of the metrics collected for each type.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    types: Vec<TypeMetrics>,
+}
+
+impl MetricsSnapshot {
+    /// The metrics for each type, sorted by name.
+    pub fn types(&self) -> &[TypeMetrics] {
+        &self.types
+    }
+
+    /// The metrics for `type_name`, if it's been resolved.
+    pub fn get(&self, type_name: &str) -> Option<&TypeMetrics> {
+        self.types.iter().find(|metrics| metrics.type_name == type_name)
+    }
+}
+
+impl fmt::Display for MetricsSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for metrics in &self.types {
+            writeln!(f,
+                     "{}: {} resolutions, {:.0}% cached, {} constructed (mean: {:?})",
+                     metrics.type_name,
+                     metrics.resolutions,
+                     metrics.hit_ratio() * 100.0,
+                     metrics.construction.count(),
+                     metrics.construction.mean())?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,10 +1,119 @@
 mod impls;
 mod brw_scope;
+mod ref_scope;
+mod branded;
+mod closed_scope;
+mod registry;
+mod platform;
+mod lazy;
+mod factory;
+mod pinned;
+mod invoke;
+mod verify;
+mod fingerprint;
+mod graph;
+mod correlation;
+mod dispose;
+mod initialize;
+mod panic;
+mod error;
+mod names;
+mod scope_log;
+mod depth;
+mod hash;
+mod detach;
+mod sync_scope;
+mod timings;
+mod trace;
+mod locator;
+mod storage;
+mod transient;
+mod shared_mut;
+mod declare;
+mod open;
+mod lifecycle;
+mod observed;
+mod env;
+mod args;
+mod context;
+mod logger;
+mod intercept;
+mod scope_guard;
+mod dynamic;
+mod external;
+mod stats;
+mod metrics;
+mod bus;
+mod decorators;
+mod async_factories;
+mod events;
+mod assert;
+mod pool;
+mod transaction;
+#[cfg(feature = "config")]
+mod config;
+#[cfg(feature = "rayon")]
+mod par;
 
-use std::rc::Rc;
+pub mod resolver;
+pub mod factories;
+
+use std::any::type_name;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::{Rc, Weak};
+use std::sync::Arc;
 
-pub use self::impls::*;
 pub use self::brw_scope::*;
+pub use self::ref_scope::*;
+pub use self::branded::*;
+pub use self::closed_scope::*;
+pub use self::registry::*;
+pub use self::platform::*;
+pub use self::lazy::*;
+pub use self::factory::*;
+pub use self::pinned::PinnedRc;
+pub use self::invoke::*;
+pub use self::verify::*;
+pub use self::fingerprint::*;
+pub use self::graph::*;
+pub use self::correlation::*;
+pub use self::dispose::{Disposable, Dispose};
+pub use self::initialize::{Initialize, Initializer};
+pub use self::panic::*;
+pub use self::error::Error;
+pub use self::names::*;
+pub use self::scope_log::*;
+pub use self::depth::*;
+pub use self::hash::*;
+pub use self::detach::*;
+pub use self::sync_scope::*;
+pub use self::locator::*;
+pub use self::storage::*;
+pub use self::open::*;
+pub use self::lifecycle::{Startable, ShutdownHandle, ShutdownReport, Stopped};
+pub use self::observed::*;
+pub use self::env::*;
+pub use self::args::*;
+pub use self::context::*;
+pub use self::logger::*;
+pub use self::intercept::{Resolution, Veto};
+pub use self::bus::*;
+pub use self::scope_guard::*;
+pub use self::dynamic::*;
+pub use self::external::External;
+pub use self::stats::{ScopeStats, EntryStats};
+pub use self::metrics::*;
+pub use self::assert::assert_resolvable;
+pub use self::pool::{Pool, Pooled};
+pub use self::transaction::{Transactional, Outcome, InTransaction};
+#[cfg(feature = "config")]
+pub use self::config::*;
+#[cfg(feature = "rayon")]
+pub use self::par::Par;
+pub use self::transient::*;
+pub use self::shared_mut::SharedMut;
+#[cfg(feature = "timings")]
+pub use self::timings::{Phase, PhaseTiming, PhaseTimings, phase_timings, reset_phase_timings};
 
 /// A container that can resolve dependencies.
 pub trait Container
@@ -14,10 +123,106 @@ pub trait Container
         where R: Resolvable<Self, Dependency = D>,
               D: ResolvableFromContainer<Self>
     {
+        let _traced = trace::Traced::resolve(type_name::<R>());
+
         let d = D::resolve_from_container(self);
 
         R::resolve(d)
     }
+
+    /// Resolve a dependency, catching any panic while it's resolved.
+    ///
+    /// Missing bindings and cycles are returned as their own errors. Any
+    /// other panic is returned as `Error::ConstructionFailed`. Each error
+    /// has the chain of dependencies that were being resolved.
+    ///
+    /// The container is still usable afterwards. Nothing is cached for a
+    /// dependency that panicked, so resolving it again will try again.
+    /// This has no effect if panics abort instead of unwinding.
+    fn try_resolve<D, R>(&self) -> Result<R, Error>
+        where R: Resolvable<Self, Dependency = D>,
+              D: ResolvableFromContainer<Self>
+    {
+        error::reset();
+
+        catch_unwind(AssertUnwindSafe(|| {
+            let _resolving = error::Resolving::enter_named(type_name::<R>());
+
+            self.resolve()
+        }))
+        .map_err(|payload| error::caught(type_name::<R>(), payload))
+    }
+
+    /// Resolve a dependency that also needs a runtime parameter.
+    ///
+    /// The parameter is passed to `R` along with its dependencies, which
+    /// are resolved from the container as normal.
+    fn resolve_with<D, P, R>(&self, params: P) -> R
+        where R: ResolvableWith<Self, P, Dependency = D>,
+              D: ResolvableFromContainer<Self>
+    {
+        let d = D::resolve_from_container(self);
+
+        R::resolve_with(d, params)
+    }
+
+    /// Check that a dependency could be resolved without constructing it.
+    ///
+    /// Static dependencies are always resolvable, so this looks for runtime
+    /// bindings that are missing or depend on themselves.
+    fn verify<D, R>(&self) -> Result<(), Vec<VerifyError>>
+        where R: Resolvable<Self, Dependency = D>,
+              D: ResolvableFromContainer<Self>
+    {
+        let mut verification = Verification::new();
+
+        verification.visit(DependencyKind::Root, type_name::<R>(), |verification| D::verify(self, verification));
+
+        verification.finish()
+    }
+
+    /// Record the dependencies of `R` without constructing them.
+    ///
+    /// This walks the same graph as `verify`, so problems it finds are
+    /// marked on the nodes they were found at.
+    fn dependency_graph<D, R>(&self) -> DependencyGraph
+        where R: Resolvable<Self, Dependency = D>,
+              D: ResolvableFromContainer<Self>
+    {
+        let mut verification = Verification::new();
+
+        verification.visit(DependencyKind::Root, type_name::<R>(), |verification| D::verify(self, verification));
+
+        verification.into_graph()
+    }
+
+    /// Call a function with its arguments resolved from the container.
+    ///
+    /// Arguments are resolved the same way as a `Resolvable::Dependency`,
+    /// so closures need to annotate their argument types:
+    ///
+    /// ```
+    /// # #[macro_use] extern crate ioc;
+    /// # use ioc::prelude::*;
+    /// # use std::cell::RefCell;
+    /// # use std::rc::Rc;
+    /// # resolvable! {
+    /// #     struct X;
+    /// #     struct Y;
+    /// # }
+    /// # fn main() {
+    /// # let container = Scoped::new();
+    /// container.invoke(|x: RefCell<X>, y: Rc<Y>| {
+    ///     // do something with x and y
+    /// #   let _ = (x, y);
+    /// });
+    /// # }
+    /// ```
+    fn invoke<F, Args>(&self, f: F) -> F::Output
+        where F: Injectable<Self, Args>
+    {
+        f.invoke(self)
+    }
 }
 
 /// A trait for creating a new scope and using it within a closure.
@@ -25,6 +230,39 @@ pub trait Scope {
     type Container: Container;
 
     fn scope<F, T>(&self, f: F) -> T where F: FnOnce(Self::Container) -> T;
+
+    /// Create a new scope, seed it with existing values and then use it.
+    ///
+    /// The `seed` closure runs before `f`, so anything it inserts into the
+    /// scope is picked up by resolutions made within `f`.
+    fn scope_with<S, F, T>(&self, seed: S, f: F) -> T
+        where S: FnOnce(&Self::Container),
+              F: FnOnce(Self::Container) -> T
+    {
+        self.scope(|scope| {
+            seed(&scope);
+
+            f(scope)
+        })
+    }
+
+    /// Create a new scope that ends when the returned guard is dropped.
+    ///
+    /// This is an alternative to `scope` for code that uses `?` or returns
+    /// early. See `ScopeGuard` for details.
+    fn begin_scope(&self) -> ScopeGuard<Self::Container> {
+        ScopeGuard::new(self.scope(|scope| scope))
+    }
+
+    /// Create a new scope and use it for work that can fail.
+    ///
+    /// See `ScopedContainer::try_run` for details.
+    fn try_scope<F, T, E>(&self, f: F) -> Result<T, E>
+        where F: FnOnce(&Self::Container) -> Result<T, E>,
+              Self::Container: ScopedContainer
+    {
+        self.scope(|scope| scope.try_run(f))
+    }
 }
 
 /// A scoped container that can resolve shared dependencies.
@@ -34,31 +272,214 @@ pub trait ScopedContainer
     fn get_or_add<T, D>(&self) -> Rc<T>
         where T: Resolvable<Self, Dependency = D> + 'static,
               D: ResolvableFromContainer<Self>;
+
+    /// Get a weak reference to the shared instance of `T`.
+    ///
+    /// This doesn't construct `T`. If the scope doesn't have a `T`, and
+    /// isn't in the middle of constructing one, the reference is empty.
+    fn get_weak<T>(&self) -> Weak<T>
+        where T: 'static;
+
+    /// The shared dependencies the scope has constructed so far, and what
+    /// they depended on.
+    ///
+    /// Scopes that don't record this return an empty graph. See
+    /// `ObservedGraph` for details.
+    fn observed_graph(&self) -> ObservedGraph {
+        ObservedGraph::default()
+    }
+
+    /// Clone a tuple of shared dependencies out of the scope.
+    ///
+    /// See `Detach` for details.
+    fn detach<T>(&self) -> T
+        where T: Detach<Self>
+    {
+        T::detach(self)
+    }
+
+    /// Use the scope for work that can fail, and then end it.
+    ///
+    /// Whether `f` returned `Ok` or `Err` is recorded in the scope's
+    /// `Outcome` before it ends, so dependencies that end with the scope,
+    /// like an `InTransaction`, can commit or roll back.
+    fn try_run<F, T, E>(self, f: F) -> Result<T, E>
+        where F: FnOnce(&Self) -> Result<T, E>
+    {
+        let result = f(&self);
+
+        if let Some(outcome) = self.get_weak::<Outcome>().upgrade() {
+            match result {
+                Ok(_) => outcome.succeed(),
+                Err(_) => outcome.fail(),
+            }
+        }
+
+        result
+    }
+}
+
+/// A scoped container that can resolve thread-safe shared dependencies.
+pub trait SyncScopedContainer
+    where Self: Container
+{
+    fn get_or_add_sync<T, D>(&self) -> Arc<T>
+        where T: Resolvable<Self, Dependency = D> + Send + Sync + 'static,
+              D: ResolvableFromContainer<Self>;
+}
+
+/// A container that can be captured by dependencies that resolve later.
+///
+/// The captured value is an owned handle to the container, like a weak
+/// reference to a scope.
+pub trait CaptureContainer
+    where Self: Container
+{
+    type Captured: 'static;
+
+    fn capture(&self) -> Self::Captured;
+
+    /// Use the container a handle was captured from.
+    ///
+    /// This may panic if the container no longer exists.
+    fn with_captured<F, T>(captured: &Self::Captured, f: F) -> T
+        where F: FnOnce(&Self) -> T;
+}
+
+/// A container that may have a registry of runtime bindings.
+pub trait RegisteredContainer
+    where Self: Container
+{
+    fn registry(&self) -> Option<&Registry<Self>>;
+
+    /// A value to resolve in place of the binding for `T`, if there is one.
+    ///
+    /// Containers that can't override dependencies don't have any.
+    fn overridden<T>(&self) -> Option<Rc<T>>
+        where T: ?Sized + 'static
+    {
+        None
+    }
+
+    /// Check that every binding in the registry could be resolved.
+    fn verify_all(&self) -> Result<(), Vec<VerifyError>>
+        where Self: 'static
+    {
+        let mut verification = Verification::new();
+
+        if let Some(registry) = self.registry() {
+            registry.verify_all(self, &mut verification);
+        }
+
+        verification.finish()
+    }
+
+    /// Start every startable service in the registry.
+    ///
+    /// Services are started in dependency order, and stopped in reverse
+    /// when the returned `ShutdownHandle` is shut down or dropped. See
+    /// `Startable` for details.
+    fn start_all(&self) -> ShutdownHandle
+        where Self: ScopedContainer + 'static
+    {
+        self.registry().map(|registry| registry.start_all(self)).unwrap_or_default()
+    }
+
+    /// Fingerprint the bindings in the registry.
+    ///
+    /// A container without a registry has an empty fingerprint.
+    fn fingerprint(&self) -> Fingerprint
+        where Self: 'static
+    {
+        self.registry().map(Registry::fingerprint).unwrap_or_default()
+    }
+}
+
+/// A container with a correlation id for the work it resolves dependencies for.
+pub trait CorrelatedContainer
+    where Self: Container
+{
+    fn correlation_id(&self) -> CorrelationId;
 }
 
 /// A dependency that can be resolved directly from the container.
 ///
 /// This trait is different from `Resolvable` because it doesn't declare
 /// the type of the dependency the implementor requires.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be resolved from `{C}`",
+    label = "`{Self}` isn't a root dependency",
+    note = "a `Resolvable` type is resolved through a root dependency, like `Rc<{Self}>`, `RefCell<{Self}>`, `Transient<{Self}>` or `Bound<{Self}>`",
+    note = "values the container can't provide can be passed in with `ResolvableWith` instead"
+)]
 pub trait ResolvableFromContainer<C>
     where C: Container
 {
+    /// How deeply nested the dependencies resolved for this one are.
+    ///
+    /// Root dependencies that wrap another, like `RefCell<T>`, are one
+    /// level deeper than `T`'s dependency. The default is `0`, for
+    /// dependencies that don't resolve anything else.
+    /// See `max_depth!` for details.
+    const DEPTH: usize = 0;
+
     fn resolve_from_container(container: &C) -> Self;
+
+    /// Check that this dependency could be resolved without resolving it.
+    ///
+    /// Dependencies that wrap others should verify them too. The default
+    /// implementation assumes there's nothing to check.
+    fn verify(_container: &C, _verification: &mut Verification) {}
 }
 
 /// A dependency that can be resolved.
+///
+/// Root dependencies like `Rc<T>` and `Transient<T>` wrap a `Resolvable`
+/// type, but most aren't `Resolvable` themselves, so they can't be stacked
+/// in ways that don't make sense, like `Rc<Transient<T>>`. The exceptions
+/// are `RefCell<T>`, `Mutex<T>` and `RwLock<T>`, which are how
+/// `Rc<RefCell<T>>` and `Arc<Mutex<T>>` are shared.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` isn't `Resolvable` from `{C}`",
+    label = "`{Self}` isn't `Resolvable`",
+    note = "root dependencies like `Rc<T>` and `Transient<T>` wrap a `Resolvable` type, not another root dependency like `Transient<T>` or `()`"
+)]
 pub trait Resolvable<C> {
     type Dependency;
 
     fn resolve(dependency: Self::Dependency) -> Self;
 }
 
+/// A dependency that can be resolved with a runtime parameter.
+///
+/// This is like `Resolvable`, but for types that need a value the
+/// container can't provide, like a user id or a request body.
+pub trait ResolvableWith<C, P> {
+    type Dependency;
+
+    fn resolve_with(dependency: Self::Dependency, params: P) -> Self;
+}
+
 /// A basic implementation of a container.
-#[derive(Default)]
+#[derive(Default, Clone, Copy)]
 pub struct BasicContainer;
 
 impl Container for BasicContainer {}
 
+impl CaptureContainer for BasicContainer {
+    type Captured = BasicContainer;
+
+    fn capture(&self) -> BasicContainer {
+        *self
+    }
+
+    fn with_captured<F, T>(captured: &BasicContainer, f: F) -> T
+        where F: FnOnce(&Self) -> T
+    {
+        f(captured)
+    }
+}
+
 impl Scope for BasicContainer {
     type Container = Scoped;
 
@@ -0,0 +1,116 @@
+//! Type names for diagnostics
+//!
+//! `type_name` is cheap, but the names it returns are fully qualified, so
+//! something like `alloc::rc::Rc<core::cell::RefCell<app::Y>>` is hard to
+//! read in a report. A `TypeName` can also give a short name without module
+//! paths, like `Rc<RefCell<Y>>`.
+//!
+//! Shortening a name means scanning and formatting it, so short names are
+//! interned in a table shared by every container and only computed once for
+//! each type. The table is process-wide rather than per container because
+//! the names of types don't depend on the container they're resolved from.
+//! Error messages, trace spans, and reports all use the same table, so a
+//! name is only shortened once no matter how often it's shown.
+
+use std::any::type_name;
+use std::collections::HashMap as StdHashMap;
+use std::fmt;
+use std::hash::BuildHasherDefault;
+use std::sync::{Mutex, OnceLock};
+use fnv::FnvHasher;
+
+type HashMap<K, V> = StdHashMap<K, V, BuildHasherDefault<FnvHasher>>;
+
+/// The name of a type.
+#[derive(Debug, Clone, Copy)]
+pub struct TypeName {
+    name: &'static str,
+}
+
+impl TypeName {
+    /// The name of `T`.
+    pub fn of<T>() -> Self
+        where T: ?Sized
+    {
+        TypeName::from_static(type_name::<T>())
+    }
+
+    /// A name returned by `type_name`.
+    pub fn from_static(name: &'static str) -> Self {
+        TypeName { name }
+    }
+
+    /// The fully qualified name.
+    pub fn full(&self) -> &'static str {
+        self.name
+    }
+
+    /// The name without module paths.
+    ///
+    /// The short name is computed the first time it's asked for, and shared
+    /// after that.
+    pub fn short(&self) -> &'static str {
+        static SHORT_NAMES: OnceLock<Mutex<HashMap<(usize, usize), &'static str>>> = OnceLock::new();
+
+        // names are keyed by address, which is cheaper than hashing them
+        // the same name may have several addresses, but that's harmless
+        let key = (self.name.as_ptr() as usize, self.name.len());
+
+        let mut short_names = SHORT_NAMES
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        short_names.entry(key).or_insert_with(|| Box::leak(shorten(self.name).into_boxed_str()))
+    }
+}
+
+impl PartialEq for TypeName {
+    fn eq(&self, other: &TypeName) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for TypeName {}
+
+impl fmt::Display for TypeName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+/// The short name of a type name returned by `type_name`.
+pub(super) fn short_name(name: &'static str) -> &'static str {
+    TypeName::from_static(name).short()
+}
+
+/// Join the short names of a chain of types, like `Service -> Bound<dyn Store>`.
+pub(super) fn short_path(path: &[&'static str]) -> String {
+    let names: Vec<_> = path.iter().map(|&name| short_name(name)).collect();
+
+    names.join(" -> ")
+}
+
+/// Remove the module paths from a type name.
+fn shorten(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut segment = 0;
+    let mut chars = name.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            // a path separator, so drop the segment before it
+            ':' if chars.peek() == Some(&':') => {
+                chars.next();
+                short.truncate(segment);
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '{' || c == '}' => short.push(c),
+            c => {
+                short.push(c);
+                segment = short.len();
+            }
+        }
+    }
+
+    short
+}
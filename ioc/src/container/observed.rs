@@ -0,0 +1,65 @@
+//! Observed construction order
+//!
+//! The static dependency graph doesn't include runtime bindings, but a scope
+//! sees every shared dependency it actually constructs. `Scoped` records
+//! the order its shared dependencies finished constructing in, and which
+//! shared dependencies each one resolved while it was being constructed.
+//!
+//! A value always finishes constructing after its own dependencies, so the
+//! construction order is a dependency order, and its reverse is a safe order
+//! to shut things down in.
+
+use std::any::TypeId;
+use std::fmt;
+
+/// The shared dependencies a scope constructed, and what they depended on.
+#[derive(Debug, Default, Clone)]
+pub struct ObservedGraph {
+    /// Each constructed value, in the order it finished constructing.
+    order: Vec<(TypeId, &'static str)>,
+    /// Each dependent and dependency, by name.
+    edges: Vec<(&'static str, &'static str)>,
+}
+
+impl ObservedGraph {
+    pub(super) fn constructed(&mut self, key: TypeId, type_name: &'static str) {
+        self.order.push((key, type_name));
+    }
+
+    pub(super) fn depended_on(&mut self, dependent: &'static str, dependency: &'static str) {
+        if !self.edges.contains(&(dependent, dependency)) {
+            self.edges.push((dependent, dependency));
+        }
+    }
+
+    /// Where `key` is in the construction order, if it was constructed.
+    pub(super) fn position(&self, key: TypeId) -> Option<usize> {
+        self.order.iter().position(|&(constructed, _)| constructed == key)
+    }
+
+    /// The names of the constructed values, in the order they finished
+    /// constructing.
+    pub fn order(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.order.iter().map(|&(_, type_name)| type_name)
+    }
+
+    /// Each shared dependency resolved while constructing another, as
+    /// `(dependent, dependency)`.
+    pub fn edges(&self) -> impl Iterator<Item = (&'static str, &'static str)> + '_ {
+        self.edges.iter().cloned()
+    }
+}
+
+impl fmt::Display for ObservedGraph {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for type_name in self.order() {
+            writeln!(f, "{}", type_name)?;
+
+            for (_, dependency) in self.edges.iter().filter(|&&(dependent, _)| dependent == type_name) {
+                writeln!(f, "  -> {}", dependency)?;
+            }
+        }
+
+        Ok(())
+    }
+}
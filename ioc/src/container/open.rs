@@ -0,0 +1,145 @@
+//! Open generic bindings
+//!
+//! Runtime bindings are keyed by a closed type, like `dyn Repository<User>`,
+//! so a generic service needs a binding for each type argument it's used
+//! with. An open binding maps the generic service to a generic
+//! implementation once, for any type argument:
+//!
+//! ```
+//! # #[macro_use] extern crate ioc;
+//! # use ioc::prelude::*;
+//! # use std::marker::PhantomData;
+//! # trait Entity {}
+//! # trait Repository<T> {}
+//! # struct SqlRepository<T>(PhantomData<T>);
+//! # impl<T> Repository<T> for SqlRepository<T> {}
+//! # impl<C, T> Resolvable<C> for SqlRepository<T> {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { SqlRepository(PhantomData) }
+//! # }
+//! # struct User;
+//! # impl Entity for User {}
+//! open_binding! {
+//!     impl<T> dyn Repository<T> => SqlRepository<T> where T: Entity + 'static;
+//! }
+//!
+//! # fn main() {
+//! # let scope = Scoped::new();
+//! let users: Open<dyn Repository<User>> = scope.resolve();
+//! # }
+//! ```
+//!
+//! Open bindings are resolved statically, so they don't need a registry and
+//! their dependencies are checked by the compiler like any other type.
+
+use std::ops::Deref;
+use std::rc::Rc;
+
+use super::Resolvable;
+
+/// A generic service with an implementation for any of its type arguments.
+///
+/// This is usually implemented with the `open_binding!` macro.
+pub trait OpenBinding<C> {
+    /// The implementation of the service.
+    type Implementation: Resolvable<C>;
+
+    /// Convert a resolved implementation into the service.
+    fn bind(implementation: Self::Implementation) -> Rc<Self>;
+}
+
+/// The open binding for `T`.
+///
+/// Each request resolves a new implementation.
+pub struct Open<T>
+    where T: ?Sized
+{
+    bound: Rc<T>,
+}
+
+impl<T> Open<T>
+    where T: ?Sized
+{
+    pub fn new(bound: Rc<T>) -> Self {
+        Open { bound }
+    }
+
+    pub fn into_inner(self) -> Rc<T> {
+        self.bound
+    }
+}
+
+impl<T> Deref for Open<T>
+    where T: ?Sized
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.bound
+    }
+}
+
+/// Bind a generic service to a generic implementation for any type argument.
+///
+/// The implementation needs a `Resolvable` impl for any container, and the
+/// service is usually a generic trait object:
+///
+/// ```
+/// # #[macro_use] extern crate ioc;
+/// # use ioc::prelude::*;
+/// # use std::marker::PhantomData;
+/// # trait Entity {}
+/// # trait Repository<T> {}
+/// # struct SqlRepository<T>(PhantomData<T>);
+/// # impl<T> Repository<T> for SqlRepository<T> {}
+/// # impl<C, T> Resolvable<C> for SqlRepository<T> {
+/// #     type Dependency = ();
+/// #     fn resolve(_: ()) -> Self { SqlRepository(PhantomData) }
+/// # }
+/// # trait Cache<K, V> {}
+/// # struct MemoryCache<K, V>(PhantomData<(K, V)>);
+/// # impl<K, V> Cache<K, V> for MemoryCache<K, V> {}
+/// # impl<C, K, V> Resolvable<C> for MemoryCache<K, V> {
+/// #     type Dependency = ();
+/// #     fn resolve(_: ()) -> Self { MemoryCache(PhantomData) }
+/// # }
+/// open_binding! {
+///     impl<T> dyn Repository<T> => SqlRepository<T> where T: Entity + 'static;
+///     impl<K, V> dyn Cache<K, V> => MemoryCache<K, V> where K: 'static, V: 'static;
+/// }
+/// # fn main() {}
+/// ```
+///
+/// Type parameters need to be `'static`. They can't have inline bounds, so
+/// those go in the `where` clause instead.
+#[macro_export]
+macro_rules! open_binding {
+    () => {};
+    (impl<$($param:ident),*> $service:ty => $implementation:ty where $($bound:tt)*) => {
+        $crate::open_binding!(@bounds [$($param),*] [$service] [$implementation] [] $($bound)*);
+    };
+    (impl<$($param:ident),*> $service:ty => $implementation:ty; $($rest:tt)*) => {
+        $crate::open_binding!(@impl [$($param),*] [$service] [$implementation] []);
+        $crate::open_binding!($($rest)*);
+    };
+    // collect the `where` clause up to the `;` that ends the binding
+    (@bounds [$($param:ident),*] [$service:ty] [$implementation:ty] [$($bound:tt)*] ; $($rest:tt)*) => {
+        $crate::open_binding!(@impl [$($param),*] [$service] [$implementation] [$($bound)*]);
+        $crate::open_binding!($($rest)*);
+    };
+    (@bounds [$($param:ident),*] [$service:ty] [$implementation:ty] [$($bound:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::open_binding!(@bounds [$($param),*] [$service] [$implementation] [$($bound)* $next] $($rest)*);
+    };
+    (@impl [$($param:ident),*] [$service:ty] [$implementation:ty] [$($bound:tt)*]) => {
+        impl<__C, $($param),*> $crate::OpenBinding<__C> for $service
+            where $implementation: $crate::Resolvable<__C>,
+                  $($bound)*
+        {
+            type Implementation = $implementation;
+
+            fn bind(implementation: $implementation) -> ::std::rc::Rc<Self> {
+                ::std::rc::Rc::new(implementation)
+            }
+        }
+    };
+}
@@ -0,0 +1,79 @@
+//! Catching panics during resolution
+//!
+//! Dependencies are resolved by user code, which can panic. Containers don't
+//! hold any borrows of their own state while that code runs, and only cache
+//! a value once it's been fully constructed, so a container is still usable
+//! after a panic. `Container::try_resolve` catches the panic and returns it
+//! as the source of an `Error::ConstructionFailed`, for callers that would
+//! rather handle it than unwind.
+
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+
+use super::names::short_name;
+
+/// A panic caught while resolving a dependency.
+pub enum ResolveError {
+    /// Resolving the dependency panicked.
+    Panicked {
+        /// The name of the type that was being resolved.
+        type_name: &'static str,
+        /// The payload of the panic.
+        payload: Box<dyn Any + Send>,
+    },
+}
+
+impl ResolveError {
+    /// The message of the panic, if it had one.
+    ///
+    /// Panics raised with `panic!` have a message, but panics raised with
+    /// `panic_any` or `resume_unwind` might not.
+    pub fn message(&self) -> Option<&str> {
+        match *self {
+            ResolveError::Panicked { ref payload, .. } => {
+                payload.downcast_ref::<&'static str>()
+                    .cloned()
+                    .or_else(|| payload.downcast_ref::<String>().map(|message| &**message))
+            }
+        }
+    }
+
+    /// Continue unwinding with the original panic.
+    pub fn resume_unwind(self) -> ! {
+        match self {
+            ResolveError::Panicked { payload, .. } => ::std::panic::resume_unwind(payload),
+        }
+    }
+}
+
+impl fmt::Debug for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ResolveError::Panicked { type_name, .. } => {
+                f.debug_struct("Panicked")
+                    .field("type_name", &type_name)
+                    .field("message", &self.message())
+                    .finish()
+            }
+        }
+    }
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ResolveError::Panicked { type_name, .. } => {
+                write!(f, "resolving `{}` panicked", short_name(type_name))?;
+
+                if let Some(message) = self.message() {
+                    write!(f, ": {}", message)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for ResolveError {}
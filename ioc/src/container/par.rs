@@ -0,0 +1,111 @@
+//! Parallel dependencies
+//!
+//! Members of a tuple dependency are resolved one after the other. When
+//! they're independent and expensive to construct, like several clients
+//! that each connect to a different service at startup, wrapping the tuple
+//! in a `Par` resolves each member on the rayon pool instead:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use ioc::Par;
+//! # use std::sync::Arc;
+//! # struct Billing;
+//! # struct Search;
+//! # struct Mail;
+//! # impl<C> Resolvable<C> for Billing { type Dependency = (); fn resolve(_: ()) -> Self { Billing } }
+//! # impl<C> Resolvable<C> for Search { type Dependency = (); fn resolve(_: ()) -> Self { Search } }
+//! # impl<C> Resolvable<C> for Mail { type Dependency = (); fn resolve(_: ()) -> Self { Mail } }
+//! # struct Services { billing: Arc<Billing>, search: Arc<Search>, mail: Arc<Mail> }
+//! impl<C: SyncScopedContainer + Sync> Resolvable<C> for Services {
+//!     type Dependency = Par<(Arc<Billing>, Arc<Search>, Arc<Mail>)>;
+//!
+//!     fn resolve(clients: Self::Dependency) -> Self {
+//!         let (billing, search, mail) = clients.into_inner();
+//!
+//!         Services { billing, search, mail }
+//!     }
+//! }
+//! # let services: Services = SyncScoped::new().resolve();
+//! ```
+//!
+//! The container is shared between threads while the members resolve, so
+//! it needs to be `Sync`, like `SyncScoped` or `ArcContainer`, and each
+//! member needs to be `Send`. Members that share a dependency may both
+//! construct it, the same as two threads resolving it at once.
+
+use std::ops::Deref;
+
+use super::*;
+use super::impls::max_depth;
+
+/// A tuple of dependencies that are resolved in parallel.
+pub struct Par<T> {
+    value: T,
+}
+
+impl<T> Par<T> {
+    pub fn new(value: T) -> Self {
+        Par { value }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Par<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+macro_rules! resolve_par {
+    ($(($T:ident, $t:ident))*) => (
+        impl<C $(,$T)*> ResolvableFromContainer<C> for Par<($($T,)*)>
+            where $($T: ResolvableFromContainer<C> + Send,)*
+                  C: Container + Sync
+        {
+            const DEPTH: usize = {
+                let mut depth = 0;
+                $(depth = max_depth(depth, $T::DEPTH);)*
+                depth
+            };
+
+            fn resolve_from_container(container: &C) -> Self {
+                $(let mut $t = None;)*
+
+                rayon::scope(|scope| {
+                    $(
+                        let $t = &mut $t;
+                        scope.spawn(move |_| *$t = Some($T::resolve_from_container(container)));
+                    )*
+                });
+
+                // a member that panicked would have panicked the scope too
+                Par::new(($($t.expect("the member was resolved"),)*))
+            }
+
+            fn verify(container: &C, verification: &mut Verification) {
+                $($T::verify(container, verification);)*
+            }
+        }
+    )
+}
+
+resolve_par!((T1, t1)(T2, t2));
+resolve_par!((T1, t1)(T2, t2)(T3, t3));
+resolve_par!((T1, t1)(T2, t2)(T3, t3)(T4, t4));
+resolve_par!((T1, t1)(T2, t2)(T3, t3)(T4, t4)(T5, t5));
+resolve_par!((T1, t1)(T2, t2)(T3, t3)(T4, t4)(T5, t5)(T6, t6));
+resolve_par!((T1, t1)(T2, t2)(T3, t3)(T4, t4)(T5, t5)(T6, t6)(T7, t7));
+resolve_par!((T1, t1)(T2, t2)(T3, t3)(T4, t4)(T5, t5)(T6, t6)(T7, t7)(T8, t8));
+resolve_par!((T1, t1)(T2, t2)(T3, t3)(T4, t4)(T5, t5)(T6, t6)(T7, t7)(T8, t8)(T9, t9));
+resolve_par!((T1, t1)(T2, t2)(T3, t3)(T4, t4)(T5, t5)(T6, t6)(T7, t7)(T8, t8)(T9, t9)(T10, t10));
+resolve_par!((T1, t1)(T2, t2)(T3, t3)(T4, t4)(T5, t5)(T6, t6)(T7, t7)(T8, t8)(T9, t9)(T10, t10)(T11, t11));
+resolve_par!((T1, t1)(T2, t2)(T3, t3)(T4, t4)(T5, t5)(T6, t6)(T7, t7)(T8, t8)(T9, t9)(T10, t10)(T11, t11)(T12, t12));
+resolve_par!((T1, t1)(T2, t2)(T3, t3)(T4, t4)(T5, t5)(T6, t6)(T7, t7)(T8, t8)(T9, t9)(T10, t10)(T11, t11)(T12, t12)(T13, t13));
+resolve_par!((T1, t1)(T2, t2)(T3, t3)(T4, t4)(T5, t5)(T6, t6)(T7, t7)(T8, t8)(T9, t9)(T10, t10)(T11, t11)(T12, t12)(T13, t13)(T14, t14));
+resolve_par!((T1, t1)(T2, t2)(T3, t3)(T4, t4)(T5, t5)(T6, t6)(T7, t7)(T8, t8)(T9, t9)(T10, t10)(T11, t11)(T12, t12)(T13, t13)(T14, t14)(T15, t15));
+resolve_par!((T1, t1)(T2, t2)(T3, t3)(T4, t4)(T5, t5)(T6, t6)(T7, t7)(T8, t8)(T9, t9)(T10, t10)(T11, t11)(T12, t12)(T13, t13)(T14, t14)(T15, t15)(T16, t16));
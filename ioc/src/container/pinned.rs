@@ -0,0 +1,80 @@
+//! Pinned shared dependencies
+//!
+//! Shared dependencies live in an `Rc` so they don't move while the scope
+//! holds them, but an `Rc<T>` can still be unwrapped once it's the last
+//! reference. A `PinnedRc<T>` is cached separately from `Rc<T>` and is never
+//! handed out unpinned, so types with self-references or intrusive links
+//! can rely on their address staying the same until they're dropped.
+
+use std::fmt;
+use std::ops::Deref;
+use std::pin::Pin;
+use std::ptr;
+use std::rc::Rc;
+
+use super::*;
+
+/// The type cached in the scope for a `PinnedRc<T>`.
+///
+/// This is kept private so it can't be inserted into or taken out of a
+/// scope directly.
+pub(crate) struct Pinned<T>(T);
+
+impl<C, T> Resolvable<C> for Pinned<T>
+    where T: Resolvable<C>
+{
+    type Dependency = T::Dependency;
+
+    fn resolve(dependency: Self::Dependency) -> Self {
+        Pinned(T::resolve(dependency))
+    }
+}
+
+/// A pinned shared dependency.
+///
+/// Each `PinnedRc<T>` resolved from the same scope points to the same `T`,
+/// which won't move until it's dropped. It's a different instance from the
+/// one shared as an `Rc<T>`.
+pub struct PinnedRc<T> {
+    pinned: Pin<Rc<Pinned<T>>>,
+}
+
+impl<T> PinnedRc<T> {
+    pub(super) fn new(pinned: Rc<Pinned<T>>) -> Self {
+        // the `Rc` is only ever handed out pinned
+        PinnedRc { pinned: unsafe { Pin::new_unchecked(pinned) } }
+    }
+
+    /// Get a pinned reference to the value.
+    pub fn as_pin(&self) -> Pin<&T> {
+        // `Pinned` never moves its field
+        unsafe { self.pinned.as_ref().map_unchecked(|pinned| &pinned.0) }
+    }
+
+    /// Whether or not two pinned dependencies point to the same value.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        ptr::eq(&*this.pinned, &*other.pinned)
+    }
+}
+
+impl<T> Clone for PinnedRc<T> {
+    fn clone(&self) -> Self {
+        PinnedRc { pinned: self.pinned.clone() }
+    }
+}
+
+impl<T> Deref for PinnedRc<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.pinned.0
+    }
+}
+
+impl<T> fmt::Debug for PinnedRc<T>
+    where T: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("PinnedRc").field(&**self).finish()
+    }
+}
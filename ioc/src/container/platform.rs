@@ -0,0 +1,110 @@
+//! Platform-specific bindings
+//!
+//! Some dependencies have a different implementation on each platform,
+//! like a key store backed by the macOS keychain or Windows DPAPI. These
+//! could be picked with `#[cfg]`, but then only the bindings for the
+//! current platform can be built and tested. Instead, a registry picks the
+//! implementation using a `Platform` it's given when the binding is added.
+
+use std::env::consts;
+use std::rc::Rc;
+
+use super::*;
+
+/// The operating system and architecture bindings are picked for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Platform {
+    /// The operating system, using the same names as `std::env::consts::OS`.
+    pub os: &'static str,
+    /// The architecture, using the same names as `std::env::consts::ARCH`.
+    pub arch: &'static str,
+}
+
+impl Platform {
+    /// The platform this process is running on.
+    pub fn current() -> Self {
+        Platform {
+            os: consts::OS,
+            arch: consts::ARCH,
+        }
+    }
+}
+
+impl Default for Platform {
+    fn default() -> Self {
+        Platform::current()
+    }
+}
+
+/// A binding that picks its implementation based on the platform.
+///
+/// The first matching implementation is used. Call `otherwise` to bind a
+/// fallback for other platforms, or `finish` to leave them unbound.
+#[must_use = "the binding isn't added until `otherwise` or `finish` is called"]
+pub struct PlatformBinding<'a, C, T>
+    where C: 'static,
+          T: ?Sized + 'static
+{
+    registry: &'a mut Registry<C>,
+    matched: Option<(Binding<C, T>, Implementation)>,
+}
+
+impl<'a, C, T> PlatformBinding<'a, C, T>
+    where C: 'static,
+          T: ?Sized + 'static
+{
+    pub(super) fn new(registry: &'a mut Registry<C>) -> Self {
+        PlatformBinding {
+            registry,
+            matched: None,
+        }
+    }
+
+    /// Use `f` if the platform matches `predicate`.
+    #[track_caller]
+    pub fn on<P, F>(mut self, predicate: P, f: F) -> Self
+        where P: FnOnce(&Platform) -> bool,
+              F: Fn(&C) -> Rc<T> + 'static
+    {
+        if self.matched.is_none() && predicate(self.registry.platform()) {
+            self.matched = Some((Box::new(f), Implementation::closure::<F>(Lifetime::Bound)));
+        }
+
+        self
+    }
+
+    /// Use `f` on the given operating system, like `"macos"` or `"windows"`.
+    #[track_caller]
+    pub fn on_os<F>(self, os: &str, f: F) -> Self
+        where F: Fn(&C) -> Rc<T> + 'static
+    {
+        self.on(|platform| platform.os == os, f)
+    }
+
+    /// Use `f` on the given architecture, like `"x86_64"` or `"aarch64"`.
+    #[track_caller]
+    pub fn on_arch<F>(self, arch: &str, f: F) -> Self
+        where F: Fn(&C) -> Rc<T> + 'static
+    {
+        self.on(|platform| platform.arch == arch, f)
+    }
+
+    /// Use `f` if no other implementation matched the platform.
+    #[track_caller]
+    pub fn otherwise<F>(self, f: F) -> &'a mut Registry<C>
+        where F: Fn(&C) -> Rc<T> + 'static
+    {
+        self.on(|_| true, f).finish()
+    }
+
+    /// Bind the implementation that matched the platform, if there was one.
+    pub fn finish(self) -> &'a mut Registry<C> {
+        let registry = self.registry;
+
+        if let Some((matched, implementation)) = self.matched {
+            registry.bind_implementation::<T>(matched, implementation, None);
+        }
+
+        registry
+    }
+}
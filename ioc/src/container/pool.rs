@@ -0,0 +1,164 @@
+//! Pooled connections
+//!
+//! A connection pool is shared by the whole application, but a connection
+//! should only be held for as long as the work that needs it. A `Pooled<P>`
+//! is a connection checked out of the pool `P` when it's resolved, and
+//! checked back in when it's disposed of, so an `Rc<Pooled<P>>` holds one
+//! connection for the lifetime of a scope:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use ioc::{Pool, Pooled};
+//! # use std::rc::Rc;
+//! # struct Settings { url: String }
+//! # struct Connection;
+//! # struct DbPool;
+//! # impl DbPool {
+//! #     fn new(_: &str) -> Self { DbPool }
+//! # }
+//! # impl Pool for DbPool {
+//! #     type Connection = Connection;
+//! #     type Error = String;
+//! #     fn check_out(&self) -> Result<Connection, String> { Ok(Connection) }
+//! #     fn check_in(&self, _: Connection) {}
+//! # }
+//! # struct Repository { conn: Rc<Pooled<DbPool>> }
+//! # let settings = Settings { url: "postgres://localhost".into() };
+//! # let mut registry = Registry::new();
+//! registry.bind_pool(DbPool::new(&settings.url));
+//!
+//! impl Resolvable<Scoped> for Repository {
+//!     type Dependency = Rc<Pooled<DbPool>>;
+//!
+//!     fn resolve(conn: Self::Dependency) -> Self {
+//!         Repository { conn }
+//!     }
+//! }
+//! # let repository: Repository = Scoped::new().with_registry(Rc::new(registry)).resolve();
+//! ```
+//!
+//! Orphan rules mean `Pool` can't be implemented for a pool from another
+//! crate, like `r2d2::Pool`, so wrap it in a type of your own first.
+
+use std::any::type_name;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+use super::*;
+
+/// A pool that connections are checked out of and back into.
+pub trait Pool {
+    type Connection;
+    type Error: fmt::Display;
+
+    /// Take a connection from the pool, waiting for one if it needs to.
+    fn check_out(&self) -> Result<Self::Connection, Self::Error>;
+
+    /// Give a connection back to the pool.
+    fn check_in(&self, connection: Self::Connection);
+}
+
+/// A connection checked out of the pool `P`.
+///
+/// The connection is checked back in when it's disposed of, or when it's
+/// dropped if that never happens.
+pub struct Pooled<P>
+    where P: Pool
+{
+    pool: Rc<P>,
+    connection: Option<P::Connection>,
+}
+
+impl<P> Pooled<P>
+    where P: Pool
+{
+    /// Check a connection out of `pool`.
+    pub fn check_out(pool: Rc<P>) -> Result<Self, P::Error> {
+        let connection = pool.check_out()?;
+
+        Ok(Pooled {
+            pool,
+            connection: Some(connection),
+        })
+    }
+
+    /// The pool the connection will be checked back into.
+    pub fn pool(&self) -> &Rc<P> {
+        &self.pool
+    }
+
+    fn check_in(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            self.pool.check_in(connection);
+        }
+    }
+}
+
+impl<P> Deref for Pooled<P>
+    where P: Pool
+{
+    type Target = P::Connection;
+
+    fn deref(&self) -> &P::Connection {
+        self.connection.as_ref().expect("the connection has already been checked in")
+    }
+}
+
+impl<P> DerefMut for Pooled<P>
+    where P: Pool
+{
+    fn deref_mut(&mut self) -> &mut P::Connection {
+        self.connection.as_mut().expect("the connection has already been checked in")
+    }
+}
+
+impl<P> Disposable for Pooled<P>
+    where P: Pool
+{
+    fn dispose(&mut self) {
+        self.check_in();
+    }
+}
+
+impl<P> Drop for Pooled<P>
+    where P: Pool
+{
+    fn drop(&mut self) {
+        self.check_in();
+    }
+}
+
+impl<P> fmt::Debug for Pooled<P>
+    where P: Pool
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(type_name::<Self>())
+    }
+}
+
+impl<C, P> Resolvable<C> for Pooled<P>
+    where C: RegisteredContainer + 'static,
+          P: Pool + 'static
+{
+    type Dependency = Bound<P>;
+
+    fn resolve(pool: Self::Dependency) -> Self {
+        Pooled::check_out(pool.into_inner())
+            .unwrap_or_else(|e| panic!("couldn't check out a connection from `{}`: {}", type_name::<P>(), e))
+    }
+}
+
+impl<C> Registry<C>
+    where C: 'static
+{
+    /// Share `pool` with every scope, and check each `Pooled<P>` cached by
+    /// a scope back in when the scope ends.
+    pub fn bind_pool<P>(&mut self, pool: P) -> &mut Self
+        where P: Pool + 'static
+    {
+        let pool = Rc::new(pool);
+
+        self.bind::<P, _>(move |_| pool.clone()).disposable::<Pooled<P>>()
+    }
+}
@@ -0,0 +1,372 @@
+//! Borrowed scoped dependencies
+//!
+//! `Scoped` hands out shared dependencies as `Rc<T>`, which costs a
+//! reference count increment and decrement for each one. A `Borrowed<'scope>`
+//! container hands them out as `&'scope T` instead, wrapped in a `B<'scope, T>`.
+//!
+//! The `'scope` lifetime is introduced by `BorrowScope::borrow_scope` for
+//! the duration of its closure, so borrowed dependencies can't escape it.
+//! Values are bumped into chunks of memory that are never moved or freed
+//! until the scope ends, so references to them stay valid even when the
+//! map holding them grows.
+
+use super::*;
+
+use std::alloc::{self, Layout};
+use std::any::TypeId;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap as StdHashMap;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::Deref;
+use std::ptr::{self, NonNull};
+
+// `TypeId`s are already hashes, so they're used as-is
+type HashMap<K, V> = StdHashMap<K, V, BuildTypeIdHasher>;
+
+/// The size of the first chunk of memory values are bumped into.
+///
+/// Each chunk after it is twice as big, up to `MAX_CHUNK_SIZE`.
+const MIN_CHUNK_SIZE: usize = 256;
+const MAX_CHUNK_SIZE: usize = 4096;
+/// The alignment of each chunk.
+///
+/// Values that need a greater alignment, or are too big to share a chunk,
+/// get a chunk of their own.
+const CHUNK_ALIGN: usize = 16;
+
+/// A value in the arena and a function to drop it.
+struct Entry {
+    ptr: *mut (),
+    drop: unsafe fn(*mut ()),
+}
+
+unsafe fn drop_entry<T>(ptr: *mut ()) {
+    ptr::drop_in_place(ptr as *mut T);
+}
+
+/// A block of memory owned by the arena.
+struct Chunk {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+impl Chunk {
+    fn new(layout: Layout) -> Self {
+        let ptr = NonNull::new(unsafe { alloc::alloc(layout) }).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+
+        Chunk { ptr, layout }
+    }
+}
+
+impl Drop for Chunk {
+    fn drop(&mut self) {
+        unsafe { alloc::dealloc(self.ptr.as_ptr(), self.layout) }
+    }
+}
+
+/// Storage for a borrowed scope.
+///
+/// Values are bumped into chunks of memory that are never moved or freed
+/// until the arena is dropped, so references to them stay valid even when
+/// the map of entries grows. Entries are only dropped when the arena is,
+/// in the reverse order they were inserted.
+struct Arena {
+    refs: RefCell<HashMap<TypeId, Entry>>,
+    order: RefCell<Vec<TypeId>>,
+    chunks: RefCell<Vec<Chunk>>,
+    /// The last chunk values can be bumped into, if there is one.
+    current: Cell<Option<NonNull<u8>>>,
+    /// The number of bytes used in the current chunk.
+    used: Cell<usize>,
+    /// The size of the current chunk.
+    capacity: Cell<usize>,
+}
+
+impl Arena {
+    fn new() -> Self {
+        Arena {
+            refs: RefCell::new(HashMap::default()),
+            order: RefCell::new(Vec::new()),
+            chunks: RefCell::new(Vec::new()),
+            current: Cell::new(None),
+            used: Cell::new(0),
+            capacity: Cell::new(0),
+        }
+    }
+
+    fn get<T>(&self) -> Option<*const T>
+        where T: 'static
+    {
+        self.refs.borrow().get(&TypeId::of::<T>()).map(|entry| entry.ptr as *const T)
+    }
+
+    /// Allocate uninitialized memory for a value with the given layout.
+    ///
+    /// The layout can't be zero-sized.
+    fn alloc(&self, layout: Layout) -> NonNull<u8> {
+        // values that don't fit a shared chunk get their own
+        if layout.align() > CHUNK_ALIGN || layout.size() > MAX_CHUNK_SIZE / 4 {
+            let chunk = Chunk::new(layout);
+            let ptr = chunk.ptr;
+
+            self.chunks.borrow_mut().push(chunk);
+
+            return ptr;
+        }
+
+        // chunks are aligned to `CHUNK_ALIGN`, so aligning the offset aligns the pointer
+        let start = (self.used.get() + layout.align() - 1) & !(layout.align() - 1);
+
+        let current = match self.current.get() {
+            Some(current) if start + layout.size() <= self.capacity.get() => {
+                self.used.set(start + layout.size());
+
+                current
+            }
+            _ => {
+                let capacity = (self.capacity.get() * 2)
+                    .clamp(MIN_CHUNK_SIZE, MAX_CHUNK_SIZE)
+                    .max(layout.size().next_power_of_two());
+
+                let chunk = Chunk::new(Layout::from_size_align(capacity, CHUNK_ALIGN).expect("the chunk layout is valid"));
+                let current = chunk.ptr;
+
+                self.chunks.borrow_mut().push(chunk);
+                self.current.set(Some(current));
+                self.used.set(layout.size());
+                self.capacity.set(capacity);
+
+                return current;
+            }
+        };
+
+        unsafe { NonNull::new_unchecked(current.as_ptr().add(start)) }
+    }
+
+    /// Insert a value into the arena.
+    ///
+    /// It's a bug to insert a value for a type that's already in the arena.
+    fn insert<T>(&self, t: T) -> *const T
+        where T: 'static
+    {
+        let ptr = if mem::size_of::<T>() == 0 {
+            NonNull::<T>::dangling()
+        } else {
+            self.alloc(Layout::new::<T>()).cast::<T>()
+        };
+
+        unsafe { ptr.as_ptr().write(t) };
+
+        let previous = self.refs.borrow_mut().insert(TypeId::of::<T>(), Entry {
+            ptr: ptr.as_ptr() as *mut (),
+            drop: drop_entry::<T>,
+        });
+
+        debug_assert!(previous.is_none(), "a value was inserted into a borrowed scope twice");
+
+        self.order.borrow_mut().push(TypeId::of::<T>());
+
+        ptr.as_ptr()
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        // values may borrow from the values they were resolved from, which
+        // are always inserted first, so they need to be dropped first
+        let refs = self.refs.get_mut();
+
+        for key in self.order.get_mut().drain(..).rev() {
+            if let Some(entry) = refs.remove(&key) {
+                unsafe { (entry.drop)(entry.ptr) }
+            }
+        }
+
+        // the chunks are freed after everything in them has been dropped
+    }
+}
+
+/// A scoped container that hands out borrowed shared dependencies.
+pub struct Borrowed<'scope> {
+    arena: &'scope Arena,
+    // `'scope` is invariant so it can't be shortened or extended
+    _scope: PhantomData<fn(&'scope ()) -> &'scope ()>,
+}
+
+impl<'scope> Container for Borrowed<'scope> {}
+
+/// A scoped container that can resolve borrowed shared dependencies.
+pub trait BorrowedContainer<'scope>
+    where Self: Container
+{
+    fn get_or_add_ref<T, D>(&self) -> &'scope T
+        where T: Resolvable<Self, Dependency = D> + 'static,
+              D: ResolvableFromContainer<Self>;
+}
+
+impl<'scope> BorrowedContainer<'scope> for Borrowed<'scope> {
+    fn get_or_add_ref<T, D>(&self) -> &'scope T
+        where T: Resolvable<Self, Dependency = D> + 'static,
+              D: ResolvableFromContainer<Self>
+    {
+        let ptr = match self.arena.get::<T>() {
+            Some(ptr) => ptr,
+            None => {
+                let d = D::resolve_from_container(self);
+                let t = T::resolve(d);
+
+                self.arena.insert(t)
+            }
+        };
+
+        // the value is in a chunk that lives until the arena is dropped
+        // at the end of `'scope`
+        unsafe { &*ptr }
+    }
+}
+
+/// A trait for creating a new borrowed scope and using it within a closure.
+///
+/// The closure's value is returned from the scope, as long as it doesn't
+/// borrow anything from it. Borrowed dependencies can't outlive the scope:
+///
+/// ```compile_fail
+/// use ioc::prelude::*;
+///
+/// struct X;
+///
+/// impl<C> Resolvable<C> for X {
+///     type Dependency = ();
+///
+///     fn resolve(_: ()) -> Self {
+///         X
+///     }
+/// }
+///
+/// let x: &X = BasicContainer.borrow_scope(|scope| scope.get_or_add_ref::<X, ()>());
+/// ```
+pub trait BorrowScope {
+    fn borrow_scope<F, T>(&self, f: F) -> T where F: for<'scope> FnOnce(Borrowed<'scope>) -> T;
+}
+
+impl BorrowScope for BasicContainer {
+    fn borrow_scope<F, T>(&self, f: F) -> T
+        where F: for<'scope> FnOnce(Borrowed<'scope>) -> T
+    {
+        let arena = Arena::new();
+
+        f(Borrowed {
+            arena: &arena,
+            _scope: PhantomData,
+        })
+    }
+}
+
+/// A borrowed shared dependency.
+///
+/// Each `B<'scope, T>` resolved from the same scope points to the same `T`.
+/// A `B` can only be resolved from its scope, not created from any other
+/// `&'scope T`.
+///
+/// `'scope` brands the value with the scope it came from. Like the scope's
+/// own lifetime it's invariant, so values from different scopes can't be
+/// mixed, even when one scope is nested inside the other:
+///
+/// ```compile_fail
+/// use ioc::prelude::*;
+///
+/// struct X;
+///
+/// impl<C> Resolvable<C> for X {
+///     type Dependency = ();
+///
+///     fn resolve(_: ()) -> Self {
+///         X
+///     }
+/// }
+///
+/// fn same_scope<'scope>(_: B<'scope, X>, _: B<'scope, X>) {}
+///
+/// BasicContainer.borrow_scope(|outer| {
+///     let a = B::<X>::resolve_from_container(&outer);
+///
+///     BasicContainer.borrow_scope(|inner| {
+///         let b = B::<X>::resolve_from_container(&inner);
+///
+///         same_scope(a, b);
+///     });
+/// });
+/// ```
+///
+/// They also can't outlive their scope:
+///
+/// ```compile_fail
+/// use ioc::prelude::*;
+///
+/// struct X;
+///
+/// impl<C> Resolvable<C> for X {
+///     type Dependency = ();
+///
+///     fn resolve(_: ()) -> Self {
+///         X
+///     }
+/// }
+///
+/// let x = BasicContainer.borrow_scope(|scope| B::<X>::resolve_from_container(&scope));
+/// ```
+pub struct B<'scope, T>
+    where T: 'scope
+{
+    value: &'scope T,
+    // `'scope` is invariant so it can't be shortened to another scope's
+    _scope: PhantomData<fn(&'scope ()) -> &'scope ()>,
+}
+
+impl<'scope, T> B<'scope, T> {
+    pub(crate) fn new(value: &'scope T) -> Self {
+        B {
+            value,
+            _scope: PhantomData,
+        }
+    }
+
+    /// The borrowed value, for as long as the scope lives.
+    ///
+    /// Dereferencing a `B` only borrows the value for as long as the `B`.
+    pub fn get(&self) -> &'scope T {
+        self.value
+    }
+
+    pub fn into_inner(self) -> &'scope T {
+        self.value
+    }
+
+    #[deprecated(note = "use `into_inner` or `get` instead")]
+    pub fn value(self) -> &'scope T {
+        self.value
+    }
+}
+
+impl<'scope, T> AsRef<T> for B<'scope, T> {
+    fn as_ref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<'scope, T> Clone for B<'scope, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'scope, T> Copy for B<'scope, T> {}
+
+impl<'scope, T> Deref for B<'scope, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
@@ -0,0 +1,1077 @@
+//! Runtime bindings
+//!
+//! Most dependencies are resolved statically through `Resolvable`, but that
+//! can't express things like _every implementation of a trait_. The
+//! `Registry` holds bindings that are added at runtime, keyed by the type
+//! they produce, so containers that carry one can resolve them.
+
+use super::*;
+use super::dispose::Dispose;
+use super::lifecycle::Startup;
+
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap as StdHashMap;
+use std::collections::hash_map;
+use std::fmt;
+use std::hash::{BuildHasherDefault, Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::panic::Location;
+use std::slice;
+use std::vec;
+use fnv::FnvHasher;
+
+type HashMap<K, V> = StdHashMap<K, V, BuildHasherDefault<FnvHasher>>;
+
+/// A factory for a single binding of `T`.
+///
+/// These are stored in the registry as `Box<dyn Any>` so bindings for
+/// different types can live in the same map.
+pub(super) type Binding<C, T> = Box<dyn Fn(&C) -> Rc<T>>;
+
+/// A decorator that wraps resolved values of `T`.
+type Decorator<T> = Box<dyn Fn(Rc<T>) -> Rc<T>>;
+
+/// A function that verifies the dependencies of a binding.
+pub(super) type VerifyBinding<C> = fn(&C, &mut Verification);
+
+/// What a binding resolves, which is what its fingerprint is built from.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Implementation {
+    /// The name of the implementation, or the closure that produces it.
+    name: &'static str,
+    /// Where a closure was bound.
+    ///
+    /// Every closure in a function has the same name, so this is what tells
+    /// them apart.
+    location: Option<&'static Location<'static>>,
+    /// How long the values it resolves live for.
+    lifetime: Lifetime,
+}
+
+impl Implementation {
+    /// A binding to the implementation `R`.
+    pub(super) fn of<R>(lifetime: Lifetime) -> Self
+        where R: ?Sized
+    {
+        Implementation {
+            name: type_name::<R>(),
+            location: None,
+            lifetime,
+        }
+    }
+
+    /// A binding to the closure `F`, bound by the caller.
+    #[track_caller]
+    pub(super) fn closure<F>(lifetime: Lifetime) -> Self {
+        Implementation {
+            name: type_name::<F>(),
+            location: Some(Location::caller()),
+            lifetime,
+        }
+    }
+
+    /// Describe the implementation for a fingerprint.
+    fn describe(&self) -> String {
+        match self.location {
+            Some(location) => format!("{} at {} ({:?})", self.name, location, self.lifetime),
+            None => format!("{} ({:?})", self.name, self.lifetime),
+        }
+    }
+}
+
+/// A binding in the registry.
+struct Entry<C> {
+    /// The name of the type the binding produces.
+    type_name: &'static str,
+    /// What the binding resolves.
+    implementation: Implementation,
+    /// The `Binding<C, T>` for the type.
+    binding: Box<dyn Any>,
+    /// Verify the binding's dependencies, if they're known.
+    verify: Option<VerifyBinding<C>>,
+}
+
+impl<C> Entry<C> {
+    fn new<T>(binding: Binding<C, T>, implementation: Implementation, verify: Option<VerifyBinding<C>>) -> Self
+        where C: 'static,
+              T: ?Sized + 'static
+    {
+        Entry {
+            type_name: type_name::<T>(),
+            implementation,
+            binding: Box::new(binding),
+            verify,
+        }
+    }
+
+    fn resolve<T>(&self, container: &C) -> Rc<T>
+        where C: 'static,
+              T: ?Sized + 'static
+    {
+        let binding = self.binding
+            .downcast_ref::<Binding<C, T>>()
+            .expect("bindings are keyed by their type");
+
+        binding(container)
+    }
+
+    fn verify(&self, container: &C, verification: &mut Verification) {
+        if let Some(verify) = self.verify {
+            verify(container, verification);
+        }
+    }
+}
+
+/// A binding in the registry that's added under a key.
+struct KeyedEntry<C> {
+    /// The key, which is a `K` for a `KeyedAll<K, T>`.
+    key: Box<dyn Any>,
+    /// The name of the key's type.
+    key_type_name: &'static str,
+    /// A hash of the key, so it can be fingerprinted.
+    key_hash: u64,
+    entry: Entry<C>,
+}
+
+impl<C> KeyedEntry<C> {
+    fn key<K>(&self) -> &K
+        where K: 'static
+    {
+        self.key.downcast_ref::<K>().expect("keyed bindings are keyed by their key type")
+    }
+}
+
+/// A decorator in the registry.
+struct Decoration {
+    /// The name of the type the decorator wraps.
+    type_name: &'static str,
+    /// The closure that wraps it.
+    implementation: Implementation,
+    /// The `Decorator<T>` for the type.
+    decorator: Box<dyn Any>,
+}
+
+impl Decoration {
+    fn decorate<T>(&self, value: Rc<T>) -> Rc<T>
+        where T: ?Sized + 'static
+    {
+        let decorator = self.decorator
+            .downcast_ref::<Decorator<T>>()
+            .expect("decorators are keyed by their type");
+
+        decorator(value)
+    }
+}
+
+/// Verify the dependencies of a binding to `R`.
+fn verify_resolvable<C, R, D>(container: &C, verification: &mut Verification)
+    where C: Container,
+          D: ResolvableFromContainer<C>
+{
+    verification.visit(DependencyKind::Implementation, type_name::<R>(), |verification| D::verify(container, verification));
+}
+
+/// A set of bindings added at runtime.
+pub struct Registry<C> {
+    single: HashMap<TypeId, Entry<C>>,
+    multi: HashMap<TypeId, Vec<Entry<C>>>,
+    keyed: HashMap<TypeId, Vec<KeyedEntry<C>>>,
+    decorators: HashMap<TypeId, Vec<Decoration>>,
+    modules: Vec<&'static str>,
+    disposables: HashMap<TypeId, (&'static str, Box<dyn Any>)>,
+    initializers: HashMap<TypeId, (&'static str, Box<dyn Any>)>,
+    startables: Vec<Startup<C>>,
+    platform: Platform,
+    _container: PhantomData<fn(&C)>,
+}
+
+impl<C> Default for Registry<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> Registry<C> {
+    pub fn new() -> Self {
+        Registry {
+            single: HashMap::default(),
+            multi: HashMap::default(),
+            keyed: HashMap::default(),
+            decorators: HashMap::default(),
+            modules: Vec::new(),
+            disposables: HashMap::default(),
+            initializers: HashMap::default(),
+            startables: Vec::new(),
+            platform: Platform::current(),
+            _container: PhantomData,
+        }
+    }
+
+    /// Use a different platform when picking platform-specific bindings.
+    ///
+    /// By default this is the platform the registry is running on. Tests
+    /// can override it to check the bindings for other platforms.
+    pub fn with_platform(mut self, platform: Platform) -> Self {
+        self.platform = platform;
+
+        self
+    }
+
+    /// The platform used to pick platform-specific bindings.
+    pub fn platform(&self) -> &Platform {
+        &self.platform
+    }
+
+    /// Dispose of any `T` cached by a scope when the scope ends.
+    ///
+    /// See `Disposable` for details.
+    pub fn disposable<T>(&mut self) -> &mut Self
+        where T: Disposable + 'static
+    {
+        let dispose: Dispose<T> = T::dispose;
+
+        self.disposables.insert(Self::key::<T>(), (type_name::<T>(), Box::new(dispose)));
+
+        self
+    }
+
+    /// The function to dispose of a `T`, if it's disposable.
+    pub(super) fn disposer<T>(&self) -> Option<Dispose<T>>
+        where T: 'static
+    {
+        self.disposables
+            .get(&Self::key::<T>())
+            .map(|(_, dispose)| *dispose.downcast_ref::<Dispose<T>>().expect("disposers are keyed by their type"))
+    }
+
+    /// Wrap a resolved `T` in each of its decorators.
+    fn decorated<T>(&self, value: Rc<T>) -> Rc<T>
+        where T: ?Sized + 'static
+    {
+        match self.decorators.get(&Self::key::<T>()) {
+            Some(decorations) => decorations.iter().fold(value, |value, decoration| decoration.decorate(value)),
+            None => value,
+        }
+    }
+
+    fn key<T>() -> TypeId
+        where T: ?Sized + 'static
+    {
+        TypeId::of::<T>()
+    }
+
+    fn keyed_key<K, T>() -> TypeId
+        where K: 'static,
+              T: ?Sized + 'static
+    {
+        TypeId::of::<KeyedAll<K, T>>()
+    }
+
+    fn insert_keyed<K, T>(&mut self, key: K, entry: Entry<C>)
+        where K: Eq + Hash + 'static,
+              T: ?Sized + 'static
+    {
+        let entries = self.keyed.entry(Self::keyed_key::<K, T>()).or_default();
+
+        match entries.iter_mut().find(|keyed| *keyed.key::<K>() == key) {
+            Some(keyed) => keyed.entry = entry,
+            None => {
+                let mut hasher = FnvHasher::default();
+                key.hash(&mut hasher);
+
+                entries.push(KeyedEntry {
+                    key: Box::new(key),
+                    key_type_name: type_name::<K>(),
+                    key_hash: hasher.finish(),
+                    entry,
+                });
+            }
+        }
+    }
+}
+
+impl<C> Registry<C>
+    where C: 'static
+{
+    /// Initialize any `T` constructed by a scope before it's cached.
+    ///
+    /// See `Initialize` for details.
+    pub fn initializable<T>(&mut self) -> &mut Self
+        where T: Initialize<C> + 'static
+    {
+        let initialize: Initializer<C, T> = T::initialize;
+
+        self.initializers.insert(Self::key::<T>(), (type_name::<T>(), Box::new(initialize)));
+
+        self
+    }
+
+    /// The function to initialize a `T`, if it needs initializing.
+    pub(super) fn initializer<T>(&self) -> Option<Initializer<C, T>>
+        where T: 'static
+    {
+        self.initializers
+            .get(&Self::key::<T>())
+            .map(|(_, initialize)| *initialize.downcast_ref::<Initializer<C, T>>().expect("initializers are keyed by their type"))
+    }
+
+    /// Bind `T`, replacing any previous binding.
+    ///
+    /// The binding can be resolved using a `Bound<T>` dependency. `T` is
+    /// usually a trait object, like `dyn KeyStore`:
+    ///
+    /// ```
+    /// # use ioc::prelude::*;
+    /// # use std::rc::Rc;
+    /// # trait KeyStore {}
+    /// # struct FileStore;
+    /// # impl<C> Resolvable<C> for FileStore {
+    /// #     type Dependency = ();
+    /// #     fn resolve(_: ()) -> Self { FileStore }
+    /// # }
+    /// # impl KeyStore for FileStore {}
+    /// # let mut registry = Registry::<Scoped>::new();
+    /// registry.bind::<dyn KeyStore, _>(|c| Rc::new(c.resolve::<_, FileStore>()));
+    /// ```
+    #[track_caller]
+    pub fn bind<T, F>(&mut self, f: F) -> &mut Self
+        where T: ?Sized + 'static,
+              F: Fn(&C) -> Rc<T> + 'static
+    {
+        self.bind_implementation::<T>(Box::new(f), Implementation::closure::<F>(Lifetime::Bound), None)
+    }
+
+    /// Bind `T` to a boxed binding, replacing any previous binding.
+    pub(super) fn bind_implementation<T>(&mut self, binding: Binding<C, T>, implementation: Implementation, verify: Option<VerifyBinding<C>>) -> &mut Self
+        where T: ?Sized + 'static
+    {
+        self.single.insert(Self::key::<T>(), Entry::new(binding, implementation, verify));
+
+        self
+    }
+
+    /// Bind `T` to one of several implementations depending on the platform.
+    ///
+    /// The implementation is picked now, rather than each time `T` is
+    /// resolved:
+    ///
+    /// ```
+    /// # use ioc::prelude::*;
+    /// # use std::rc::Rc;
+    /// # trait KeyStore {}
+    /// # struct KeychainStore;
+    /// # impl<C> Resolvable<C> for KeychainStore {
+    /// #     type Dependency = ();
+    /// #     fn resolve(_: ()) -> Self { KeychainStore }
+    /// # }
+    /// # impl KeyStore for KeychainStore {}
+    /// # struct DpapiStore;
+    /// # impl<C> Resolvable<C> for DpapiStore {
+    /// #     type Dependency = ();
+    /// #     fn resolve(_: ()) -> Self { DpapiStore }
+    /// # }
+    /// # impl KeyStore for DpapiStore {}
+    /// # struct FileStore;
+    /// # impl<C> Resolvable<C> for FileStore {
+    /// #     type Dependency = ();
+    /// #     fn resolve(_: ()) -> Self { FileStore }
+    /// # }
+    /// # impl KeyStore for FileStore {}
+    /// # let mut registry = Registry::<Scoped>::new();
+    /// registry
+    ///     .bind_platform::<dyn KeyStore>()
+    ///     .on_os("macos", |c| Rc::new(c.resolve::<_, KeychainStore>()))
+    ///     .on_os("windows", |c| Rc::new(c.resolve::<_, DpapiStore>()))
+    ///     .otherwise(|c| Rc::new(c.resolve::<_, FileStore>()));
+    /// ```
+    pub fn bind_platform<T>(&mut self) -> PlatformBinding<'_, C, T>
+        where T: ?Sized + 'static
+    {
+        PlatformBinding::new(self)
+    }
+
+    /// Add a binding for `T` alongside any others.
+    ///
+    /// All bindings for `T` can be resolved together using an `All<T>`
+    /// dependency. `T` is usually a trait object, like `dyn Handler`:
+    ///
+    /// ```
+    /// # use ioc::prelude::*;
+    /// # use std::rc::Rc;
+    /// # trait Handler {}
+    /// # struct LogHandler;
+    /// # impl<C> Resolvable<C> for LogHandler {
+    /// #     type Dependency = ();
+    /// #     fn resolve(_: ()) -> Self { LogHandler }
+    /// # }
+    /// # impl Handler for LogHandler {}
+    /// # let mut registry = Registry::<Scoped>::new();
+    /// registry.bind_all::<dyn Handler, _>(|c| Rc::new(c.resolve::<_, LogHandler>()));
+    /// ```
+    #[track_caller]
+    pub fn bind_all<T, F>(&mut self, f: F) -> &mut Self
+        where T: ?Sized + 'static,
+              F: Fn(&C) -> Rc<T> + 'static
+    {
+        self.multi
+            .entry(Self::key::<T>())
+            .or_default()
+            .push(Entry::new::<T>(Box::new(f), Implementation::closure::<F>(Lifetime::Bound), None));
+
+        self
+    }
+
+    /// Add a binding for `T` under `key`, replacing any previous binding
+    /// under the same key.
+    ///
+    /// All bindings for `T` with keys of type `K` can be resolved together
+    /// as a map using a `KeyedAll<K, T>` dependency. `K` is usually an enum,
+    /// like the formats a file can be exported in:
+    ///
+    /// ```
+    /// # use ioc::prelude::*;
+    /// # use std::rc::Rc;
+    /// # #[derive(Clone, PartialEq, Eq, Hash)]
+    /// # enum Format { Csv, Json }
+    /// # trait Exporter {}
+    /// # struct CsvExporter;
+    /// # struct JsonExporter;
+    /// # impl Exporter for CsvExporter {}
+    /// # impl Exporter for JsonExporter {}
+    /// # let mut registry = Registry::<Scoped>::new();
+    /// registry
+    ///     .bind_keyed::<Format, dyn Exporter, _>(Format::Csv, |_| Rc::new(CsvExporter))
+    ///     .bind_keyed::<Format, dyn Exporter, _>(Format::Json, |_| Rc::new(JsonExporter));
+    /// ```
+    #[track_caller]
+    pub fn bind_keyed<K, T, F>(&mut self, key: K, f: F) -> &mut Self
+        where K: Eq + Hash + Clone + 'static,
+              T: ?Sized + 'static,
+              F: Fn(&C) -> Rc<T> + 'static
+    {
+        self.insert_keyed::<K, T>(key, Entry::new::<T>(Box::new(f), Implementation::closure::<F>(Lifetime::Bound), None));
+
+        self
+    }
+
+    /// Wrap every resolved `T` with a decorator.
+    ///
+    /// Decorators apply to the binding for `T` and to each of its `All<T>`
+    /// and `KeyedAll<K, T>` bindings. They're applied in the order they were
+    /// added, so the last decorator is the outermost:
+    ///
+    /// ```
+    /// # use ioc::prelude::*;
+    /// # use std::rc::Rc;
+    /// # trait Repo {}
+    /// # struct CachingRepo(Rc<dyn Repo>);
+    /// # struct LoggingRepo(Rc<dyn Repo>);
+    /// # impl CachingRepo { fn new(inner: Rc<dyn Repo>) -> Self { CachingRepo(inner) } }
+    /// # impl LoggingRepo { fn new(inner: Rc<dyn Repo>) -> Self { LoggingRepo(inner) } }
+    /// # impl Repo for CachingRepo {}
+    /// # impl Repo for LoggingRepo {}
+    /// # let mut registry = Registry::<Scoped>::new();
+    /// registry
+    ///     .decorate::<dyn Repo, _>(|inner| Rc::new(CachingRepo::new(inner)))
+    ///     .decorate::<dyn Repo, _>(|inner| Rc::new(LoggingRepo::new(inner)));
+    /// ```
+    ///
+    /// Values used in place of a binding with `Scoped::override_with` aren't
+    /// decorated.
+    #[track_caller]
+    pub fn decorate<T, F>(&mut self, f: F) -> &mut Self
+        where T: ?Sized + 'static,
+              F: Fn(Rc<T>) -> Rc<T> + 'static
+    {
+        let decorator: Decorator<T> = Box::new(f);
+
+        self.decorators
+            .entry(Self::key::<T>())
+            .or_default()
+            .push(Decoration {
+                type_name: type_name::<T>(),
+                implementation: Implementation::closure::<F>(Lifetime::Bound),
+                decorator: Box::new(decorator),
+            });
+
+        self
+    }
+
+    /// Add all of the bindings in a module.
+    pub fn add_module<M>(&mut self, module: M) -> &mut Self
+        where M: Module<C>
+    {
+        self.modules.push(type_name::<M>());
+
+        module.register(self);
+
+        self
+    }
+
+    /// Whether or not `T` is bound.
+    pub fn is_bound<T>(&self) -> bool
+        where T: ?Sized + 'static
+    {
+        self.single.contains_key(&Self::key::<T>())
+    }
+
+    /// Resolve the binding for `T`, if there is one.
+    pub fn resolve<T>(&self, container: &C) -> Option<Rc<T>>
+        where T: ?Sized + 'static
+    {
+        self.single.get(&Self::key::<T>()).map(|entry| self.decorated(entry.resolve(container)))
+    }
+
+    /// Fingerprint the bindings and modules in the registry.
+    ///
+    /// See `Fingerprint` for details.
+    pub fn fingerprint(&self) -> Fingerprint {
+        let mut fingerprint = Fingerprint::new();
+
+        for entry in self.single.values() {
+            fingerprint.add("bind", entry.type_name, &[entry.implementation.describe()]);
+        }
+
+        for entries in self.multi.values() {
+            let implementations: Vec<_> = entries.iter().map(|entry| entry.implementation.describe()).collect();
+
+            fingerprint.add("bind_all", entries[0].type_name, &implementations);
+        }
+
+        for entries in self.keyed.values() {
+            // keyed bindings are a map, so the order they were added in doesn't matter
+            let mut implementations: Vec<_> = entries
+                .iter()
+                .map(|keyed| format!("{:016x} {}", keyed.key_hash, keyed.entry.implementation.describe()))
+                .collect();
+            implementations.sort_unstable();
+
+            let type_name = format!("{} => {}", entries[0].key_type_name, entries[0].entry.type_name);
+
+            fingerprint.add("bind_keyed", &type_name, &implementations);
+        }
+
+        for decorations in self.decorators.values() {
+            let implementations: Vec<_> = decorations.iter().map(|decoration| decoration.implementation.describe()).collect();
+
+            fingerprint.add("decorate", decorations[0].type_name, &implementations);
+        }
+
+        for module in &self.modules {
+            fingerprint.add("module", module, &[]);
+        }
+
+        for &(type_name, _) in self.disposables.values() {
+            fingerprint.add("disposable", type_name, &[]);
+        }
+
+        for &(type_name, _) in self.initializers.values() {
+            fingerprint.add("initializable", type_name, &[]);
+        }
+
+        for startup in &self.startables {
+            fingerprint.add("startable", startup.type_name, &[]);
+        }
+
+        fingerprint
+    }
+
+    /// Resolve every binding for `T` in the order they were added.
+    pub fn resolve_all<T>(&self, container: &C) -> Vec<Rc<T>>
+        where T: ?Sized + 'static
+    {
+        match self.multi.get(&Self::key::<T>()) {
+            Some(entries) => entries.iter().map(|entry| self.decorated(entry.resolve(container))).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// The number of bindings for `T` added with `bind_all`.
+    pub fn count_all<T>(&self) -> usize
+        where T: ?Sized + 'static
+    {
+        self.multi.get(&Self::key::<T>()).map_or(0, Vec::len)
+    }
+
+    /// Resolve the binding for `T` at `index`, in the order they were added.
+    pub fn resolve_nth<T>(&self, container: &C, index: usize) -> Option<Rc<T>>
+        where T: ?Sized + 'static
+    {
+        self.multi
+            .get(&Self::key::<T>())
+            .and_then(|entries| entries.get(index))
+            .map(|entry| self.decorated(entry.resolve(container)))
+    }
+
+    /// Resolve every binding for `T` with keys of type `K`.
+    pub fn resolve_keyed<K, T>(&self, container: &C) -> StdHashMap<K, Rc<T>>
+        where K: Eq + Hash + Clone + 'static,
+              T: ?Sized + 'static
+    {
+        match self.keyed.get(&Self::keyed_key::<K, T>()) {
+            Some(entries) => entries
+                .iter()
+                .map(|keyed| (keyed.key::<K>().clone(), self.decorated(keyed.entry.resolve(container))))
+                .collect(),
+            None => StdHashMap::new(),
+        }
+    }
+}
+
+impl<C> Registry<C>
+    where C: Container + 'static
+{
+    /// Bind `T` to a resolvable implementation, replacing any previous binding.
+    ///
+    /// This is like `bind`, but because the implementation is known its
+    /// dependencies can be verified:
+    ///
+    /// ```
+    /// # use ioc::prelude::*;
+    /// # use std::rc::Rc;
+    /// # trait KeyStore {}
+    /// # struct FileStore;
+    /// # impl<C> Resolvable<C> for FileStore {
+    /// #     type Dependency = ();
+    /// #     fn resolve(_: ()) -> Self { FileStore }
+    /// # }
+    /// # impl KeyStore for FileStore {}
+    /// # let mut registry = Registry::<Scoped>::new();
+    /// registry.bind_to::<dyn KeyStore, FileStore, _, _>(|store| Rc::new(store));
+    /// ```
+    pub fn bind_to<T, R, D, F>(&mut self, into: F) -> &mut Self
+        where T: ?Sized + 'static,
+              R: Resolvable<C, Dependency = D>,
+              D: ResolvableFromContainer<C>,
+              F: Fn(R) -> Rc<T> + 'static
+    {
+        let binding: Binding<C, T> = Box::new(move |container| into(container.resolve()));
+        let verify: VerifyBinding<C> = verify_resolvable::<C, R, D>;
+
+        self.single.insert(Self::key::<T>(), Entry::new(binding, Implementation::of::<R>(Lifetime::Transient), Some(verify)));
+
+        self
+    }
+
+    /// Add a binding for `T` to a resolvable implementation alongside any others.
+    ///
+    /// This is like `bind_all`, but because the implementation is known its
+    /// dependencies can be verified.
+    pub fn bind_all_to<T, R, D, F>(&mut self, into: F) -> &mut Self
+        where T: ?Sized + 'static,
+              R: Resolvable<C, Dependency = D>,
+              D: ResolvableFromContainer<C>,
+              F: Fn(R) -> Rc<T> + 'static
+    {
+        let binding: Binding<C, T> = Box::new(move |container| into(container.resolve()));
+        let verify: VerifyBinding<C> = verify_resolvable::<C, R, D>;
+
+        self.multi
+            .entry(Self::key::<T>())
+            .or_default()
+            .push(Entry::new(binding, Implementation::of::<R>(Lifetime::Transient), Some(verify)));
+
+        self
+    }
+
+    /// Add a binding for `T` under `key` to a resolvable implementation,
+    /// replacing any previous binding under the same key.
+    ///
+    /// This is like `bind_keyed`, but because the implementation is known
+    /// its dependencies can be verified.
+    pub fn bind_keyed_to<K, T, R, D, F>(&mut self, key: K, into: F) -> &mut Self
+        where K: Eq + Hash + Clone + 'static,
+              T: ?Sized + 'static,
+              R: Resolvable<C, Dependency = D>,
+              D: ResolvableFromContainer<C>,
+              F: Fn(R) -> Rc<T> + 'static
+    {
+        let binding: Binding<C, T> = Box::new(move |container| into(container.resolve()));
+        let verify: VerifyBinding<C> = verify_resolvable::<C, R, D>;
+
+        self.insert_keyed::<K, T>(key, Entry::new(binding, Implementation::of::<R>(Lifetime::Transient), Some(verify)));
+
+        self
+    }
+
+    /// Verify the dependencies of the binding for `T`.
+    ///
+    /// Bindings added with a closure can't be verified.
+    pub fn verify<T>(&self, container: &C, verification: &mut Verification)
+        where T: ?Sized + 'static
+    {
+        match self.single.get(&Self::key::<T>()) {
+            Some(entry) => entry.verify(container, verification),
+            None => verification.missing_binding(type_name::<T>()),
+        }
+    }
+
+    /// Verify the dependencies of every binding for `T`.
+    pub fn verify_each<T>(&self, container: &C, verification: &mut Verification)
+        where T: ?Sized + 'static
+    {
+        if let Some(entries) = self.multi.get(&Self::key::<T>()) {
+            for entry in entries {
+                entry.verify(container, verification);
+            }
+        }
+    }
+
+    /// Verify the dependencies of every binding for `T` with keys of type `K`.
+    pub fn verify_keyed<K, T>(&self, container: &C, verification: &mut Verification)
+        where K: 'static,
+              T: ?Sized + 'static
+    {
+        if let Some(entries) = self.keyed.get(&Self::keyed_key::<K, T>()) {
+            for keyed in entries {
+                keyed.entry.verify(container, verification);
+            }
+        }
+    }
+
+    /// Verify the dependencies of every binding in the registry.
+    pub fn verify_all(&self, container: &C, verification: &mut Verification) {
+        let entries = self.single.values()
+            .chain(self.multi.values().flatten())
+            .chain(self.keyed.values().flatten().map(|keyed| &keyed.entry));
+
+        for entry in entries {
+            verification.visit(DependencyKind::Binding, entry.type_name, |verification| entry.verify(container, verification));
+        }
+
+        for startup in &self.startables {
+            (startup.verify)(container, verification);
+        }
+    }
+}
+
+impl<C> Registry<C>
+    where C: ScopedContainer + 'static
+{
+    /// Start the shared `T` with `RegisteredContainer::start_all`.
+    ///
+    /// See `Startable` for details.
+    pub fn startable<T, D>(&mut self) -> &mut Self
+        where T: Startable + Resolvable<C, Dependency = D> + 'static,
+              D: ResolvableFromContainer<C>
+    {
+        self.startables.push(Startup {
+            key: Self::key::<T>(),
+            type_name: type_name::<T>(),
+            depth: depth::<C, T, D>(),
+            resolve: resolve_startable::<C, T, D>,
+            verify: verify_resolvable::<C, T, D>,
+        });
+
+        self
+    }
+
+    /// Bind `T` to the scope's shared instance of `R`, replacing any
+    /// previous binding.
+    ///
+    /// This lets one instance satisfy several bindings. Each alias resolves
+    /// the same `Rc<R>` that's cached by the scope, rather than constructing
+    /// a new `R` of its own:
+    ///
+    /// ```
+    /// # use ioc::prelude::*;
+    /// # use std::rc::Rc;
+    /// # trait Reader {}
+    /// # trait Writer {}
+    /// # struct FileStore;
+    /// # impl<C> Resolvable<C> for FileStore {
+    /// #     type Dependency = ();
+    /// #     fn resolve(_: ()) -> Self { FileStore }
+    /// # }
+    /// # impl Reader for FileStore {}
+    /// # impl Writer for FileStore {}
+    /// # let mut registry = Registry::<Scoped>::new();
+    /// registry
+    ///     .alias::<dyn Reader, FileStore, _, _>(|store| store)
+    ///     .alias::<dyn Writer, FileStore, _, _>(|store| store);
+    /// ```
+    ///
+    /// `into` only needs to convert the `Rc<R>` into an `Rc<T>`, which for
+    /// a trait object is usually an unsizing coercion.
+    pub fn alias<T, R, D, F>(&mut self, into: F) -> &mut Self
+        where T: ?Sized + 'static,
+              R: Resolvable<C, Dependency = D> + 'static,
+              D: ResolvableFromContainer<C>,
+              F: Fn(Rc<R>) -> Rc<T> + 'static
+    {
+        let binding: Binding<C, T> = Box::new(move |container| into(container.get_or_add::<R, D>()));
+        let verify: VerifyBinding<C> = verify_resolvable::<C, R, D>;
+
+        self.single.insert(Self::key::<T>(), Entry::new(binding, Implementation::of::<R>(Lifetime::Scoped), Some(verify)));
+
+        self
+    }
+
+    /// Start every startable service in dependency order.
+    pub fn start_all(&self, container: &C) -> ShutdownHandle {
+        // resolving a service constructs its dependencies first, so the
+        // order the container observed is a dependency order
+        let mut startables: Vec<_> = self.startables
+            .iter()
+            .map(|startup| (startup, (startup.resolve)(container)))
+            .collect();
+
+        let observed = container.observed_graph();
+        startables.sort_by_key(|&(startup, _)| (observed.position(startup.key), startup.depth));
+
+        let mut started = ShutdownHandle::default();
+
+        for (startup, service) in startables {
+            started.start(startup.key, startup.type_name, service);
+        }
+
+        started
+    }
+}
+
+/// Resolve the shared instance of a startable `T`.
+fn resolve_startable<C, T, D>(container: &C) -> Rc<dyn Startable>
+    where C: ScopedContainer,
+          T: Startable + Resolvable<C, Dependency = D> + 'static,
+          D: ResolvableFromContainer<C>
+{
+    container.get_or_add::<T, D>()
+}
+
+/// A group of related bindings that can be added to a registry together.
+pub trait Module<C> {
+    fn register(self, registry: &mut Registry<C>);
+}
+
+impl<C, F> Module<C> for F
+    where F: FnOnce(&mut Registry<C>)
+{
+    fn register(self, registry: &mut Registry<C>) {
+        self(registry)
+    }
+}
+
+/// The binding for `T` in a container's registry.
+pub struct Bound<T>
+    where T: ?Sized
+{
+    bound: Rc<T>,
+}
+
+impl<T> Bound<T>
+    where T: ?Sized
+{
+    pub fn new(bound: Rc<T>) -> Self {
+        Bound { bound }
+    }
+
+    pub fn into_inner(self) -> Rc<T> {
+        self.bound
+    }
+}
+
+impl<T> Deref for Bound<T>
+    where T: ?Sized
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.bound
+    }
+}
+
+/// A collection of every binding for `T` in a container's registry.
+pub struct All<T>
+    where T: ?Sized
+{
+    all: Vec<Rc<T>>,
+}
+
+impl<T> All<T>
+    where T: ?Sized
+{
+    pub fn new(all: Vec<Rc<T>>) -> Self {
+        All { all }
+    }
+
+    pub fn into_inner(self) -> Vec<Rc<T>> {
+        self.all
+    }
+}
+
+impl<T> Deref for All<T>
+    where T: ?Sized
+{
+    type Target = [Rc<T>];
+
+    fn deref(&self) -> &[Rc<T>] {
+        &self.all
+    }
+}
+
+impl<T> IntoIterator for All<T>
+    where T: ?Sized
+{
+    type Item = Rc<T>;
+    type IntoIter = vec::IntoIter<Rc<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.all.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a All<T>
+    where T: ?Sized
+{
+    type Item = &'a Rc<T>;
+    type IntoIter = slice::Iter<'a, Rc<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.all.iter()
+    }
+}
+
+/// An iterator that resolves each binding for `T` in a container's
+/// registry as it's reached.
+///
+/// This is like `All<T>`, but bindings after the last one that's needed
+/// are never resolved:
+///
+/// ```
+/// # use ioc::prelude::*;
+/// # use std::rc::Rc;
+/// # trait Handler { fn accepts(&self, input: &str) -> bool; }
+/// # struct Csv;
+/// # impl Handler for Csv { fn accepts(&self, input: &str) -> bool { input.ends_with(".csv") } }
+/// # let mut registry = Registry::new();
+/// # registry.bind_all::<dyn Handler, _>(|_| Rc::new(Csv));
+/// # let scope = Scoped::new().with_registry(Rc::new(registry));
+/// # let mut handlers = ResolveIter::<dyn Handler>::resolve_from_container(&scope);
+/// # let input = "orders.csv";
+/// let handler = handlers.find(|handler| handler.accepts(&input));
+/// # assert!(handler.is_some());
+/// ```
+pub struct ResolveIter<T>
+    where T: ?Sized
+{
+    next: usize,
+    len: usize,
+    resolve: Rc<dyn Fn(usize) -> Option<Rc<T>>>,
+}
+
+impl<T> ResolveIter<T>
+    where T: ?Sized
+{
+    /// Create an iterator that calls `resolve` with each index up to `len`.
+    pub fn new<F>(len: usize, resolve: F) -> Self
+        where F: Fn(usize) -> Option<Rc<T>> + 'static
+    {
+        ResolveIter { next: 0, len, resolve: Rc::new(resolve) }
+    }
+}
+
+impl<T> Iterator for ResolveIter<T>
+    where T: ?Sized
+{
+    type Item = Rc<T>;
+
+    fn next(&mut self) -> Option<Rc<T>> {
+        if self.next >= self.len {
+            return None;
+        }
+
+        let index = self.next;
+        self.next += 1;
+
+        (self.resolve)(index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.next;
+
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for ResolveIter<T> where T: ?Sized {}
+
+impl<T> Clone for ResolveIter<T>
+    where T: ?Sized
+{
+    fn clone(&self) -> Self {
+        ResolveIter { next: self.next, len: self.len, resolve: self.resolve.clone() }
+    }
+}
+
+impl<T> fmt::Debug for ResolveIter<T>
+    where T: ?Sized
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ResolveIter")
+            .field("next", &self.next)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+/// A map of every binding for `T` in a container's registry, by the key
+/// of type `K` it was added under.
+pub struct KeyedAll<K, T>
+    where T: ?Sized
+{
+    all: StdHashMap<K, Rc<T>>,
+}
+
+impl<K, T> KeyedAll<K, T>
+    where T: ?Sized
+{
+    pub fn new(all: StdHashMap<K, Rc<T>>) -> Self {
+        KeyedAll { all }
+    }
+
+    pub fn into_inner(self) -> StdHashMap<K, Rc<T>> {
+        self.all
+    }
+}
+
+impl<K, T> Deref for KeyedAll<K, T>
+    where T: ?Sized
+{
+    type Target = StdHashMap<K, Rc<T>>;
+
+    fn deref(&self) -> &StdHashMap<K, Rc<T>> {
+        &self.all
+    }
+}
+
+impl<K, T> IntoIterator for KeyedAll<K, T>
+    where T: ?Sized
+{
+    type Item = (K, Rc<T>);
+    type IntoIter = hash_map::IntoIter<K, Rc<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.all.into_iter()
+    }
+}
+
+impl<'a, K, T> IntoIterator for &'a KeyedAll<K, T>
+    where T: ?Sized
+{
+    type Item = (&'a K, &'a Rc<T>);
+    type IntoIter = hash_map::Iter<'a, K, Rc<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.all.iter()
+    }
+}
+
+impl<K, T> fmt::Debug for KeyedAll<K, T>
+    where K: fmt::Debug,
+          T: ?Sized
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.all.keys()).finish()
+    }
+}
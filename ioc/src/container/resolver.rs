@@ -0,0 +1,240 @@
+//! Resolver combinators
+//!
+//! Implementing `Resolvable` means declaring a type for every intermediate
+//! value in a graph. A `Resolver` is a value instead, that can be built up
+//! from smaller resolvers and run against a container later:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use std::rc::Rc;
+//! use ioc::resolver::{self, Resolver};
+//!
+//! # struct Config { name: &'static str }
+//! # impl<C> Resolvable<C> for Config {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { Config { name: "app" } }
+//! # }
+//! # let scope = Scoped::new();
+//! let greeting = resolver::resolve::<Rc<Config>>()
+//!     .zip(resolver::of("hello"))
+//!     .map(|(config, greeting)| format!("{}, {}", greeting, config.name));
+//!
+//! let greeting = greeting.resolve(&scope);
+//! # assert_eq!("hello, app", greeting);
+//! ```
+
+use std::marker::PhantomData;
+
+use super::*;
+
+/// A value that can be resolved from a container.
+///
+/// The type of the value doesn't depend on the container, so resolvers
+/// can be combined before it's known what they'll be resolved from.
+pub trait Resolver {
+    type Output;
+
+    /// Resolve the value from the container.
+    fn resolve<C>(&self, container: &C) -> Self::Output
+        where Self: ResolveFrom<C>
+    {
+        self.resolve_from(container)
+    }
+
+    /// Transform the resolved value.
+    fn map<F, U>(self, f: F) -> Map<Self, F>
+        where Self: Sized,
+              F: Fn(Self::Output) -> U
+    {
+        Map { resolver: self, f }
+    }
+
+    /// Resolve another value from the same container alongside this one.
+    fn zip<R>(self, other: R) -> Zip<Self, R>
+        where Self: Sized,
+              R: Resolver
+    {
+        Zip { a: self, b: other }
+    }
+
+    /// Use the resolved value to pick another resolver, and resolve that
+    /// from the same container.
+    fn and_then<F, R>(self, f: F) -> AndThen<Self, F>
+        where Self: Sized,
+              F: Fn(Self::Output) -> R,
+              R: Resolver
+    {
+        AndThen { resolver: self, f }
+    }
+}
+
+/// A resolver that can resolve its value from a container of type `C`.
+pub trait ResolveFrom<C>
+    where Self: Resolver
+{
+    fn resolve_from(&self, container: &C) -> Self::Output;
+}
+
+/// A resolver that always returns a clone of `value`.
+pub fn of<T>(value: T) -> Of<T>
+    where T: Clone
+{
+    Of { value }
+}
+
+/// A resolver for a root dependency, like `Rc<T>` or `Bound<T>`.
+pub fn resolve<T>() -> Resolve<T> {
+    Resolve { _t: PhantomData }
+}
+
+/// A resolver that calls `f` with the container.
+pub fn from_fn<C, F, T>(f: F) -> FromFn<C, F>
+    where F: Fn(&C) -> T
+{
+    FromFn { f, _container: PhantomData }
+}
+
+/// The resolver returned by `of`.
+#[derive(Debug, Clone)]
+pub struct Of<T> {
+    value: T,
+}
+
+impl<T> Resolver for Of<T>
+    where T: Clone
+{
+    type Output = T;
+}
+
+impl<C, T> ResolveFrom<C> for Of<T>
+    where T: Clone
+{
+    fn resolve_from(&self, _: &C) -> T {
+        self.value.clone()
+    }
+}
+
+/// The resolver returned by `resolve`.
+pub struct Resolve<T> {
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Resolve<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Resolve<T> {}
+
+impl<T> Resolver for Resolve<T> {
+    type Output = T;
+}
+
+impl<C, T> ResolveFrom<C> for Resolve<T>
+    where C: Container,
+          T: ResolvableFromContainer<C>
+{
+    fn resolve_from(&self, container: &C) -> T {
+        T::resolve_from_container(container)
+    }
+}
+
+/// The resolver returned by `from_fn`.
+pub struct FromFn<C, F> {
+    f: F,
+    _container: PhantomData<fn(&C)>,
+}
+
+impl<C, F> Clone for FromFn<C, F>
+    where F: Clone
+{
+    fn clone(&self) -> Self {
+        FromFn { f: self.f.clone(), _container: PhantomData }
+    }
+}
+
+impl<C, F, T> Resolver for FromFn<C, F>
+    where F: Fn(&C) -> T
+{
+    type Output = T;
+}
+
+impl<C, F, T> ResolveFrom<C> for FromFn<C, F>
+    where F: Fn(&C) -> T
+{
+    fn resolve_from(&self, container: &C) -> T {
+        (self.f)(container)
+    }
+}
+
+/// The resolver returned by `Resolver::map`.
+#[derive(Clone)]
+pub struct Map<R, F> {
+    resolver: R,
+    f: F,
+}
+
+impl<R, F, U> Resolver for Map<R, F>
+    where R: Resolver,
+          F: Fn(R::Output) -> U
+{
+    type Output = U;
+}
+
+impl<C, R, F, U> ResolveFrom<C> for Map<R, F>
+    where R: ResolveFrom<C>,
+          F: Fn(R::Output) -> U
+{
+    fn resolve_from(&self, container: &C) -> U {
+        (self.f)(self.resolver.resolve_from(container))
+    }
+}
+
+/// The resolver returned by `Resolver::zip`.
+#[derive(Clone)]
+pub struct Zip<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> Resolver for Zip<A, B>
+    where A: Resolver,
+          B: Resolver
+{
+    type Output = (A::Output, B::Output);
+}
+
+impl<C, A, B> ResolveFrom<C> for Zip<A, B>
+    where A: ResolveFrom<C>,
+          B: ResolveFrom<C>
+{
+    fn resolve_from(&self, container: &C) -> Self::Output {
+        (self.a.resolve_from(container), self.b.resolve_from(container))
+    }
+}
+
+/// The resolver returned by `Resolver::and_then`.
+#[derive(Clone)]
+pub struct AndThen<R, F> {
+    resolver: R,
+    f: F,
+}
+
+impl<R, F, N> Resolver for AndThen<R, F>
+    where R: Resolver,
+          F: Fn(R::Output) -> N,
+          N: Resolver
+{
+    type Output = N::Output;
+}
+
+impl<C, R, F, N> ResolveFrom<C> for AndThen<R, F>
+    where R: ResolveFrom<C>,
+          F: Fn(R::Output) -> N,
+          N: ResolveFrom<C>
+{
+    fn resolve_from(&self, container: &C) -> N::Output {
+        (self.f)(self.resolver.resolve_from(container)).resolve_from(container)
+    }
+}
@@ -0,0 +1,63 @@
+//! Scope guards
+//!
+//! `Scope::scope` runs everything that uses the scope inside a closure,
+//! which gets in the way of `?` and early returns. A `ScopeGuard` owns the
+//! scope instead, and ends it when it's dropped:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use ioc::Error;
+//! # struct Handler;
+//! # impl Handler {
+//! #     fn handle(&self) -> Result<(), Error> { Ok(()) }
+//! # }
+//! # impl<C> Resolvable<C> for Handler {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { Handler }
+//! # }
+//! fn handle(c: &BasicContainer) -> Result<(), Error> {
+//!     let scope = c.begin_scope();
+//!
+//!     let handler: Handler = scope.try_resolve()?;
+//!     handler.handle()?;
+//!
+//!     Ok(())
+//! }
+//! # handle(&BasicContainer).unwrap();
+//! ```
+//!
+//! Ending the scope drops everything it cached, disposing of any
+//! `Disposable` values, the same as returning from the `scope` closure.
+
+use std::ops::Deref;
+
+/// A scope that ends when the guard is dropped.
+pub struct ScopeGuard<C> {
+    container: C,
+}
+
+impl<C> ScopeGuard<C> {
+    pub fn new(container: C) -> Self {
+        ScopeGuard { container }
+    }
+
+    /// End the scope now, instead of when the guard goes out of scope.
+    pub fn end(self) {
+        drop(self.container);
+    }
+
+    /// Take the scope out of the guard.
+    ///
+    /// The scope ends whenever the returned container is dropped.
+    pub fn into_inner(self) -> C {
+        self.container
+    }
+}
+
+impl<C> Deref for ScopeGuard<C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.container
+    }
+}
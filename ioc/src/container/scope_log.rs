@@ -0,0 +1,132 @@
+//! Buffered scope logs
+//!
+//! Logs from concurrent requests get interleaved, which makes it hard to
+//! follow what happened in any one of them. A `ScopeLog` buffers the lines
+//! logged within a scope and writes them out together, tagged with the
+//! scope's correlation id, when the scope ends.
+//!
+//! Services depend on an `Rc<ScopeLog>` so they share the same buffer:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use std::rc::Rc;
+//! # struct Handler { log: Rc<ScopeLog> }
+//! # struct Request { path: &'static str }
+//! impl<C> Resolvable<C> for Handler {
+//!     type Dependency = Rc<ScopeLog>;
+//!
+//!     fn resolve(log: Self::Dependency) -> Self {
+//!         Handler { log }
+//!     }
+//! }
+//!
+//! # let scope = Scoped::new();
+//! # let handler: Handler = scope.resolve();
+//! # let request = Request { path: "/" };
+//! handler.log.log(format_args!("handling {}", request.path));
+//! ```
+
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, Write};
+use std::mem;
+
+use super::*;
+
+/// A function that writes out a block of log lines.
+type Sink = Box<dyn Fn(&CorrelationId, &[String])>;
+
+/// A log that's written out as a single block when its scope ends.
+///
+/// By default lines are written to standard error. Lines are also written
+/// out straight away when an error is logged, so they aren't lost if the
+/// scope never ends cleanly.
+pub struct ScopeLog {
+    correlation_id: CorrelationId,
+    lines: RefCell<Vec<String>>,
+    sink: Sink,
+}
+
+impl ScopeLog {
+    /// Create a log that writes to standard error.
+    pub fn new(correlation_id: CorrelationId) -> Self {
+        ScopeLog::with_sink(correlation_id, write_stderr)
+    }
+
+    /// Create a log that writes blocks of lines using `sink`.
+    ///
+    /// The sink isn't called for empty blocks.
+    pub fn with_sink<F>(correlation_id: CorrelationId, sink: F) -> Self
+        where F: Fn(&CorrelationId, &[String]) + 'static
+    {
+        ScopeLog {
+            correlation_id,
+            lines: RefCell::new(Vec::new()),
+            sink: Box::new(sink),
+        }
+    }
+
+    /// The correlation id the log is written with.
+    pub fn correlation_id(&self) -> &CorrelationId {
+        &self.correlation_id
+    }
+
+    /// Buffer a line.
+    pub fn log<L>(&self, line: L)
+        where L: fmt::Display
+    {
+        self.lines.borrow_mut().push(line.to_string());
+    }
+
+    /// Buffer a line, then write out everything that's been buffered.
+    pub fn error<L>(&self, line: L)
+        where L: fmt::Display
+    {
+        self.log(line);
+        self.flush();
+    }
+
+    /// Write out everything that's been buffered.
+    pub fn flush(&self) {
+        let lines = mem::take(&mut *self.lines.borrow_mut());
+
+        if !lines.is_empty() {
+            (self.sink)(&self.correlation_id, &lines);
+        }
+    }
+}
+
+impl<C> Resolvable<C> for ScopeLog
+    where C: CorrelatedContainer
+{
+    type Dependency = CorrelationId;
+
+    fn resolve(correlation_id: Self::Dependency) -> Self {
+        ScopeLog::new(correlation_id)
+    }
+}
+
+impl Drop for ScopeLog {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+impl fmt::Debug for ScopeLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ScopeLog")
+            .field("correlation_id", &self.correlation_id)
+            .field("buffered", &self.lines.borrow().len())
+            .finish()
+    }
+}
+
+fn write_stderr(correlation_id: &CorrelationId, lines: &[String]) {
+    let stderr = io::stderr();
+    let mut stderr = stderr.lock();
+
+    for line in lines {
+        // there's nowhere to report a failure to write to stderr
+        let _ = writeln!(stderr, "[{}] {}", correlation_id, line);
+    }
+}
@@ -0,0 +1,89 @@
+//! Shared, mutable dependencies
+//!
+//! An `Rc<T>` can't be mutated, so services that need to share a mutable
+//! value depend on an `Rc<RefCell<T>>`. That's noisy, and it's easy to end
+//! up with an `Rc<T>` somewhere else that's a separate instance. A
+//! `SharedMut<T>` is the same instance as the scope's `Rc<RefCell<T>>`,
+//! with `borrow` and `borrow_mut` available directly:
+//!
+//! ```
+//! # #[macro_use] extern crate ioc;
+//! # use ioc::prelude::*;
+//! # #[derive(Default)]
+//! # struct Items(Vec<&'static str>);
+//! # impl Items {
+//! #     fn push(&mut self, item: &'static str) { self.0.push(item) }
+//! # }
+//! # resolvable! {
+//! #     default Items;
+//! # }
+//! resolvable! {
+//!     struct Cart {
+//!         items: SharedMut<Items>,
+//!     }
+//! }
+//!
+//! # fn main() {
+//! # let cart: Cart = Scoped::new().resolve();
+//! # let item = "widget";
+//! cart.items.borrow_mut().push(item);
+//! # }
+//! ```
+//!
+//! `T` needs to be `Resolvable` itself, so wrapping another root
+//! dependency, like a `SharedMut<Rc<T>>`, doesn't compile.
+
+use std::cell::{BorrowError, BorrowMutError, Ref, RefCell, RefMut};
+use std::fmt;
+use std::rc::Rc;
+
+/// A dependency that's shared and can be mutated.
+pub struct SharedMut<T> {
+    value: Rc<RefCell<T>>,
+}
+
+impl<T> SharedMut<T> {
+    pub fn new(value: Rc<RefCell<T>>) -> Self {
+        SharedMut { value }
+    }
+
+    /// Borrow the value.
+    ///
+    /// This panics if the value is mutably borrowed.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.value.borrow()
+    }
+
+    /// Mutably borrow the value.
+    ///
+    /// This panics if the value is already borrowed.
+    pub fn borrow_mut(&self) -> RefMut<'_, T> {
+        self.value.borrow_mut()
+    }
+
+    pub fn try_borrow(&self) -> Result<Ref<'_, T>, BorrowError> {
+        self.value.try_borrow()
+    }
+
+    pub fn try_borrow_mut(&self) -> Result<RefMut<'_, T>, BorrowMutError> {
+        self.value.try_borrow_mut()
+    }
+
+    pub fn into_inner(self) -> Rc<RefCell<T>> {
+        self.value
+    }
+}
+
+impl<T> Clone for SharedMut<T> {
+    fn clone(&self) -> Self {
+        SharedMut { value: self.value.clone() }
+    }
+}
+
+impl<T> fmt::Debug for SharedMut<T>
+    where T: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("SharedMut").field(&self.value).finish()
+    }
+}
@@ -0,0 +1,143 @@
+//! Scope statistics
+//!
+//! When a scope is heavier than expected, it's useful to see exactly what
+//! it built. `Scoped::stats` takes a snapshot of each shared dependency the
+//! scope holds, how many references to it are outstanding, and how long it
+//! took to construct:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # let scope = Scoped::new();
+//! let stats = scope.stats();
+//!
+//! println!("{}", stats);
+//! ```
+//!
+//! Construction times don't include the shared dependencies a value
+//! resolved while it was being constructed, since those have their own
+//! entries. They do include any transient dependencies it resolved.
+
+use std::any::{Any, TypeId};
+use std::fmt;
+use std::rc::Weak;
+use std::time::Duration;
+
+/// A shared dependency held by a scope.
+struct Held {
+    key: TypeId,
+    type_name: &'static str,
+    value: Weak<dyn Any>,
+    construction: Duration,
+}
+
+/// The shared dependencies held by a scope, in the order they were added.
+#[derive(Default)]
+pub(super) struct Holdings {
+    held: Vec<Held>,
+}
+
+impl Holdings {
+    /// Record a value added to the scope.
+    ///
+    /// Values that were inserted or overridden rather than constructed
+    /// have no construction time. A value that replaces a previous one
+    /// is considered newer than everything else already in the scope.
+    pub(super) fn added<T>(&mut self, type_name: &'static str, value: Weak<T>, construction: Duration)
+        where T: 'static
+    {
+        let key = TypeId::of::<T>();
+        let construction = match self.held.iter().position(|held| held.key == key) {
+            Some(index) => self.held.remove(index).construction + construction,
+            None => construction,
+        };
+
+        self.held.push(Held {
+            key,
+            type_name,
+            value,
+            construction,
+        });
+    }
+
+    /// Record a value removed from the scope.
+    pub(super) fn removed<T>(&mut self)
+        where T: 'static
+    {
+        let key = TypeId::of::<T>();
+
+        self.held.retain(|held| held.key != key);
+    }
+
+    /// Record every value being removed from the scope.
+    pub(super) fn clear(&mut self) {
+        self.held.clear();
+    }
+
+    pub(super) fn stats(&self) -> ScopeStats {
+        ScopeStats {
+            entries: self.held
+                .iter()
+                .map(|held| EntryStats {
+                    type_name: held.type_name,
+                    strong_count: held.value.strong_count(),
+                    construction: held.construction,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A shared dependency in a snapshot of a scope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryStats {
+    /// The name of the dependency type.
+    pub type_name: &'static str,
+    /// The number of `Rc`s pointing to the value, including the scope's own.
+    pub strong_count: usize,
+    /// The total time spent constructing values of this type in the scope.
+    pub construction: Duration,
+}
+
+/// A snapshot of the shared dependencies held by a scope.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScopeStats {
+    entries: Vec<EntryStats>,
+}
+
+impl ScopeStats {
+    /// Each shared dependency, in the order it was added to the scope.
+    pub fn entries(&self) -> &[EntryStats] {
+        &self.entries
+    }
+
+    /// The stats for `type_name`, if the scope holds one.
+    pub fn entry(&self, type_name: &str) -> Option<&EntryStats> {
+        self.entries.iter().find(|entry| entry.type_name == type_name)
+    }
+
+    /// The number of shared dependencies.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The total time spent constructing everything in the scope.
+    pub fn construction(&self) -> Duration {
+        self.entries.iter().map(|entry| entry.construction).sum()
+    }
+}
+
+impl fmt::Display for ScopeStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} entries, constructed in {:?}", self.len(), self.construction())?;
+
+        for entry in &self.entries {
+            writeln!(f, "  {} (strong: {}, construction: {:?})", entry.type_name, entry.strong_count, entry.construction)?;
+        }
+
+        Ok(())
+    }
+}
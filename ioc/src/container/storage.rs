@@ -0,0 +1,290 @@
+//! Storage for scoped containers
+//!
+//! A `Scoped` container keeps the shared dependencies it resolves in a
+//! `ScopeStorage`. The default is a `TypeMap`, which can hold any `'static`
+//! type, but other backends can be swapped in without changing `Scoped`:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use ioc::TypeMap as MyArenaStorage;
+//! let scope = Scoped::with_storage(MyArenaStorage::new());
+//! ```
+
+use std::any::TypeId;
+use std::collections::HashMap as StdHashMap;
+use std::mem::{self, MaybeUninit};
+use std::ptr;
+use std::rc::Rc;
+use std::slice;
+
+use super::*;
+
+/// The storage behind a `Scoped` container.
+///
+/// Storage holds a single `Rc<T>` for each type `T`. Values need to be
+/// dropped in the reverse order they were inserted, since a dependency is
+/// always inserted after the dependencies it was resolved from.
+pub trait ScopeStorage: 'static {
+    /// Get a shared reference to a value, if there is one.
+    fn get<T>(&self) -> Option<Rc<T>>
+        where T: 'static;
+
+    /// Whether there's a value for `T`.
+    fn contains<T>(&self) -> bool
+        where T: 'static;
+
+    /// Insert a value, returning any previous one instead of dropping it.
+    ///
+    /// If there's a `dispose` function then it should be called on the
+    /// value when it's drained, as long as nothing else still shares it.
+    /// A replaced value is considered newer than everything else already
+    /// in the storage.
+    fn insert<T>(&mut self, t: Rc<T>, dispose: Option<Dispose<T>>) -> Option<Rc<T>>
+        where T: 'static;
+
+    /// Remove the value for `T` and hand it back without disposing it.
+    fn remove<T>(&mut self) -> Option<Rc<T>>
+        where T: 'static;
+
+    /// Drop every value, in the reverse order they were inserted.
+    ///
+    /// This is called when the scope ends.
+    fn drain(&mut self);
+}
+
+// `TypeId`s are already hashes, so they're used as-is
+type HashMap<K, V> = StdHashMap<K, V, BuildTypeIdHasher>;
+
+/// An `Rc` in the map and the functions to drop it.
+///
+/// Entries are plain pointers, so copying one doesn't copy the `Rc`.
+#[derive(Clone, Copy)]
+struct Entry {
+    ptr: *const (),
+    /// The `Dispose<T>` for the value, or null if it isn't disposable.
+    dispose: *const (),
+    drop: unsafe fn(*const (), *const ()),
+}
+
+/// Drop an `Rc<T>`, disposing of the value if nothing else still shares it.
+unsafe fn drop_entry<T>(ptr: *const (), dispose: *const ()) {
+    let rc = Rc::from_raw(ptr as *const T);
+
+    if !dispose.is_null() {
+        let dispose: Dispose<T> = mem::transmute::<*const (), Dispose<T>>(dispose);
+
+        // values that are still shared outside the scope can't be disposed,
+        // but ones that are only weakly referenced can
+        if let Some(mut t) = Rc::into_inner(rc) {
+            dispose(&mut t);
+        }
+    }
+}
+
+/// The number of entries a `TypeMap` scans before it spills into a hash map.
+const INLINE: usize = 8;
+
+/// The entries in a `TypeMap`.
+// keeping the inline entries out of the heap is the point
+#[allow(clippy::large_enum_variant)]
+enum Refs {
+    /// A few entries in the order their values were inserted, found by
+    /// scanning them.
+    ///
+    /// Most scopes only hold a handful of values, and comparing that many
+    /// `TypeId`s is cheaper than hashing one. Only the first `len` entries
+    /// are initialized.
+    Inline {
+        entries: [MaybeUninit<(TypeId, Entry)>; INLINE],
+        len: usize,
+    },
+    /// Entries found by hashing their keys.
+    Spilled {
+        refs: HashMap<TypeId, Entry>,
+        /// The keys in `refs` in the order their values were inserted.
+        order: Vec<TypeId>,
+    },
+}
+
+impl Refs {
+    fn new() -> Self {
+        Refs::Inline {
+            entries: [MaybeUninit::uninit(); INLINE],
+            len: 0,
+        }
+    }
+
+    /// The initialized inline entries.
+    fn inline(entries: &[MaybeUninit<(TypeId, Entry)>; INLINE], len: usize) -> &[(TypeId, Entry)] {
+        // the first `len` entries are always initialized
+        unsafe { slice::from_raw_parts(entries.as_ptr() as *const (TypeId, Entry), len) }
+    }
+
+    fn get(&self, key: TypeId) -> Option<&Entry> {
+        match *self {
+            Refs::Inline { ref entries, len } => Self::inline(entries, len)
+                .iter()
+                .find(|&&(k, _)| k == key)
+                .map(|(_, entry)| entry),
+            Refs::Spilled { ref refs, .. } => refs.get(&key),
+        }
+    }
+
+    /// Insert an entry as the newest, returning any previous one for the key.
+    fn insert(&mut self, key: TypeId, entry: Entry) -> Option<Entry> {
+        let previous = self.remove(key);
+
+        if let Refs::Inline { len: INLINE, .. } = *self {
+            self.spill();
+        }
+
+        match *self {
+            Refs::Inline { ref mut entries, ref mut len } => {
+                entries[*len] = MaybeUninit::new((key, entry));
+                *len += 1;
+            }
+            Refs::Spilled { ref mut refs, ref mut order } => {
+                refs.insert(key, entry);
+                order.push(key);
+            }
+        }
+
+        previous
+    }
+
+    fn remove(&mut self, key: TypeId) -> Option<Entry> {
+        match *self {
+            Refs::Inline { ref mut entries, ref mut len } => {
+                let index = Self::inline(entries, *len).iter().position(|&(k, _)| k == key)?;
+                let (_, entry) = Self::inline(entries, *len)[index];
+
+                // keep the remaining entries in order
+                entries.copy_within(index + 1..*len, index);
+                *len -= 1;
+
+                Some(entry)
+            }
+            Refs::Spilled { ref mut refs, ref mut order } => {
+                let entry = refs.remove(&key)?;
+                order.retain(|&k| k != key);
+
+                Some(entry)
+            }
+        }
+    }
+
+    /// Remove the newest entry.
+    fn pop(&mut self) -> Option<Entry> {
+        match *self {
+            Refs::Inline { ref entries, ref mut len } => {
+                let (_, entry) = *Self::inline(entries, *len).last()?;
+                *len -= 1;
+
+                Some(entry)
+            }
+            Refs::Spilled { ref mut refs, ref mut order } => order.pop().and_then(|key| refs.remove(&key)),
+        }
+    }
+
+    /// Move the inline entries into a hash map.
+    fn spill(&mut self) {
+        if let Refs::Inline { ref entries, len } = *self {
+            let inline = Self::inline(entries, len);
+
+            let refs = inline.iter().cloned().collect();
+            let mut order = Vec::with_capacity(len * 2);
+            order.extend(inline.iter().map(|&(key, _)| key));
+
+            *self = Refs::Spilled { refs, order };
+        }
+    }
+}
+
+/// The default storage, which can hold any `'static` type.
+///
+/// The first few values are kept inline and found by scanning them. Once
+/// there are more than that they're moved into a hash map.
+pub struct TypeMap {
+    refs: Refs,
+}
+
+impl Default for TypeMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeMap {
+    pub fn new() -> Self {
+        TypeMap { refs: Refs::new() }
+    }
+
+    fn key<T>() -> TypeId
+        where T: 'static
+    {
+        TypeId::of::<T>()
+    }
+}
+
+impl ScopeStorage for TypeMap {
+    fn contains<T>(&self) -> bool
+        where T: 'static
+    {
+        self.refs.get(Self::key::<T>()).is_some()
+    }
+
+    /// Get a shared reference to a dependency, if it exists.
+    ///
+    /// This will increment the reference count.
+    fn get<T>(&self) -> Option<Rc<T>>
+        where T: 'static
+    {
+        self.refs.get(Self::key::<T>()).map(|entry| unsafe {
+            // the map keeps its own reference, so take a new one rather than
+            // reconstituting and forgetting the map's
+            Rc::increment_strong_count(entry.ptr as *const T);
+
+            Rc::from_raw(entry.ptr as *const T)
+        })
+    }
+
+    fn insert<T>(&mut self, t: Rc<T>, dispose: Option<Dispose<T>>) -> Option<Rc<T>>
+        where T: 'static
+    {
+        let entry = Entry {
+            ptr: Rc::into_raw(t) as *const (),
+            dispose: dispose.map_or(ptr::null(), |dispose| dispose as *const ()),
+            drop: drop_entry::<T>,
+        };
+
+        // add the dependency as the newest, taking ownership of any previous value
+        self.refs
+            .insert(Self::key::<T>(), entry)
+            .map(|entry| unsafe { Rc::from_raw(entry.ptr as *const T) })
+    }
+
+    fn remove<T>(&mut self) -> Option<Rc<T>>
+        where T: 'static
+    {
+        // the drop function is discarded so the value isn't disposed
+        self.refs
+            .remove(Self::key::<T>())
+            .map(|entry| unsafe { Rc::from_raw(entry.ptr as *const T) })
+    }
+
+    fn drain(&mut self) {
+        while let Some(entry) = self.refs.pop() {
+            unsafe { (entry.drop)(entry.ptr, entry.dispose) }
+        }
+
+        if let Refs::Spilled { .. } = self.refs {
+            self.refs = Refs::new();
+        }
+    }
+}
+
+impl Drop for TypeMap {
+    fn drop(&mut self) {
+        self.drain();
+    }
+}
@@ -0,0 +1,210 @@
+//! Thread-safe scoped containers
+//!
+//! `Scoped` shares dependencies as `Rc<T>`, so neither the scope nor the
+//! values it hands out can leave the thread that created them. A
+//! `SyncScoped` shares dependencies as `Arc<T>` instead, so both the scope
+//! and its values can be used from any thread:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use std::sync::{Arc, RwLock};
+//! # use std::thread;
+//! # #[derive(Default)]
+//! # struct Cache;
+//! # impl<C> Resolvable<C> for Cache {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { Cache }
+//! # }
+//! let scope = Arc::new(SyncScoped::new());
+//!
+//! let worker = scope.clone();
+//! thread::spawn(move || {
+//!     let cache = Arc::<RwLock<Cache>>::resolve_from_container(&*worker);
+//! # drop(cache);
+//! });
+//! ```
+//!
+//! Shared mutable dependencies use `Arc<Mutex<T>>` or `Arc<RwLock<T>>`.
+//!
+//! An `ArcContainer` is a cheaply cloneable handle to a set of `Arc<T>`
+//! singletons, for moving into `thread::spawn` or `tokio::spawn` closures
+//! so background jobs resolve the same instances as everything else:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use std::sync::Arc;
+//! # use std::thread;
+//! # struct JobQueue;
+//! # impl<C> Resolvable<C> for JobQueue {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { JobQueue }
+//! # }
+//! let container = ArcContainer::new();
+//!
+//! let jobs = container.clone();
+//! thread::spawn(move || {
+//!     let queue = Arc::<JobQueue>::resolve_from_container(&jobs);
+//! # drop(queue);
+//! });
+//! ```
+
+use super::*;
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap as StdHashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+// `TypeId`s are already hashes, so they're used as-is
+type HashMap<K, V> = StdHashMap<K, V, BuildTypeIdHasher>;
+
+struct SyncTypeMap {
+    refs: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+    /// The keys in `refs` in the order their values were inserted.
+    order: Vec<TypeId>,
+}
+
+impl SyncTypeMap {
+    fn new() -> Self {
+        SyncTypeMap {
+            refs: HashMap::default(),
+            order: Vec::new(),
+        }
+    }
+
+    fn get<T>(&self) -> Option<Arc<T>>
+        where T: Send + Sync + 'static
+    {
+        self.refs
+            .get(&TypeId::of::<T>())
+            .map(|t| t.clone().downcast().expect("the value doesn't match its key"))
+    }
+
+    /// Insert a dependency into the map, unless there's already one.
+    ///
+    /// Whichever value ends up in the map is returned.
+    fn get_or_insert<T>(&mut self, t: Arc<T>) -> Arc<T>
+        where T: Send + Sync + 'static
+    {
+        if let Some(existing) = self.get::<T>() {
+            return existing;
+        }
+
+        let key = TypeId::of::<T>();
+
+        self.refs.insert(key, t.clone());
+        self.order.push(key);
+
+        t
+    }
+}
+
+impl Drop for SyncTypeMap {
+    /// Drop dependencies in the reverse order they were inserted.
+    fn drop(&mut self) {
+        for key in self.order.drain(..).rev() {
+            self.refs.remove(&key);
+        }
+    }
+}
+
+/// A scoped container that can be shared between threads.
+///
+/// Dependencies are resolved without holding the scope's lock, so two
+/// threads that resolve the same `Arc<T>` at the same time may both
+/// construct a `T`. Only the first one is kept and handed out to both.
+pub struct SyncScoped {
+    map: Mutex<SyncTypeMap>,
+}
+
+impl Default for SyncScoped {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SyncScoped {
+    pub fn new() -> Self {
+        SyncScoped {
+            map: Mutex::new(SyncTypeMap::new()),
+        }
+    }
+
+    fn map(&self) -> MutexGuard<'_, SyncTypeMap> {
+        // the map is never left half-updated, so a panic elsewhere doesn't matter
+        self.map.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Container for SyncScoped {}
+
+impl SyncScopedContainer for SyncScoped {
+    fn get_or_add_sync<T, D>(&self) -> Arc<T>
+        where T: Resolvable<Self, Dependency = D> + Send + Sync + 'static,
+              D: ResolvableFromContainer<Self>
+    {
+        if let Some(t) = self.map().get::<T>() {
+            return t;
+        }
+
+        // the lock isn't held while resolving, since `T`'s dependencies
+        // may need it too
+        let d = D::resolve_from_container(self);
+        let t = Arc::new(T::resolve(d));
+
+        self.map().get_or_insert(t)
+    }
+}
+
+/// A handle to thread-safe singletons that can be cloned into other threads.
+///
+/// Every clone shares the same `Arc<T>`s. Like `SyncScoped`, dependencies
+/// are resolved without holding the lock, so two threads that resolve the
+/// same `Arc<T>` at the same time may both construct a `T`. Only the first
+/// one is kept and handed out to both.
+#[derive(Clone)]
+pub struct ArcContainer {
+    map: Arc<RwLock<SyncTypeMap>>,
+}
+
+impl Default for ArcContainer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ArcContainer {
+    pub fn new() -> Self {
+        ArcContainer {
+            map: Arc::new(RwLock::new(SyncTypeMap::new())),
+        }
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, SyncTypeMap> {
+        // the map is never left half-updated, so a panic elsewhere doesn't matter
+        self.map.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, SyncTypeMap> {
+        self.map.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Container for ArcContainer {}
+
+impl SyncScopedContainer for ArcContainer {
+    fn get_or_add_sync<T, D>(&self) -> Arc<T>
+        where T: Resolvable<Self, Dependency = D> + Send + Sync + 'static,
+              D: ResolvableFromContainer<Self>
+    {
+        if let Some(t) = self.read().get::<T>() {
+            return t;
+        }
+
+        // the lock isn't held while resolving, since `T`'s dependencies
+        // may need it too
+        let d = D::resolve_from_container(self);
+        let t = Arc::new(T::resolve(d));
+
+        self.write().get_or_insert(t)
+    }
+}
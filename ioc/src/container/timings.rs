@@ -0,0 +1,178 @@
+//! Per-phase timings for shared dependencies
+//!
+//! End-to-end benchmarks show whether resolving a shared dependency got
+//! faster, but not which part of it did. With the `timings` feature, scopes
+//! record how long they spend in each phase of `get_or_add`:
+//!
+//! ```
+//! # #[cfg(not(feature = "timings"))]
+//! # fn main() {}
+//! # #[cfg(feature = "timings")]
+//! # fn main() {
+//! # use ioc::prelude::*;
+//! # use ioc::{phase_timings, reset_phase_timings};
+//! # use std::rc::Rc;
+//! # struct Y;
+//! # impl<C> Resolvable<C> for Y {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { Y }
+//! # }
+//! # let scope = Scoped::new();
+//! reset_phase_timings();
+//!
+//! let y: Rc<Y> = scope.invoke(|y: Rc<Y>| y);
+//!
+//! println!("{}", phase_timings());
+//! # }
+//! ```
+//!
+//! Timing each phase has a cost of its own, so the feature shouldn't be
+//! enabled when comparing end-to-end numbers. Without it, nothing is timed.
+
+#[cfg(feature = "timings")]
+use std::cell::RefCell;
+#[cfg(feature = "timings")]
+use std::fmt;
+#[cfg(feature = "timings")]
+use std::time::{Duration, Instant};
+
+/// A phase of resolving a shared dependency.
+#[cfg(feature = "timings")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+    /// Looking up an existing value in the scope.
+    Lookup,
+    /// Constructing a new value from its dependencies.
+    ///
+    /// This doesn't include resolving the dependencies themselves, which
+    /// are timed in their own phases.
+    Construct,
+    /// Cloning the `Rc` for a new value so the scope can keep one.
+    Clone,
+    /// Inserting a new value into the scope.
+    Insert,
+}
+
+/// The time spent in a single phase.
+#[cfg(feature = "timings")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseTiming {
+    /// The number of times the phase ran.
+    pub count: u64,
+    /// The total time spent in the phase.
+    pub total: Duration,
+}
+
+#[cfg(feature = "timings")]
+impl PhaseTiming {
+    /// The average time spent in the phase each time it ran.
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            Duration::default()
+        } else {
+            Duration::from_nanos((self.total.as_nanos() / u128::from(self.count)) as u64)
+        }
+    }
+}
+
+/// The time spent in each phase on the current thread.
+#[cfg(feature = "timings")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseTimings {
+    pub lookup: PhaseTiming,
+    pub construct: PhaseTiming,
+    pub clone: PhaseTiming,
+    pub insert: PhaseTiming,
+}
+
+#[cfg(feature = "timings")]
+impl PhaseTimings {
+    /// The time spent in a given phase.
+    pub fn phase(&self, phase: Phase) -> PhaseTiming {
+        match phase {
+            Phase::Lookup => self.lookup,
+            Phase::Construct => self.construct,
+            Phase::Clone => self.clone,
+            Phase::Insert => self.insert,
+        }
+    }
+
+    fn phase_mut(&mut self, phase: Phase) -> &mut PhaseTiming {
+        match phase {
+            Phase::Lookup => &mut self.lookup,
+            Phase::Construct => &mut self.construct,
+            Phase::Clone => &mut self.clone,
+            Phase::Insert => &mut self.insert,
+        }
+    }
+}
+
+#[cfg(feature = "timings")]
+impl fmt::Display for PhaseTimings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for &phase in &[Phase::Lookup, Phase::Construct, Phase::Clone, Phase::Insert] {
+            let timing = self.phase(phase);
+
+            writeln!(f, "{:<10} {:>10} calls {:>12?} total {:>10?} mean",
+                     format!("{:?}", phase), timing.count, timing.total, timing.mean())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "timings")]
+thread_local! {
+    static TIMINGS: RefCell<PhaseTimings> = RefCell::new(PhaseTimings::default());
+}
+
+/// Get the time spent in each phase on the current thread since the last reset.
+#[cfg(feature = "timings")]
+pub fn phase_timings() -> PhaseTimings {
+    TIMINGS.with(|timings| *timings.borrow())
+}
+
+/// Reset the time spent in each phase on the current thread.
+#[cfg(feature = "timings")]
+pub fn reset_phase_timings() {
+    TIMINGS.with(|timings| *timings.borrow_mut() = PhaseTimings::default());
+}
+
+/// Time a phase, adding it to the current thread's timings.
+#[cfg(feature = "timings")]
+#[inline]
+pub(super) fn timed<F, T>(phase: Phase, f: F) -> T
+    where F: FnOnce() -> T
+{
+    let start = Instant::now();
+    let t = f();
+    let elapsed = start.elapsed();
+
+    TIMINGS.with(|timings| {
+        let mut timings = timings.borrow_mut();
+        let timing = timings.phase_mut(phase);
+
+        timing.count += 1;
+        timing.total += elapsed;
+    });
+
+    t
+}
+
+/// Without the `timings` feature phases aren't timed.
+#[cfg(not(feature = "timings"))]
+#[inline(always)]
+pub(super) fn timed<F, T>(_: Phase, f: F) -> T
+    where F: FnOnce() -> T
+{
+    f()
+}
+
+#[cfg(not(feature = "timings"))]
+#[derive(Clone, Copy)]
+pub(super) enum Phase {
+    Lookup,
+    Construct,
+    Clone,
+    Insert,
+}
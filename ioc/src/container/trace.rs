@@ -0,0 +1,86 @@
+//! Tracing what a scope resolves
+//!
+//! With the `tracing` feature, each call to `Container::resolve` and each
+//! shared dependency looked up by `ScopedContainer::get_or_add` runs in its
+//! own `trace`-level span. The spans are nested the same way dependencies
+//! are, so a subscriber that renders span hierarchies shows the tree of
+//! dependencies a scope actually built, something like:
+//!
+//! ```text
+//! resolve{type_name="Dispatcher"}
+//!   get_or_add{type_name="Transaction" cached=false}
+//!     elapsed=1.2µs
+//!   get_or_add{type_name="Clock" cached=true}
+//!     elapsed=85ns
+//!   elapsed=4.7µs
+//! ```
+//!
+//! Spans use the short name of each type from `TypeName`, so names are only
+//! shortened once. Each span ends with an event that has how long it took,
+//! including its dependencies. Without the feature, nothing is traced.
+
+#[cfg(feature = "tracing")]
+use std::time::Instant;
+
+#[cfg(feature = "tracing")]
+use tracing::field;
+#[cfg(feature = "tracing")]
+use super::names::short_name;
+#[cfg(feature = "tracing")]
+use tracing::span::EnteredSpan;
+
+/// The span for a dependency that's being resolved.
+#[cfg(feature = "tracing")]
+pub(super) struct Traced {
+    span: EnteredSpan,
+    start: Instant,
+}
+
+#[cfg(feature = "tracing")]
+impl Traced {
+    fn enter(span: tracing::Span) -> Self {
+        Traced {
+            span: span.entered(),
+            start: Instant::now(),
+        }
+    }
+
+    pub(super) fn resolve(type_name: &'static str) -> Self {
+        Traced::enter(tracing::trace_span!("resolve", type_name = short_name(type_name)))
+    }
+
+    pub(super) fn get_or_add(type_name: &'static str) -> Self {
+        Traced::enter(tracing::trace_span!("get_or_add", type_name = short_name(type_name), cached = field::Empty))
+    }
+
+    /// Record whether the dependency was already in the scope.
+    pub(super) fn cached(&self, cached: bool) {
+        self.span.record("cached", cached);
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for Traced {
+    fn drop(&mut self) {
+        tracing::trace!(elapsed = ?self.start.elapsed());
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(super) struct Traced;
+
+#[cfg(not(feature = "tracing"))]
+impl Traced {
+    #[inline(always)]
+    pub(super) fn resolve(_: &'static str) -> Self {
+        Traced
+    }
+
+    #[inline(always)]
+    pub(super) fn get_or_add(_: &'static str) -> Self {
+        Traced
+    }
+
+    #[inline(always)]
+    pub(super) fn cached(&self, _: bool) {}
+}
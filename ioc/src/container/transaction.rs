@@ -0,0 +1,235 @@
+//! Transactions per scope
+//!
+//! Work done in a scope usually succeeds or fails as a whole, so a scope
+//! can share a single transaction. An `Rc<InTransaction<P>>` is a pooled
+//! connection that begins a transaction when it's first resolved, and
+//! commits or rolls it back when the scope ends:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use ioc::{InTransaction, Pool, Transactional};
+//! # use std::error::Error;
+//! # use std::rc::Rc;
+//! # struct Settings { url: String }
+//! # struct Connection;
+//! # impl Transactional for Connection {
+//! #     type Error = String;
+//! #     fn begin(&mut self) -> Result<(), String> { Ok(()) }
+//! #     fn commit(&mut self) -> Result<(), String> { Ok(()) }
+//! #     fn rollback(&mut self) -> Result<(), String> { Ok(()) }
+//! # }
+//! # struct DbPool;
+//! # impl DbPool {
+//! #     fn new(_: &str) -> Self { DbPool }
+//! # }
+//! # impl Pool for DbPool {
+//! #     type Connection = Connection;
+//! #     type Error = String;
+//! #     fn check_out(&self) -> Result<Connection, String> { Ok(Connection) }
+//! #     fn check_in(&self, _: Connection) {}
+//! # }
+//! # struct RenameProduct { tx: Rc<InTransaction<DbPool>> }
+//! # impl RenameProduct {
+//! #     fn rename(&self, _: u32, _: &str) -> Result<(), Box<dyn Error>> { let _ = &self.tx; Ok(()) }
+//! # }
+//! # impl Resolvable<Scoped> for RenameProduct {
+//! #     type Dependency = Rc<InTransaction<DbPool>>;
+//! #     fn resolve(tx: Self::Dependency) -> Self { RenameProduct { tx } }
+//! # }
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! # let settings = Settings { url: "postgres://localhost".into() };
+//! # let mut registry = Registry::new();
+//! registry.bind_pool(DbPool::new(&settings.url));
+//! # let registry = Rc::new(registry);
+//!
+//! let scope = Scoped::new().with_registry(registry.clone());
+//!
+//! scope.try_run(|scope| {
+//!     let handler: RenameProduct = scope.try_resolve()?;
+//!
+//!     handler.rename(1, "A new title")
+//! })?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The transaction is committed if the scope's `Outcome` succeeded, which
+//! `ScopedContainer::try_run` records from the result of its closure.
+//! Scopes used some other way, like with a `ScopeGuard`, can resolve the
+//! `Rc<Outcome>` and record it themselves. Anything else is rolled back.
+//!
+//! The scope has already ended by the time the transaction does, so a
+//! commit or roll back that fails is logged to the scope's `dyn Logger`,
+//! or standard error if there isn't one.
+
+use std::any::type_name;
+use std::cell::{Cell, RefCell, RefMut};
+use std::fmt;
+use std::rc::Rc;
+use std::thread;
+
+use super::*;
+use super::pool::{Pool, Pooled};
+
+/// A connection that can begin, commit and roll back transactions.
+pub trait Transactional {
+    type Error: fmt::Display;
+
+    fn begin(&mut self) -> Result<(), Self::Error>;
+
+    fn commit(&mut self) -> Result<(), Self::Error>;
+
+    fn rollback(&mut self) -> Result<(), Self::Error>;
+}
+
+/// Whether the work done in a scope succeeded.
+///
+/// The outcome is undecided until it's recorded, which counts as a failure.
+#[derive(Debug, Default)]
+pub struct Outcome {
+    succeeded: Cell<Option<bool>>,
+}
+
+impl Outcome {
+    pub fn new() -> Self {
+        Outcome::default()
+    }
+
+    /// Record that the work succeeded.
+    pub fn succeed(&self) {
+        self.succeeded.set(Some(true));
+    }
+
+    /// Record that the work failed.
+    pub fn fail(&self) {
+        self.succeeded.set(Some(false));
+    }
+
+    /// Whether the work succeeded, if it's been recorded.
+    pub fn succeeded(&self) -> Option<bool> {
+        self.succeeded.get()
+    }
+}
+
+impl<C> Resolvable<C> for Outcome {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Outcome::new()
+    }
+}
+
+/// A connection checked out of the pool `P` with an open transaction.
+///
+/// The transaction ends when the `InTransaction` is dropped, unless it's
+/// been committed or rolled back already. Errors ending it then are only
+/// logged, so call `commit` directly to handle them.
+pub struct InTransaction<P>
+    where P: Pool,
+          P::Connection: Transactional
+{
+    connection: RefCell<Pooled<P>>,
+    outcome: Rc<Outcome>,
+    log: Option<Bound<dyn Logger>>,
+    open: Cell<bool>,
+}
+
+impl<P> InTransaction<P>
+    where P: Pool,
+          P::Connection: Transactional
+{
+    /// The connection the transaction is open on.
+    pub fn connection(&self) -> RefMut<'_, P::Connection> {
+        RefMut::map(self.connection.borrow_mut(), |connection| &mut **connection)
+    }
+
+    /// Commit the transaction now.
+    ///
+    /// Does nothing if the transaction has already ended.
+    pub fn commit(&self) -> Result<(), <P::Connection as Transactional>::Error> {
+        self.end(|connection| connection.commit())
+    }
+
+    /// Roll the transaction back now.
+    ///
+    /// Does nothing if the transaction has already ended.
+    pub fn rollback(&self) -> Result<(), <P::Connection as Transactional>::Error> {
+        self.end(|connection| connection.rollback())
+    }
+
+    /// Whether the transaction hasn't been committed or rolled back yet.
+    pub fn is_open(&self) -> bool {
+        self.open.get()
+    }
+
+    fn end<F>(&self, f: F) -> Result<(), <P::Connection as Transactional>::Error>
+        where F: FnOnce(&mut P::Connection) -> Result<(), <P::Connection as Transactional>::Error>
+    {
+        if !self.open.replace(false) {
+            return Ok(());
+        }
+
+        f(&mut self.connection())
+    }
+}
+
+impl<P> Drop for InTransaction<P>
+    where P: Pool,
+          P::Connection: Transactional
+{
+    fn drop(&mut self) {
+        // the scope may be half-finished, so don't commit anything it did
+        let (ending, result) = match self.outcome.succeeded() {
+            Some(true) if !thread::panicking() => ("commit", self.commit()),
+            _ => ("roll back", self.rollback()),
+        };
+
+        if let Err(e) = result {
+            let record = Record::new(Level::Error, format_args!("couldn't {} the transaction", ending))
+                .with("pool", type_name::<P>())
+                .with("error", e);
+
+            match self.log {
+                Some(ref log) => log.log(record),
+                None => StderrLogger.log(record),
+            }
+        }
+    }
+}
+
+impl<P> fmt::Debug for InTransaction<P>
+    where P: Pool,
+          P::Connection: Transactional
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("InTransaction")
+            .field("pool", &type_name::<P>())
+            .field("open", &self.is_open())
+            .field("outcome", &self.outcome.succeeded())
+            .finish()
+    }
+}
+
+impl<C, P> Resolvable<C> for InTransaction<P>
+    where C: RegisteredContainer + ScopedContainer + 'static,
+          P: Pool + 'static,
+          P::Connection: Transactional
+{
+    type Dependency = (Bound<P>, Rc<Outcome>, Option<Bound<dyn Logger>>);
+
+    fn resolve((pool, outcome, log): Self::Dependency) -> Self {
+        let mut connection = Pooled::check_out(pool.into_inner())
+            .unwrap_or_else(|e| panic!("couldn't check out a connection from `{}`: {}", type_name::<P>(), e));
+
+        if let Err(e) = connection.begin() {
+            panic!("couldn't begin a transaction on `{}`: {}", type_name::<P>(), e);
+        }
+
+        InTransaction {
+            connection: RefCell::new(connection),
+            outcome,
+            log,
+            open: Cell::new(true),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Transient dependencies
+//!
+//! Inside a scope every `Rc<T>` points to the same instance. A
+//! `Transient<T>` is always a new instance instead, even when it's resolved
+//! from a scope, which suits stateful helpers that shouldn't be shared, like
+//! builders or random number generators:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use std::rc::Rc;
+//! # struct Config;
+//! # struct Rng;
+//! # impl<C> Resolvable<C> for Config {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { Config }
+//! # }
+//! # impl<C> Resolvable<C> for Rng {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { Rng }
+//! # }
+//! # struct Handler { config: Rc<Config>, rng: Rng }
+//! impl<C> Resolvable<C> for Handler {
+//!     type Dependency = (Rc<Config>, Transient<Rng>);
+//!
+//!     fn resolve((config, rng): Self::Dependency) -> Self {
+//!         Handler { config, rng: rng.into_inner() }
+//!     }
+//! }
+//! ```
+//!
+//! A `RefCell<T>` is also a new instance each time, but it can only be used
+//! through the `RefCell`. A `Transient<T>` can be used as a `T` directly.
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+/// A dependency that's a new instance of `T` each time it's resolved.
+pub struct Transient<T> {
+    value: T,
+}
+
+impl<T> Transient<T> {
+    pub fn new(value: T) -> Self {
+        Transient { value }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Transient<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Transient<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T> AsRef<T> for Transient<T> {
+    fn as_ref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> AsMut<T> for Transient<T> {
+    fn as_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T> From<T> for Transient<T> {
+    fn from(value: T) -> Self {
+        Transient::new(value)
+    }
+}
+
+impl<T> fmt::Debug for Transient<T>
+    where T: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Transient").field(&self.value).finish()
+    }
+}
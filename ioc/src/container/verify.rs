@@ -0,0 +1,137 @@
+//! Dependency graph verification
+//!
+//! Static dependencies are checked by the compiler, but runtime bindings
+//! aren't, so a missing binding or a cycle through bindings won't be found
+//! until something tries to resolve it. Verifying walks the dependency graph
+//! without constructing anything, so these problems can be found at startup.
+
+use std::error::Error;
+use std::fmt;
+
+use super::{DependencyGraph, DependencyKind, Node};
+use super::names::{short_name, short_path};
+
+/// A problem found while verifying a dependency graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+    /// A `Bound<T>` dependency with no binding for `T`.
+    MissingBinding {
+        type_name: &'static str,
+        /// The chain of dependencies that led to the missing binding.
+        path: Vec<&'static str>,
+    },
+    /// A binding that depends on itself.
+    Cycle {
+        /// The chain of dependencies, starting and ending with the same type.
+        path: Vec<&'static str>,
+    },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VerifyError::MissingBinding { type_name, ref path } => {
+                write!(f, "there's no binding for `{}` (required by {})", short_name(type_name), short_path(path))
+            }
+            VerifyError::Cycle { ref path } => write!(f, "there's a dependency cycle: {}", short_path(path)),
+        }
+    }
+}
+
+impl Error for VerifyError {}
+
+/// The state of a graph that's being verified.
+#[derive(Debug, Default)]
+pub struct Verification {
+    path: Vec<&'static str>,
+    /// Where the innermost deferred dependency starts in the path.
+    deferred: usize,
+    nodes: Vec<usize>,
+    errors: Vec<VerifyError>,
+    graph: DependencyGraph,
+}
+
+impl Verification {
+    pub fn new() -> Self {
+        Verification::default()
+    }
+
+    /// Verify the dependencies of `type_name` within `f`.
+    ///
+    /// If `type_name` is already being verified since the innermost
+    /// deferred dependency then there's a cycle, which is recorded instead
+    /// of calling `f`.
+    pub fn visit<F>(&mut self, kind: DependencyKind, type_name: &'static str, f: F)
+        where F: FnOnce(&mut Self)
+    {
+        let cycle = self.path[self.deferred..].contains(&type_name);
+
+        let node = self.graph.add(Node {
+            type_name,
+            kind,
+            parent: self.nodes.last().cloned(),
+            cycle,
+            missing_binding: false,
+        });
+
+        if cycle {
+            let mut path = self.path.clone();
+            path.push(type_name);
+
+            self.errors.push(VerifyError::Cycle { path });
+            return;
+        }
+
+        self.path.push(type_name);
+        self.nodes.push(node);
+        f(self);
+        self.nodes.pop();
+        self.path.pop();
+    }
+
+    /// Verify the dependencies of a deferred `type_name` within `f`.
+    ///
+    /// Deferred dependencies like `Lazy<T>` aren't resolved until after
+    /// whatever depends on them is constructed, so types within them can
+    /// refer back to types outside them without a cycle. If `type_name` is
+    /// already being verified then `f` isn't called and no cycle is
+    /// recorded.
+    pub fn visit_deferred<F>(&mut self, kind: DependencyKind, type_name: &'static str, f: F)
+        where F: FnOnce(&mut Self)
+    {
+        if self.path.contains(&type_name) {
+            return;
+        }
+
+        let outer = self.deferred;
+        self.deferred = self.path.len();
+        self.visit(kind, type_name, f);
+        self.deferred = outer;
+    }
+
+    /// Record that there's no binding for `type_name`.
+    pub fn missing_binding(&mut self, type_name: &'static str) {
+        if let Some(&node) = self.nodes.last() {
+            self.graph.node_mut(node).missing_binding = true;
+        }
+
+        self.errors.push(VerifyError::MissingBinding {
+            type_name,
+            path: self.path.clone(),
+        });
+    }
+
+    /// Finish verifying, returning any problems that were found.
+    pub fn finish(self) -> Result<(), Vec<VerifyError>> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+
+    /// Finish verifying, returning the dependencies that were visited.
+    pub fn into_graph(self) -> DependencyGraph {
+        self.graph
+    }
+}
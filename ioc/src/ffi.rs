@@ -0,0 +1,225 @@
+//! A C interface to the container
+//!
+//! This lets applications that host Rust from C, C++ or Python resolve
+//! dependencies by name. Types are registered on the Rust side, under a
+//! name, in a function passed to `set_module`. The host can then create a
+//! container and scopes, and resolve named types into opaque handles:
+//!
+//! ```c
+//! ioc_container* container = ioc_container_new();
+//! ioc_scope* scope = ioc_scope_new(container);
+//!
+//! ioc_handle* service = ioc_resolve(scope, "order_service");
+//! // pass `service` back to Rust functions that understand it
+//!
+//! ioc_handle_free(service);
+//! ioc_scope_free(scope);
+//! ioc_container_free(container);
+//! ```
+//!
+//! Rust functions exported by the embedding library can look inside a
+//! handle using `FfiHandle::downcast_ref`.
+//!
+//! Panics are caught at the boundary, and the functions that return a
+//! pointer return null instead.
+//!
+//! Containers, their scopes and the handles resolved from them share
+//! state that isn't thread-safe. The host has to make every call for a
+//! container and anything created from it on the same thread.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::rc::Rc;
+use std::sync::Mutex;
+
+use super::*;
+
+type NamedBinding = Box<dyn Fn(&Scoped) -> Box<dyn Any>>;
+
+/// A set of types that can be resolved by name.
+#[derive(Default)]
+pub struct FfiRegistry {
+    named: HashMap<String, NamedBinding>,
+}
+
+impl FfiRegistry {
+    pub fn new() -> Self {
+        FfiRegistry::default()
+    }
+
+    /// Register a new instance of `T` to be resolved under `name`.
+    pub fn register<T, D>(&mut self, name: &str) -> &mut Self
+        where T: Resolvable<Scoped, Dependency = D> + 'static,
+              D: ResolvableFromContainer<Scoped>
+    {
+        self.register_with(name, |scope| scope.resolve::<D, T>())
+    }
+
+    /// Register a function to call when `name` is resolved.
+    pub fn register_with<T, F>(&mut self, name: &str, f: F) -> &mut Self
+        where T: 'static,
+              F: Fn(&Scoped) -> T + 'static
+    {
+        self.named.insert(name.to_owned(), Box::new(move |scope| Box::new(f(scope))));
+
+        self
+    }
+
+    fn resolve(&self, scope: &Scoped, name: &str) -> Option<Box<dyn Any>> {
+        self.named.get(name).map(|binding| binding(scope))
+    }
+}
+
+static MODULE: Mutex<Option<fn(&mut FfiRegistry)>> = Mutex::new(None);
+
+/// Set the function that registers named types for new containers.
+///
+/// This needs to be called by the embedding library before the host calls
+/// `ioc_container_new`.
+pub fn set_module(module: fn(&mut FfiRegistry)) {
+    *MODULE.lock().unwrap_or_else(|err| err.into_inner()) = Some(module);
+}
+
+/// A container created through the C interface.
+pub struct FfiContainer {
+    registry: Rc<FfiRegistry>,
+}
+
+impl FfiContainer {
+    pub fn new(registry: FfiRegistry) -> Self {
+        FfiContainer { registry: Rc::new(registry) }
+    }
+}
+
+/// A scope created through the C interface.
+pub struct FfiScope {
+    scope: Scoped,
+    registry: Rc<FfiRegistry>,
+}
+
+/// A resolved value handed out through the C interface.
+pub struct FfiHandle {
+    value: Box<dyn Any>,
+}
+
+impl FfiHandle {
+    /// Get the value in the handle if it's a `T`.
+    pub fn downcast_ref<T>(&self) -> Option<&T>
+        where T: 'static
+    {
+        self.value.downcast_ref()
+    }
+}
+
+fn catch<F, T>(f: F) -> *mut T
+    where F: FnOnce() -> Option<T>
+{
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Some(t)) => Box::into_raw(Box::new(t)),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Create a new container using the module set by `set_module`.
+///
+/// Returns null if no module has been set.
+#[no_mangle]
+pub extern "C" fn ioc_container_new() -> *mut FfiContainer {
+    catch(|| {
+        let module = (*MODULE.lock().unwrap_or_else(|err| err.into_inner()))?;
+
+        let mut registry = FfiRegistry::new();
+        module(&mut registry);
+
+        Some(FfiContainer::new(registry))
+    })
+}
+
+/// Free a container.
+///
+/// # Safety
+///
+/// `container` must have come from `ioc_container_new` and not been freed.
+/// Scopes created from the container can outlive it. This must be called
+/// on the same thread as every other call for the container.
+#[no_mangle]
+pub unsafe extern "C" fn ioc_container_free(container: *mut FfiContainer) {
+    if !container.is_null() {
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(container))));
+    }
+}
+
+/// Create a new scope to resolve named types from.
+///
+/// # Safety
+///
+/// `container` must be a live pointer from `ioc_container_new`. This must
+/// be called on the same thread as every other call for the container.
+#[no_mangle]
+pub unsafe extern "C" fn ioc_scope_new(container: *const FfiContainer) -> *mut FfiScope {
+    catch(|| {
+        let container = container.as_ref()?;
+
+        Some(FfiScope {
+            scope: Scoped::new(),
+            registry: container.registry.clone(),
+        })
+    })
+}
+
+/// Free a scope, dropping everything it cached.
+///
+/// # Safety
+///
+/// `scope` must have come from `ioc_scope_new` and not been freed. This
+/// must be called on the same thread as every other call for the container
+/// the scope was created from.
+#[no_mangle]
+pub unsafe extern "C" fn ioc_scope_free(scope: *mut FfiScope) {
+    if !scope.is_null() {
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(scope))));
+    }
+}
+
+/// Resolve a type by name.
+///
+/// Returns null if the name isn't registered or resolving it panics.
+///
+/// # Safety
+///
+/// `scope` must be a live pointer from `ioc_scope_new` and `name` must be a
+/// nul-terminated string. This must be called on the same thread as every
+/// other call for the container the scope was created from.
+#[no_mangle]
+pub unsafe extern "C" fn ioc_resolve(scope: *const FfiScope, name: *const c_char) -> *mut FfiHandle {
+    catch(|| {
+        let scope = scope.as_ref()?;
+
+        if name.is_null() {
+            return None;
+        }
+
+        let name = CStr::from_ptr(name).to_str().ok()?;
+        let value = scope.registry.resolve(&scope.scope, name)?;
+
+        Some(FfiHandle { value })
+    })
+}
+
+/// Free a handle.
+///
+/// # Safety
+///
+/// `handle` must have come from `ioc_resolve` and not been freed. This
+/// must be called on the same thread as every other call for the container
+/// the handle was resolved from.
+#[no_mangle]
+pub unsafe extern "C" fn ioc_handle_free(handle: *mut FfiHandle) {
+    if !handle.is_null() {
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(handle))));
+    }
+}
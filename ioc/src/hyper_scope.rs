@@ -0,0 +1,335 @@
+//! A scope for each request served by hyper
+//!
+//! `ScopedService` is a hyper `Service` that creates a new `Scoped` for
+//! each request, seeds it with the request's head, and then calls a
+//! handler with the scope and the request:
+//!
+//! ```edition2018,no_run
+//! # extern crate hyper;
+//! # extern crate ioc;
+//! # use hyper::{Request, Response};
+//! # use ioc::hyper_scope::ScopedService;
+//! # use ioc::prelude::*;
+//! # use std::rc::Rc;
+//! # // `Incoming` and `http1` need hyper's `server` and `http1` features
+//! # type Incoming = ();
+//! # mod http1 {
+//! #     use hyper::service::Service;
+//! #     use hyper::Request;
+//! #     pub struct Builder;
+//! #     impl Builder {
+//! #         pub fn new() -> Self { Builder }
+//! #         pub async fn serve_connection<I, S>(&self, _: I, _: S) -> Result<(), ()>
+//! #             where S: Service<Request<crate::Incoming>> { Ok(()) }
+//! #     }
+//! # }
+//! # struct OrderHandler;
+//! # impl OrderHandler {
+//! #     fn handle<B>(&self, _: Request<B>) -> Response<String> { Response::new(String::new()) }
+//! # }
+//! # impl<C> Resolvable<C> for OrderHandler {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { OrderHandler }
+//! # }
+//! fn handle(scope: &Scoped, req: Request<Incoming>) -> Response<String> {
+//!     let handler: OrderHandler = scope.resolve();
+//!
+//!     handler.handle(req)
+//! }
+//!
+//! # async fn serve(io: (), registry: Rc<Registry<Scoped>>) -> Result<(), ()> {
+//! let service = ScopedService::new(handle).with_registry(registry);
+//!
+//! http1::Builder::new().serve_connection(io, service).await
+//! # }
+//! # fn main() {}
+//! ```
+//!
+//! Anything resolved from the scope can depend on an `Rc<RequestHead>` to
+//! get the method, uri and headers of the request it's handling. If the
+//! request has an `x-correlation-id` header then it's used as the scope's
+//! correlation id.
+//!
+//! Scopes aren't `Send`, so neither is the service. Connections need to be
+//! served on a single thread, like with a `tokio::task::LocalSet`.
+//!
+//! Services that are already written as hyper `Service`s can be wrapped by
+//! a `ScopeLayer` instead. It creates the scope the same way, and makes it
+//! the current scope while the inner service is called and while its
+//! future is polled:
+//!
+//! ```no_run
+//! # extern crate hyper;
+//! # extern crate ioc;
+//! # use ioc::hyper_scope::{current_scope, ScopeLayer};
+//! # use ioc::prelude::*;
+//! # use std::rc::Rc;
+//! # struct OrderHandler;
+//! # impl<C> Resolvable<C> for OrderHandler {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { OrderHandler }
+//! # }
+//! # fn main() {
+//! # let registry = Rc::new(Registry::new());
+//! # let router = ();
+//! let service = ScopeLayer::new().with_registry(registry).layer(router);
+//!
+//! // anywhere inside the router
+//! let handler: OrderHandler = current_scope().expect("missing scope").resolve();
+//! # }
+//! ```
+//!
+//! It's a layer in the same sense as tower's, but over hyper's `Service`
+//! trait, so it doesn't need tower. The `ioc_tower` crate implements
+//! tower's `Layer` and `Service` on top of it.
+
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::future::{self, Future, Ready};
+use std::ops::Deref;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+use hyper::http::request::Parts;
+use hyper::service::Service;
+use hyper::Response;
+
+/// The request type scopes are created for.
+///
+/// It's re-exported so integrations use the same version of `http` as
+/// `hyper` does.
+pub use hyper::Request;
+
+use super::*;
+
+/// The header a request's correlation id is read from.
+pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+
+/// The head of the request a scope was created for.
+///
+/// This is the method, uri, version, headers and extensions of the
+/// request, without its body.
+#[derive(Debug, Clone)]
+pub struct RequestHead {
+    parts: Parts,
+}
+
+impl RequestHead {
+    pub fn into_inner(self) -> Parts {
+        self.parts
+    }
+}
+
+impl Deref for RequestHead {
+    type Target = Parts;
+
+    fn deref(&self) -> &Parts {
+        &self.parts
+    }
+}
+
+// the head is inserted into the scope by `ScopedService`, so it can't be
+// resolved anywhere else
+impl<C> Resolvable<C> for RequestHead {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        panic!("`RequestHead` is only available in scopes created by `ScopedService`")
+    }
+}
+
+/// A hyper `Service` that handles each request in a new scope.
+pub struct ScopedService<H> {
+    handler: Rc<H>,
+    registry: Option<Rc<Registry<Scoped>>>,
+}
+
+impl<H> Clone for ScopedService<H> {
+    fn clone(&self) -> Self {
+        ScopedService {
+            handler: self.handler.clone(),
+            registry: self.registry.clone(),
+        }
+    }
+}
+
+impl<H> ScopedService<H> {
+    /// Create a service that calls `handler` for each request.
+    pub fn new(handler: H) -> Self {
+        ScopedService {
+            handler: Rc::new(handler),
+            registry: None,
+        }
+    }
+
+    /// Use a registry of runtime bindings for each request's scope.
+    pub fn with_registry(mut self, registry: Rc<Registry<Scoped>>) -> Self {
+        self.registry = Some(registry);
+
+        self
+    }
+
+}
+
+/// Create a scope for a request.
+fn request_scope<B>(registry: Option<&Rc<Registry<Scoped>>>, req: &Request<B>) -> Scoped {
+    let mut scope = Scoped::new();
+
+    if let Some(registry) = registry {
+        scope = scope.with_registry(registry.clone());
+    }
+
+    match req.headers().get(CORRELATION_ID_HEADER).and_then(|id| id.to_str().ok()) {
+        Some(id) => scope.with_correlation_id(CorrelationId::new(id)),
+        None => scope,
+    }
+}
+
+impl<H, ReqBody, ResBody> Service<Request<ReqBody>> for ScopedService<H>
+    where H: Fn(&Scoped, Request<ReqBody>) -> Response<ResBody>
+{
+    type Response = Response<ResBody>;
+    type Error = Infallible;
+    type Future = Ready<Result<Response<ResBody>, Infallible>>;
+
+    fn call(&self, req: Request<ReqBody>) -> Self::Future {
+        let scope = request_scope(self.registry.as_ref(), &req);
+
+        let (parts, body) = req.into_parts();
+        scope.replace(RequestHead { parts: parts.clone() });
+
+        let res = (self.handler)(&scope, Request::from_parts(parts, body));
+
+        future::ready(Ok(res))
+    }
+}
+
+thread_local! {
+    static CURRENT_SCOPES: RefCell<Vec<Rc<Scoped>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The scope of the request being handled by a `ScopeLayer`, if there is one.
+pub fn current_scope() -> Option<Rc<Scoped>> {
+    CURRENT_SCOPES.with(|scopes| scopes.borrow().last().cloned())
+}
+
+/// Make `scope` the current scope while `f` runs.
+///
+/// Scopes are nested, so the previous current scope is restored when `f`
+/// returns or panics.
+pub fn in_scope<F, T>(scope: &Rc<Scoped>, f: F) -> T
+    where F: FnOnce() -> T
+{
+    struct Exit;
+
+    // pop the scope even if `f` panics
+    impl Drop for Exit {
+        fn drop(&mut self) {
+            CURRENT_SCOPES.with(|scopes| scopes.borrow_mut().pop());
+        }
+    }
+
+    CURRENT_SCOPES.with(|scopes| scopes.borrow_mut().push(scope.clone()));
+    let _exit = Exit;
+
+    f()
+}
+
+/// Wraps hyper `Service`s in a `Scoped` service.
+#[derive(Default, Clone)]
+pub struct ScopeLayer {
+    registry: Option<Rc<Registry<Scoped>>>,
+}
+
+impl ScopeLayer {
+    pub fn new() -> Self {
+        ScopeLayer::default()
+    }
+
+    /// Use a registry of runtime bindings for each request's scope.
+    pub fn with_registry(mut self, registry: Rc<Registry<Scoped>>) -> Self {
+        self.registry = Some(registry);
+
+        self
+    }
+
+    /// Wrap `inner` so it's called in a new scope for each request.
+    pub fn layer<S>(&self, inner: S) -> Scoping<S> {
+        Scoping {
+            inner,
+            layer: self.clone(),
+        }
+    }
+
+    /// Create the scope for a request, seeded with the request's head.
+    pub fn scope_request<B>(&self, req: Request<B>) -> (Rc<Scoped>, Request<B>) {
+        let scope = request_scope(self.registry.as_ref(), &req);
+
+        let (parts, body) = req.into_parts();
+        scope.replace(RequestHead { parts: parts.clone() });
+
+        (Rc::new(scope), Request::from_parts(parts, body))
+    }
+}
+
+/// A hyper `Service` that calls an inner service in a new scope for each
+/// request.
+///
+/// The scope is seeded with the request's head, like a `ScopedService`'s,
+/// and is available from `current_scope`.
+#[derive(Clone)]
+pub struct Scoping<S> {
+    inner: S,
+    layer: ScopeLayer,
+}
+
+impl<S> Scoping<S> {
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for Scoping<S>
+    where S: Service<Request<ReqBody>>
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = InScope<S::Future>;
+
+    fn call(&self, req: Request<ReqBody>) -> Self::Future {
+        let (scope, req) = self.layer.scope_request(req);
+        let future = in_scope(&scope, || self.inner.call(req));
+
+        InScope::new(scope, future)
+    }
+}
+
+/// A future that's polled in the scope of the request it's handling.
+///
+/// The scope ends when the future completes or is dropped.
+pub struct InScope<F> {
+    scope: Rc<Scoped>,
+    future: Pin<Box<F>>,
+}
+
+impl<F> InScope<F> {
+    pub fn new(scope: Rc<Scoped>, future: F) -> Self {
+        InScope {
+            scope,
+            future: Box::pin(future),
+        }
+    }
+}
+
+impl<F> Future for InScope<F>
+    where F: Future
+{
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<F::Output> {
+        let InScope { ref scope, ref mut future } = *self;
+
+        in_scope(scope, || future.as_mut().poll(cx))
+    }
+}
@@ -1,4 +1,48 @@
 extern crate fnv;
+extern crate ioc_macros;
+#[cfg(feature = "hyper")]
+extern crate hyper;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(feature = "config")]
+extern crate serde;
+#[cfg(feature = "config")]
+extern crate toml;
+#[cfg(feature = "rayon")]
+extern crate rayon;
 
 mod container;
-pub use container::*;
\ No newline at end of file
+pub use container::*;
+pub use ioc_macros::{ioc_test, Resolvable};
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+#[cfg(feature = "hyper")]
+pub mod hyper_scope;
+
+pub mod testing;
+
+pub mod std_services;
+
+/// The traits and types needed to declare and resolve dependencies.
+///
+/// ```
+/// extern crate ioc;
+///
+/// use ioc::prelude::*;
+/// # fn main() {}
+/// ```
+pub mod prelude {
+    pub use container::{Container, Scope, ScopedContainer, BorrowScope, BorrowedContainer, BrandedContainer,
+                        RegisteredContainer, CaptureContainer, CorrelatedContainer, ContextContainer, SyncScopedContainer,
+                        Resolvable, ResolvableWith, ResolvableFromContainer, OpenBinding, Disposable, Initialize,
+                        Startable, EnvVar, FromArgs};
+    pub use container::factories::{Query, Command, QueryOf, CommandOf};
+    pub use container::{BasicContainer, DynamicContainer, Scoped, SyncScoped, ArcContainer, Borrowed, B, BrandedScope, Shared, PinnedRc, Registry,
+                        Module, Platform, Bound, All, KeyedAll, ResolveIter, Open, Env, Args, Context, Dyn, External, Lazy, Factory, Transient, SharedMut, CorrelationId,
+                        ScopeLog, ScopeGuard, Metrics, VerifyError, Fingerprint, ReplacePolicy, DependencyGraph,
+                        ResolveError};
+    pub use ioc_test;
+    pub use ioc_macros::Resolvable;
+}
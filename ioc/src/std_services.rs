@@ -0,0 +1,502 @@
+//! Built-in services
+//!
+//! Some dependencies are needed by almost every application and replaced in
+//! almost every test. Time is the most common one, so it's provided here as
+//! a `Clock` service that's bound like any other:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use ioc::std_services::*;
+//! # use std::rc::Rc;
+//! # use std::time::SystemTime;
+//! # struct Session { started: SystemTime, clock: Bound<dyn Clock> }
+//! # let mut registry = Registry::new();
+//! registry.add_module(StdServices);
+//!
+//! impl<C> Resolvable<C> for Session {
+//!     type Dependency = Bound<dyn Clock>;
+//!
+//!     fn resolve(clock: Self::Dependency) -> Self {
+//!         Session { started: clock.now(), clock }
+//!     }
+//! }
+//! # let session: Session = Scoped::new().with_registry(Rc::new(registry)).resolve();
+//! ```
+//!
+//! Tests can use a `TestClock` in its place, which only moves when it's
+//! told to:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use ioc::std_services::*;
+//! # use ioc::testing::TestScope;
+//! # use std::rc::Rc;
+//! # use std::time::Duration;
+//! let clock = TestClock::new();
+//!
+//! let scope = TestScope::new()
+//!     .with_module(StdServices)
+//!     .with_override::<dyn Clock>(Rc::new(clock.clone()))
+//!     .build();
+//!
+//! clock.advance(Duration::from_secs(60));
+//! # let now = Bound::<dyn Clock>::resolve_from_container(&scope).now();
+//! # assert_eq!(clock.now(), now);
+//! ```
+//!
+//! Randomness is the same. Services that generate ids or tokens can depend
+//! on a `Bound<dyn RandomSource>`, which tests can replace with a
+//! `SeededRandom` that always produces the same values. Services that
+//! touch files can depend on a `Bound<dyn FileSystem>`, which tests can
+//! replace with a `MemoryFileSystem` so they don't need a real disk.
+
+use std::cell::{Cell, RefCell};
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+
+use container::{Container, Module, Registry, Resolvable};
+
+/// The current time.
+pub trait Clock {
+    /// The current time on the system clock.
+    ///
+    /// This can go backwards if the system clock is changed, so use
+    /// `monotonic` to measure how long something takes.
+    fn now(&self) -> SystemTime;
+
+    /// The current time on a clock that never goes backwards.
+    fn monotonic(&self) -> Instant;
+}
+
+/// The clock of the system the application is running on.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl<C> Resolvable<C> for SystemClock {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        SystemClock
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    fn monotonic(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when it's advanced.
+///
+/// Clones share the same time, so a test can keep one to advance after
+/// the other has been resolved.
+#[derive(Clone)]
+pub struct TestClock {
+    now: SystemTime,
+    monotonic: Instant,
+    elapsed: Rc<Cell<Duration>>,
+}
+
+impl TestClock {
+    /// A clock that's stopped at the current time.
+    pub fn new() -> Self {
+        TestClock::starting_at(SystemTime::now())
+    }
+
+    /// A clock that's stopped at `now`.
+    pub fn starting_at(now: SystemTime) -> Self {
+        TestClock {
+            now,
+            monotonic: Instant::now(),
+            elapsed: Rc::new(Cell::new(Duration::ZERO)),
+        }
+    }
+
+    /// Move the clock forwards.
+    pub fn advance(&self, by: Duration) {
+        self.elapsed.set(self.elapsed.get() + by);
+    }
+
+    /// How far the clock has been advanced since it started.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed.get()
+    }
+}
+
+impl Default for TestClock {
+    fn default() -> Self {
+        TestClock::new()
+    }
+}
+
+impl<C> Resolvable<C> for TestClock {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        TestClock::new()
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> SystemTime {
+        self.now + self.elapsed()
+    }
+
+    fn monotonic(&self) -> Instant {
+        self.monotonic + self.elapsed()
+    }
+}
+
+impl fmt::Debug for TestClock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TestClock")
+            .field("now", &self.now())
+            .field("elapsed", &self.elapsed())
+            .finish()
+    }
+}
+
+/// A source of random numbers.
+pub trait RandomSource {
+    /// The next random number.
+    fn next_u64(&self) -> u64;
+
+    /// Fill `dest` with random bytes.
+    fn fill_bytes(&self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
+
+/// Random numbers from keys the operating system provides.
+///
+/// Each number hashes a counter with the keys of a fresh `RandomState`,
+/// which the standard library seeds from the operating system. They're
+/// unpredictable enough for ids and tokens, but use a dedicated crate for
+/// cryptographic keys.
+pub struct OsRandom {
+    keys: RandomState,
+    counter: Cell<u64>,
+}
+
+impl OsRandom {
+    pub fn new() -> Self {
+        OsRandom {
+            keys: RandomState::new(),
+            counter: Cell::new(0),
+        }
+    }
+}
+
+impl Default for OsRandom {
+    fn default() -> Self {
+        OsRandom::new()
+    }
+}
+
+impl<C> Resolvable<C> for OsRandom {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        OsRandom::new()
+    }
+}
+
+impl RandomSource for OsRandom {
+    fn next_u64(&self) -> u64 {
+        let counter = self.counter.get();
+        self.counter.set(counter.wrapping_add(1));
+
+        let mut hasher = self.keys.build_hasher();
+        hasher.write_u64(counter);
+
+        hasher.finish()
+    }
+}
+
+impl fmt::Debug for OsRandom {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("OsRandom")
+    }
+}
+
+/// Random numbers that are the same for the same seed.
+///
+/// Clones carry on from the same point independently, so each produces the
+/// same numbers as the original would have.
+#[derive(Debug, Clone)]
+pub struct SeededRandom {
+    state: Cell<u64>,
+}
+
+impl SeededRandom {
+    pub fn new(seed: u64) -> Self {
+        SeededRandom { state: Cell::new(seed) }
+    }
+}
+
+impl Default for SeededRandom {
+    fn default() -> Self {
+        SeededRandom::new(0)
+    }
+}
+
+impl<C> Resolvable<C> for SeededRandom {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        SeededRandom::default()
+    }
+}
+
+impl RandomSource for SeededRandom {
+    // splitmix64, which is fast and fine for tests but not much else
+    fn next_u64(&self) -> u64 {
+        let state = self.state.get().wrapping_add(0x9e37_79b9_7f4a_7c15);
+        self.state.set(state);
+
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+        z ^ (z >> 31)
+    }
+}
+
+/// A filesystem that files can be read from and written to.
+pub trait FileSystem {
+    /// Read the contents of a file.
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+    /// Read the contents of a file as UTF-8.
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        String::from_utf8(self.read(path)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Write the contents of a file, replacing it if it exists.
+    ///
+    /// The directory it's in needs to exist already.
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+
+    /// Whether a file or directory exists.
+    fn exists(&self, path: &Path) -> bool;
+
+    /// Create a directory and any of its parents that don't exist.
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    /// Create a new, empty temporary directory.
+    ///
+    /// The directory isn't removed afterwards.
+    fn temp_dir(&self) -> io::Result<PathBuf>;
+}
+
+/// The filesystem of the operating system.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OsFileSystem;
+
+impl<C> Resolvable<C> for OsFileSystem {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        OsFileSystem
+    }
+}
+
+impl FileSystem for OsFileSystem {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        fs::write(path, contents)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir_all(path)
+    }
+
+    fn temp_dir(&self) -> io::Result<PathBuf> {
+        static TEMP_DIRS: AtomicUsize = AtomicUsize::new(0);
+
+        loop {
+            let n = TEMP_DIRS.fetch_add(1, Ordering::Relaxed);
+            let path = env::temp_dir().join(format!("ioc-{}-{}", process::id(), n));
+
+            // a previous process with the same id may have left it behind
+            match fs::create_dir(&path) {
+                Ok(()) => return Ok(path),
+                Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// A filesystem that only exists in memory.
+///
+/// Clones share the same files, so a test can keep one to check what was
+/// written through the other.
+#[derive(Default, Clone)]
+pub struct MemoryFileSystem {
+    inner: Rc<RefCell<MemoryFiles>>,
+}
+
+#[derive(Default)]
+struct MemoryFiles {
+    files: HashMap<PathBuf, Vec<u8>>,
+    dirs: HashSet<PathBuf>,
+    temp_dirs: usize,
+}
+
+impl MemoryFiles {
+    fn is_dir(&self, path: &Path) -> bool {
+        // roots, and the current directory of relative paths, always exist
+        path.parent().is_none() || path.as_os_str().is_empty() || self.dirs.contains(path)
+    }
+}
+
+impl MemoryFileSystem {
+    pub fn new() -> Self {
+        MemoryFileSystem::default()
+    }
+
+    /// Add a file, along with the directory it's in.
+    pub fn with_file<P, B>(self, path: P, contents: B) -> Self
+        where P: AsRef<Path>,
+              B: Into<Vec<u8>>
+    {
+        let path = path.as_ref();
+
+        if let Some(parent) = path.parent() {
+            let _ = self.create_dir_all(parent);
+        }
+        let _ = self.write(path, &contents.into());
+
+        self
+    }
+
+    /// The paths of every file, in order.
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<_> = self.inner.borrow().files.keys().cloned().collect();
+        files.sort();
+
+        files
+    }
+}
+
+impl<C> Resolvable<C> for MemoryFileSystem {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        MemoryFileSystem::new()
+    }
+}
+
+impl fmt::Debug for MemoryFileSystem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MemoryFileSystem")
+            .field("files", &self.files())
+            .finish()
+    }
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        match self.inner.borrow().files.get(path) {
+            Some(contents) => Ok(contents.clone()),
+            None => Err(io::Error::new(io::ErrorKind::NotFound, format!("`{}` doesn't exist", path.display()))),
+        }
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let mut inner = self.inner.borrow_mut();
+
+        if inner.is_dir(path) {
+            return Err(io::Error::new(io::ErrorKind::IsADirectory, format!("`{}` is a directory", path.display())));
+        }
+        if !path.parent().is_none_or(|parent| inner.is_dir(parent)) {
+            return Err(io::Error::new(io::ErrorKind::NotFound, format!("the directory of `{}` doesn't exist", path.display())));
+        }
+
+        inner.files.insert(path.to_owned(), contents.to_owned());
+
+        Ok(())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        let inner = self.inner.borrow();
+
+        inner.files.contains_key(path) || inner.is_dir(path)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        let mut inner = self.inner.borrow_mut();
+
+        for dir in path.ancestors() {
+            if inner.files.contains_key(dir) {
+                return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("`{}` is a file", dir.display())));
+            }
+        }
+        for dir in path.ancestors().filter(|dir| dir.parent().is_some() && !dir.as_os_str().is_empty()) {
+            inner.dirs.insert(dir.to_owned());
+        }
+
+        Ok(())
+    }
+
+    fn temp_dir(&self) -> io::Result<PathBuf> {
+        loop {
+            let path = {
+                let mut inner = self.inner.borrow_mut();
+                inner.temp_dirs += 1;
+
+                env::temp_dir().join(format!("ioc-{}", inner.temp_dirs))
+            };
+
+            if !self.exists(&path) {
+                self.create_dir_all(&path)?;
+
+                return Ok(path);
+            }
+        }
+    }
+}
+
+/// Bind the built-in services to their default implementations.
+///
+/// This binds `dyn Clock` to a `SystemClock`, `dyn RandomSource` to an
+/// `OsRandom` and `dyn FileSystem` to an `OsFileSystem`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StdServices;
+
+impl<C> Module<C> for StdServices
+    where C: Container + 'static
+{
+    fn register(self, registry: &mut Registry<C>) {
+        registry
+            .bind_to::<dyn Clock, SystemClock, _, _>(|clock| Rc::new(clock))
+            .bind_to::<dyn RandomSource, OsRandom, _, _>(|random| Rc::new(random))
+            .bind_to::<dyn FileSystem, OsFileSystem, _, _>(|fs| Rc::new(fs));
+    }
+}
@@ -0,0 +1,102 @@
+//! Scopes for unit tests
+//!
+//! A `TestScope` builds a `Scoped` container with the same modules the
+//! application uses, but with specific dependencies replaced by fakes:
+//!
+//! ```
+//! # use ioc::prelude::*;
+//! # use ioc::testing::TestScope;
+//! # use std::rc::Rc;
+//! # trait Clock { fn now(&self) -> u64; }
+//! # struct SystemClock;
+//! # impl Clock for SystemClock { fn now(&self) -> u64 { 1 } }
+//! # struct FakeClock(u64);
+//! # impl Clock for FakeClock { fn now(&self) -> u64 { self.0 } }
+//! # struct Db;
+//! # struct TodoModule { db: Db }
+//! # impl TodoModule {
+//! #     fn new(db: Db) -> Self { TodoModule { db } }
+//! # }
+//! # impl Module<Scoped> for TodoModule {
+//! #     fn register(self, registry: &mut Registry<Scoped>) {
+//! #         let _ = self.db;
+//! #         registry.bind::<dyn Clock, _>(|_| Rc::new(SystemClock));
+//! #     }
+//! # }
+//! # struct CreateTodo { clock: Bound<dyn Clock> }
+//! # impl Resolvable<Scoped> for CreateTodo {
+//! #     type Dependency = Bound<dyn Clock>;
+//! #     fn resolve(clock: Self::Dependency) -> Self { CreateTodo { clock } }
+//! # }
+//! # let db = Db;
+//! let scope = TestScope::new()
+//!     .with_module(TodoModule::new(db))
+//!     .with_override::<dyn Clock>(Rc::new(FakeClock(42)))
+//!     .build();
+//!
+//! let handler: CreateTodo = scope.resolve();
+//! # assert_eq!(42, handler.clock.now());
+//! ```
+//!
+//! Overridden dependencies are resolved in place of any `Rc<T>` or
+//! `Bound<T>`. Everything else resolves normally.
+
+use std::rc::Rc;
+
+use container::{Module, Registry, Scoped};
+
+type Override = Box<dyn FnOnce(&Scoped)>;
+
+/// A builder for a scope with overridden dependencies.
+pub struct TestScope {
+    registry: Registry<Scoped>,
+    overrides: Vec<Override>,
+}
+
+impl Default for TestScope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestScope {
+    pub fn new() -> Self {
+        TestScope {
+            registry: Registry::new(),
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Add the bindings in a module to the scope's registry.
+    pub fn with_module<M>(mut self, module: M) -> Self
+        where M: Module<Scoped>
+    {
+        self.registry.add_module(module);
+
+        self
+    }
+
+    /// Resolve `value` in place of any `Rc<T>` or `Bound<T>` dependency.
+    ///
+    /// See `Scoped::override_with` for details.
+    pub fn with_override<T>(mut self, value: Rc<T>) -> Self
+        where T: ?Sized + 'static
+    {
+        self.overrides.push(Box::new(move |scope: &Scoped| {
+            scope.override_with(value);
+        }));
+
+        self
+    }
+
+    /// Create the scope.
+    pub fn build(self) -> Scoped {
+        let scope = Scoped::new().with_registry(Rc::new(self.registry));
+
+        for apply in self.overrides {
+            apply(&scope);
+        }
+
+        scope
+    }
+}
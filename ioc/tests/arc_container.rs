@@ -0,0 +1,86 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::sync::{Arc, Barrier, Mutex};
+use std::thread;
+
+#[derive(Default)]
+struct JobQueue {
+    jobs: Mutex<Vec<usize>>,
+}
+
+impl<C> Resolvable<C> for JobQueue {
+    type Dependency = ();
+
+    fn resolve(_: ()) -> Self {
+        JobQueue::default()
+    }
+}
+
+struct Scheduler {
+    queue: Arc<JobQueue>,
+}
+
+impl Resolvable<ArcContainer> for Scheduler {
+    type Dependency = Arc<JobQueue>;
+
+    fn resolve(queue: Self::Dependency) -> Self {
+        Scheduler { queue }
+    }
+}
+
+fn assert_send_sync<T: Send + Sync + Clone>() {}
+
+#[test]
+fn arc_container_is_a_cloneable_thread_safe_handle() {
+    assert_send_sync::<ArcContainer>();
+}
+
+#[test]
+fn clones_share_the_same_singletons() {
+    let container = ArcContainer::new();
+    let clone = container.clone();
+
+    let scheduler = Arc::<Scheduler>::resolve_from_container(&container);
+
+    assert!(Arc::ptr_eq(&scheduler, &Arc::<Scheduler>::resolve_from_container(&clone)));
+    assert!(Arc::ptr_eq(&scheduler.queue, &Arc::<JobQueue>::resolve_from_container(&clone)));
+
+    // separate containers have their own singletons
+    assert!(!Arc::ptr_eq(&scheduler, &Arc::<Scheduler>::resolve_from_container(&ArcContainer::new())));
+}
+
+#[test]
+fn threads_resolve_the_same_instances() {
+    let container = ArcContainer::new();
+    let barrier = Arc::new(Barrier::new(8));
+
+    let workers: Vec<_> = (0..8)
+        .map(|job| {
+            let container = container.clone();
+            let barrier = barrier.clone();
+
+            thread::spawn(move || {
+                // resolve at the same time, so some threads may construct their own queue
+                barrier.wait();
+
+                let scheduler = Arc::<Scheduler>::resolve_from_container(&container);
+                scheduler.queue.jobs.lock().unwrap().push(job);
+
+                scheduler
+            })
+        })
+        .collect();
+
+    let schedulers: Vec<_> = workers.into_iter().map(|worker| worker.join().unwrap()).collect();
+
+    for scheduler in &schedulers {
+        assert!(Arc::ptr_eq(&schedulers[0], scheduler));
+    }
+
+    let mut jobs = schedulers[0].queue.jobs.lock().unwrap().clone();
+    jobs.sort();
+
+    assert_eq!((0..8).collect::<Vec<_>>(), jobs);
+}
@@ -0,0 +1,115 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::{ArgsProvider, DependencyKind, Error, MemoryArgs};
+
+use std::rc::Rc;
+
+#[derive(Debug, PartialEq)]
+struct ImportArgs {
+    path: String,
+    dry_run: bool,
+}
+
+impl FromArgs for ImportArgs {
+    fn from_args(args: &[String]) -> Result<Self, String> {
+        let path = args.iter().find(|arg| !arg.starts_with("--")).ok_or("missing the file to import")?;
+
+        Ok(ImportArgs {
+            path: path.clone(),
+            dry_run: args.iter().any(|arg| arg == "--dry-run"),
+        })
+    }
+}
+
+struct ImportCommand {
+    args: Args<ImportArgs>,
+}
+
+impl<C> Resolvable<C> for ImportCommand
+    where C: RegisteredContainer + 'static
+{
+    type Dependency = Args<ImportArgs>;
+
+    fn resolve(args: Self::Dependency) -> Self {
+        ImportCommand { args }
+    }
+}
+
+fn scope(args: MemoryArgs) -> Scoped {
+    let args: Rc<dyn ArgsProvider> = Rc::new(args);
+
+    let mut registry = Registry::new();
+    registry.bind::<dyn ArgsProvider, _>(move |_| args.clone());
+
+    Scoped::new().with_registry(Rc::new(registry))
+}
+
+#[test]
+fn args_are_parsed_from_the_bound_provider() {
+    let command: ImportCommand = scope(MemoryArgs::new(vec!["--dry-run", "orders.csv"])).resolve();
+
+    assert_eq!("orders.csv", command.args.path);
+    assert!(command.args.dry_run);
+    assert_eq!(
+        ImportArgs { path: "orders.csv".into(), dry_run: true },
+        command.args.into_inner()
+    );
+}
+
+#[test]
+fn args_can_be_overridden() {
+    let scope = scope(MemoryArgs::new(vec!["orders.csv"]));
+    scope.override_with::<dyn ArgsProvider>(Rc::new(MemoryArgs::new(vec!["users.csv"])));
+
+    let args = Args::<ImportArgs>::resolve_from_container(&scope);
+
+    assert_eq!("users.csv", args.path);
+    assert!(!args.dry_run);
+}
+
+#[test]
+fn args_are_parsed_each_time_theyre_resolved() {
+    let scope = Scoped::new();
+
+    scope.override_with::<dyn ArgsProvider>(Rc::new(MemoryArgs::new(vec!["a"])));
+    let first = Args::<Vec<String>>::resolve_from_container(&scope);
+
+    scope.override_with::<dyn ArgsProvider>(Rc::new(MemoryArgs::new(vec!["b", "c"])));
+    let second = Args::<Vec<String>>::resolve_from_container(&scope);
+
+    assert_eq!(vec!["a"], *first);
+    assert_eq!(vec!["b", "c"], *second);
+    assert_eq!("Args([\"b\", \"c\"])", format!("{:?}", second));
+}
+
+#[test]
+fn args_are_read_from_the_process_without_a_provider() {
+    let args = Args::<Vec<String>>::resolve_from_container(&Scoped::new());
+
+    assert_eq!(std::env::args().skip(1).collect::<Vec<_>>(), *args);
+}
+
+#[test]
+fn invalid_args_are_an_error() {
+    match scope(MemoryArgs::new(vec!["--dry-run"])).try_resolve::<_, ImportCommand>() {
+        Err(err @ Error::InvalidArgs { .. }) => {
+            assert_eq!(
+                "the command-line arguments couldn't be parsed as `ImportArgs`: missing the file to import (required by ImportCommand -> Args<ImportArgs>)",
+                err.to_string()
+            );
+        }
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("expected invalid arguments"),
+    }
+}
+
+#[test]
+fn args_are_recorded_when_verifying() {
+    let graph = Scoped::new().dependency_graph::<_, ImportCommand>();
+
+    let kinds: Vec<_> = graph.nodes().iter().map(|node| node.kind).collect();
+
+    assert_eq!(vec![DependencyKind::Root, DependencyKind::Args], kinds);
+    assert_eq!(Ok(()), Scoped::new().verify::<_, ImportCommand>());
+}
@@ -0,0 +1,34 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+
+trait Store {}
+
+resolvable! {
+    struct Config;
+
+    struct Repository {
+        config: Rc<Config>,
+        store: Bound<dyn Store>,
+    }
+
+    struct App {
+        config: Transient<Config>,
+        repository: Rc<Repository>,
+    }
+}
+
+assert_resolvable!(Config, BasicContainer);
+assert_resolvable!(App, Scoped);
+
+#[test]
+fn resolvable_types_compile() {
+    // assertions can go in functions too
+    assert_resolvable!(Repository, Scoped);
+
+    // only the static graph is checked, so missing bindings aren't caught
+    assert!(Scoped::new().verify::<_, App>().is_err());
+}
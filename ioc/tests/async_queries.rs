@@ -0,0 +1,144 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::factories::{blocking, into_async, AsyncCommand, AsyncCommandOf, AsyncQuery, AsyncQueryOf};
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Product {
+    id: u32,
+}
+
+struct GetProduct {
+    id: u32,
+}
+
+struct DeleteProduct {
+    id: u32,
+}
+
+#[derive(Default)]
+struct Db {
+    deleted: RefCell<Vec<u32>>,
+}
+
+impl<C> Resolvable<C> for Db {
+    type Dependency = ();
+
+    fn resolve(_: ()) -> Self {
+        Db::default()
+    }
+}
+
+/// A future that's woken from another thread before it completes.
+struct Delayed<T> {
+    value: Option<T>,
+    waiting: bool,
+}
+
+fn delayed<T>(value: T) -> Delayed<T> {
+    Delayed { value: Some(value), waiting: false }
+}
+
+impl<T> Future for Delayed<T>
+    where T: Unpin
+{
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<T> {
+        if self.waiting {
+            return Poll::Ready(self.value.take().expect("the future was already complete"));
+        }
+
+        self.waiting = true;
+
+        let waker = cx.waker().clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            waker.wake();
+        });
+
+        Poll::Pending
+    }
+}
+
+fn get_product() -> impl AsyncQuery<GetProduct, Result<Product, String>> {
+    |action: GetProduct| delayed(Ok(Product { id: action.id }))
+}
+
+fn delete_product(db: Rc<Db>) -> impl AsyncCommand<DeleteProduct, Error = String> {
+    move |action: DeleteProduct| {
+        let result = if db.deleted.borrow().contains(&action.id) {
+            Err(format!("product {} was already deleted", action.id))
+        } else {
+            db.deleted.borrow_mut().push(action.id);
+
+            Ok(())
+        };
+
+        delayed(result)
+    }
+}
+
+fn scope() -> Scoped {
+    let mut registry = Registry::new();
+    registry
+        .bind_async_query(|_: ()| get_product())
+        .bind_async_command(|db: Rc<Db>| delete_product(db));
+
+    Scoped::new().with_registry(Rc::new(registry))
+}
+
+#[test]
+fn bound_async_queries_and_commands_resolve() {
+    let scope = scope();
+
+    let get_product: AsyncQueryOf<GetProduct, Result<Product, String>> = scope.resolve();
+
+    assert_eq!(Ok(Product { id: 1 }), blocking(get_product.clone()).query(GetProduct { id: 1 }));
+
+    let delete: AsyncCommandOf<DeleteProduct, String> = scope.resolve();
+    assert_eq!(Ok(()), blocking(delete).execute(DeleteProduct { id: 1 }));
+
+    let delete: AsyncCommandOf<DeleteProduct, String> = scope.resolve();
+    assert_eq!(Err("product 1 was already deleted".to_owned()), blocking(delete).execute(DeleteProduct { id: 1 }));
+
+    assert_eq!(vec![1], *scope.get_or_add::<Db, _>().deleted.borrow());
+}
+
+#[test]
+fn sync_queries_and_commands_can_be_async() {
+    let query = into_async(|action: GetProduct| Product { id: action.id });
+    let command = into_async(|_: DeleteProduct| Err::<(), _>("can't delete"));
+
+    assert_eq!(Product { id: 2 }, blocking(query).query(GetProduct { id: 2 }));
+    assert_eq!(Err("can't delete"), blocking(command).execute(DeleteProduct { id: 2 }));
+}
+
+#[test]
+fn sync_queries_can_be_bound_as_async_ones() {
+    let mut registry = Registry::new();
+    registry.bind_async_query(|_: ()| into_async(|action: GetProduct| Product { id: action.id }));
+
+    let scope = Scoped::new().with_registry(Rc::new(registry));
+
+    let get_product: AsyncQueryOf<GetProduct, Product> = scope.resolve();
+
+    assert_eq!(Product { id: 3 }, blocking(get_product).query(GetProduct { id: 3 }));
+}
+
+#[test]
+fn async_queries_are_verified() {
+    let scope = Scoped::new().with_registry(Rc::new(Registry::new()));
+
+    assert!(scope.verify::<_, AsyncQueryOf<GetProduct, Product>>().is_err());
+    assert!(scope.verify::<_, AsyncQueryOf<GetProduct, Result<Product, String>>>().is_err());
+    assert!(self::scope().verify::<_, AsyncQueryOf<GetProduct, Result<Product, String>>>().is_ok());
+}
@@ -0,0 +1,140 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+struct Connection {
+    id: usize,
+}
+
+impl<C> Resolvable<C> for Connection {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Connection { id: 1 }
+    }
+}
+
+struct Repository<'scope> {
+    connection: B<'scope, Connection>,
+}
+
+impl<'scope> Resolvable<Borrowed<'scope>> for Repository<'scope> {
+    type Dependency = B<'scope, Connection>;
+
+    fn resolve(connection: Self::Dependency) -> Self {
+        Repository { connection }
+    }
+}
+
+struct Handler<'scope> {
+    repository: Repository<'scope>,
+    connection: B<'scope, Connection>,
+}
+
+impl<'scope> Resolvable<Borrowed<'scope>> for Handler<'scope> {
+    type Dependency = (Transient<Repository<'scope>>, B<'scope, Connection>);
+
+    fn resolve((repository, connection): Self::Dependency) -> Self {
+        Handler { repository: repository.into_inner(), connection }
+    }
+}
+
+#[test]
+fn borrowed_scope_shares_dependencies() {
+    BasicContainer.borrow_scope(|scope| {
+        let handler = scope.resolve::<_, Handler>();
+
+        assert!(std::ptr::eq(handler.connection.get(), handler.repository.connection.get()));
+        assert!(std::ptr::eq(handler.connection.get(), scope.get_or_add_ref::<Connection, ()>()));
+        assert_eq!(1, handler.connection.id);
+    });
+}
+
+#[test]
+fn borrowed_scopes_dont_share_with_each_other() {
+    let outer = BasicContainer.borrow_scope(|outer| {
+        let a = outer.get_or_add_ref::<Connection, ()>() as *const Connection;
+
+        let b = BasicContainer.borrow_scope(|inner| inner.get_or_add_ref::<Connection, ()>() as *const Connection);
+
+        a != b
+    });
+
+    assert!(outer);
+}
+
+#[derive(Clone, Copy)]
+#[repr(align(64))]
+struct Aligned([u8; 64]);
+
+impl<C> Resolvable<C> for Aligned {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Aligned([7; 64])
+    }
+}
+
+struct Large([u64; 512]);
+
+impl<C> Resolvable<C> for Large {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Large([9; 512])
+    }
+}
+
+struct Empty;
+
+impl<C> Resolvable<C> for Empty {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Empty
+    }
+}
+
+#[test]
+fn borrowed_scope_stores_values_of_any_layout() {
+    BasicContainer.borrow_scope(|scope| {
+        let small = B::<Connection>::resolve_from_container(&scope);
+        let aligned = B::<Aligned>::resolve_from_container(&scope);
+        let large = B::<Large>::resolve_from_container(&scope);
+        let empty = B::<Empty>::resolve_from_container(&scope);
+
+        assert_eq!(0, aligned.get() as *const Aligned as usize % 64);
+        assert_eq!([7; 64], aligned.0);
+        assert!(large.0.iter().all(|&n| n == 9));
+        assert!(std::ptr::eq(empty.get(), scope.get_or_add_ref::<Empty, ()>()));
+        assert_eq!(1, small.id);
+    });
+}
+
+#[test]
+fn borrowed_values_can_be_copied_and_converted() {
+    BasicContainer.borrow_scope(|scope| {
+        let connection = B::<Connection>::resolve_from_container(&scope);
+        let copied = connection;
+
+        let reference: &Connection = copied.into_inner();
+
+        assert!(std::ptr::eq(reference, connection.as_ref()));
+    });
+}
+
+#[test]
+fn borrowed_values_outlive_their_b() {
+    BasicContainer.borrow_scope(|scope| {
+        let reference = {
+            let connection = B::<Connection>::resolve_from_container(&scope);
+
+            connection.get()
+        };
+
+        #[allow(deprecated)]
+        let value = B::<Connection>::resolve_from_container(&scope).value();
+
+        assert!(std::ptr::eq(reference, value));
+    });
+}
@@ -0,0 +1,89 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+struct Connection {
+    dropped: Rc<Cell<bool>>,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.dropped.set(true);
+    }
+}
+
+thread_local!(static DROPPED: Rc<Cell<bool>> = Rc::new(Cell::new(false)));
+
+impl<C> Resolvable<C> for Connection {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Connection { dropped: DROPPED.with(Rc::clone) }
+    }
+}
+
+struct Repository<'scope> {
+    connection: Shared<'scope, Connection>,
+}
+
+impl<'scope> Resolvable<BrandedScope<'scope>> for Repository<'scope> {
+    type Dependency = Shared<'scope, Connection>;
+
+    fn resolve(connection: Self::Dependency) -> Self {
+        Repository { connection }
+    }
+}
+
+struct Handler<'scope> {
+    repository: Transient<Repository<'scope>>,
+    connection: Shared<'scope, Connection>,
+}
+
+impl<'scope> Resolvable<BrandedScope<'scope>> for Handler<'scope> {
+    type Dependency = (Transient<Repository<'scope>>, Shared<'scope, Connection>);
+
+    fn resolve((repository, connection): Self::Dependency) -> Self {
+        Handler { repository, connection }
+    }
+}
+
+#[test]
+fn branded_scope_shares_dependencies() {
+    Scoped::new().brand(|scope| {
+        let handler = scope.resolve::<_, Handler>();
+
+        assert!(Shared::ptr_eq(&handler.connection, &handler.repository.connection));
+        assert!(Shared::ptr_eq(&handler.connection, &Shared::<Connection>::resolve_from_container(&scope)));
+    });
+}
+
+#[test]
+fn branded_scopes_dont_share_with_each_other() {
+    Scoped::new().brand(|outer| {
+        let a = outer.resolve::<_, Handler>();
+
+        Scoped::new().brand(|inner| {
+            let b = inner.resolve::<_, Handler>();
+
+            assert!(!std::ptr::eq(&*a.connection, &*b.connection));
+        });
+    });
+}
+
+#[test]
+fn branded_scope_drops_its_dependencies_when_the_closure_returns() {
+    let dropped = DROPPED.with(Rc::clone);
+    dropped.set(false);
+
+    let returned = Scoped::new().brand(|scope| {
+        scope.resolve::<_, Handler>();
+
+        dropped.get()
+    });
+
+    assert!(!returned);
+    assert!(dropped.get());
+}
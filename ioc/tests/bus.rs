@@ -0,0 +1,213 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::{Behavior, CommandBus, Dispatch, DispatchError, Failure, Next, Retry, Veto};
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+#[derive(Default)]
+struct Events {
+    events: RefCell<Vec<String>>,
+    // how many more times the handler fails
+    failures: Cell<usize>,
+}
+
+resolvable! {
+    default Events;
+}
+
+impl Events {
+    fn push(&self, event: &str) {
+        self.events.borrow_mut().push(event.to_owned());
+    }
+
+    fn take(&self) -> Vec<String> {
+        self.events.borrow_mut().drain(..).collect()
+    }
+}
+
+#[derive(Clone)]
+struct SetTitle {
+    title: &'static str,
+}
+
+struct SetTitleHandler {
+    events: Rc<Events>,
+}
+
+impl Resolvable<Scoped> for SetTitleHandler {
+    type Dependency = Rc<Events>;
+
+    fn resolve(events: Self::Dependency) -> Self {
+        SetTitleHandler { events }
+    }
+}
+
+impl Command<SetTitle> for SetTitleHandler {
+    type Error = String;
+
+    fn execute(self, request: SetTitle) -> Result<(), String> {
+        self.events.push(&format!("handle {}", request.title));
+
+        match self.events.failures.get() {
+            0 => Ok(()),
+            n => {
+                self.events.failures.set(n - 1);
+                Err("the database is busy".to_owned())
+            }
+        }
+    }
+}
+
+struct Around {
+    events: Rc<Events>,
+}
+
+impl Resolvable<Scoped> for Around {
+    type Dependency = Rc<Events>;
+
+    fn resolve(events: Self::Dependency) -> Self {
+        events.push("resolve behavior");
+
+        Around { events }
+    }
+}
+
+impl Behavior for Around {
+    fn handle(&self, _: &Dispatch, next: Next) -> Result<(), Failure> {
+        self.events.push("behavior>");
+        let result = next.run();
+        self.events.push("<behavior");
+
+        result
+    }
+}
+
+fn around(events: &Rc<Events>, name: &'static str) -> impl Fn(&Dispatch, Next) -> Result<(), Failure> {
+    let events = events.clone();
+
+    move |_: &Dispatch, next: Next| {
+        events.push(&format!("{}>", name));
+        let result = next.run();
+        events.push(&format!("<{}", name));
+
+        result
+    }
+}
+
+fn bus() -> CommandBus<Scoped> {
+    let mut bus = CommandBus::new();
+    bus.handle::<SetTitle, SetTitleHandler, _>();
+
+    bus
+}
+
+#[test]
+fn pipeline_runs_in_the_order_it_was_added() {
+    let scope = Scoped::new();
+    let events = scope.get_or_add::<Events, _>();
+
+    let mut bus = bus();
+    bus.with(around(&events, "outer"))
+        .behavior::<Around, _>()
+        .with(around(&events, "inner"));
+
+    let result: Result<(), DispatchError<String>> = bus.dispatch(&scope, SetTitle { title: "a" });
+
+    assert!(result.is_ok());
+    assert_eq!(
+        vec!["outer>", "resolve behavior", "behavior>", "inner>", "handle a", "<inner", "<behavior", "<outer"],
+        events.take()
+    );
+}
+
+#[test]
+fn retry_retries_failed_handlers() {
+    let scope = Scoped::new();
+    let events = scope.get_or_add::<Events, _>();
+    events.failures.set(2);
+
+    let mut bus = bus();
+    bus.with(Retry::new(3));
+
+    let result: Result<(), DispatchError<String>> = bus.dispatch(&scope, SetTitle { title: "a" });
+
+    assert!(result.is_ok());
+    assert_eq!(vec!["handle a", "handle a", "handle a"], events.take());
+}
+
+#[test]
+fn retry_gives_up_after_its_attempts() {
+    let scope = Scoped::new();
+    let events = scope.get_or_add::<Events, _>();
+    events.failures.set(5);
+
+    let mut bus = bus();
+    bus.with(Retry::new(2));
+
+    match bus.dispatch::<_, String>(&scope, SetTitle { title: "a" }) {
+        Err(DispatchError::Failed { error, .. }) => assert_eq!("the database is busy", error),
+        result => panic!("expected a failure, got {:?}", result),
+    }
+    assert_eq!(2, events.take().len());
+}
+
+#[test]
+fn retry_doesnt_retry_rejected_commands() {
+    let scope = Scoped::new();
+    let events = scope.get_or_add::<Events, _>();
+
+    let validated = Rc::new(Cell::new(0));
+
+    let mut bus = bus();
+    let counted = validated.clone();
+    bus.with(Retry::new(3)).validate(move |command: &SetTitle| {
+        counted.set(counted.get() + 1);
+
+        match command.title.is_empty() {
+            true => Err(Veto::new("titles can't be empty")),
+            false => Ok(()),
+        }
+    });
+
+    match bus.dispatch::<_, String>(&scope, SetTitle { title: "" }) {
+        Err(DispatchError::Rejected { veto, .. }) => assert_eq!("titles can't be empty", veto.reason()),
+        result => panic!("expected a rejection, got {:?}", result),
+    }
+    assert_eq!(1, validated.get());
+    assert!(events.take().is_empty());
+}
+
+#[test]
+fn behaviors_are_resolved_for_each_run() {
+    let scope = Scoped::new();
+    let events = scope.get_or_add::<Events, _>();
+    events.failures.set(1);
+
+    let mut bus = bus();
+    bus.with(Retry::new(2)).behavior::<Around, _>();
+
+    let result: Result<(), DispatchError<String>> = bus.dispatch(&scope, SetTitle { title: "a" });
+    assert!(result.is_ok());
+
+    let resolved = events.take().iter().filter(|event| *event == "resolve behavior").count();
+    assert_eq!(2, resolved);
+}
+
+#[test]
+fn unhandled_commands_are_errors() {
+    let result: Result<(), DispatchError<String>> = CommandBus::new().dispatch(&Scoped::new(), SetTitle { title: "a" });
+
+    match result {
+        Err(DispatchError::Unhandled { command }) => assert_eq!(std::any::type_name::<SetTitle>(), command),
+        result => panic!("expected an unhandled command, got {:?}", result),
+    }
+}
+
+#[test]
+#[should_panic(expected = "returns `alloc::string::String` errors, not `()`")]
+fn dispatching_with_the_wrong_error_type_panics() {
+    let _ = bus().dispatch::<_, ()>(&Scoped::new(), SetTitle { title: "a" });
+}
@@ -0,0 +1,77 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+
+#[derive(Default)]
+struct Db;
+
+resolvable! {
+    default Db;
+}
+
+struct Repository {
+    db: Rc<Db>,
+}
+
+impl<C> Resolvable<C> for Repository
+    where C: ScopedContainer
+{
+    type Dependency = Rc<Db>;
+
+    fn resolve(db: Self::Dependency) -> Self {
+        Repository { db }
+    }
+}
+
+#[derive(Default)]
+struct Outside;
+
+resolvable! {
+    default Outside;
+}
+
+closed_scope! {
+    struct RequestScope {
+        db: Db,
+        repository: Repository,
+    }
+}
+
+#[test]
+fn each_slot_holds_one_instance() {
+    let scope = RequestScope::new();
+
+    let repository = scope.get_or_add::<Repository, _>();
+
+    assert!(Rc::ptr_eq(&repository, &scope.get_or_add::<Repository, _>()));
+    assert!(Rc::ptr_eq(&repository.db, &scope.get_or_add::<Db, _>()));
+}
+
+#[test]
+fn scopes_have_their_own_instances() {
+    let first = RequestScope::new();
+    let second = RequestScope::new();
+
+    assert!(!Rc::ptr_eq(&first.get_or_add::<Db, _>(), &second.get_or_add::<Db, _>()));
+}
+
+#[test]
+fn weak_refers_to_the_slot_once_its_filled() {
+    let scope = RequestScope::new();
+
+    assert!(scope.get_weak::<Db>().upgrade().is_none());
+    assert!(scope.get_weak::<Outside>().upgrade().is_none());
+
+    let db = scope.get_or_add::<Db, _>();
+
+    assert!(Rc::ptr_eq(&db, &scope.get_weak::<Db>().upgrade().unwrap()));
+}
+
+#[test]
+#[should_panic(expected = "isn't part of the closed scope `RequestScope`")]
+fn types_outside_the_set_panic() {
+    RequestScope::new().get_or_add::<Outside, _>();
+}
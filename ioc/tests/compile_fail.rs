@@ -0,0 +1,11 @@
+extern crate trybuild;
+
+// Each case is a misuse of the API that must not compile. The expected
+// errors are in the `.stderr` file next to each case, and can be updated
+// by running the tests with `TRYBUILD=overwrite`.
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+
+    t.compile_fail("tests/compile_fail/*.rs");
+}
@@ -0,0 +1,29 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+
+struct X;
+
+impl<C> Resolvable<C> for X {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        X
+    }
+}
+
+resolvable! {
+    struct Y {
+        x: Rc<X>,
+    }
+}
+
+// `Rc<X>` is shared from a scope, which a `BasicContainer` doesn't have
+assert_resolvable!(X, BasicContainer);
+assert_resolvable!(Y, Scoped);
+assert_resolvable!(Y, BasicContainer);
+
+fn main() {}
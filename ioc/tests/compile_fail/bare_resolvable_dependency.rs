@@ -0,0 +1,31 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+
+struct X;
+
+impl<C> Resolvable<C> for X {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        X
+    }
+}
+
+struct Y;
+
+// `X` is `Resolvable`, but it needs a root dependency like `Rc<X>` to be
+// resolved from the container
+impl<C> Resolvable<C> for Y {
+    type Dependency = (Rc<X>, X);
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Y
+    }
+}
+
+fn main() {
+    let _: Y = Scoped::new().resolve();
+}
@@ -0,0 +1,26 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+struct X;
+impl<C> Resolvable<C> for X {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        X
+    }
+}
+
+fn same_scope<'scope>(_: B<'scope, X>, _: B<'scope, X>) {}
+
+fn main() {
+    BasicContainer.borrow_scope(|outer| {
+        let a = B::<X>::resolve_from_container(&outer);
+
+        BasicContainer.borrow_scope(|inner| {
+            let b = B::<X>::resolve_from_container(&inner);
+
+            same_scope(a, b);
+        });
+    });
+}
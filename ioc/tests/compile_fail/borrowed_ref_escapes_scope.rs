@@ -0,0 +1,16 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+struct X;
+impl<C> Resolvable<C> for X {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        X
+    }
+}
+
+fn main() {
+    let _x: &X = BasicContainer.borrow_scope(|scope| scope.get_or_add_ref::<X, ()>());
+}
@@ -0,0 +1,26 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+struct X;
+impl<C> Resolvable<C> for X {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        X
+    }
+}
+
+fn same_scope<'scope>(_: Shared<'scope, X>, _: Shared<'scope, X>) {}
+
+fn main() {
+    Scoped::new().brand(|outer| {
+        let a = Shared::<X>::resolve_from_container(&outer);
+
+        Scoped::new().brand(|inner| {
+            let b = Shared::<X>::resolve_from_container(&inner);
+
+            same_scope(a, b);
+        });
+    });
+}
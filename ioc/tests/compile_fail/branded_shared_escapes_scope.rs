@@ -0,0 +1,16 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+struct X;
+impl<C> Resolvable<C> for X {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        X
+    }
+}
+
+fn main() {
+    let _x = Scoped::new().brand(|scope| Shared::<X>::resolve_from_container(&scope));
+}
@@ -0,0 +1,37 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::RefCell;
+
+struct X;
+impl<C> Resolvable<C> for X {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        X
+    }
+}
+
+// a plain `String` isn't a dependency the container knows how to resolve
+struct Z {
+    x: X,
+    name: String,
+}
+
+impl<C> Resolvable<C> for Z {
+    type Dependency = (RefCell<X>, String);
+
+    fn resolve((x, name): Self::Dependency) -> Self {
+        Z {
+            x: x.into_inner(),
+            name,
+        }
+    }
+}
+
+fn main() {
+    let z: Z = BasicContainer.resolve();
+
+    println!("{}", z.name);
+}
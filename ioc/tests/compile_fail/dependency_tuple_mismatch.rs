@@ -0,0 +1,43 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::RefCell;
+
+struct X;
+impl<C> Resolvable<C> for X {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        X
+    }
+}
+
+struct Y;
+impl<C> Resolvable<C> for Y {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Y
+    }
+}
+
+struct Z {
+    x: X,
+    y: Y,
+}
+
+impl<C> Resolvable<C> for Z {
+    type Dependency = (RefCell<X>, RefCell<Y>);
+
+    fn resolve((y, x): (RefCell<Y>, RefCell<X>)) -> Self {
+        Z {
+            x: x.into_inner(),
+            y: y.into_inner(),
+        }
+    }
+}
+
+fn main() {
+    let _z: Z = BasicContainer.resolve();
+}
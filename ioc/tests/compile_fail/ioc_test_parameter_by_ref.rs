@@ -0,0 +1,21 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+
+struct X;
+impl<C> Resolvable<C> for X {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        X
+    }
+}
+
+#[ioc_test]
+fn by_ref(ref x: Rc<X>) {
+    let _ = x;
+}
+
+fn main() {}
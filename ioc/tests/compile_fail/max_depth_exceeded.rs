@@ -0,0 +1,40 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::RefCell;
+
+#[derive(Default)]
+struct Config;
+struct Store;
+struct App;
+
+resolvable! {
+    default Config;
+}
+
+impl<C> Resolvable<C> for Store
+    where C: Container
+{
+    type Dependency = RefCell<Config>;
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Store
+    }
+}
+
+impl<C> Resolvable<C> for App
+    where C: Container
+{
+    type Dependency = RefCell<Store>;
+
+    fn resolve(_: Self::Dependency) -> Self {
+        App
+    }
+}
+
+// `App` -> `Store` -> `Config` -> `()` is 3 levels deep
+max_depth!(BasicContainer, App, 2);
+
+fn main() {}
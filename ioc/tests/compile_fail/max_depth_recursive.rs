@@ -0,0 +1,23 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::RefCell;
+
+// a node that depends on a new node, which depends on a new node, ...
+struct Node;
+
+impl<C> Resolvable<C> for Node
+    where C: Container
+{
+    type Dependency = RefCell<Node>;
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Node
+    }
+}
+
+max_depth!(BasicContainer, Node, 16);
+
+fn main() {}
@@ -0,0 +1,46 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+// `Cell` makes `'a` invariant, so a `Borrows<'static>` can't stand in for it
+struct Borrows<'a> {
+    value: Cell<&'a str>,
+}
+
+impl<'a, C> Resolvable<C> for Borrows<'a> {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Borrows { value: Cell::new("") }
+    }
+}
+
+struct Holder<'a> {
+    borrows: Rc<Borrows<'a>>,
+}
+
+impl<'a> Resolvable<Scoped> for Holder<'a> {
+    type Dependency = Rc<Borrows<'a>>;
+
+    fn resolve(borrows: Self::Dependency) -> Self {
+        Holder { borrows }
+    }
+}
+
+// `Scoped` can only share `'static` values
+fn resolve<'a>(value: &'a str) -> Holder<'a> {
+    let holder: Holder<'a> = Scoped::new().resolve();
+    holder.borrows.value.set(value);
+
+    holder
+}
+
+fn main() {
+    let value = String::from("value");
+    let holder = resolve(&value);
+
+    println!("{}", holder.borrows.value.get());
+}
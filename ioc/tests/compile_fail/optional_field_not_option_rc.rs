@@ -0,0 +1,15 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+
+trait AuditLog {}
+
+#[derive(Resolvable)]
+struct Checkout {
+    #[inject(optional)]
+    audit: Rc<dyn AuditLog>,
+}
+
+fn main() {}
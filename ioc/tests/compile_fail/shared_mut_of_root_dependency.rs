@@ -0,0 +1,28 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+
+struct X;
+
+impl<C> Resolvable<C> for X {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        X
+    }
+}
+
+// `Rc<X>` is a root dependency rather than a `Resolvable` type, so it
+// can't be shared mutably a second time
+resolvable! {
+    struct Holder {
+        x: SharedMut<Rc<X>>,
+    }
+}
+
+fn main() {
+    let _: Holder = Scoped::new().resolve();
+}
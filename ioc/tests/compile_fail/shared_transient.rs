@@ -0,0 +1,27 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+
+struct X;
+
+impl<C> Resolvable<C> for X {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        X
+    }
+}
+
+// a `Transient<X>` is a new instance each time, so it can't be shared
+resolvable! {
+    struct Holder {
+        x: Rc<Transient<X>>,
+    }
+}
+
+fn main() {
+    let _: Holder = Scoped::new().resolve();
+}
@@ -0,0 +1,15 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+
+// `()` is already a root dependency, so there's nothing to make transient
+resolvable! {
+    struct Holder {
+        unit: Transient<()>,
+    }
+}
+
+fn main() {
+    let _: Holder = Scoped::new().resolve();
+}
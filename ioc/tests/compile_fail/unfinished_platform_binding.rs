@@ -0,0 +1,22 @@
+#![deny(unused_must_use)]
+
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+
+trait KeyStore {}
+
+struct FileKeyStore;
+
+impl KeyStore for FileKeyStore {}
+
+fn main() {
+    let mut registry = Registry::<Scoped>::new();
+
+    // without `otherwise` or `finish` nothing is bound
+    registry
+        .bind_platform::<dyn KeyStore>()
+        .on_os("linux", |_| Rc::new(FileKeyStore));
+}
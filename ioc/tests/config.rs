@@ -0,0 +1,130 @@
+#![cfg(feature = "config")]
+
+extern crate ioc;
+#[macro_use]
+extern crate serde;
+
+use ioc::{Config, ConfigError, ConfigSection, ConfigSource, EnvSource, MemorySource, TomlSource};
+use ioc::prelude::*;
+
+use std::rc::Rc;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct DbSettings {
+    host: String,
+    port: u16,
+    #[serde(default)]
+    pool_size: usize,
+}
+
+impl ConfigSection for DbSettings {
+    const SECTION: &'static str = "db";
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct ReplicaSettings {
+    host: String,
+}
+
+impl ConfigSection for ReplicaSettings {
+    const SECTION: &'static str = "db.replica";
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+struct CacheSettings {
+    #[serde(default)]
+    ttl: u64,
+}
+
+impl ConfigSection for CacheSettings {
+    const SECTION: &'static str = "cache";
+}
+
+struct Db {
+    settings: Config<DbSettings>,
+}
+
+impl<C> Resolvable<C> for Db
+    where C: RegisteredContainer + 'static
+{
+    type Dependency = Config<DbSettings>;
+
+    fn resolve(settings: Self::Dependency) -> Self {
+        Db { settings }
+    }
+}
+
+fn scope(registry: Registry<Scoped>) -> Scoped {
+    Scoped::new().with_registry(Rc::new(registry))
+}
+
+#[test]
+fn config_reads_a_section() {
+    let mut registry = Registry::new();
+    registry.add_config_source(TomlSource::parse("[db]\nhost = \"localhost\"\nport = 5432\n").unwrap());
+
+    let db: Db = scope(registry).resolve();
+
+    assert_eq!(DbSettings { host: "localhost".into(), port: 5432, pool_size: 0 }, *db.settings);
+}
+
+#[test]
+fn config_reads_a_nested_section() {
+    let mut registry = Registry::new();
+    registry.add_config_source(TomlSource::parse("[db.replica]\nhost = \"replica\"\n").unwrap());
+
+    let replica = Config::<ReplicaSettings>::resolve_from_container(&scope(registry));
+
+    assert_eq!("replica", replica.host);
+}
+
+#[test]
+fn later_sources_override_earlier_ones() {
+    let mut registry = Registry::new();
+    registry
+        .add_config_source(MemorySource::new().with("db.host", "localhost").with("db.port", 5432).with("db.pool_size", 4))
+        .add_config_source(TomlSource::parse("[db]\nport = 6543\n").unwrap())
+        .add_config_source(EnvSource::from_vars("APP_", vec![("APP_DB__HOST", "db.internal"), ("OTHER_DB__PORT", "1")]));
+
+    let db: Db = scope(registry).resolve();
+
+    assert_eq!(DbSettings { host: "db.internal".into(), port: 6543, pool_size: 4 }, *db.settings);
+}
+
+#[test]
+fn env_source_parses_toml_values() {
+    let sources: Vec<Rc<dyn ConfigSource>> = vec![Rc::new(EnvSource::from_vars("APP_", vec![("APP_DB__HOST", "localhost"), ("APP_DB__PORT", "5432")]))];
+
+    let settings = Config::<DbSettings>::load(&sources).unwrap();
+
+    assert_eq!(5432, settings.port);
+    assert_eq!("localhost", settings.host);
+}
+
+#[test]
+fn missing_section_is_empty() {
+    let settings = Config::<CacheSettings>::resolve_from_container(&scope(Registry::new()));
+
+    assert_eq!(CacheSettings::default(), settings.into_inner());
+}
+
+#[test]
+fn invalid_section_is_an_error() {
+    let sources: Vec<Rc<dyn ConfigSource>> = vec![Rc::new(MemorySource::new().with("db.host", "localhost"))];
+
+    assert!(Config::<DbSettings>::load(&sources).is_err());
+}
+
+#[test]
+#[should_panic(expected = "the `db` config section couldn't be read")]
+fn resolving_an_invalid_section_panics() {
+    let _ = Config::<DbSettings>::resolve_from_container(&scope(Registry::new()));
+}
+
+#[test]
+fn invalid_toml_is_an_error() {
+    match TomlSource::parse("[db") {
+        Err(ConfigError::Toml(_)) => (),
+        _ => panic!("expected a TOML error"),
+    }
+}
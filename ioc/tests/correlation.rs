@@ -0,0 +1,80 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::{CorrelationIdProvider, RandomCorrelationIds};
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+struct Job {
+    id: CorrelationId,
+}
+
+impl<C> Resolvable<C> for Job
+    where C: CorrelatedContainer
+{
+    type Dependency = CorrelationId;
+
+    fn resolve(id: Self::Dependency) -> Self {
+        Job { id }
+    }
+}
+
+#[test]
+fn everything_in_a_scope_shares_its_correlation_id() {
+    let scope = Scoped::new();
+
+    let a = scope.resolve::<_, Job>();
+    let b = scope.resolve::<_, Job>();
+
+    assert_eq!(a.id, b.id);
+    assert_eq!(scope.correlation_id(), a.id);
+}
+
+#[test]
+fn each_scope_has_its_own_correlation_id() {
+    let a = Scoped::new().resolve::<_, Job>();
+    let b = Scoped::new().resolve::<_, Job>();
+
+    assert_ne!(a.id, b.id);
+}
+
+#[test]
+fn scope_can_use_a_specific_correlation_id() {
+    let scope = Scoped::new().with_correlation_id(CorrelationId::new("req-1"));
+
+    assert_eq!("req-1", scope.resolve::<_, Job>().id.as_str());
+}
+
+#[test]
+fn scope_generates_its_correlation_id_with_its_provider() {
+    let generated = Rc::new(Cell::new(0));
+
+    let provider = {
+        let generated = generated.clone();
+
+        move || {
+            generated.set(generated.get() + 1);
+
+            CorrelationId::new(format!("job-{}", generated.get()))
+        }
+    };
+
+    let scope = Scoped::new().with_correlation_ids(Rc::new(provider));
+
+    // the id isn't generated until it's needed, and then only once
+    assert_eq!(0, generated.get());
+    assert_eq!("job-1", scope.resolve::<_, Job>().id.as_str());
+    assert_eq!("job-1", scope.resolve::<_, Job>().id.as_str());
+    assert_eq!(1, generated.get());
+}
+
+#[test]
+fn random_correlation_ids_look_like_v4_uuids() {
+    let id = RandomCorrelationIds.next_id();
+    let parts: Vec<_> = id.as_str().split('-').map(str::len).collect();
+
+    assert_eq!(vec![8, 4, 4, 4, 12], parts);
+    assert_eq!(Some('4'), id.as_str().chars().nth(14));
+    assert_ne!(id, RandomCorrelationIds.next_id());
+}
@@ -0,0 +1,140 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+use std::time::Duration;
+
+#[derive(Debug, Default, PartialEq)]
+struct Settings {
+    retries: usize,
+}
+
+resolvable! {
+    default Settings;
+}
+
+struct AppContainer;
+impl Container for AppContainer {}
+
+// downstream crates can implement `Resolvable` for types from other crates
+// as long as the container is their own
+impl Resolvable<AppContainer> for Duration {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Duration::from_secs(5)
+    }
+}
+
+#[test]
+fn default_types_resolve_as_their_default() {
+    let settings: Settings = BasicContainer.resolve();
+
+    assert_eq!(Settings::default(), settings);
+}
+
+#[test]
+fn foreign_types_can_be_resolvable() {
+    let timeout: Duration = AppContainer.resolve();
+
+    assert_eq!(Duration::from_secs(5), timeout);
+}
+
+trait AuditLog {
+    fn name(&self) -> &'static str;
+}
+
+struct FileAudit;
+impl AuditLog for FileAudit {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+}
+
+resolvable! {
+    #[derive(Debug)]
+    struct Orders;
+
+    pub(crate) struct Checkout {
+        pub(crate) orders: Rc<Orders>,
+        settings: Transient<Settings>,
+        audit: Option<Bound<dyn AuditLog>>,
+    }
+}
+
+#[test]
+fn declared_structs_resolve_their_fields() {
+    let scope = Scoped::new();
+
+    let checkout: Checkout = scope.resolve();
+
+    assert!(Rc::ptr_eq(&checkout.orders, &scope.get_or_add::<Orders, _>()));
+    assert_eq!(Settings::default(), *checkout.settings);
+    assert!(checkout.audit.is_none());
+}
+
+#[test]
+fn declared_structs_keep_their_attributes() {
+    let orders: Orders = BasicContainer.resolve();
+
+    assert_eq!("Orders", format!("{:?}", orders));
+}
+
+#[test]
+fn declared_optional_fields_use_bindings_when_theres_one() {
+    let mut registry = Registry::new();
+    registry.bind::<dyn AuditLog, _>(|_| Rc::new(FileAudit));
+
+    let checkout: Checkout = Scoped::new().with_registry(Rc::new(registry)).resolve();
+
+    assert_eq!(Some("file"), checkout.audit.as_ref().map(|audit| audit.name()));
+}
+
+struct Db {
+    settings: Rc<Settings>,
+}
+
+struct Repository {
+    db: Db,
+    name: &'static str,
+}
+
+struct Clock;
+
+fn connect(settings: Rc<Settings>) -> Db {
+    Db { settings }
+}
+
+mod repositories {
+    use ioc::prelude::*;
+
+    use super::{Db, Repository};
+
+    pub fn orders(db: Transient<Db>) -> Repository {
+        Repository { db: db.into_inner(), name: "orders" }
+    }
+}
+
+fn clock() -> Clock {
+    Clock
+}
+
+provider! {
+    fn connect(settings: Rc<Settings>) -> Db;
+    fn repositories::orders(db: Transient<Db>,) -> Repository;
+    fn clock() -> Clock;
+}
+
+#[test]
+fn provided_types_resolve_with_their_function() {
+    let scope = Scoped::new();
+
+    let repository: Repository = scope.resolve();
+
+    assert_eq!("orders", repository.name);
+    assert!(Rc::ptr_eq(&repository.db.settings, &scope.get_or_add::<Settings, _>()));
+
+    let _: Clock = BasicContainer.resolve();
+}
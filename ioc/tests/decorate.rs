@@ -0,0 +1,101 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+
+trait Repo {
+    fn describe(&self) -> String;
+}
+
+struct SqlRepo(&'static str);
+
+impl Repo for SqlRepo {
+    fn describe(&self) -> String {
+        self.0.to_owned()
+    }
+}
+
+struct Wrapped(&'static str, Rc<dyn Repo>);
+
+impl Repo for Wrapped {
+    fn describe(&self) -> String {
+        format!("{}({})", self.0, self.1.describe())
+    }
+}
+
+fn decorated(mut registry: Registry<Scoped>) -> Scoped {
+    registry
+        .decorate::<dyn Repo, _>(|inner| Rc::new(Wrapped("caching", inner)))
+        .decorate::<dyn Repo, _>(|inner| Rc::new(Wrapped("logging", inner)));
+
+    Scoped::new().with_registry(Rc::new(registry))
+}
+
+#[test]
+fn decorators_wrap_the_binding_in_order() {
+    let mut registry = Registry::new();
+    registry.bind::<dyn Repo, _>(|_| Rc::new(SqlRepo("sql")));
+
+    let repo = Bound::<dyn Repo>::resolve_from_container(&decorated(registry));
+
+    assert_eq!("logging(caching(sql))", repo.describe());
+}
+
+#[test]
+fn decorators_wrap_each_multi_binding() {
+    let mut registry = Registry::new();
+    registry
+        .bind_all::<dyn Repo, _>(|_| Rc::new(SqlRepo("a")))
+        .bind_all::<dyn Repo, _>(|_| Rc::new(SqlRepo("b")))
+        .bind_keyed::<&'static str, dyn Repo, _>("c", |_| Rc::new(SqlRepo("c")));
+
+    let scope = decorated(registry);
+
+    let all = All::<dyn Repo>::resolve_from_container(&scope);
+    let keyed = KeyedAll::<&'static str, dyn Repo>::resolve_from_container(&scope);
+
+    assert_eq!(vec!["logging(caching(a))", "logging(caching(b))"],
+               all.iter().map(|repo| repo.describe()).collect::<Vec<_>>());
+    assert_eq!("logging(caching(c))", keyed["c"].describe());
+}
+
+#[test]
+fn decorators_only_wrap_their_own_type() {
+    trait Other {}
+    struct OtherImpl;
+    impl Other for OtherImpl {}
+
+    let mut registry = Registry::new();
+    registry
+        .bind::<dyn Repo, _>(|_| Rc::new(SqlRepo("sql")))
+        .bind::<dyn Other, _>(|_| Rc::new(OtherImpl))
+        .decorate::<dyn Other, _>(|_| panic!("the wrong type was decorated"));
+
+    let repo = Bound::<dyn Repo>::resolve_from_container(&Scoped::new().with_registry(Rc::new(registry)));
+
+    assert_eq!("sql", repo.describe());
+}
+
+#[test]
+fn overrides_arent_decorated() {
+    let scope = decorated(Registry::new());
+    scope.override_with::<dyn Repo>(Rc::new(SqlRepo("fake")));
+
+    let repo = Bound::<dyn Repo>::resolve_from_container(&scope);
+
+    assert_eq!("fake", repo.describe());
+}
+
+#[test]
+fn decorators_change_the_fingerprint() {
+    let mut plain = Registry::<Scoped>::new();
+    plain.bind::<dyn Repo, _>(|_| Rc::new(SqlRepo("sql")));
+
+    let mut decorated = Registry::<Scoped>::new();
+    decorated
+        .bind::<dyn Repo, _>(|_| Rc::new(SqlRepo("sql")))
+        .decorate::<dyn Repo, _>(|inner| inner);
+
+    assert_ne!(plain.fingerprint(), decorated.fingerprint());
+}
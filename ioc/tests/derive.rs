@@ -0,0 +1,86 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Default)]
+struct Orders;
+
+resolvable! {
+    default Orders;
+}
+
+trait AuditLog {
+    fn record(&self, event: &str);
+}
+
+#[derive(Default)]
+struct MemoryLog {
+    events: RefCell<Vec<String>>,
+}
+
+impl AuditLog for MemoryLog {
+    fn record(&self, event: &str) {
+        self.events.borrow_mut().push(event.to_owned());
+    }
+}
+
+#[derive(Resolvable)]
+struct Checkout {
+    orders: Rc<Orders>,
+    #[inject(optional)]
+    audit: Option<Rc<dyn AuditLog>>,
+}
+
+impl Checkout {
+    fn place(&self) {
+        if let Some(ref audit) = self.audit {
+            audit.record("placed");
+        }
+    }
+}
+
+#[derive(Resolvable)]
+struct Audited(#[inject(optional)] Option<Rc<dyn AuditLog>>);
+
+fn scope(registry: Registry<Scoped>) -> Scoped {
+    Scoped::new().with_registry(Rc::new(registry))
+}
+
+#[test]
+fn optional_fields_are_set_when_bound() {
+    let log = Rc::new(MemoryLog::default());
+
+    let mut registry = Registry::new();
+    let bound = log.clone();
+    registry.bind::<dyn AuditLog, _>(move |_| bound.clone());
+
+    let scope = scope(registry);
+    let checkout: Checkout = scope.resolve();
+    checkout.place();
+
+    assert!(Rc::ptr_eq(&checkout.orders, &scope.get_or_add::<Orders, _>()));
+    assert_eq!(vec!["placed".to_owned()], *log.events.borrow());
+}
+
+#[test]
+fn optional_fields_are_none_when_unbound() {
+    let scope = scope(Registry::new());
+
+    let checkout: Checkout = scope.resolve();
+    let audited: Audited = scope.resolve();
+
+    assert!(checkout.audit.is_none());
+    assert!(audited.0.is_none());
+}
+
+#[test]
+fn verify_ignores_unbound_optional_fields() {
+    let scope = scope(Registry::new());
+
+    assert!(scope.verify::<_, Checkout>().is_ok());
+    assert!(scope.verify::<_, Audited>().is_ok());
+}
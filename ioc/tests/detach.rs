@@ -0,0 +1,74 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::RefCell;
+use std::thread;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Config {
+    name: String,
+}
+
+impl<C> Resolvable<C> for Config {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Config { name: "app".to_owned() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Client {
+    retries: u32,
+}
+
+impl<C> Resolvable<C> for Client {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Client { retries: 3 }
+    }
+}
+
+#[test]
+fn detached_values_are_clones_of_the_scopes_instances() {
+    let scope = Scoped::new();
+    scope.replace(Config { name: "replaced".to_owned() });
+
+    let (config, client) = scope.detach::<(Config, Client)>();
+
+    assert_eq!("replaced", config.name);
+    assert_eq!(*scope.get_or_add::<Client, _>(), client);
+}
+
+#[test]
+fn changes_to_detached_values_arent_seen_by_the_scope() {
+    let scope = Scoped::new();
+
+    let (mut config,) = scope.detach::<(Config,)>();
+    config.name.push_str("-detached");
+
+    assert_eq!("app", scope.get_or_add::<Config, _>().name);
+}
+
+#[test]
+fn detached_values_can_leave_the_thread() {
+    let scope = Scoped::new();
+    let (config, client) = scope.detach::<(Config, Client)>();
+
+    let sent = thread::spawn(move || format!("{} {}", config.name, client.retries)).join().unwrap();
+
+    assert_eq!("app 3", sent);
+}
+
+#[test]
+fn detached_shared_mutable_values_are_snapshots() {
+    let scope = Scoped::new();
+    scope.get_or_add::<RefCell<Client>, _>().borrow_mut().retries = 5;
+
+    let (client,) = scope.detach::<(RefCell<Client>,)>();
+    scope.get_or_add::<RefCell<Client>, _>().borrow_mut().retries = 7;
+
+    assert_eq!(5, client.into_inner().retries);
+}
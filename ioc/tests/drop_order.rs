@@ -0,0 +1,234 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+thread_local! {
+    static DROPPED: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+fn dropped() -> Vec<&'static str> {
+    DROPPED.with(|dropped| dropped.borrow_mut().drain(..).collect())
+}
+
+fn log_drop(name: &'static str) {
+    DROPPED.with(|dropped| dropped.borrow_mut().push(name));
+}
+
+// These don't hold on to their dependencies, like a service with a raw
+// handle into a pooled connection, so they rely on the scope to drop
+// them in the right order.
+
+struct Pool;
+impl<C> Resolvable<C> for Pool {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Pool
+    }
+}
+impl Drop for Pool {
+    fn drop(&mut self) {
+        log_drop("pool");
+    }
+}
+
+struct Service;
+impl<C> Resolvable<C> for Service {
+    type Dependency = Rc<Pool>;
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Service
+    }
+}
+impl Drop for Service {
+    fn drop(&mut self) {
+        log_drop("service");
+    }
+}
+
+struct Handler;
+impl<C> Resolvable<C> for Handler {
+    type Dependency = (Rc<Service>, Rc<Pool>);
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Handler
+    }
+}
+impl Drop for Handler {
+    fn drop(&mut self) {
+        log_drop("handler");
+    }
+}
+
+struct BorrowedService;
+impl<'scope> Resolvable<Borrowed<'scope>> for BorrowedService {
+    type Dependency = B<'scope, Pool>;
+
+    fn resolve(_: Self::Dependency) -> Self {
+        BorrowedService
+    }
+}
+impl Drop for BorrowedService {
+    fn drop(&mut self) {
+        log_drop("service");
+    }
+}
+
+struct BorrowedHandler;
+impl<'scope> Resolvable<Borrowed<'scope>> for BorrowedHandler {
+    type Dependency = (B<'scope, BorrowedService>, B<'scope, Pool>);
+
+    fn resolve(_: Self::Dependency) -> Self {
+        BorrowedHandler
+    }
+}
+impl Drop for BorrowedHandler {
+    fn drop(&mut self) {
+        log_drop("handler");
+    }
+}
+
+#[test]
+fn scoped_drops_in_reverse_construction_order() {
+    BasicContainer.scope(|scope| {
+        scope.invoke(|_: Rc<Handler>| ());
+    });
+
+    assert_eq!(vec!["handler", "service", "pool"], dropped());
+}
+
+#[test]
+fn scoped_drops_replaced_values_as_if_newly_constructed() {
+    let scope = Scoped::new();
+
+    scope.insert(Pool).unwrap();
+    scope.insert(Service).unwrap();
+
+    let previous = scope.replace(Pool);
+    drop(previous);
+
+    assert_eq!(vec!["pool"], dropped());
+
+    drop(scope);
+
+    assert_eq!(vec!["pool", "service"], dropped());
+}
+
+#[test]
+fn borrowed_drops_in_reverse_construction_order() {
+    BasicContainer.borrow_scope(|scope| {
+        scope.invoke(|_: B<BorrowedHandler>| ());
+    });
+
+    assert_eq!(vec!["handler", "service", "pool"], dropped());
+}
+
+#[test]
+fn scoped_clear_drops_in_reverse_construction_order() {
+    let scope = Scoped::new();
+
+    scope.invoke(|_: Rc<Handler>| ());
+    scope.clear();
+
+    assert_eq!(vec!["handler", "service", "pool"], dropped());
+
+    scope.invoke(|_: Rc<Pool>| ());
+    drop(scope);
+
+    assert_eq!(vec!["pool"], dropped());
+}
+
+struct Connection;
+impl<C> Resolvable<C> for Connection {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Connection
+    }
+}
+impl Disposable for Connection {
+    fn dispose(&mut self) {
+        log_drop("dispose connection");
+    }
+}
+
+#[test]
+fn scoped_disposes_when_invalidated_and_when_ended() {
+    let mut registry = Registry::new();
+    registry.disposable::<Connection>();
+
+    let scope = Scoped::new().with_registry(Rc::new(registry));
+
+    scope.invoke(|_: Rc<Connection>| ());
+    assert!(scope.invalidate::<Connection>());
+
+    assert_eq!(vec!["dispose connection"], dropped());
+
+    scope.invoke(|_: Rc<Connection>| ());
+    drop(scope);
+
+    assert_eq!(vec!["dispose connection"], dropped());
+}
+
+struct Flusher {
+    log: Lazy<Connection>,
+}
+impl Resolvable<Scoped> for Flusher {
+    type Dependency = Lazy<Connection>;
+
+    fn resolve(log: Self::Dependency) -> Self {
+        Flusher { log }
+    }
+}
+impl Disposable for Flusher {
+    fn dispose(&mut self) {
+        log_drop("dispose flusher");
+    }
+}
+impl Drop for Flusher {
+    fn drop(&mut self) {
+        // resolves from the scope that's dropping it
+        self.log.get();
+        log_drop("flusher");
+    }
+}
+
+#[test]
+fn scoped_disposes_values_replaced_by_insert() {
+    let mut registry = Registry::new();
+    registry.disposable::<Flusher>();
+
+    let scope = Scoped::new().with_registry(Rc::new(registry));
+
+    scope.get_or_add::<Flusher, _>();
+    scope.insert(scope.resolve::<_, Flusher>()).unwrap();
+
+    assert_eq!(vec!["dispose flusher", "flusher"], dropped());
+
+    // it can't resolve anything once the scope has ended
+    drop(scope.remove::<Flusher>());
+    dropped();
+}
+
+#[test]
+fn scoped_disposes_values_that_are_only_weakly_referenced() {
+    let mut registry = Registry::new();
+    registry.disposable::<Connection>();
+
+    let scope = Scoped::new().with_registry(Rc::new(registry));
+
+    let weak = Rc::downgrade(&scope.get_or_add::<Connection, _>());
+    assert!(scope.invalidate::<Connection>());
+
+    assert_eq!(vec!["dispose connection"], dropped());
+    assert!(weak.upgrade().is_none());
+
+    let weak = Rc::downgrade(&scope.get_or_add::<Connection, _>());
+    drop(scope);
+
+    assert_eq!(vec!["dispose connection"], dropped());
+    assert!(weak.upgrade().is_none());
+}
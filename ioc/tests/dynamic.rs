@@ -0,0 +1,121 @@
+extern crate ioc;
+
+use ioc::{BuildContext, Condition, Contested, DynamicBuilder, DynamicContainer, MemoryEnv, Profile};
+
+fn staging(ctx: &BuildContext) -> bool {
+    ctx.var("APP_ENV").as_deref() == Some("staging")
+}
+
+fn build(builder: DynamicBuilder, profile: Profile, env: MemoryEnv) -> DynamicContainer {
+    builder.build_in(BuildContext::new(profile).with_env(env))
+}
+
+#[test]
+fn conditional_factories_beat_always() {
+    let mut builder = DynamicContainer::builder();
+    builder
+        .register_for::<&'static str, _>(Profile::Test, |_| "fake")
+        .register::<&'static str, _>(|_| "real");
+
+    let container = build(builder, Profile::Test, MemoryEnv::new());
+
+    assert_eq!(Some("fake"), container.get::<&'static str>());
+}
+
+#[test]
+fn predicates_beat_always() {
+    let builder = || {
+        let mut builder = DynamicContainer::builder();
+        builder
+            .register_if::<&'static str, _, _>(staging, |_| "sqs")
+            .register::<&'static str, _>(|_| "memory");
+
+        builder
+    };
+
+    let staged = build(builder(), Profile::Prod, MemoryEnv::new().with("APP_ENV", "staging"));
+    let unstaged = build(builder(), Profile::Prod, MemoryEnv::new());
+
+    assert_eq!(Some("sqs"), staged.get::<&'static str>());
+    assert_eq!(Some("memory"), unstaged.get::<&'static str>());
+}
+
+#[test]
+fn the_last_factory_wins_among_equals() {
+    let mut builder = DynamicContainer::builder();
+    builder
+        .register::<&'static str, _>(|_| "first")
+        .register::<&'static str, _>(|_| "second")
+        .register_for::<u8, _>(Profile::Dev, |_| 1)
+        .register_for::<u8, _>(Profile::Dev, |_| 2);
+
+    let container = build(builder, Profile::Dev, MemoryEnv::new());
+
+    assert_eq!(Some("second"), container.get::<&'static str>());
+    assert_eq!(Some(2), container.get::<u8>());
+}
+
+#[test]
+fn profiles_filter_out_other_profiles() {
+    let builder = || {
+        let mut builder = DynamicContainer::builder();
+        builder
+            .register_for::<&'static str, _>(Profile::Test, |_| "fake")
+            .register_for::<u8, _>(Profile::Prod, |_| 1);
+
+        builder
+    };
+
+    let test = build(builder(), Profile::Test, MemoryEnv::new());
+    let prod = build(builder(), Profile::Prod, MemoryEnv::new());
+
+    assert_eq!(Some(Profile::Test), test.profile());
+    assert_eq!(Some("fake"), test.get::<&'static str>());
+    assert!(!test.is_registered::<u8>());
+
+    assert!(!prod.is_registered::<&'static str>());
+    assert_eq!(Some(1), prod.get::<u8>());
+}
+
+#[test]
+fn contested_types_are_reported() {
+    let mut builder = DynamicContainer::builder();
+    builder
+        .register::<&'static str, _>(|_| "real")
+        .register_for::<&'static str, _>(Profile::Test, |_| "fake")
+        .register::<&'static str, _>(|_| "other")
+        .register_for::<u8, _>(Profile::Dev, |_| 1)
+        .register_for::<u8, _>(Profile::Prod, |_| 2)
+        .register::<u16, _>(|_| 1);
+
+    let container = build(builder, Profile::Test, MemoryEnv::new());
+
+    assert_eq!(
+        &[
+            Contested {
+                type_name: "&str",
+                candidates: vec![
+                    (Condition::Always, true),
+                    (Condition::Profile(Profile::Test), true),
+                    (Condition::Always, true),
+                ],
+                winner: Some(1),
+            },
+            Contested {
+                type_name: "u8",
+                candidates: vec![
+                    (Condition::Profile(Profile::Dev), false),
+                    (Condition::Profile(Profile::Prod), false),
+                ],
+                winner: None,
+            },
+        ],
+        container.contested()
+    );
+
+    assert_eq!(
+        "`&str` has 3 factories\n  always: replaced\n  in the `test` profile: used\n  always: replaced",
+        container.contested()[0].to_string()
+    );
+    assert!(!container.is_registered::<u8>());
+}
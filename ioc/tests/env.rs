@@ -0,0 +1,111 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::{EnvProvider, Error, MemoryEnv};
+
+use std::env;
+use std::rc::Rc;
+
+struct DatabaseUrl;
+
+impl EnvVar for DatabaseUrl {
+    const NAME: &'static str = "DATABASE_URL";
+    type Value = String;
+}
+
+struct PoolSize;
+
+impl EnvVar for PoolSize {
+    const NAME: &'static str = "POOL_SIZE";
+    const DEFAULT: Option<&'static str> = Some("4");
+    type Value = usize;
+}
+
+struct Db {
+    url: Env<DatabaseUrl>,
+    pool_size: Env<PoolSize>,
+}
+
+impl<C> Resolvable<C> for Db
+    where C: RegisteredContainer + 'static
+{
+    type Dependency = (Env<DatabaseUrl>, Env<PoolSize>);
+
+    fn resolve((url, pool_size): Self::Dependency) -> Self {
+        Db { url, pool_size }
+    }
+}
+
+fn scope(env: MemoryEnv) -> Scoped {
+    let env: Rc<dyn EnvProvider> = Rc::new(env);
+
+    let mut registry = Registry::new();
+    registry.bind::<dyn EnvProvider, _>(move |_| env.clone());
+
+    Scoped::new().with_registry(Rc::new(registry))
+}
+
+#[test]
+fn env_reads_and_parses_variables_from_the_bound_provider() {
+    let db: Db = scope(MemoryEnv::new().with("DATABASE_URL", "postgres://localhost").with("POOL_SIZE", "16")).resolve();
+
+    assert_eq!("postgres://localhost", *db.url);
+    assert_eq!(16, *db.pool_size);
+}
+
+#[test]
+fn env_uses_the_default_for_a_missing_variable() {
+    let db: Db = scope(MemoryEnv::new().with("DATABASE_URL", "postgres://localhost")).resolve();
+
+    assert_eq!(4, db.pool_size.into_inner());
+}
+
+#[test]
+fn env_can_be_overridden() {
+    let scope = Scoped::new();
+    scope.override_with::<dyn EnvProvider>(Rc::new(MemoryEnv::new().with("DATABASE_URL", "sqlite://memory")));
+
+    let url = Env::<DatabaseUrl>::resolve_from_container(&scope);
+
+    assert_eq!("sqlite://memory", *url);
+}
+
+#[test]
+fn env_reads_the_process_environment_without_a_provider() {
+    struct ProcessVar;
+
+    impl EnvVar for ProcessVar {
+        const NAME: &'static str = "IOC_TEST_ENV_PROCESS_VAR";
+        type Value = bool;
+    }
+
+    env::set_var(ProcessVar::NAME, "true");
+
+    let var = Env::<ProcessVar>::resolve_from_container(&Scoped::new());
+
+    assert!(*var);
+    assert_eq!("Env { name: \"IOC_TEST_ENV_PROCESS_VAR\", value: true }", format!("{:?}", var));
+}
+
+#[test]
+fn missing_variable_is_an_error() {
+    match scope(MemoryEnv::new()).try_resolve::<_, Db>() {
+        Err(Error::MissingEnvVar { name, .. }) => assert_eq!("DATABASE_URL", name),
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("expected a missing variable"),
+    }
+}
+
+#[test]
+fn invalid_variable_is_an_error() {
+    let scope = scope(MemoryEnv::new().with("DATABASE_URL", "postgres://localhost").with("POOL_SIZE", "lots"));
+
+    match scope.try_resolve::<_, Db>() {
+        Err(Error::InvalidEnvVar { name, value, .. }) => {
+            assert_eq!("POOL_SIZE", name);
+            assert_eq!("lots", value);
+        }
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("expected an invalid variable"),
+    }
+}
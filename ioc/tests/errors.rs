@@ -0,0 +1,157 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::Error;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+trait Store {
+    fn name(&self) -> &'static str;
+}
+
+struct FileStore;
+impl Store for FileStore {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+}
+
+struct Service {
+    store: Bound<dyn Store>,
+}
+impl<C> Resolvable<C> for Service
+    where C: RegisteredContainer + 'static
+{
+    type Dependency = Bound<dyn Store>;
+
+    fn resolve(store: Self::Dependency) -> Self {
+        Service { store }
+    }
+}
+
+#[test]
+fn binding_that_depends_on_itself_is_a_cycle() {
+    let mut registry = Registry::new();
+    registry.bind::<dyn Store, _>(|c: &Scoped| {
+        c.resolve::<_, Service>();
+
+        Rc::new(FileStore)
+    });
+
+    let scope = Scoped::new().with_registry(Rc::new(registry));
+
+    match scope.try_resolve::<_, Service>() {
+        Err(Error::Cycle { path }) => assert_eq!(
+            vec![
+                "errors::Service",
+                "ioc::container::registry::Bound<dyn errors::Store>",
+                "ioc::container::registry::Bound<dyn errors::Store>",
+            ],
+            path
+        ),
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("expected a cycle"),
+    }
+}
+
+#[test]
+fn nested_scope_resolving_the_same_type_isnt_a_cycle() {
+    // the nested scope has its own binding for the same type
+    let mut nested = Registry::new();
+    nested.bind::<dyn Store, _>(|_| Rc::new(FileStore));
+    let nested = Rc::new(nested);
+
+    let mut registry = Registry::new();
+    registry.bind::<dyn Store, _>(move |_: &Scoped| {
+        let scope = Scoped::new().with_registry(nested.clone());
+
+        scope.resolve::<_, Service>().store.into_inner()
+    });
+
+    let scope = Scoped::new().with_registry(Rc::new(registry));
+
+    let service = scope.try_resolve::<_, Service>().unwrap();
+
+    assert_eq!("file", service.store.name());
+}
+
+thread_local!(static FAIL: Cell<bool> = const { Cell::new(true) });
+
+struct Flaky;
+impl<C> Resolvable<C> for Flaky {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        if FAIL.with(Cell::get) {
+            panic!("couldn't connect");
+        }
+
+        Flaky
+    }
+}
+
+struct Client {
+    _flaky: Rc<Flaky>,
+}
+impl Resolvable<Scoped> for Client {
+    type Dependency = Rc<Flaky>;
+
+    fn resolve(flaky: Self::Dependency) -> Self {
+        Client { _flaky: flaky }
+    }
+}
+
+struct Shared {
+    _client: Rc<Client>,
+}
+impl Resolvable<Scoped> for Shared {
+    type Dependency = Rc<Client>;
+
+    fn resolve(client: Self::Dependency) -> Self {
+        Shared { _client: client }
+    }
+}
+
+struct Root {
+    _shared: Rc<Shared>,
+}
+impl Resolvable<Scoped> for Root {
+    type Dependency = Rc<Shared>;
+
+    fn resolve(shared: Self::Dependency) -> Self {
+        Root { _shared: shared }
+    }
+}
+
+#[test]
+fn panic_while_constructing_leaves_nothing_behind() {
+    FAIL.with(|fail| fail.set(true));
+
+    let scope = Scoped::new();
+
+    match scope.try_resolve::<_, Root>() {
+        Err(Error::ConstructionFailed { path, source: ResolveError::Panicked { type_name, .. } }) => {
+            assert_eq!("errors::Flaky", type_name);
+            assert_eq!(Some(&"errors::Flaky"), path.last());
+        }
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("expected a panic"),
+    }
+
+    let observed = scope.observed_graph();
+    assert_eq!(0, observed.order().count());
+    assert_eq!(0, observed.edges().count());
+
+    // nothing was cached, so resolving again tries again
+    FAIL.with(|fail| fail.set(false));
+
+    scope.try_resolve::<_, Root>().unwrap();
+
+    let observed = scope.observed_graph();
+    assert_eq!(vec!["errors::Flaky", "errors::Client", "errors::Shared"], observed.order().collect::<Vec<_>>());
+    assert_eq!(
+        vec![("errors::Client", "errors::Flaky"), ("errors::Shared", "errors::Client")],
+        observed.edges().collect::<Vec<_>>()
+    );
+}
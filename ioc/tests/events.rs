@@ -0,0 +1,134 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::factories::{Events, Handles};
+
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+struct ProductRenamed {
+    id: u32,
+}
+
+#[derive(Default)]
+struct Log {
+    lines: RefCell<Vec<String>>,
+}
+
+impl<C> Resolvable<C> for Log {
+    type Dependency = ();
+
+    fn resolve(_: ()) -> Self {
+        Log::default()
+    }
+}
+
+macro_rules! subscriber {
+    ($($name:ident => $prefix:expr),*) => {$(
+        struct $name {
+            log: Rc<Log>,
+        }
+
+        impl<C> Resolvable<C> for $name
+            where C: ScopedContainer
+        {
+            type Dependency = Rc<Log>;
+
+            fn resolve(log: Self::Dependency) -> Self {
+                $name { log }
+            }
+        }
+
+        impl Handles<ProductRenamed> for $name {
+            fn handle(&self, event: &ProductRenamed) {
+                self.log.lines.borrow_mut().push(format!("{} {}", $prefix, event.id));
+            }
+        }
+    )*};
+}
+
+subscriber!(ReindexProduct => "reindex", NotifyWatchers => "notify");
+
+fn scope() -> Scoped {
+    let mut registry = Registry::new();
+    registry
+        .subscribe::<ProductRenamed, ReindexProduct, _>()
+        .subscribe::<ProductRenamed, NotifyWatchers, _>();
+
+    Scoped::new().with_registry(Rc::new(registry))
+}
+
+fn lines(log: &Log) -> Vec<String> {
+    log.lines.borrow_mut().drain(..).collect()
+}
+
+#[test]
+fn published_events_are_handled_by_each_subscriber_in_order() {
+    let scope = scope();
+
+    let events: Rc<Events<ProductRenamed>> = scope.get_or_add();
+    events.publish(ProductRenamed { id: 1 });
+
+    assert_eq!(2, events.subscribers());
+    assert_eq!(vec!["reindex 1", "notify 1"], lines(&scope.get_or_add::<Log, _>()));
+}
+
+#[test]
+fn events_without_subscribers_are_ignored() {
+    let scope = Scoped::new().with_registry(Rc::new(Registry::new()));
+
+    let events: Rc<Events<ProductRenamed>> = scope.get_or_add();
+    events.publish(ProductRenamed { id: 1 });
+
+    assert_eq!(0, events.subscribers());
+}
+
+#[test]
+fn deferred_events_are_published_when_the_scope_ends() {
+    let scope = scope();
+    let log = scope.get_or_add::<Log, _>();
+
+    scope.invoke(|events: Rc<Events<ProductRenamed>>| {
+        events.defer(ProductRenamed { id: 1 });
+        events.defer(ProductRenamed { id: 2 });
+
+        assert_eq!(2, events.deferred());
+        assert_eq!("Events { subscribers: 2, deferred: 2 }", format!("{:?}", events));
+    });
+
+    assert!(lines(&log).is_empty());
+
+    drop(scope);
+
+    assert_eq!(vec!["reindex 1", "notify 1", "reindex 2", "notify 2"], lines(&log));
+}
+
+#[test]
+fn deferred_events_can_be_flushed() {
+    let scope = scope();
+
+    let events: Rc<Events<ProductRenamed>> = scope.get_or_add();
+    events.defer(ProductRenamed { id: 1 });
+    events.flush();
+
+    assert_eq!(0, events.deferred());
+    assert_eq!(vec!["reindex 1", "notify 1"], lines(&scope.get_or_add::<Log, _>()));
+}
+
+#[test]
+fn deferred_events_are_discarded_if_the_scope_panics() {
+    let log = Rc::new(Log::default());
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let scope = scope();
+        scope.override_with(log.clone());
+
+        scope.invoke(|events: Rc<Events<ProductRenamed>>| events.defer(ProductRenamed { id: 1 }));
+
+        panic!("the request failed");
+    }));
+
+    assert!(result.is_err());
+    assert!(lines(&log).is_empty());
+}
@@ -0,0 +1,136 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::Error;
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+// stands in for a type from another crate
+struct Pool {
+    url: String,
+    timeout: Duration,
+}
+
+struct Url;
+
+impl<C> Resolvable<C> for Url {
+    type Dependency = ();
+
+    fn resolve(_: ()) -> Self {
+        Url
+    }
+}
+
+struct Repository {
+    pool: Rc<External<Pool>>,
+}
+
+impl<C> Resolvable<C> for Repository
+    where C: ScopedContainer + RegisteredContainer + 'static
+{
+    type Dependency = Rc<External<Pool>>;
+
+    fn resolve(pool: Self::Dependency) -> Self {
+        Repository { pool }
+    }
+}
+
+fn scope(registry: Registry<Scoped>) -> Scoped {
+    Scoped::new().with_registry(Rc::new(registry))
+}
+
+#[test]
+fn external_is_created_by_its_factory() {
+    let mut registry = Registry::new();
+    registry.bind_external::<Duration, _>(|_| Duration::from_secs(5));
+
+    let timeout = External::<Duration>::resolve_from_container(&scope(registry));
+
+    assert_eq!(Duration::from_secs(5), *timeout);
+    assert_eq!("External(5s)", format!("{:?}", timeout));
+}
+
+#[test]
+fn external_factory_resolves_its_dependencies() {
+    let mut registry = Registry::new();
+    registry
+        .bind_external::<Duration, _>(|_| Duration::from_secs(5))
+        .bind_external_with::<Pool, _, _>(|(_, timeout): (Transient<Url>, External<Duration>)| Pool {
+            url: "postgres://localhost".into(),
+            timeout: timeout.into_inner(),
+        });
+
+    let repository: Repository = scope(registry).resolve();
+
+    assert_eq!("postgres://localhost", repository.pool.url);
+    assert_eq!(Duration::from_secs(5), repository.pool.timeout);
+}
+
+#[test]
+fn external_is_new_each_time_unless_shared() {
+    let created = Rc::new(Cell::new(0));
+
+    let mut registry = Registry::new();
+    registry.bind_external::<Pool, _>({
+        let created = created.clone();
+        move |_| {
+            created.set(created.get() + 1);
+
+            Pool { url: String::new(), timeout: Duration::from_secs(1) }
+        }
+    });
+
+    let scope = scope(registry);
+
+    let mut pool = External::<Pool>::resolve_from_container(&scope);
+    pool.url.push_str("changed");
+    External::<Pool>::resolve_from_container(&scope);
+
+    assert_eq!(2, created.get());
+
+    let a: Repository = scope.resolve();
+    let b: Repository = scope.resolve();
+
+    assert!(Rc::ptr_eq(&a.pool, &b.pool));
+    assert_eq!(3, created.get());
+}
+
+#[test]
+fn external_without_a_factory_is_a_missing_binding() {
+    let scope = scope(Registry::new());
+
+    match scope.try_resolve::<_, Repository>() {
+        Err(Error::MissingBinding { type_name, .. }) => assert_eq!("external::Pool", type_name),
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("expected a missing binding"),
+    }
+
+    assert_eq!(vec![VerifyError::MissingBinding {
+                        type_name: "external::Pool",
+                        path: vec![
+                            "external::Repository",
+                            "alloc::rc::Rc<ioc::container::external::External<external::Pool>>",
+                            "external::Pool",
+                        ],
+                    }],
+               scope.verify::<_, Repository>().unwrap_err());
+}
+
+#[test]
+fn external_factory_dependencies_are_verified() {
+    let mut registry = Registry::new();
+    registry.bind_external_with::<Pool, _, _>(|timeout: External<Duration>| Pool {
+        url: String::new(),
+        timeout: timeout.into_inner(),
+    });
+
+    match scope(registry).verify::<_, Repository>() {
+        Err(ref errors) if errors.len() == 1 => match errors[0] {
+            VerifyError::MissingBinding { type_name, .. } => assert_eq!("core::time::Duration", type_name),
+            ref err => panic!("unexpected error: {}", err),
+        },
+        result => panic!("expected a missing binding, got {:?}", result),
+    }
+}
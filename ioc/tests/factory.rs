@@ -0,0 +1,96 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+struct Connection;
+
+impl<C> Resolvable<C> for Connection {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Connection
+    }
+}
+
+thread_local!(static CREATED: Cell<usize> = const { Cell::new(0) });
+
+struct Worker {
+    connection: Rc<Connection>,
+}
+
+impl Resolvable<Scoped> for Worker {
+    type Dependency = Rc<Connection>;
+
+    fn resolve(connection: Self::Dependency) -> Self {
+        CREATED.with(|created| created.set(created.get() + 1));
+
+        Worker { connection }
+    }
+}
+
+struct Pool {
+    workers: Factory<Worker>,
+}
+
+impl Resolvable<Scoped> for Pool {
+    type Dependency = Factory<Worker>;
+
+    fn resolve(workers: Self::Dependency) -> Self {
+        Pool { workers }
+    }
+}
+
+#[test]
+fn factory_creates_a_new_instance_each_time() {
+    CREATED.with(|created| created.set(0));
+
+    let scope = Scoped::new();
+    let pool = scope.resolve::<_, Pool>();
+
+    assert_eq!(0, CREATED.with(Cell::get));
+
+    let workers = [pool.workers.create(), pool.workers.create(), pool.workers.clone().create()];
+
+    assert_eq!(3, CREATED.with(Cell::get));
+    assert!(!std::ptr::eq(&workers[0], &workers[1]));
+}
+
+#[test]
+fn factory_instances_share_dependencies_from_its_scope() {
+    let scope = Scoped::new();
+    let pool = scope.resolve::<_, Pool>();
+
+    let a = pool.workers.create();
+    let b = pool.workers.create();
+
+    assert!(Rc::ptr_eq(&a.connection, &b.connection));
+    assert!(Rc::ptr_eq(&a.connection, &scope.get_or_add::<Connection, _>()));
+}
+
+#[test]
+#[should_panic(expected = "the captured scope has already ended")]
+fn factory_used_after_its_scope_ends_panics() {
+    let pool = Scoped::new().resolve::<_, Pool>();
+
+    pool.workers.create();
+}
+
+#[test]
+fn factory_from_a_closure() {
+    let next = Rc::new(Cell::new(0));
+    let counter = {
+        let next = next.clone();
+
+        Factory::new(move || {
+            next.set(next.get() + 1);
+            next.get()
+        })
+    };
+
+    assert_eq!(1, counter.create());
+    assert_eq!(2, counter.create());
+    assert_eq!("Factory", format!("{:?}", counter));
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "ffi")]
+
+extern crate ioc;
+
+use ioc::ffi::*;
+
+use std::ffi::CString;
+use std::ptr;
+
+#[derive(Debug, PartialEq)]
+struct Greeter {
+    greeting: &'static str,
+}
+
+fn module(registry: &mut FfiRegistry) {
+    registry
+        .register_with("greeter", |_| Greeter { greeting: "hello" })
+        .register_with("broken", |_| -> Greeter { panic!("couldn't construct") });
+}
+
+fn resolve(scope: *const FfiScope, name: &str) -> *mut FfiHandle {
+    let name = CString::new(name).unwrap();
+
+    unsafe { ioc_resolve(scope, name.as_ptr()) }
+}
+
+#[test]
+fn named_types_resolve_into_handles() {
+    set_module(module);
+
+    let container = ioc_container_new();
+    assert!(!container.is_null());
+
+    unsafe {
+        let scope = ioc_scope_new(container);
+        assert!(!scope.is_null());
+
+        let handle = resolve(scope, "greeter");
+        assert!(!handle.is_null());
+        assert_eq!(Some(&Greeter { greeting: "hello" }), (*handle).downcast_ref::<Greeter>());
+        assert_eq!(None, (*handle).downcast_ref::<String>());
+
+        ioc_handle_free(handle);
+        ioc_scope_free(scope);
+        ioc_container_free(container);
+    }
+}
+
+#[test]
+fn failures_resolve_to_null() {
+    set_module(module);
+
+    let container = ioc_container_new();
+
+    unsafe {
+        let scope = ioc_scope_new(container);
+
+        assert!(ioc_resolve(scope, ptr::null()).is_null());
+        assert!(resolve(scope, "unknown").is_null());
+        assert!(resolve(scope, "broken").is_null());
+        assert!(resolve(ptr::null(), "greeter").is_null());
+
+        // the scope is still usable after a panic
+        let handle = resolve(scope, "greeter");
+        assert!(!handle.is_null());
+
+        ioc_handle_free(handle);
+        ioc_scope_free(scope);
+        ioc_container_free(container);
+    }
+
+    assert!(unsafe { ioc_scope_new(ptr::null()) }.is_null());
+}
+
+#[test]
+fn panicking_drops_dont_unwind_out_of_free() {
+    struct PanicOnDrop;
+    impl Drop for PanicOnDrop {
+        fn drop(&mut self) {
+            panic!("couldn't drop");
+        }
+    }
+
+    let on_drop = PanicOnDrop;
+
+    let mut registry = FfiRegistry::new();
+    registry.register_with("greeter", move |_| {
+        let _ = &on_drop;
+        Greeter { greeting: "hello" }
+    });
+
+    let container = Box::into_raw(Box::new(FfiContainer::new(registry)));
+
+    unsafe { ioc_container_free(container) };
+}
@@ -0,0 +1,205 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+
+trait KeyStore {}
+
+struct FileStore;
+struct MemoryStore;
+
+impl KeyStore for FileStore {}
+impl KeyStore for MemoryStore {}
+
+impl<C> Resolvable<C> for FileStore {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        FileStore
+    }
+}
+
+impl<C> Resolvable<C> for MemoryStore {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        MemoryStore
+    }
+}
+
+// a module shared by several binaries
+fn shared(registry: &mut Registry<Scoped>) {
+    registry.bind::<dyn KeyStore, _>(|_| Rc::new(FileStore));
+}
+
+#[test]
+fn registries_built_from_the_same_module_match() {
+    let mut cli = Registry::new();
+    shared(&mut cli);
+
+    let mut server = Registry::new();
+    shared(&mut server);
+
+    assert_eq!(cli.fingerprint(), server.fingerprint());
+    assert!(cli.fingerprint().diff(&server.fingerprint()).is_empty());
+}
+
+#[test]
+fn different_closures_in_the_same_function_differ() {
+    let mut cli = Registry::<Scoped>::new();
+    cli.bind::<dyn KeyStore, _>(|_| Rc::new(FileStore));
+
+    let mut server = Registry::<Scoped>::new();
+    server.bind::<dyn KeyStore, _>(|_| Rc::new(MemoryStore));
+
+    assert_eq!(vec!["bind dyn fingerprint::KeyStore"], cli.fingerprint().diff(&server.fingerprint()));
+}
+
+#[test]
+fn bindings_to_the_same_implementation_match() {
+    let mut cli = Registry::<Scoped>::new();
+    cli.bind_to::<dyn KeyStore, FileStore, _, _>(|store| Rc::new(store));
+
+    let mut server = Registry::<Scoped>::new();
+    server.bind_to::<dyn KeyStore, FileStore, _, _>(|store| Rc::new(store));
+
+    assert_eq!(cli.fingerprint(), server.fingerprint());
+}
+
+#[test]
+fn bindings_to_different_implementations_differ() {
+    let mut cli = Registry::<Scoped>::new();
+    cli.bind_to::<dyn KeyStore, FileStore, _, _>(|store| Rc::new(store));
+
+    let mut server = Registry::<Scoped>::new();
+    server.bind_to::<dyn KeyStore, MemoryStore, _, _>(|store| Rc::new(store));
+
+    assert_eq!(vec!["bind dyn fingerprint::KeyStore"], cli.fingerprint().diff(&server.fingerprint()));
+}
+
+#[test]
+fn bindings_with_different_lifetimes_differ() {
+    let mut transient = Registry::<Scoped>::new();
+    transient.bind_to::<dyn KeyStore, FileStore, _, _>(|store| Rc::new(store));
+
+    let mut shared = Registry::<Scoped>::new();
+    shared.alias::<dyn KeyStore, FileStore, _, _>(|store| store);
+
+    assert_eq!(vec!["bind dyn fingerprint::KeyStore"], transient.fingerprint().diff(&shared.fingerprint()));
+}
+
+#[test]
+fn bind_all_depends_on_order() {
+    let mut cli = Registry::<Scoped>::new();
+    cli
+        .bind_all_to::<dyn KeyStore, FileStore, _, _>(|store| Rc::new(store))
+        .bind_all_to::<dyn KeyStore, MemoryStore, _, _>(|store| Rc::new(store));
+
+    let mut server = Registry::<Scoped>::new();
+    server
+        .bind_all_to::<dyn KeyStore, MemoryStore, _, _>(|store| Rc::new(store))
+        .bind_all_to::<dyn KeyStore, FileStore, _, _>(|store| Rc::new(store));
+
+    assert_eq!(vec!["bind_all dyn fingerprint::KeyStore"], cli.fingerprint().diff(&server.fingerprint()));
+}
+
+#[test]
+fn diff_includes_entries_only_in_one_fingerprint() {
+    let mut cli = Registry::<Scoped>::new();
+    cli.alias::<FileStore, FileStore, _, _>(|store| store);
+
+    let server = Registry::<Scoped>::new();
+
+    assert_eq!(vec!["bind fingerprint::FileStore"], cli.fingerprint().diff(&server.fingerprint()));
+    assert_eq!(vec!["bind fingerprint::FileStore"], server.fingerprint().diff(&cli.fingerprint()));
+}
+
+#[test]
+fn fingerprint_round_trips_through_its_display() {
+    let mut registry = Registry::<Scoped>::new();
+    shared(&mut registry);
+    registry.alias::<FileStore, FileStore, _, _>(|store| store);
+
+    let fingerprint = registry.fingerprint();
+    let parsed: Fingerprint = fingerprint.to_string().parse().unwrap();
+
+    assert_eq!(fingerprint, parsed);
+    assert_eq!(fingerprint.hash(), parsed.hash());
+}
+
+#[test]
+fn invalid_fingerprint_reports_its_line() {
+    let err = "0123456789abcdef bind dyn fingerprint::KeyStore\n\nnot-a-hash bind fingerprint::FileStore"
+        .parse::<Fingerprint>()
+        .unwrap_err();
+
+    assert_eq!(3, err.line);
+}
+
+#[test]
+fn scope_without_a_registry_has_an_empty_fingerprint() {
+    assert_eq!(Fingerprint::new(), Scoped::new().fingerprint());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Format {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Tenant {
+    Internal,
+}
+
+#[test]
+fn keyed_bindings_with_different_key_types_are_separate_entries() {
+    let mut registry = Registry::<Scoped>::new();
+    registry
+        .bind_keyed_to::<Format, dyn KeyStore, FileStore, _, _>(Format::Csv, |store| Rc::new(store))
+        .bind_keyed_to::<Tenant, dyn KeyStore, FileStore, _, _>(Tenant::Internal, |store| Rc::new(store));
+
+    let entries: Vec<_> = registry.fingerprint().entries().map(|(entry, _)| entry.to_owned()).collect();
+
+    assert_eq!(
+        vec![
+            "bind_keyed fingerprint::Format => dyn fingerprint::KeyStore",
+            "bind_keyed fingerprint::Tenant => dyn fingerprint::KeyStore",
+        ],
+        entries
+    );
+}
+
+#[test]
+fn keyed_bindings_under_different_keys_differ() {
+    let mut cli = Registry::<Scoped>::new();
+    cli
+        .bind_keyed_to::<Format, dyn KeyStore, FileStore, _, _>(Format::Csv, |store| Rc::new(store))
+        .bind_keyed_to::<Format, dyn KeyStore, MemoryStore, _, _>(Format::Json, |store| Rc::new(store));
+
+    let mut server = Registry::<Scoped>::new();
+    server
+        .bind_keyed_to::<Format, dyn KeyStore, MemoryStore, _, _>(Format::Csv, |store| Rc::new(store))
+        .bind_keyed_to::<Format, dyn KeyStore, FileStore, _, _>(Format::Json, |store| Rc::new(store));
+
+    assert_eq!(
+        vec!["bind_keyed fingerprint::Format => dyn fingerprint::KeyStore"],
+        cli.fingerprint().diff(&server.fingerprint())
+    );
+}
+
+#[test]
+fn keyed_bindings_ignore_the_order_they_were_added_in() {
+    let mut cli = Registry::<Scoped>::new();
+    cli
+        .bind_keyed_to::<Format, dyn KeyStore, FileStore, _, _>(Format::Csv, |store| Rc::new(store))
+        .bind_keyed_to::<Format, dyn KeyStore, MemoryStore, _, _>(Format::Json, |store| Rc::new(store));
+
+    let mut server = Registry::<Scoped>::new();
+    server
+        .bind_keyed_to::<Format, dyn KeyStore, MemoryStore, _, _>(Format::Json, |store| Rc::new(store))
+        .bind_keyed_to::<Format, dyn KeyStore, FileStore, _, _>(Format::Csv, |store| Rc::new(store));
+
+    assert_eq!(cli.fingerprint(), server.fingerprint());
+}
@@ -0,0 +1,94 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::{DependencyKind, Lifetime};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+trait Store {}
+
+struct Connection;
+impl<C> Resolvable<C> for Connection {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Connection
+    }
+}
+
+struct Buffer;
+impl<C> Resolvable<C> for Buffer {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Buffer
+    }
+}
+
+struct App;
+impl Resolvable<Scoped> for App {
+    type Dependency = (Rc<Connection>, RefCell<Buffer>, Bound<dyn Store>);
+
+    fn resolve(_: Self::Dependency) -> Self {
+        App
+    }
+}
+
+#[test]
+fn graph_records_each_dependency_and_how_its_resolved() {
+    let graph = Scoped::new().dependency_graph::<_, App>();
+
+    let nodes: Vec<_> = graph.nodes().iter().map(|node| (node.kind, node.parent)).collect();
+    assert_eq!(
+        vec![
+            (DependencyKind::Root, None),
+            (DependencyKind::Shared, Some(0)),
+            (DependencyKind::Owned, Some(0)),
+            (DependencyKind::Bound, Some(0)),
+        ],
+        nodes
+    );
+
+    let dependencies: Vec<_> = graph.dependencies(0).map(|(_, node)| node.type_name).collect();
+    assert_eq!(
+        vec![
+            "alloc::rc::Rc<graph::Connection>",
+            "core::cell::RefCell<graph::Buffer>",
+            "ioc::container::registry::Bound<dyn graph::Store>",
+        ],
+        dependencies
+    );
+
+    assert_eq!(Lifetime::Scoped, DependencyKind::Shared.lifetime());
+    assert_eq!(Lifetime::Deferred, DependencyKind::Lazy.lifetime());
+}
+
+#[test]
+fn graph_marks_missing_bindings() {
+    let graph = Scoped::new().dependency_graph::<_, App>();
+
+    let missing: Vec<_> = graph.nodes().iter().filter(|node| node.missing_binding).map(|node| node.type_name).collect();
+
+    assert_eq!(vec!["ioc::container::registry::Bound<dyn graph::Store>"], missing);
+}
+
+#[test]
+fn graph_is_written_as_dot() {
+    let graph = Scoped::new().dependency_graph::<_, App>();
+
+    let expected = "\
+digraph dependencies {
+    node [shape=box];
+    n0 [label=\"App\\nRoot (Transient)\", tooltip=\"graph::App\"];
+    n1 [label=\"Rc<Connection>\\nShared (Scoped)\", tooltip=\"alloc::rc::Rc<graph::Connection>\"];
+    n0 -> n1;
+    n2 [label=\"RefCell<Buffer>\\nOwned (Transient)\", tooltip=\"core::cell::RefCell<graph::Buffer>\"];
+    n0 -> n2;
+    n3 [label=\"Bound<dyn Store>\\nBound (Bound)\", tooltip=\"ioc::container::registry::Bound<dyn graph::Store>\", color=red];
+    n0 -> n3;
+}
+";
+
+    assert_eq!(expected, graph.to_dot());
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "hyper")]
+
+extern crate hyper;
+extern crate ioc;
+
+use ioc::hyper_scope::{current_scope, RequestHead, ScopeLayer};
+use ioc::prelude::*;
+
+use std::future::Future;
+use std::pin::{pin, Pin};
+use std::task::{Context, Poll, Waker};
+
+use hyper::service::Service;
+use hyper::{Request, Response};
+
+/// A response that's only ready on its second poll, once the service
+/// that made it has returned.
+struct Respond {
+    polled: bool,
+}
+
+impl Future for Respond {
+    type Output = Result<Response<String>, ()>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        if !self.polled {
+            self.polled = true;
+            cx.waker().wake_by_ref();
+
+            return Poll::Pending;
+        }
+
+        let scope = current_scope().expect("missing scope");
+        let head = scope.get_or_add::<RequestHead, _>();
+
+        Poll::Ready(Ok(Response::new(format!("{} {}", scope.correlation_id(), head.uri))))
+    }
+}
+
+struct Echo;
+
+impl Service<Request<()>> for Echo {
+    type Response = Response<String>;
+    type Error = ();
+    type Future = Respond;
+
+    fn call(&self, _: Request<()>) -> Respond {
+        Respond { polled: false }
+    }
+}
+
+#[test]
+fn current_scope_is_set_while_the_future_is_polled() {
+    let service = ScopeLayer::new().layer(Echo);
+
+    let req = Request::builder()
+        .uri("/orders")
+        .header("x-correlation-id", "req-1")
+        .body(())
+        .unwrap();
+
+    let mut future = pin!(service.call(req));
+    let mut cx = Context::from_waker(Waker::noop());
+
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+    assert!(current_scope().is_none());
+
+    match future.as_mut().poll(&mut cx) {
+        Poll::Ready(Ok(res)) => assert_eq!("req-1 /orders", res.body()),
+        _ => panic!("expected the response"),
+    }
+    assert!(current_scope().is_none());
+}
+
+#[test]
+fn each_request_gets_its_own_scope() {
+    let service = ScopeLayer::new().layer(Echo);
+    let mut cx = Context::from_waker(Waker::noop());
+
+    let mut first = pin!(service.call(Request::new(())));
+    let mut second = pin!(service.call(Request::new(())));
+
+    assert!(first.as_mut().poll(&mut cx).is_pending());
+    assert!(second.as_mut().poll(&mut cx).is_pending());
+
+    let first = first.as_mut().poll(&mut cx);
+    let second = second.as_mut().poll(&mut cx);
+
+    match (first, second) {
+        (Poll::Ready(Ok(first)), Poll::Ready(Ok(second))) => assert_ne!(first.body(), second.body()),
+        _ => panic!("expected the responses"),
+    }
+}
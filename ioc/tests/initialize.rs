@@ -0,0 +1,116 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+
+struct AuditLog {
+    subscribed: bool,
+    recorded: RefCell<Vec<&'static str>>,
+}
+impl<C> Resolvable<C> for AuditLog {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        AuditLog { subscribed: false, recorded: RefCell::new(Vec::new()) }
+    }
+}
+impl Initialize<Scoped> for AuditLog {
+    fn initialize(&mut self, container: &Scoped) {
+        let bus = container.get_or_add::<EventBus, _>();
+
+        bus.subscribe(container.get_weak::<AuditLog>());
+        self.subscribed = true;
+    }
+}
+
+// the bus only holds onto its subscribers weakly
+struct EventBus {
+    subscribers: RefCell<Vec<Weak<AuditLog>>>,
+}
+impl<C> Resolvable<C> for EventBus {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        EventBus { subscribers: RefCell::new(Vec::new()) }
+    }
+}
+impl EventBus {
+    fn subscribe(&self, subscriber: Weak<AuditLog>) {
+        self.subscribers.borrow_mut().push(subscriber);
+    }
+
+    fn publish(&self, event: &'static str) {
+        for subscriber in self.subscribers.borrow().iter().filter_map(Weak::upgrade) {
+            subscriber.recorded.borrow_mut().push(event);
+        }
+    }
+}
+
+#[test]
+fn cached_value_is_the_initialized_one() {
+    let mut registry = Registry::new();
+    registry.initializable::<AuditLog>();
+
+    let scope = Scoped::new().with_registry(Rc::new(registry));
+
+    let constructed = scope.get_or_add::<AuditLog, _>();
+    let cached = scope.get_or_add::<AuditLog, _>();
+
+    assert!(constructed.subscribed);
+    assert!(Rc::ptr_eq(&constructed, &cached));
+}
+
+#[test]
+fn initializer_registers_the_value_with_another_service() {
+    let mut registry = Registry::new();
+    registry.initializable::<AuditLog>();
+
+    let scope = Scoped::new().with_registry(Rc::new(registry));
+
+    let log = scope.get_or_add::<AuditLog, _>();
+    let bus = scope.get_or_add::<EventBus, _>();
+
+    bus.publish("renamed");
+
+    assert_eq!(vec!["renamed"], *log.recorded.borrow());
+}
+
+#[test]
+fn values_are_not_initialized_without_registering() {
+    let scope = Scoped::new();
+
+    let log = scope.get_or_add::<AuditLog, _>();
+
+    assert!(!log.subscribed);
+}
+
+#[test]
+fn initializer_runs_once_per_scope() {
+    thread_local!(static INITIALIZED: Cell<usize> = const { Cell::new(0) });
+
+    struct Counted;
+    impl<C> Resolvable<C> for Counted {
+        type Dependency = ();
+
+        fn resolve(_: Self::Dependency) -> Self {
+            Counted
+        }
+    }
+    impl Initialize<Scoped> for Counted {
+        fn initialize(&mut self, _: &Scoped) {
+            INITIALIZED.with(|initialized| initialized.set(initialized.get() + 1));
+        }
+    }
+
+    let mut registry = Registry::new();
+    registry.initializable::<Counted>();
+
+    let scope = Scoped::new().with_registry(Rc::new(registry));
+
+    scope.get_or_add::<Counted, _>();
+    scope.get_or_add::<Counted, _>();
+
+    assert_eq!(1, INITIALIZED.with(Cell::get));
+}
@@ -0,0 +1,137 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::{Error, Resolution, Veto};
+
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+struct Db;
+
+impl<C> Resolvable<C> for Db {
+    type Dependency = ();
+
+    fn resolve(_: ()) -> Self {
+        Db
+    }
+}
+
+struct Handler {
+    _db: Rc<Db>,
+}
+
+impl<C> Resolvable<C> for Handler
+    where C: ScopedContainer
+{
+    type Dependency = Rc<Db>;
+
+    fn resolve(db: Self::Dependency) -> Self {
+        Handler { _db: db }
+    }
+}
+
+struct App {
+    _handler: Rc<Handler>,
+}
+
+impl<C> Resolvable<C> for App
+    where C: ScopedContainer
+{
+    type Dependency = Rc<Handler>;
+
+    fn resolve(handler: Self::Dependency) -> Self {
+        App { _handler: handler }
+    }
+}
+
+/// The resolutions seen by hooks.
+#[derive(Default, Clone)]
+struct Log(Rc<RefCell<Vec<String>>>);
+
+impl Log {
+    fn record(&self, hook: &'static str, resolution: &Resolution) {
+        self.0.borrow_mut().push(format!("{} {} dependent={:?} cached={}",
+                                         hook,
+                                         resolution.type_name,
+                                         resolution.dependent,
+                                         resolution.cached));
+    }
+}
+
+#[test]
+fn hooks_run_around_each_shared_dependency() {
+    let log = Log::default();
+
+    let scope = Scoped::new()
+        .on_resolving({
+            let log = log.clone();
+            move |resolution| {
+                log.record("resolving", resolution);
+                Ok(())
+            }
+        })
+        .on_resolved({
+            let log = log.clone();
+            move |resolution| log.record("resolved", resolution)
+        });
+
+    scope.get_or_add::<Handler, _>();
+    scope.get_or_add::<Db, _>();
+
+    assert_eq!(vec![
+                   "resolving intercept::Handler dependent=None cached=false",
+                   "resolving intercept::Db dependent=Some(\"intercept::Handler\") cached=false",
+                   "resolved intercept::Db dependent=Some(\"intercept::Handler\") cached=false",
+                   "resolved intercept::Handler dependent=None cached=false",
+                   "resolving intercept::Db dependent=None cached=true",
+                   "resolved intercept::Db dependent=None cached=true",
+               ],
+               *log.0.borrow());
+}
+
+#[test]
+fn resolution_has_the_type_id() {
+    let ids = Rc::new(RefCell::new(Vec::new()));
+
+    let scope = Scoped::new().on_resolved({
+        let ids = ids.clone();
+        move |resolution| ids.borrow_mut().push(resolution.type_id)
+    });
+
+    scope.get_or_add::<Db, _>();
+
+    assert_eq!(vec![TypeId::of::<Db>()], *ids.borrow());
+}
+
+#[test]
+fn vetoed_dependency_is_an_error() {
+    let scope = Scoped::new().on_resolving(|resolution| {
+        if resolution.type_id == TypeId::of::<Db>() && resolution.dependent.is_some() {
+            Err(Veto::new("handlers can't use the database directly"))
+        } else {
+            Ok(())
+        }
+    });
+
+    match scope.try_resolve::<_, App>() {
+        Err(Error::Vetoed { type_name, reason, .. }) => {
+            assert_eq!("intercept::Db", type_name);
+            assert_eq!("handlers can't use the database directly", reason);
+        }
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("expected a veto"),
+    }
+
+    // nothing was cached for the vetoed dependency, and it can be resolved on its own
+    assert!(scope.get_weak::<Handler>().upgrade().is_none());
+    scope.get_or_add::<Db, _>();
+}
+
+#[test]
+fn veto_has_a_reason() {
+    let veto = Veto::new("not allowed");
+
+    assert_eq!("not allowed", veto.reason());
+    assert_eq!("not allowed", veto.to_string());
+}
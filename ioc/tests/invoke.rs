@@ -0,0 +1,72 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Default)]
+struct Counter {
+    count: usize,
+}
+
+impl<C> Resolvable<C> for Counter {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Counter::default()
+    }
+}
+
+struct Config {
+    name: &'static str,
+}
+
+impl<C> Resolvable<C> for Config {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Config { name: "app" }
+    }
+}
+
+fn describe(config: Rc<Config>, counter: Rc<RefCell<Counter>>) -> String {
+    format!("{} {}", config.name, counter.borrow().count)
+}
+
+#[test]
+fn invoke_resolves_each_argument() {
+    let scope = Scoped::new();
+
+    scope.invoke(|counter: Rc<RefCell<Counter>>| counter.borrow_mut().count += 1);
+    scope.invoke(|counter: Rc<RefCell<Counter>>| counter.borrow_mut().count += 1);
+
+    assert_eq!("app 2", scope.invoke(describe));
+}
+
+#[test]
+fn invoke_resolves_owned_arguments_each_time() {
+    let scope = Scoped::new();
+
+    let first = scope.invoke(|mut counter: RefCell<Counter>| {
+        counter.get_mut().count += 1;
+        counter.into_inner().count
+    });
+    let second = scope.invoke(|counter: RefCell<Counter>| counter.into_inner().count);
+
+    assert_eq!((1, 0), (first, second));
+}
+
+#[test]
+fn invoke_without_arguments() {
+    assert_eq!(42, BasicContainer.invoke(|| 42));
+}
+
+#[test]
+fn invoke_moves_captured_values_in() {
+    let name = String::from("captured");
+
+    let described = Scoped::new().invoke(move |config: Rc<Config>| format!("{} {}", name, config.name));
+
+    assert_eq!("captured app", described);
+}
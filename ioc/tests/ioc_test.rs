@@ -0,0 +1,69 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+
+trait Greeter {
+    fn greet(&self) -> String;
+}
+
+struct English;
+
+impl Greeter for English {
+    fn greet(&self) -> String {
+        "hello".to_owned()
+    }
+}
+
+fn greetings(registry: &mut Registry<Scoped>) {
+    registry.bind::<dyn Greeter, _>(|_| Rc::new(English));
+}
+
+#[derive(Default)]
+struct Clock {
+    now: u64,
+}
+
+impl<C> Resolvable<C> for Clock {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Clock::default()
+    }
+}
+
+struct Orders {
+    clock: Rc<Clock>,
+}
+
+impl Resolvable<Scoped> for Orders {
+    type Dependency = Rc<Clock>;
+
+    fn resolve(clock: Self::Dependency) -> Self {
+        Orders { clock }
+    }
+}
+
+#[ioc_test(modules(greetings))]
+fn parameters_are_resolved_from_modules(greeter: Bound<dyn Greeter>) {
+    assert_eq!("hello", greeter.greet());
+}
+
+#[ioc_test(overrides(Clock { now: 42 }))]
+fn overrides_replace_shared_dependencies(orders: Transient<Orders>, clock: Rc<Clock>) {
+    assert_eq!(42, orders.clock.now);
+    assert!(Rc::ptr_eq(&orders.clock, &clock));
+}
+
+#[ioc_test]
+fn parameters_share_the_tests_scope(a: Rc<Orders>, b: Rc<Orders>) {
+    assert!(Rc::ptr_eq(&a, &b));
+    assert_eq!(0, a.clock.now);
+}
+
+#[ioc_test(modules(greetings))]
+#[should_panic(expected = "failed on purpose")]
+fn attributes_are_kept(_greeter: Bound<dyn Greeter>) {
+    panic!("failed on purpose");
+}
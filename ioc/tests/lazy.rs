@@ -0,0 +1,92 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+thread_local!(static CONNECTED: Cell<usize> = const { Cell::new(0) });
+
+#[derive(Debug)]
+struct Connection;
+
+impl<C> Resolvable<C> for Connection {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        CONNECTED.with(|connected| connected.set(connected.get() + 1));
+
+        Connection
+    }
+}
+
+struct Repository {
+    connection: Rc<Connection>,
+}
+
+impl Resolvable<Scoped> for Repository {
+    type Dependency = Rc<Connection>;
+
+    fn resolve(connection: Self::Dependency) -> Self {
+        Repository { connection }
+    }
+}
+
+struct Handler {
+    repository: Lazy<Repository>,
+}
+
+impl Resolvable<Scoped> for Handler {
+    type Dependency = Lazy<Repository>;
+
+    fn resolve(repository: Self::Dependency) -> Self {
+        Handler { repository }
+    }
+}
+
+#[test]
+fn lazy_dependency_is_resolved_on_first_access() {
+    CONNECTED.with(|connected| connected.set(0));
+
+    let scope = Scoped::new();
+    let handler = scope.resolve::<_, Handler>();
+
+    assert!(!handler.repository.is_resolved());
+    assert_eq!(0, CONNECTED.with(Cell::get));
+
+    let first = handler.repository.get() as *const Repository;
+    let second = handler.repository.get() as *const Repository;
+
+    assert!(handler.repository.is_resolved());
+    assert_eq!(first, second);
+    assert_eq!(1, CONNECTED.with(Cell::get));
+}
+
+#[test]
+fn lazy_dependency_shares_its_scope() {
+    let scope = Scoped::new();
+    let handler = scope.resolve::<_, Handler>();
+
+    let connection = scope.get_or_add::<Connection, _>();
+
+    assert!(Rc::ptr_eq(&connection, &handler.repository.get().connection));
+}
+
+#[test]
+#[should_panic(expected = "the captured scope has already ended")]
+fn lazy_dependency_accessed_after_its_scope_ends_panics() {
+    let handler = Scoped::new().resolve::<_, Handler>();
+
+    handler.repository.get();
+}
+
+#[test]
+fn lazy_dependency_debug_shows_whether_its_resolved() {
+    let lazy = Lazy::new(|| Connection);
+
+    assert_eq!("Lazy(<unresolved>)", format!("{:?}", lazy));
+
+    lazy.get();
+
+    assert_eq!("Lazy(Connection)", format!("{:?}", lazy));
+}
@@ -0,0 +1,163 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::time::Duration;
+
+#[derive(Default)]
+struct Events(RefCell<Vec<String>>);
+
+resolvable! {
+    default Events;
+}
+
+impl Events {
+    fn push(&self, event: &str) {
+        self.0.borrow_mut().push(event.to_owned());
+    }
+
+    fn take(&self) -> Vec<String> {
+        self.0.borrow_mut().drain(..).collect()
+    }
+}
+
+trait Source {}
+
+struct Pool {
+    events: Rc<Events>,
+}
+
+impl Source for Pool {}
+
+impl Resolvable<Scoped> for Pool {
+    type Dependency = Rc<Events>;
+
+    fn resolve(events: Self::Dependency) -> Self {
+        Pool { events }
+    }
+}
+
+impl Startable for Pool {
+    fn start(&self) {
+        self.events.push("start pool");
+    }
+
+    fn stop(&self) {
+        self.events.push("stop pool");
+    }
+}
+
+// depends on the pool through a runtime binding, so only the order the
+// scope observed puts the pool first
+struct Consumer {
+    events: Rc<Events>,
+    _source: Bound<dyn Source>,
+}
+
+impl Resolvable<Scoped> for Consumer {
+    type Dependency = (Rc<Events>, Bound<dyn Source>);
+
+    fn resolve((events, _source): Self::Dependency) -> Self {
+        Consumer { events, _source }
+    }
+}
+
+impl Startable for Consumer {
+    fn start(&self) {
+        self.events.push("start consumer");
+    }
+
+    fn stop(&self) {
+        self.events.push("stop consumer");
+    }
+}
+
+struct Faulty {
+    _pool: Rc<Pool>,
+}
+
+impl Resolvable<Scoped> for Faulty {
+    type Dependency = Rc<Pool>;
+
+    fn resolve(_pool: Self::Dependency) -> Self {
+        Faulty { _pool }
+    }
+}
+
+impl Startable for Faulty {
+    fn start(&self) {
+        panic!("couldn't start");
+    }
+}
+
+fn scope(module: impl FnOnce(&mut Registry<Scoped>)) -> Scoped {
+    let mut registry = Registry::new();
+    registry.alias::<dyn Source, Pool, _, _>(|pool| pool);
+    module(&mut registry);
+
+    Scoped::new().with_registry(Rc::new(registry))
+}
+
+#[test]
+fn services_start_in_dependency_order_and_stop_in_reverse() {
+    let scope = scope(|registry| {
+        registry.startable::<Consumer, _>().startable::<Pool, _>();
+    });
+    let events = scope.get_or_add::<Events, _>();
+
+    let started = scope.start_all();
+    assert_eq!(vec!["start pool", "start consumer"], events.take());
+
+    let report = started.shutdown();
+    assert_eq!(vec!["stop consumer", "stop pool"], events.take());
+
+    let stopped: Vec<_> = report.stopped().iter().map(|stopped| stopped.type_name).collect();
+    assert_eq!(vec![std::any::type_name::<Consumer>(), std::any::type_name::<Pool>()], stopped);
+}
+
+#[test]
+fn dropping_the_handle_stops_services() {
+    let scope = scope(|registry| {
+        registry.startable::<Pool, _>();
+    });
+    let events = scope.get_or_add::<Events, _>();
+
+    drop(scope.start_all());
+
+    assert_eq!(vec!["start pool", "stop pool"], events.take());
+}
+
+#[test]
+fn started_services_are_stopped_when_a_later_start_panics() {
+    let scope = scope(|registry| {
+        registry.startable::<Faulty, _>().startable::<Pool, _>();
+    });
+    let events = scope.get_or_add::<Events, _>();
+
+    let started = panic::catch_unwind(AssertUnwindSafe(|| scope.start_all()));
+
+    assert!(started.is_err());
+    assert_eq!(vec!["start pool", "stop pool"], events.take());
+}
+
+#[test]
+fn timeouts_for_a_service_beat_the_default() {
+    let scope = scope(|registry| {
+        registry.startable::<Consumer, _>().startable::<Pool, _>();
+    });
+
+    // the order they're given in doesn't matter
+    let report = scope
+        .start_all()
+        .with_timeout_for::<Consumer>(Duration::from_secs(30))
+        .with_timeout(Duration::from_secs(5))
+        .shutdown();
+
+    let timeouts: Vec<_> = report.stopped().iter().map(|stopped| stopped.timeout).collect();
+    assert_eq!(vec![Some(Duration::from_secs(30)), Some(Duration::from_secs(5))], timeouts);
+    assert_eq!(0, report.timed_out().count());
+}
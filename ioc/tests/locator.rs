@@ -0,0 +1,76 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::{locator_report, reset_locator_report, Locator};
+
+use std::rc::Rc;
+
+struct Db;
+
+impl<C> Resolvable<C> for Db {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Db
+    }
+}
+
+struct Mailer;
+
+impl<C> Resolvable<C> for Mailer {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Mailer
+    }
+}
+
+struct LegacyHandler {
+    locator: Locator<Scoped>,
+}
+
+impl Resolvable<Scoped> for LegacyHandler {
+    type Dependency = Locator<Scoped>;
+
+    fn resolve(locator: Self::Dependency) -> Self {
+        LegacyHandler { locator }
+    }
+}
+
+// the report is process-wide, so everything that reads it is in one test
+#[test]
+fn locator_resolves_from_its_scope_and_records_where() {
+    reset_locator_report();
+
+    let scope = Scoped::new();
+    let handler = scope.resolve::<_, LegacyHandler>();
+
+    let mut dbs = Vec::new();
+    for _ in 0..2 {
+        dbs.push(handler.locator.get::<Rc<Db>>());
+    }
+    let line = line!() - 2;
+
+    handler.locator.resolve::<_, Mailer>();
+
+    assert!(Rc::ptr_eq(&dbs[0], &scope.get_or_add::<Db, _>()));
+
+    let report = locator_report();
+    let uses: Vec<_> = report.uses().iter().map(|use_| (use_.type_name, use_.location.line(), use_.count)).collect();
+
+    assert_eq!(
+        vec![("alloc::rc::Rc<locator::Db>", line, 2), ("locator::Mailer", line + 4, 1)],
+        uses
+    );
+    assert_eq!(
+        format!(
+            "`Rc<Db>` resolved 2 time(s) at {}\n`Mailer` resolved 1 time(s) at {}\n",
+            report.uses()[0].location,
+            report.uses()[1].location
+        ),
+        report.to_string()
+    );
+
+    reset_locator_report();
+    assert!(locator_report().is_empty());
+}
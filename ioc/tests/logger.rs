@@ -0,0 +1,58 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::{Logger, MemoryLogger, ScopeContext};
+
+use std::rc::Rc;
+
+fn scope(log: &MemoryLogger) -> Scoped {
+    let mut registry = Registry::new();
+    registry.bind_logger(log.clone());
+
+    Scoped::new().with_registry(Rc::new(registry))
+}
+
+fn fields(log: &MemoryLogger) -> Vec<Vec<(&'static str, String)>> {
+    log.records().into_iter().map(|record| record.fields).collect()
+}
+
+#[test]
+fn records_include_the_scope_context() {
+    let log = MemoryLogger::new();
+    let scope = scope(&log).with_context(ScopeContext::new().with("request_id", 42));
+
+    let logger = Bound::<dyn Logger>::resolve_from_container(&scope);
+    logger.info("renamed");
+
+    assert_eq!(vec![vec![("request_id", "42".to_owned())]], fields(&log));
+}
+
+#[test]
+fn fields_set_part_way_through_are_included_afterwards() {
+    let log = MemoryLogger::new();
+    let scope = scope(&log).with_context(ScopeContext::new().with("request_id", 42));
+
+    let logger = Bound::<dyn Logger>::resolve_from_container(&scope);
+    logger.info("received");
+
+    Context::<ScopeContext>::resolve_from_container(&scope).set("user", "ashley");
+    logger.info("authenticated");
+
+    assert_eq!(
+        vec![
+            vec![("request_id", "42".to_owned())],
+            vec![("request_id", "42".to_owned()), ("user", "ashley".to_owned())],
+        ],
+        fields(&log)
+    );
+}
+
+#[test]
+fn scopes_without_context_log_records_as_they_are() {
+    let log = MemoryLogger::new();
+    let scope = scope(&log);
+
+    Bound::<dyn Logger>::resolve_from_container(&scope).warn("no context");
+
+    assert_eq!(vec![Vec::<(&'static str, String)>::new()], fields(&log));
+}
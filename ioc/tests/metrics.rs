@@ -0,0 +1,115 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+struct Pool;
+
+impl<C> Resolvable<C> for Pool {
+    type Dependency = ();
+
+    fn resolve(_: ()) -> Self {
+        thread::sleep(Duration::from_millis(20));
+
+        Pool
+    }
+}
+
+struct Repository {
+    _pool: Rc<Pool>,
+}
+
+impl<C> Resolvable<C> for Repository
+    where C: ScopedContainer
+{
+    type Dependency = Rc<Pool>;
+
+    fn resolve(pool: Self::Dependency) -> Self {
+        Repository { _pool: pool }
+    }
+}
+
+struct Settings;
+
+impl<C> Resolvable<C> for Settings {
+    type Dependency = ();
+
+    fn resolve(_: ()) -> Self {
+        Settings
+    }
+}
+
+#[test]
+fn metrics_count_resolutions_across_scopes() {
+    let metrics = Rc::new(Metrics::new());
+
+    for _ in 0..2 {
+        let scope = Scoped::new().with_metrics(metrics.clone());
+
+        scope.get_or_add::<Repository, _>();
+        scope.get_or_add::<Pool, _>();
+    }
+
+    let snapshot = metrics.snapshot();
+
+    assert_eq!(vec!["metrics::Pool", "metrics::Repository"],
+               snapshot.types().iter().map(|ty| ty.type_name).collect::<Vec<_>>());
+
+    let pool = snapshot.get("metrics::Pool").unwrap();
+
+    assert_eq!(4, pool.resolutions);
+    assert_eq!(2, pool.hits);
+    assert_eq!(0.5, pool.hit_ratio());
+    assert_eq!(2, pool.construction.count());
+
+    let repository = snapshot.get("metrics::Repository").unwrap();
+
+    assert_eq!(2, repository.resolutions);
+    assert_eq!(0.0, repository.hit_ratio());
+    assert!(snapshot.get("metrics::Settings").is_none());
+}
+
+#[test]
+fn construction_is_a_histogram() {
+    let metrics = Rc::new(Metrics::new());
+
+    Scoped::new().with_metrics(metrics.clone()).get_or_add::<Repository, _>();
+
+    let snapshot = metrics.snapshot();
+    let pool = &snapshot.get("metrics::Pool").unwrap().construction;
+
+    assert_eq!(1, pool.count());
+    assert!(pool.sum() >= Duration::from_millis(20));
+    assert_eq!(pool.sum(), pool.mean());
+
+    // buckets are cumulative
+    let buckets: Vec<_> = pool.buckets().collect();
+
+    assert_eq!(7, buckets.len());
+    assert_eq!((Duration::from_millis(10), 0), buckets[4]);
+    assert_eq!((Duration::from_secs(1), 1), buckets[6]);
+
+    // the repository's construction doesn't include the pool's
+    let repository = &snapshot.get("metrics::Repository").unwrap().construction;
+
+    assert!(repository.sum() < pool.sum());
+}
+
+#[test]
+fn inserted_values_are_only_resolved() {
+    let metrics = Rc::new(Metrics::new());
+    let scope = Scoped::new().with_metrics(metrics.clone());
+
+    scope.insert(Settings).unwrap();
+    scope.get_or_add::<Settings, _>();
+
+    assert_eq!("metrics::Settings: 1 resolutions, 100% cached, 0 constructed (mean: 0ns)\n",
+               metrics.snapshot().to_string());
+
+    metrics.reset();
+
+    assert!(metrics.snapshot().types().is_empty());
+}
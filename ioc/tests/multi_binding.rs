@@ -0,0 +1,140 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+trait Exporter {
+    fn format(&self) -> &'static str;
+}
+
+struct Csv;
+struct Json;
+
+impl Exporter for Csv {
+    fn format(&self) -> &'static str {
+        "csv"
+    }
+}
+
+impl Exporter for Json {
+    fn format(&self) -> &'static str {
+        "json"
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Format {
+    Csv,
+    Json,
+    Xml,
+}
+
+fn scope(registry: Registry<Scoped>) -> Scoped {
+    Scoped::new().with_registry(Rc::new(registry))
+}
+
+#[test]
+fn keyed_all_looks_up_bindings_by_key() {
+    let mut registry = Registry::new();
+    registry
+        .bind_keyed::<Format, dyn Exporter, _>(Format::Csv, |_| Rc::new(Json))
+        .bind_keyed::<Format, dyn Exporter, _>(Format::Json, |_| Rc::new(Json))
+        // replaces the first binding under the same key
+        .bind_keyed::<Format, dyn Exporter, _>(Format::Csv, |_| Rc::new(Csv));
+
+    let exporters = KeyedAll::<Format, dyn Exporter>::resolve_from_container(&scope(registry));
+
+    assert_eq!(2, exporters.len());
+    assert_eq!("csv", exporters[&Format::Csv].format());
+    assert_eq!("json", exporters[&Format::Json].format());
+    assert!(!exporters.contains_key(&Format::Xml));
+}
+
+#[test]
+fn keyed_all_is_empty_without_bindings() {
+    let exporters = KeyedAll::<Format, dyn Exporter>::resolve_from_container(&scope(Registry::new()));
+
+    assert!(exporters.is_empty());
+}
+
+#[test]
+fn resolve_iter_only_resolves_what_is_consumed() {
+    let resolved = Rc::new(Cell::new(0));
+
+    let mut registry = Registry::new();
+    for &format in &[Format::Csv, Format::Json, Format::Xml] {
+        let resolved = resolved.clone();
+
+        registry.bind_all::<dyn Exporter, _>(move |_| {
+            resolved.set(resolved.get() + 1);
+
+            match format {
+                Format::Json => Rc::new(Json),
+                _ => Rc::new(Csv) as Rc<dyn Exporter>,
+            }
+        });
+    }
+
+    let scope = scope(registry);
+
+    let mut exporters = ResolveIter::<dyn Exporter>::resolve_from_container(&scope);
+    assert_eq!(3, exporters.len());
+    assert_eq!(0, resolved.get());
+
+    let json = exporters.find(|exporter| exporter.format() == "json");
+    assert!(json.is_some());
+    assert_eq!(2, resolved.get());
+    assert_eq!(1, exporters.len());
+}
+
+trait Reader {
+    fn read(&self) -> usize;
+}
+
+trait Writer {
+    fn write(&self, n: usize);
+}
+
+#[derive(Default)]
+struct FileStore {
+    written: Cell<usize>,
+}
+
+resolvable! {
+    default FileStore;
+}
+
+impl Reader for FileStore {
+    fn read(&self) -> usize {
+        self.written.get()
+    }
+}
+
+impl Writer for FileStore {
+    fn write(&self, n: usize) {
+        self.written.set(n);
+    }
+}
+
+#[test]
+fn aliases_share_one_instance() {
+    let mut registry = Registry::new();
+    registry
+        .alias::<dyn Reader, FileStore, _, _>(|store| store)
+        .alias::<dyn Writer, FileStore, _, _>(|store| store);
+
+    let scope = scope(registry);
+
+    let reader = Bound::<dyn Reader>::resolve_from_container(&scope).into_inner();
+    let writer = Bound::<dyn Writer>::resolve_from_container(&scope).into_inner();
+    let store = scope.get_or_add::<FileStore, _>();
+
+    assert_eq!(Rc::as_ptr(&store) as *const (), Rc::as_ptr(&reader) as *const ());
+    assert_eq!(Rc::as_ptr(&store) as *const (), Rc::as_ptr(&writer) as *const ());
+
+    writer.write(42);
+    assert_eq!(42, reader.read());
+}
@@ -0,0 +1,92 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::{Error, TypeName};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+mod app {
+    pub mod store {
+        pub struct Transaction;
+    }
+
+    pub struct Service<T>(pub T);
+}
+
+#[test]
+fn short_names_drop_module_paths() {
+    let name = TypeName::of::<app::store::Transaction>();
+
+    assert_eq!("names::app::store::Transaction", name.full());
+    assert_eq!("Transaction", name.short());
+}
+
+#[test]
+fn short_names_drop_module_paths_in_generics() {
+    assert_eq!("Rc<RefCell<Transaction>>", TypeName::of::<Rc<RefCell<app::store::Transaction>>>().short());
+    assert_eq!(
+        "HashMap<String, Vec<Service<Transaction>>>",
+        TypeName::of::<HashMap<String, Vec<app::Service<app::store::Transaction>>>>().short()
+    );
+}
+
+#[test]
+fn short_names_keep_trait_objects_tuples_and_references() {
+    assert_eq!(
+        "dyn Fn(usize) -> Option<Transaction>",
+        TypeName::of::<dyn Fn(usize) -> Option<app::store::Transaction>>().short()
+    );
+    assert_eq!("(Transaction, [u8; 4])", TypeName::of::<(app::store::Transaction, [u8; 4])>().short());
+    assert_eq!("&mut Transaction", TypeName::of::<&mut app::store::Transaction>().short());
+}
+
+#[test]
+fn short_names_of_closures() {
+    fn name_of<F>(_: &F) -> TypeName {
+        TypeName::of::<F>()
+    }
+
+    let closure = || ();
+    let name = name_of(&closure);
+
+    assert_eq!("names::short_names_of_closures::{{closure}}", name.full());
+    assert_eq!("{{closure}}", name.short());
+}
+
+#[test]
+fn short_names_are_shared() {
+    let first = TypeName::of::<app::Service<app::store::Transaction>>().short();
+    let second = TypeName::of::<app::Service<app::store::Transaction>>().short();
+
+    assert_eq!(first.as_ptr(), second.as_ptr());
+}
+
+#[test]
+fn errors_are_displayed_with_short_names() {
+    trait Store {}
+
+    struct Service;
+    impl<C> Resolvable<C> for Service
+        where C: RegisteredContainer + 'static
+    {
+        type Dependency = Bound<dyn Store>;
+
+        fn resolve(_: Self::Dependency) -> Self {
+            Service
+        }
+    }
+
+    match Scoped::new().try_resolve::<_, Service>() {
+        Err(err @ Error::MissingBinding { .. }) => {
+            assert_eq!(
+                "there's no binding for `dyn Store` (required by Service -> Bound<dyn Store>)",
+                err.to_string()
+            );
+            assert_eq!(Some(&"names::errors_are_displayed_with_short_names::Service"), err.path().first());
+        }
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("expected a missing binding"),
+    }
+}
@@ -0,0 +1,157 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+trait Entity {
+    fn table() -> &'static str;
+}
+
+struct User;
+struct Order;
+
+impl Entity for User {
+    fn table() -> &'static str {
+        "users"
+    }
+}
+
+impl Entity for Order {
+    fn table() -> &'static str {
+        "orders"
+    }
+}
+
+trait Repository<T> {
+    fn table(&self) -> &'static str;
+    fn connection(&self) -> &Rc<Connection>;
+}
+
+#[derive(Default)]
+struct Connection {
+    queries: RefCell<Vec<&'static str>>,
+}
+
+impl<C> Resolvable<C> for Connection {
+    type Dependency = ();
+
+    fn resolve(_: ()) -> Self {
+        Connection::default()
+    }
+}
+
+struct SqlRepository<T> {
+    connection: Rc<Connection>,
+    _entity: PhantomData<T>,
+}
+
+impl<C, T> Resolvable<C> for SqlRepository<T> {
+    type Dependency = Rc<Connection>;
+
+    fn resolve(connection: Self::Dependency) -> Self {
+        SqlRepository {
+            connection,
+            _entity: PhantomData,
+        }
+    }
+}
+
+impl<T> Repository<T> for SqlRepository<T>
+    where T: Entity
+{
+    fn table(&self) -> &'static str {
+        self.connection.queries.borrow_mut().push(T::table());
+
+        T::table()
+    }
+
+    fn connection(&self) -> &Rc<Connection> {
+        &self.connection
+    }
+}
+
+trait Cache<K, V> {
+    fn len(&self) -> usize;
+}
+
+struct MemoryCache<K, V> {
+    entries: HashMap<K, V>,
+}
+
+impl<C, K, V> Resolvable<C> for MemoryCache<K, V> {
+    type Dependency = ();
+
+    fn resolve(_: ()) -> Self {
+        MemoryCache { entries: HashMap::new() }
+    }
+}
+
+impl<K, V> Cache<K, V> for MemoryCache<K, V> {
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+open_binding! {
+    impl<T> dyn Repository<T> => SqlRepository<T> where T: Entity + 'static;
+    impl<K, V> dyn Cache<K, V> => MemoryCache<K, V> where K: 'static, V: 'static;
+}
+
+#[test]
+fn open_binding_resolves_an_implementation_for_any_type_argument() {
+    let scope = Scoped::new();
+
+    let users: Open<dyn Repository<User>> = scope.resolve();
+    let orders: Open<dyn Repository<Order>> = scope.resolve();
+
+    assert_eq!("users", users.table());
+    assert_eq!("orders", orders.table());
+
+    // the implementations share the scope's dependencies
+    assert!(Rc::ptr_eq(users.connection(), orders.connection()));
+    assert_eq!(vec!["users", "orders"], *users.connection().queries.borrow());
+}
+
+#[test]
+fn open_binding_resolves_a_new_implementation_each_time() {
+    let scope = Scoped::new();
+
+    let a = Open::<dyn Repository<User>>::resolve_from_container(&scope).into_inner();
+    let b = Open::<dyn Repository<User>>::resolve_from_container(&scope).into_inner();
+
+    assert!(!Rc::ptr_eq(&a, &b));
+    assert!(Rc::ptr_eq(a.connection(), b.connection()));
+}
+
+#[test]
+fn open_binding_with_several_type_parameters() {
+    let cache: Open<dyn Cache<String, usize>> = Scoped::new().resolve();
+
+    assert_eq!(0, cache.len());
+}
+
+#[test]
+fn open_binding_is_a_dependency_of_other_types() {
+    struct Checkout {
+        orders: Open<dyn Repository<Order>>,
+    }
+
+    impl<C> Resolvable<C> for Checkout
+        where C: Container
+    {
+        type Dependency = Open<dyn Repository<Order>>;
+
+        fn resolve(orders: Self::Dependency) -> Self {
+            Checkout { orders }
+        }
+    }
+
+    let checkout: Checkout = Scoped::new().resolve();
+
+    assert_eq!("orders", checkout.orders.table());
+}
@@ -0,0 +1,117 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::testing::TestScope;
+
+use std::rc::Rc;
+
+trait Clock {
+    fn now(&self) -> u64;
+}
+
+struct SystemClock;
+struct FakeClock(u64);
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        1
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> u64 {
+        self.0
+    }
+}
+
+struct Greeting(&'static str);
+
+impl<C> Resolvable<C> for Greeting {
+    type Dependency = ();
+
+    fn resolve(_: ()) -> Self {
+        Greeting("hello")
+    }
+}
+
+struct Stamp {
+    clock: Bound<dyn Clock>,
+    greeting: Rc<Greeting>,
+}
+
+impl<C> Resolvable<C> for Stamp
+    where C: RegisteredContainer + ScopedContainer + 'static
+{
+    type Dependency = (Bound<dyn Clock>, Rc<Greeting>);
+
+    fn resolve((clock, greeting): Self::Dependency) -> Self {
+        Stamp { clock, greeting }
+    }
+}
+
+fn clock_module(registry: &mut Registry<Scoped>) {
+    registry.bind::<dyn Clock, _>(|_| Rc::new(SystemClock));
+}
+
+#[test]
+fn override_replaces_a_binding() {
+    let mut registry = Registry::new();
+    registry.add_module(clock_module);
+
+    let scope = Scoped::new().with_registry(Rc::new(registry));
+    scope.override_with::<dyn Clock>(Rc::new(FakeClock(42)));
+
+    let stamp: Stamp = scope.resolve();
+
+    assert_eq!(42, stamp.clock.now());
+    assert_eq!("hello", stamp.greeting.0);
+}
+
+#[test]
+fn override_replaces_a_shared_dependency() {
+    let scope = Scoped::new();
+    scope.override_with(Rc::new(Greeting("hi")));
+
+    let greeting: Rc<Greeting> = scope.get_or_add();
+
+    assert_eq!("hi", greeting.0);
+
+    // the override is cached by the scope
+    assert!(Rc::ptr_eq(&greeting, &scope.get_or_add::<Greeting, _>()));
+}
+
+#[test]
+fn override_counts_as_a_binding_when_verifying() {
+    let scope = Scoped::new();
+
+    assert!(scope.verify::<_, Stamp>().is_err());
+
+    scope.override_with::<dyn Clock>(Rc::new(FakeClock(42)));
+
+    assert_eq!(Ok(()), scope.verify::<_, Stamp>());
+}
+
+#[test]
+fn test_scope_adds_modules_and_overrides() {
+    let scope = TestScope::new()
+        .with_module(clock_module)
+        .with_override(Rc::new(Greeting("hi")))
+        .build();
+
+    let stamp: Stamp = scope.resolve();
+
+    assert_eq!(1, stamp.clock.now());
+    assert_eq!("hi", stamp.greeting.0);
+}
+
+#[test]
+fn test_scope_overrides_a_module_binding() {
+    let scope = TestScope::new()
+        .with_module(clock_module)
+        .with_override::<dyn Clock>(Rc::new(FakeClock(7)))
+        .build();
+
+    let stamp: Stamp = scope.resolve();
+
+    assert_eq!(7, stamp.clock.now());
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "rayon")]
+
+extern crate ioc;
+extern crate rayon;
+
+use ioc::prelude::*;
+use ioc::Par;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The number of clients that have started connecting.
+static CONNECTING: AtomicUsize = AtomicUsize::new(0);
+
+/// Wait for every client to start connecting, which only happens if
+/// they're connecting at the same time.
+fn connect() -> bool {
+    CONNECTING.fetch_add(1, Ordering::SeqCst);
+
+    let started = Instant::now();
+    while started.elapsed() < Duration::from_secs(5) {
+        if CONNECTING.load(Ordering::SeqCst) == 3 {
+            return true;
+        }
+
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    false
+}
+
+macro_rules! client {
+    ($($name:ident),*) => {$(
+        struct $name {
+            parallel: bool,
+        }
+
+        impl<C> Resolvable<C> for $name {
+            type Dependency = ();
+
+            fn resolve(_: ()) -> Self {
+                $name { parallel: connect() }
+            }
+        }
+    )*};
+}
+
+client!(Billing, Search, Mail);
+
+struct Services {
+    billing: Arc<Billing>,
+    search: Arc<Search>,
+    mail: Arc<Mail>,
+}
+
+impl<C> Resolvable<C> for Services
+    where C: SyncScopedContainer + Sync
+{
+    type Dependency = Par<(Arc<Billing>, Arc<Search>, Arc<Mail>)>;
+
+    fn resolve(clients: Self::Dependency) -> Self {
+        let (billing, search, mail) = clients.into_inner();
+
+        Services { billing, search, mail }
+    }
+}
+
+#[test]
+fn par_resolves_members_in_parallel() {
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(3).build().unwrap();
+    let container = ArcContainer::new();
+
+    let services: Services = pool.install(|| container.resolve());
+
+    assert!(services.billing.parallel);
+    assert!(services.search.parallel);
+    assert!(services.mail.parallel);
+
+    // the members are shared by the container like any other `Arc<T>`
+    assert!(Arc::ptr_eq(&services.billing, &Arc::<Billing>::resolve_from_container(&container)));
+}
+
+struct Settings;
+
+impl<C> Resolvable<C> for Settings {
+    type Dependency = ();
+
+    fn resolve(_: ()) -> Self {
+        Settings
+    }
+}
+
+#[test]
+fn par_derefs_to_its_members() {
+    let scope = SyncScoped::new();
+
+    let par = Par::<(Arc<Settings>, Transient<Settings>)>::resolve_from_container(&scope);
+
+    assert!(Arc::ptr_eq(&par.0, &Arc::<Settings>::resolve_from_container(&scope)));
+}
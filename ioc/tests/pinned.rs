@@ -0,0 +1,69 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::Cell;
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::rc::Rc;
+
+/// A value that remembers its own address the first time it's used.
+#[derive(Debug)]
+struct Intrusive {
+    address: Cell<usize>,
+    _pinned: PhantomPinned,
+}
+
+impl Intrusive {
+    fn link(self: Pin<&Self>) {
+        let address = &*self as *const Intrusive as usize;
+
+        if self.address.get() == 0 {
+            self.address.set(address);
+        }
+
+        assert_eq!(self.address.get(), address, "the value moved");
+    }
+}
+
+impl<C> Resolvable<C> for Intrusive {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Intrusive {
+            address: Cell::new(0),
+            _pinned: PhantomPinned,
+        }
+    }
+}
+
+#[test]
+fn pinned_dependencies_are_shared_and_dont_move() {
+    let scope = Scoped::new();
+
+    let a = PinnedRc::<Intrusive>::resolve_from_container(&scope);
+    a.as_pin().link();
+
+    let b = scope.invoke(|b: PinnedRc<Intrusive>| b);
+    b.as_pin().link();
+
+    assert!(PinnedRc::ptr_eq(&a, &b));
+    assert!(PinnedRc::ptr_eq(&a, &a.clone()));
+}
+
+#[test]
+fn pinned_dependencies_are_separate_from_shared_ones() {
+    let scope = Scoped::new();
+
+    let pinned = PinnedRc::<Intrusive>::resolve_from_container(&scope);
+    let shared: Rc<Intrusive> = scope.get_or_add::<Intrusive, _>();
+
+    assert!(!std::ptr::eq(&*pinned, &*shared));
+}
+
+#[test]
+fn pinned_dependencies_are_debug() {
+    let pinned = PinnedRc::<Intrusive>::resolve_from_container(&Scoped::new());
+
+    assert!(format!("{:?}", pinned).starts_with("PinnedRc(Intrusive {"));
+}
@@ -0,0 +1,124 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::{Pool, Pooled};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A pool of numbered connections.
+struct DbPool {
+    idle: RefCell<Vec<usize>>,
+}
+
+impl DbPool {
+    fn new(size: usize) -> Self {
+        DbPool { idle: RefCell::new((0..size).rev().collect()) }
+    }
+
+    fn idle(&self) -> usize {
+        self.idle.borrow().len()
+    }
+}
+
+impl Pool for DbPool {
+    type Connection = usize;
+    type Error = &'static str;
+
+    fn check_out(&self) -> Result<usize, &'static str> {
+        self.idle.borrow_mut().pop().ok_or("the pool is exhausted")
+    }
+
+    fn check_in(&self, connection: usize) {
+        self.idle.borrow_mut().push(connection);
+    }
+}
+
+struct Repository {
+    conn: Rc<Pooled<DbPool>>,
+}
+
+impl Resolvable<Scoped> for Repository {
+    type Dependency = Rc<Pooled<DbPool>>;
+
+    fn resolve(conn: Self::Dependency) -> Self {
+        Repository { conn }
+    }
+}
+
+fn registry(size: usize) -> Rc<Registry<Scoped>> {
+    let mut registry = Registry::new();
+    registry.bind_pool(DbPool::new(size));
+
+    Rc::new(registry)
+}
+
+fn pool(scope: &Scoped) -> Rc<DbPool> {
+    Bound::<DbPool>::resolve_from_container(scope).into_inner()
+}
+
+#[test]
+fn scope_holds_one_connection_until_it_ends() {
+    let registry = registry(2);
+    let scope = Scoped::new().with_registry(registry.clone());
+    let pool = pool(&scope);
+
+    let a: Repository = scope.resolve();
+    let b: Repository = scope.resolve();
+
+    assert_eq!(0, **a.conn);
+    assert!(Rc::ptr_eq(&a.conn, &b.conn));
+    assert!(Rc::ptr_eq(&pool, a.conn.pool()));
+    assert_eq!(1, pool.idle());
+
+    // another scope checks out its own connection from the same pool
+    let other = Scoped::new().with_registry(registry);
+    let c: Repository = other.resolve();
+
+    assert_eq!(1, **c.conn);
+    assert_eq!(0, pool.idle());
+
+    drop((a, b));
+    drop(scope);
+
+    assert_eq!(1, pool.idle());
+}
+
+#[test]
+fn connection_is_checked_in_when_disposed_even_if_shared() {
+    let scope = Scoped::new().with_registry(registry(1));
+    let pool = pool(&scope);
+
+    let repository: Repository = scope.resolve();
+    assert!(scope.invalidate::<Pooled<DbPool>>());
+
+    // still shared by the repository, so the scope doesn't dispose it
+    assert_eq!(0, pool.idle());
+
+    drop(repository);
+
+    assert_eq!(1, pool.idle());
+}
+
+#[test]
+fn transient_connection_is_checked_in_when_dropped() {
+    let scope = Scoped::new().with_registry(registry(1));
+    let pool = pool(&scope);
+
+    {
+        let mut conn = Transient::<Pooled<DbPool>>::resolve_from_container(&scope);
+        **conn += 10;
+
+        assert_eq!(0, pool.idle());
+    }
+
+    assert_eq!(vec![10], *pool.idle.borrow());
+}
+
+#[test]
+#[should_panic(expected = "couldn't check out a connection from `pool::DbPool`: the pool is exhausted")]
+fn exhausted_pool_panics() {
+    let scope = Scoped::new().with_registry(registry(0));
+
+    scope.resolve::<_, Repository>();
+}
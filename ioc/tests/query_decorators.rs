@@ -0,0 +1,145 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::factories::{with_cache, with_logging, with_retry};
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct GetPrice {
+    id: u32,
+}
+
+#[derive(Debug, Clone)]
+struct SetPrice;
+
+/// A query that fails its first `failures` calls.
+fn flaky(calls: Rc<Cell<usize>>, failures: usize) -> impl Query<GetPrice, Result<u32, String>> + Clone {
+    move |action: GetPrice| {
+        calls.set(calls.get() + 1);
+
+        if calls.get() <= failures {
+            Err(format!("attempt {} failed", calls.get()))
+        } else {
+            Ok(action.id * 10)
+        }
+    }
+}
+
+/// A command that fails its first `failures` calls.
+fn flaky_command(calls: Rc<Cell<usize>>, failures: usize) -> impl Command<SetPrice, Error = String> + Clone {
+    move |_: SetPrice| {
+        calls.set(calls.get() + 1);
+
+        if calls.get() <= failures {
+            Err(format!("attempt {} failed", calls.get()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[test]
+fn logging_writes_each_request_to_its_sink() {
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let sink = {
+        let lines = lines.clone();
+        move |line: &str| lines.borrow_mut().push(line.to_owned())
+    };
+
+    let query = with_logging(flaky(Rc::new(Cell::new(0)), 1)).to(sink.clone());
+    query.query(GetPrice { id: 1 }).unwrap_err();
+    query.query(GetPrice { id: 1 }).unwrap();
+
+    with_logging(flaky_command(Rc::new(Cell::new(0)), 0)).to(sink).execute(SetPrice).unwrap();
+
+    let lines = lines.borrow();
+
+    assert_eq!(3, lines.len());
+    assert!(lines[0].starts_with("query query_decorators::GetPrice returned Err(\"attempt 1 failed\") in "));
+    assert!(lines[1].starts_with("query query_decorators::GetPrice returned Ok(10) in "));
+    assert!(lines[2].starts_with("command query_decorators::SetPrice returned Ok(()) in "));
+}
+
+#[test]
+fn retry_tries_again_until_it_succeeds() {
+    let calls = Rc::new(Cell::new(0));
+
+    assert_eq!(Ok(20), with_retry(3, flaky(calls.clone(), 2)).query(GetPrice { id: 2 }));
+    assert_eq!(3, calls.get());
+
+    let calls = Rc::new(Cell::new(0));
+
+    assert_eq!(Ok(()), with_retry(3, flaky_command(calls.clone(), 2)).execute(SetPrice));
+    assert_eq!(3, calls.get());
+}
+
+#[test]
+fn retry_gives_up_after_its_attempts() {
+    let calls = Rc::new(Cell::new(0));
+
+    assert_eq!(Err("attempt 3 failed".to_owned()), with_retry(3, flaky(calls.clone(), 5)).query(GetPrice { id: 2 }));
+    assert_eq!(3, calls.get());
+
+    // there's always at least one attempt
+    let calls = Rc::new(Cell::new(0));
+
+    assert!(with_retry(0, flaky_command(calls.clone(), 5)).execute(SetPrice).is_err());
+    assert_eq!(1, calls.get());
+}
+
+#[test]
+fn cache_reuses_responses_until_they_expire() {
+    let calls = Rc::new(Cell::new(0));
+
+    let cached = with_cache(Duration::from_secs(60), flaky(calls.clone(), 1));
+
+    // errors are cached too
+    assert!(cached.query(GetPrice { id: 1 }).is_err());
+    assert!(cached.query(GetPrice { id: 1 }).is_err());
+    assert_eq!(Ok(20), cached.query(GetPrice { id: 2 }));
+    assert_eq!(2, calls.get());
+    assert_eq!("Cached { ttl: 60s, responses: 2 }", format!("{:?}", cached));
+
+    cached.clear();
+
+    assert_eq!(Ok(10), cached.query(GetPrice { id: 1 }));
+    assert_eq!(3, calls.get());
+
+    let expired = with_cache(Duration::from_secs(0), flaky(calls.clone(), 0));
+    expired.query(GetPrice { id: 1 }).unwrap();
+    expired.query(GetPrice { id: 1 }).unwrap();
+
+    assert_eq!(5, calls.get());
+}
+
+#[test]
+fn decorators_compose_in_a_registry() {
+    let calls = Rc::new(Cell::new(0));
+    let lines = Rc::new(Cell::new(0));
+
+    let mut registry = Registry::new();
+    registry.bind_query({
+        let calls = calls.clone();
+        let lines = lines.clone();
+        move |_: ()| {
+            let lines = lines.clone();
+
+            with_logging(with_cache(Duration::from_secs(60), with_retry(2, flaky(calls.clone(), 1))))
+                .to(move |_| lines.set(lines.get() + 1))
+        }
+    });
+
+    let scope = Scoped::new().with_registry(Rc::new(registry));
+
+    let get_price: QueryOf<GetPrice, Result<u32, String>> = scope.resolve();
+
+    assert_eq!(Ok(10), get_price.query(GetPrice { id: 1 }));
+    assert_eq!(Ok(10), get_price.query(GetPrice { id: 1 }));
+
+    // retried once, then cached, but logged every time
+    assert_eq!(2, calls.get());
+    assert_eq!(2, lines.get());
+}
@@ -0,0 +1,113 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Product {
+    id: u32,
+    title: String,
+}
+
+struct GetProduct {
+    id: u32,
+}
+
+struct SetProductTitle {
+    id: u32,
+    title: &'static str,
+}
+
+#[derive(Default)]
+struct Db {
+    products: RefCell<HashMap<u32, Product>>,
+}
+
+impl<C> Resolvable<C> for Db {
+    type Dependency = ();
+
+    fn resolve(_: ()) -> Self {
+        let db = Db::default();
+        db.products.borrow_mut().insert(1, Product { id: 1, title: "widget".into() });
+
+        db
+    }
+}
+
+query! {
+    /// Get a product by its id.
+    pub fn get_product(db: Rc<Db>) -> impl GetProductQuery(action: GetProduct) -> Result<Product, String> {
+        db.products.borrow().get(&action.id).cloned().ok_or_else(|| format!("product {} wasn't found", action.id))
+    }
+}
+
+command! {
+    pub fn set_product_title<Q: GetProductQuery>(db: Rc<Db>, get_product: Q,) -> impl SetProductTitleCommand(action: SetProductTitle) -> Result<(), String> {
+        let mut product = get_product.query(GetProduct { id: action.id })?;
+        product.title = action.title.into();
+
+        db.products.borrow_mut().insert(product.id, product);
+
+        Ok(())
+    }
+}
+
+fn title<Q>(get_product: &Q, id: u32) -> Result<String, String>
+    where Q: GetProductQuery
+{
+    get_product.query(GetProduct { id }).map(|product| product.title)
+}
+
+fn scope() -> Scoped {
+    let mut registry = Registry::new();
+    registry
+        .bind_query(|db: Rc<Db>| get_product(db))
+        .bind_command(|(db, get_product): (Rc<Db>, QueryOf<GetProduct, Result<Product, String>>)| {
+            set_product_title(db, get_product)
+        });
+
+    Scoped::new().with_registry(Rc::new(registry))
+}
+
+#[test]
+fn declared_queries_are_queries() {
+    let query = get_product(Scoped::new().get_or_add());
+
+    assert_eq!(Ok("widget".to_owned()), title(&query, 1));
+    assert_eq!(Err("product 2 wasn't found".to_owned()), title(&query, 2));
+}
+
+#[test]
+fn declared_traits_are_implemented_for_bound_queries() {
+    let scope = scope();
+
+    scope.invoke(|set_title: CommandOf<SetProductTitle, String>| {
+        set_title.execute(SetProductTitle { id: 1, title: "gadget" }).unwrap()
+    });
+
+    // any `QueryOf` with the same request and response is a `GetProductQuery`
+    let get_product: QueryOf<GetProduct, Result<Product, String>> = scope.resolve();
+
+    assert_eq!(Ok("gadget".to_owned()), title(&get_product, 1));
+}
+
+#[test]
+fn declared_commands_fail_with_their_error() {
+    let scope = scope();
+
+    let set_title: CommandOf<SetProductTitle, String> = scope.resolve();
+
+    assert_eq!(Err("product 2 wasn't found".to_owned()),
+               set_title.execute(SetProductTitle { id: 2, title: "gadget" }));
+}
+
+#[test]
+fn closures_are_declared_queries() {
+    let query = |action: GetProduct| Ok(Product { id: action.id, title: "closure".into() });
+
+    assert_eq!(Ok("closure".to_owned()), title(&query, 3));
+}
@@ -0,0 +1,69 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+
+struct Users;
+
+impl<C> Resolvable<C> for Users {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Users
+    }
+}
+
+impl Users {
+    fn name(&self, id: u32) -> String {
+        format!("user-{}", id)
+    }
+}
+
+struct Profile {
+    users: Rc<Users>,
+    user_id: u32,
+}
+
+impl ResolvableWith<Scoped, u32> for Profile {
+    type Dependency = Rc<Users>;
+
+    fn resolve_with(users: Self::Dependency, user_id: u32) -> Self {
+        Profile { users, user_id }
+    }
+}
+
+impl Profile {
+    fn name(&self) -> String {
+        self.users.name(self.user_id)
+    }
+}
+
+struct Greeting(String);
+
+impl<C> ResolvableWith<C, (&'static str, u32)> for Greeting {
+    type Dependency = ();
+
+    fn resolve_with(_: Self::Dependency, (greeting, times): (&'static str, u32)) -> Self {
+        Greeting(greeting.repeat(times as usize))
+    }
+}
+
+#[test]
+fn parameters_are_passed_with_dependencies() {
+    let scope = Scoped::new();
+
+    let first = scope.resolve_with::<_, _, Profile>(1);
+    let second = scope.resolve_with::<_, _, Profile>(2);
+
+    assert_eq!("user-1", first.name());
+    assert_eq!("user-2", second.name());
+    assert!(Rc::ptr_eq(&first.users, &second.users));
+}
+
+#[test]
+fn parameters_can_be_tuples() {
+    let greeting = BasicContainer.resolve_with::<_, _, Greeting>(("hi", 2));
+
+    assert_eq!("hihi", greeting.0);
+}
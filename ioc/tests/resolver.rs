@@ -0,0 +1,55 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::resolver::{self, Resolver};
+
+use std::rc::Rc;
+
+struct Config {
+    name: &'static str,
+}
+
+impl<C> Resolvable<C> for Config {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Config { name: "app" }
+    }
+}
+
+#[test]
+fn resolvers_combine_values_from_the_container() {
+    let greeting = resolver::resolve::<Rc<Config>>()
+        .zip(resolver::of("hello"))
+        .map(|(config, greeting)| format!("{}, {}", greeting, config.name));
+
+    assert_eq!("hello, app", greeting.resolve(&Scoped::new()));
+}
+
+#[test]
+fn resolvers_share_dependencies_from_the_scope() {
+    let scope = Scoped::new();
+
+    let both = resolver::resolve::<Rc<Config>>().zip(resolver::resolve::<Rc<Config>>());
+    let (a, b) = both.resolve(&scope);
+
+    assert!(Rc::ptr_eq(&a, &b));
+}
+
+#[test]
+fn resolvers_can_be_run_more_than_once() {
+    let scope = Scoped::new();
+    let correlation_id = resolver::from_fn(|scope: &Scoped| scope.correlation_id());
+
+    assert_eq!(correlation_id.resolve(&scope), correlation_id.resolve(&scope));
+    assert_ne!(correlation_id.resolve(&scope), correlation_id.resolve(&Scoped::new()));
+}
+
+#[test]
+fn and_then_picks_the_next_resolver() {
+    let name = resolver::of(true).and_then(|long| {
+        resolver::resolve::<Rc<Config>>().map(move |config| if long { format!("{} (long)", config.name) } else { config.name.to_owned() })
+    });
+
+    assert_eq!("app (long)", name.resolve(&Scoped::new()));
+}
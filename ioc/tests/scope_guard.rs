@@ -0,0 +1,85 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::Error;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+thread_local! {
+    static ENDED: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+fn ended() -> Vec<&'static str> {
+    ENDED.with(|ended| ended.borrow_mut().drain(..).collect())
+}
+
+struct Connection;
+
+impl<C> Resolvable<C> for Connection {
+    type Dependency = ();
+
+    fn resolve(_: ()) -> Self {
+        Connection
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        ENDED.with(|ended| ended.borrow_mut().push("drop connection"));
+    }
+}
+
+struct Handler {
+    _connection: Rc<Connection>,
+}
+
+impl<C> Resolvable<C> for Handler
+    where C: ScopedContainer
+{
+    type Dependency = Rc<Connection>;
+
+    fn resolve(connection: Self::Dependency) -> Self {
+        Handler { _connection: connection }
+    }
+}
+
+fn handle(c: &BasicContainer) -> Result<(), Error> {
+    let scope = c.begin_scope();
+
+    let _handler: Handler = scope.try_resolve()?;
+
+    assert!(ended().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn guard_ends_the_scope_when_dropped() {
+    handle(&BasicContainer).unwrap();
+
+    assert_eq!(vec!["drop connection"], ended());
+}
+
+#[test]
+fn guard_can_end_the_scope_early() {
+    let scope = BasicContainer.begin_scope();
+
+    scope.invoke(|_: Rc<Connection>| ());
+    scope.end();
+
+    assert_eq!(vec!["drop connection"], ended());
+}
+
+#[test]
+fn guard_can_give_up_the_scope() {
+    let scope = BasicContainer.begin_scope().into_inner();
+
+    scope.invoke(|_: Rc<Connection>| ());
+
+    assert!(ended().is_empty());
+
+    drop(scope);
+
+    assert_eq!(vec!["drop connection"], ended());
+}
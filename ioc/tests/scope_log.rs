@@ -0,0 +1,74 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type Written = Rc<RefCell<Vec<(String, Vec<String>)>>>;
+
+fn log(id: &str) -> (ScopeLog, Written) {
+    let written = Written::default();
+
+    let log = {
+        let written = written.clone();
+
+        ScopeLog::with_sink(CorrelationId::new(id), move |id, lines| {
+            written.borrow_mut().push((id.to_string(), lines.to_vec()))
+        })
+    };
+
+    (log, written)
+}
+
+#[test]
+fn lines_are_written_together_when_the_log_is_dropped() {
+    let (log, written) = log("req-1");
+
+    log.log("first");
+    log.log(format_args!("second {}", 2));
+
+    assert!(written.borrow().is_empty());
+
+    drop(log);
+
+    assert_eq!(vec![("req-1".to_owned(), vec!["first".to_owned(), "second 2".to_owned()])], *written.borrow());
+}
+
+#[test]
+fn errors_write_out_what_has_been_buffered() {
+    let (log, written) = log("req-1");
+
+    log.log("working");
+    log.error("failed");
+    log.log("cleaning up");
+
+    assert_eq!(1, written.borrow().len());
+
+    drop(log);
+
+    let written = written.borrow();
+    assert_eq!(vec!["working".to_owned(), "failed".to_owned()], written[0].1);
+    assert_eq!(vec!["cleaning up".to_owned()], written[1].1);
+}
+
+#[test]
+fn empty_blocks_arent_written() {
+    let (log, written) = log("req-1");
+
+    log.flush();
+    drop(log);
+
+    assert!(written.borrow().is_empty());
+}
+
+#[test]
+fn scope_shares_one_log_with_its_correlation_id() {
+    let scope = Scoped::new().with_correlation_id(CorrelationId::new("req-2"));
+
+    let a = scope.get_or_add::<ScopeLog, _>();
+    let b = scope.get_or_add::<ScopeLog, _>();
+
+    assert!(Rc::ptr_eq(&a, &b));
+    assert_eq!("req-2", a.correlation_id().as_str());
+}
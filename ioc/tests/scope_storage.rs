@@ -0,0 +1,213 @@
+//! Exercise the unsafe storage behind scoped containers.
+//!
+//! These are worth running under Miri:
+//!
+//! ```text
+//! cargo +nightly miri test --test scope_storage
+//! ```
+
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+
+#[derive(Debug, PartialEq)]
+struct Value(usize);
+
+macro_rules! values {
+    ($($name:ident: $value:expr,)*) => {
+        $(
+            struct $name(Value);
+            impl<C> Resolvable<C> for $name {
+                type Dependency = ();
+
+                fn resolve(_: Self::Dependency) -> Self {
+                    $name(Value($value))
+                }
+            }
+        )*
+
+        // resolving every value forces the scope's storage to grow
+        fn check_all_scoped(scope: &Scoped) {
+            $(
+                let value: Rc<$name> = scope.invoke(|value: Rc<$name>| value);
+                assert_eq!(Value($value), value.0);
+            )*
+        }
+
+        fn check_all_borrowed<'scope>(scope: &Borrowed<'scope>) -> Vec<&'scope Value> {
+            let mut values = Vec::new();
+
+            $(
+                let value: B<'scope, $name> = scope.invoke(|value: B<'scope, $name>| value);
+                assert_eq!(Value($value), value.0);
+
+                values.push(&value.get().0);
+            )*
+
+            values
+        }
+    };
+}
+
+values! {
+    V0: 0, V1: 1, V2: 2, V3: 3, V4: 4, V5: 5, V6: 6, V7: 7,
+    V8: 8, V9: 9, V10: 10, V11: 11, V12: 12, V13: 13, V14: 14, V15: 15,
+    V16: 16, V17: 17, V18: 18, V19: 19, V20: 20, V21: 21, V22: 22, V23: 23,
+    V24: 24, V25: 25, V26: 26, V27: 27, V28: 28, V29: 29, V30: 30, V31: 31,
+}
+
+#[test]
+fn scoped_values_outlive_storage_growth() {
+    let scope = Scoped::new();
+
+    let first: Rc<V0> = scope.invoke(|value: Rc<V0>| value);
+
+    check_all_scoped(&scope);
+    check_all_scoped(&scope);
+
+    assert_eq!(Value(0), first.0);
+}
+
+#[test]
+fn scoped_values_outlive_scope() {
+    let first = {
+        let scope = Scoped::new();
+
+        check_all_scoped(&scope);
+
+        scope.invoke(|value: Rc<V31>| value)
+    };
+
+    assert_eq!(Value(31), first.0);
+}
+
+#[test]
+fn scoped_replace_returns_previous_value() {
+    let scope = Scoped::new();
+
+    let first: Rc<V0> = scope.invoke(|value: Rc<V0>| value);
+    let previous = scope.replace(V0(Value(100))).expect("a value was already cached");
+    let next: Rc<V0> = scope.invoke(|value: Rc<V0>| value);
+
+    assert!(Rc::ptr_eq(&first, &previous));
+    assert_eq!(Value(0), previous.0);
+    assert_eq!(Value(100), next.0);
+}
+
+#[test]
+fn scoped_values_outlive_removal_and_clearing() {
+    let scope = Scoped::new();
+
+    check_all_scoped(&scope);
+
+    let removed = scope.remove::<V3>().expect("a value was already cached");
+    let first: Rc<V0> = scope.invoke(|value: Rc<V0>| value);
+
+    scope.clear();
+
+    // the storage starts small again after it's been cleared
+    check_all_scoped(&scope);
+
+    assert_eq!(Value(3), removed.0);
+    assert_eq!(Value(0), first.0);
+}
+
+fn first_borrowed<'scope>(scope: &Borrowed<'scope>) -> B<'scope, V0> {
+    scope.invoke(|value: B<'scope, V0>| value)
+}
+
+#[test]
+fn borrowed_values_outlive_storage_growth() {
+    BasicContainer.borrow_scope(|scope| {
+        let first = first_borrowed(&scope);
+
+        let values = check_all_borrowed(&scope);
+        let again = check_all_borrowed(&scope);
+
+        assert_eq!(Value(0), first.0);
+
+        for (i, (value, again)) in values.into_iter().zip(again).enumerate() {
+            assert_eq!(&Value(i), value);
+            assert!(std::ptr::eq(value, again));
+        }
+    });
+}
+
+#[repr(align(64))]
+struct Aligned(Value);
+impl<C> Resolvable<C> for Aligned {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Aligned(Value(64))
+    }
+}
+
+struct Large([Value; 128]);
+impl<C> Resolvable<C> for Large {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Large(std::array::from_fn(Value))
+    }
+}
+
+struct Empty;
+impl<C> Resolvable<C> for Empty {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Empty
+    }
+}
+
+#[test]
+fn borrowed_values_of_any_size_and_alignment() {
+    BasicContainer.borrow_scope(|scope| {
+        let aligned = scope.get_or_add_ref::<Aligned, ()>();
+        let values = check_all_borrowed(&scope);
+        let large = scope.get_or_add_ref::<Large, ()>();
+        let empty = scope.get_or_add_ref::<Empty, ()>();
+
+        assert_eq!(0, aligned as *const Aligned as usize % 64);
+        assert_eq!(Value(64), aligned.0);
+        assert_eq!(Value(127), large.0[127]);
+        assert_eq!(Value(31), *values[31]);
+        assert!(std::ptr::eq(empty, scope.get_or_add_ref::<Empty, ()>()));
+    });
+}
+
+trait Seed {}
+struct Seeded;
+impl Seed for Seeded {}
+
+struct Reentrant(Value);
+impl<C> Resolvable<C> for Reentrant {
+    type Dependency = Bound<dyn Seed>;
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Reentrant(Value(1))
+    }
+}
+
+#[test]
+fn scoped_value_replaced_while_constructing_is_not_looked_up() {
+    let mut registry = Registry::new();
+    registry.bind::<dyn Seed, _>(|scope: &Scoped| {
+        // caches a `Reentrant` that constructing the outer one replaces
+        scope.insert(Reentrant(Value(0))).unwrap();
+        scope.get_or_add::<Reentrant, _>();
+
+        Rc::new(Seeded)
+    });
+
+    let scope = Scoped::new().with_registry(Rc::new(registry));
+
+    let constructed = scope.get_or_add::<Reentrant, _>();
+    let cached = scope.get_or_add::<Reentrant, _>();
+
+    assert!(Rc::ptr_eq(&constructed, &cached));
+    assert_eq!(Value(1), cached.0);
+}
@@ -0,0 +1,62 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Debug, Default)]
+struct Items(Vec<&'static str>);
+
+resolvable! {
+    default Items;
+
+    struct Cart {
+        items: SharedMut<Items>,
+    }
+
+    struct Checkout {
+        items: Rc<RefCell<Items>>,
+    }
+}
+
+#[test]
+fn shared_mut_is_the_scopes_rc_ref_cell() {
+    let scope = Scoped::new();
+
+    let cart: Cart = scope.resolve();
+    let checkout: Checkout = scope.resolve();
+
+    cart.items.borrow_mut().0.push("apple");
+
+    assert_eq!(vec!["apple"], checkout.items.borrow().0);
+    assert!(Rc::ptr_eq(&cart.items.clone().into_inner(), &checkout.items));
+}
+
+#[test]
+fn shared_mut_clones_share_the_value() {
+    let items = SharedMut::<Items>::resolve_from_container(&Scoped::new());
+    let clone = items.clone();
+
+    clone.borrow_mut().0.push("pear");
+
+    assert_eq!(vec!["pear"], items.borrow().0);
+    assert_eq!("SharedMut(RefCell { value: Items([\"pear\"]) })", format!("{:?}", items));
+}
+
+#[test]
+fn shared_mut_borrows_are_checked() {
+    let items = SharedMut::<Items>::resolve_from_container(&Scoped::new());
+
+    {
+        let _reading = items.borrow();
+
+        assert!(items.try_borrow().is_ok());
+        assert!(items.try_borrow_mut().is_err());
+    }
+
+    let _writing = items.borrow_mut();
+
+    assert!(items.try_borrow().is_err());
+}
@@ -0,0 +1,109 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+struct Pool;
+
+impl<C> Resolvable<C> for Pool {
+    type Dependency = ();
+
+    fn resolve(_: ()) -> Self {
+        thread::sleep(Duration::from_millis(200));
+
+        Pool
+    }
+}
+
+struct Repository {
+    _pool: Rc<Pool>,
+}
+
+impl<C> Resolvable<C> for Repository
+    where C: ScopedContainer
+{
+    type Dependency = Rc<Pool>;
+
+    fn resolve(pool: Self::Dependency) -> Self {
+        thread::sleep(Duration::from_millis(10));
+
+        Repository { _pool: pool }
+    }
+}
+
+#[derive(Debug)]
+struct Settings;
+
+#[test]
+fn stats_list_what_the_scope_holds() {
+    let scope = Scoped::new();
+
+    assert!(scope.stats().is_empty());
+
+    let repository: Rc<Repository> = scope.get_or_add();
+    scope.insert(Settings).unwrap();
+
+    let stats = scope.stats();
+
+    assert_eq!(vec!["stats::Pool", "stats::Repository", "stats::Settings"],
+               stats.entries().iter().map(|entry| entry.type_name).collect::<Vec<_>>());
+
+    // the scope's own reference, plus the repository's or ours
+    assert_eq!(2, stats.entry("stats::Pool").unwrap().strong_count);
+    assert_eq!(2, stats.entry("stats::Repository").unwrap().strong_count);
+    assert_eq!(1, stats.entry("stats::Settings").unwrap().strong_count);
+    assert!(stats.entry("stats::Missing").is_none());
+
+    drop(repository);
+
+    assert_eq!(1, scope.stats().entry("stats::Repository").unwrap().strong_count);
+}
+
+#[test]
+fn construction_time_excludes_shared_dependencies() {
+    let scope = Scoped::new();
+
+    scope.get_or_add::<Repository, _>();
+    scope.insert(Settings).unwrap();
+
+    let stats = scope.stats();
+
+    let pool = stats.entry("stats::Pool").unwrap().construction;
+    let repository = stats.entry("stats::Repository").unwrap().construction;
+
+    assert!(pool >= Duration::from_millis(200));
+    assert!(repository >= Duration::from_millis(10) && repository < pool);
+
+    // inserted values weren't constructed by the scope
+    assert_eq!(Duration::default(), stats.entry("stats::Settings").unwrap().construction);
+    assert_eq!(pool + repository, stats.construction());
+}
+
+#[test]
+fn stats_forget_removed_values() {
+    let scope = Scoped::new();
+
+    scope.insert(Settings).unwrap();
+    scope.get_or_add::<Pool, _>();
+
+    scope.remove::<Settings>();
+    assert_eq!(1, scope.stats().len());
+
+    scope.clear();
+    assert!(scope.stats().is_empty());
+}
+
+#[test]
+fn stats_display_and_debug() {
+    let scope = Scoped::new();
+
+    scope.insert(Settings).unwrap();
+
+    assert_eq!("1 entries, constructed in 0ns\n  stats::Settings (strong: 1, construction: 0ns)\n",
+               scope.stats().to_string());
+    assert_eq!("Scoped { entries: [EntryStats { type_name: \"stats::Settings\", strong_count: 1, construction: 0ns }] }",
+               format!("{:?}", scope));
+}
@@ -0,0 +1,218 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::std_services::*;
+use ioc::testing::TestScope;
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+struct Session {
+    started: SystemTime,
+    clock: Bound<dyn Clock>,
+}
+
+impl<C> Resolvable<C> for Session
+    where C: RegisteredContainer + 'static
+{
+    type Dependency = Bound<dyn Clock>;
+
+    fn resolve(clock: Self::Dependency) -> Self {
+        Session { started: clock.now(), clock }
+    }
+}
+
+impl Session {
+    fn age(&self) -> Duration {
+        self.clock.now().duration_since(self.started).unwrap()
+    }
+}
+
+#[test]
+fn std_services_bind_the_system_clock() {
+    let mut registry = Registry::new();
+    registry.add_module(StdServices);
+
+    let before = SystemTime::now();
+    let session: Session = Scoped::new().with_registry(Rc::new(registry)).resolve();
+
+    assert!(session.started >= before);
+    assert!(session.clock.monotonic() <= session.clock.monotonic());
+}
+
+#[test]
+fn test_clock_only_moves_when_advanced() {
+    let clock = TestClock::starting_at(UNIX_EPOCH);
+
+    let scope = TestScope::new()
+        .with_module(StdServices)
+        .with_override::<dyn Clock>(Rc::new(clock.clone()))
+        .build();
+
+    let session: Session = scope.resolve();
+    let started = session.clock.monotonic();
+
+    assert_eq!(UNIX_EPOCH, session.started);
+    assert_eq!(Duration::ZERO, session.age());
+
+    clock.advance(Duration::from_secs(60));
+    clock.advance(Duration::from_secs(30));
+
+    assert_eq!(Duration::from_secs(90), session.age());
+    assert_eq!(Duration::from_secs(90), clock.elapsed());
+    assert_eq!(Duration::from_secs(90), session.clock.monotonic() - started);
+    assert_eq!(UNIX_EPOCH + Duration::from_secs(90), clock.now());
+}
+
+struct TokenIssuer {
+    random: Bound<dyn RandomSource>,
+}
+
+impl<C> Resolvable<C> for TokenIssuer
+    where C: RegisteredContainer + 'static
+{
+    type Dependency = Bound<dyn RandomSource>;
+
+    fn resolve(random: Self::Dependency) -> Self {
+        TokenIssuer { random }
+    }
+}
+
+impl TokenIssuer {
+    fn issue(&self) -> [u8; 12] {
+        let mut token = [0; 12];
+        self.random.fill_bytes(&mut token);
+
+        token
+    }
+}
+
+#[test]
+fn std_services_bind_os_randomness() {
+    let mut registry = Registry::new();
+    registry.add_module(StdServices);
+
+    let issuer: TokenIssuer = Scoped::new().with_registry(Rc::new(registry)).resolve();
+
+    assert_ne!(issuer.issue(), issuer.issue());
+    assert_ne!(OsRandom::new().next_u64(), OsRandom::new().next_u64());
+}
+
+#[test]
+fn seeded_randomness_is_the_same_for_the_same_seed() {
+    let issue = |seed| {
+        let scope = TestScope::new()
+            .with_module(StdServices)
+            .with_override::<dyn RandomSource>(Rc::new(SeededRandom::new(seed)))
+            .build();
+
+        scope.resolve::<_, TokenIssuer>().issue()
+    };
+
+    assert_eq!(issue(42), issue(42));
+    assert_ne!(issue(42), issue(43));
+}
+
+#[test]
+fn seeded_random_clones_carry_on_independently() {
+    let random = SeededRandom::new(7);
+    random.next_u64();
+
+    let clone = random.clone();
+
+    assert_eq!(random.next_u64(), clone.next_u64());
+    assert_eq!(random.next_u64(), clone.next_u64());
+
+    // bytes are taken from each number in turn
+    let mut bytes = [0; 10];
+    SeededRandom::new(7).fill_bytes(&mut bytes);
+
+    let numbers = SeededRandom::new(7);
+    let first = numbers.next_u64().to_le_bytes();
+    let second = numbers.next_u64().to_le_bytes();
+
+    assert_eq!(first, bytes[..8]);
+    assert_eq!(second[..2], bytes[8..]);
+}
+
+struct Reports {
+    fs: Bound<dyn FileSystem>,
+}
+
+impl<C> Resolvable<C> for Reports
+    where C: RegisteredContainer + 'static
+{
+    type Dependency = Bound<dyn FileSystem>;
+
+    fn resolve(fs: Self::Dependency) -> Self {
+        Reports { fs }
+    }
+}
+
+impl Reports {
+    fn save(&self, dir: &Path, name: &str, report: &str) -> std::io::Result<()> {
+        self.fs.create_dir_all(dir)?;
+        self.fs.write(&dir.join(name), report.as_bytes())
+    }
+}
+
+#[test]
+fn std_services_bind_the_os_filesystem() {
+    let mut registry = Registry::new();
+    registry.add_module(StdServices);
+
+    let reports: Reports = Scoped::new().with_registry(Rc::new(registry)).resolve();
+
+    let dir = reports.fs.temp_dir().unwrap();
+    assert!(reports.fs.exists(&dir));
+    assert_ne!(dir, reports.fs.temp_dir().unwrap());
+
+    reports.save(&dir.join("daily"), "report.txt", "all good").unwrap();
+
+    assert_eq!("all good", reports.fs.read_to_string(&dir.join("daily/report.txt")).unwrap());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn memory_filesystem_keeps_files_in_memory() {
+    let files = MemoryFileSystem::new().with_file("/etc/app.toml", "port = 80");
+
+    let scope = TestScope::new()
+        .with_module(StdServices)
+        .with_override::<dyn FileSystem>(Rc::new(files.clone()))
+        .build();
+
+    let reports: Reports = scope.resolve();
+
+    reports.save(Path::new("/var/reports"), "report.txt", "all good").unwrap();
+
+    assert_eq!(vec![Path::new("/etc/app.toml").to_owned(), Path::new("/var/reports/report.txt").to_owned()], files.files());
+    assert_eq!("port = 80", files.read_to_string(Path::new("/etc/app.toml")).unwrap());
+    assert!(files.exists(Path::new("/var")));
+    assert!(!files.exists(Path::new("/var/missing")));
+}
+
+#[test]
+fn memory_filesystem_fails_like_a_real_one() {
+    let files = MemoryFileSystem::new().with_file("/etc/app.toml", "port = 80");
+
+    assert_eq!(ErrorKind::NotFound, files.read(Path::new("/etc/missing.toml")).unwrap_err().kind());
+    assert_eq!(ErrorKind::NotFound, files.write(Path::new("/missing/app.toml"), b"").unwrap_err().kind());
+    assert_eq!(ErrorKind::IsADirectory, files.write(Path::new("/etc"), b"").unwrap_err().kind());
+    assert_eq!(ErrorKind::AlreadyExists, files.create_dir_all(Path::new("/etc/app.toml/nested")).unwrap_err().kind());
+
+    files.write(Path::new("/etc/binary"), &[0xff]).unwrap();
+    assert_eq!(ErrorKind::InvalidData, files.read_to_string(Path::new("/etc/binary")).unwrap_err().kind());
+
+    // relative paths are in a current directory that always exists
+    files.write(Path::new("relative.txt"), b"here").unwrap();
+    assert!(files.exists(Path::new("relative.txt")));
+
+    let temp = files.temp_dir().unwrap();
+    assert!(files.exists(&temp));
+    assert_ne!(temp, files.temp_dir().unwrap());
+}
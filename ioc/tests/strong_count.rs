@@ -0,0 +1,62 @@
+#[macro_use]
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::StrongCountExceeded;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Default)]
+struct Session;
+
+resolvable! {
+    default Session;
+}
+
+fn scope(threshold: usize) -> (Scoped, Rc<RefCell<Vec<StrongCountExceeded>>>) {
+    let exceeded = Rc::new(RefCell::new(Vec::new()));
+
+    let record = exceeded.clone();
+    let scope = Scoped::new().with_strong_count_threshold(threshold, move |e| record.borrow_mut().push(*e));
+
+    (scope, exceeded)
+}
+
+#[test]
+fn threshold_fires_once_the_count_is_above_it() {
+    let (scope, exceeded) = scope(2);
+    let mut held = Vec::new();
+
+    held.push(scope.get_or_add::<Session, _>());
+    held.push(scope.get_or_add::<Session, _>());
+    assert!(exceeded.borrow().is_empty());
+
+    held.push(scope.get_or_add::<Session, _>());
+    assert_eq!(
+        vec![StrongCountExceeded {
+            type_name: std::any::type_name::<Session>(),
+            strong_count: 3,
+            threshold: 2,
+        }],
+        *exceeded.borrow()
+    );
+
+    // every resolution above the threshold fires, not just the first
+    held.push(scope.get_or_add::<Session, _>());
+    assert_eq!(2, exceeded.borrow().len());
+    assert_eq!(4, exceeded.borrow()[1].strong_count);
+}
+
+#[test]
+fn threshold_stops_firing_once_references_are_dropped() {
+    let (scope, exceeded) = scope(1);
+
+    let held = scope.get_or_add::<Session, _>();
+    scope.get_or_add::<Session, _>();
+    assert_eq!(1, exceeded.borrow().len());
+
+    drop(held);
+    scope.get_or_add::<Session, _>();
+    assert_eq!(1, exceeded.borrow().len());
+}
@@ -0,0 +1,127 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+
+#[derive(Default)]
+struct Counter {
+    count: usize,
+}
+
+impl<C> Resolvable<C> for Counter {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Counter::default()
+    }
+}
+
+struct Cache {
+    counter: Arc<Mutex<Counter>>,
+}
+
+impl Resolvable<SyncScoped> for Cache {
+    type Dependency = Arc<Mutex<Counter>>;
+
+    fn resolve(counter: Self::Dependency) -> Self {
+        Cache { counter }
+    }
+}
+
+#[test]
+fn arc_dependencies_are_shared() {
+    let scope = SyncScoped::new();
+
+    let a = Arc::<Cache>::resolve_from_container(&scope);
+    let b = Arc::<Cache>::resolve_from_container(&scope);
+
+    assert!(Arc::ptr_eq(&a, &b));
+    assert!(Arc::ptr_eq(&a.counter, &Arc::<Mutex<Counter>>::resolve_from_container(&scope)));
+}
+
+#[test]
+fn shared_mutable_dependencies_are_shared_between_threads() {
+    let scope = Arc::new(SyncScoped::new());
+
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let scope = scope.clone();
+
+            thread::spawn(move || {
+                let cache = Arc::<Cache>::resolve_from_container(&*scope);
+                cache.counter.lock().unwrap().count += 1;
+
+                let counter = Arc::<RwLock<Counter>>::resolve_from_container(&*scope);
+                counter.write().unwrap().count += 1;
+            })
+        })
+        .collect();
+
+    for worker in workers {
+        worker.join().unwrap();
+    }
+
+    assert_eq!(4, Arc::<Mutex<Counter>>::resolve_from_container(&*scope).lock().unwrap().count);
+    assert_eq!(4, Arc::<RwLock<Counter>>::resolve_from_container(&*scope).read().unwrap().count);
+}
+
+#[test]
+fn locks_are_owned_unless_theyre_in_an_arc() {
+    let scope = SyncScoped::new();
+
+    let owned = scope.invoke(|counter: Mutex<Counter>| {
+        counter.lock().unwrap().count += 1;
+        counter.into_inner().unwrap().count
+    });
+
+    assert_eq!(1, owned);
+    assert_eq!(0, scope.invoke(|counter: Mutex<Counter>| counter.into_inner().unwrap().count));
+}
+
+static DROPPED: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+struct Pool;
+
+impl<C> Resolvable<C> for Pool {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Pool
+    }
+}
+
+impl Drop for Pool {
+    fn drop(&mut self) {
+        DROPPED.lock().unwrap().push("pool");
+    }
+}
+
+struct Service {
+    _pool: Arc<Pool>,
+}
+
+impl Resolvable<SyncScoped> for Service {
+    type Dependency = Arc<Pool>;
+
+    fn resolve(pool: Self::Dependency) -> Self {
+        Service { _pool: pool }
+    }
+}
+
+impl Drop for Service {
+    fn drop(&mut self) {
+        DROPPED.lock().unwrap().push("service");
+    }
+}
+
+#[test]
+fn dependencies_are_dropped_before_what_they_depend_on() {
+    let scope = SyncScoped::new();
+    Arc::<Service>::resolve_from_container(&scope);
+
+    drop(scope);
+
+    assert_eq!(vec!["service", "pool"], *DROPPED.lock().unwrap());
+}
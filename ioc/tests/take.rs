@@ -0,0 +1,95 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+thread_local! {
+    static DISPOSED: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+fn disposed() -> Vec<usize> {
+    DISPOSED.with(|disposed| disposed.borrow_mut().drain(..).collect())
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct UnitOfWork {
+    changes: RefCell<Vec<&'static str>>,
+}
+
+impl<C> Resolvable<C> for UnitOfWork {
+    type Dependency = ();
+
+    fn resolve(_: ()) -> Self {
+        UnitOfWork::default()
+    }
+}
+
+impl Disposable for UnitOfWork {
+    fn dispose(&mut self) {
+        DISPOSED.with(|disposed| disposed.borrow_mut().push(self.changes.borrow().len()));
+    }
+}
+
+fn scope() -> Scoped {
+    let mut registry = Registry::new();
+    registry.disposable::<UnitOfWork>();
+
+    Scoped::new().with_registry(Rc::new(registry))
+}
+
+#[test]
+fn remove_hands_back_the_value_without_disposing_it() {
+    let scope = scope();
+
+    let work: Rc<UnitOfWork> = scope.get_or_add();
+    work.changes.borrow_mut().push("insert");
+
+    let removed = scope.remove::<UnitOfWork>().unwrap();
+
+    assert!(Rc::ptr_eq(&work, &removed));
+    assert!(scope.remove::<UnitOfWork>().is_none());
+    assert!(scope.stats().is_empty());
+
+    // the next resolution constructs a new value
+    let next: Rc<UnitOfWork> = scope.get_or_add();
+    assert!(!Rc::ptr_eq(&work, &next));
+
+    drop(next);
+    drop(scope);
+
+    // only the value still in the scope is disposed
+    assert_eq!(vec![0], disposed());
+}
+
+#[test]
+fn take_moves_a_uniquely_held_value_out() {
+    let scope = scope();
+
+    scope.invoke(|work: Rc<UnitOfWork>| work.changes.borrow_mut().push("update"));
+
+    let work = scope.take::<UnitOfWork>().unwrap();
+
+    assert_eq!(vec!["update"], *work.changes.borrow());
+    assert!(scope.get_weak::<UnitOfWork>().upgrade().is_none());
+    assert!(scope.take::<UnitOfWork>().is_none());
+
+    drop(scope);
+
+    assert!(disposed().is_empty());
+}
+
+#[test]
+fn take_leaves_a_shared_value_in_the_scope() {
+    let scope = scope();
+
+    let work: Rc<UnitOfWork> = scope.get_or_add();
+
+    assert_eq!(None, scope.take::<UnitOfWork>());
+    assert!(Rc::ptr_eq(&work, &scope.get_or_add::<UnitOfWork, _>()));
+
+    drop(work);
+
+    assert!(scope.take::<UnitOfWork>().is_some());
+}
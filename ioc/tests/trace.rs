@@ -0,0 +1,119 @@
+#![cfg(feature = "tracing")]
+
+extern crate ioc;
+extern crate tracing;
+
+use ioc::prelude::*;
+
+use std::fmt;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// The spans a subscriber has seen.
+#[derive(Default)]
+struct Spans {
+    /// The name and fields of each span, indexed by its id.
+    spans: Vec<String>,
+    /// The depth and id of each span, in the order they were entered.
+    entered: Vec<(usize, usize)>,
+    depth: usize,
+}
+
+impl Spans {
+    /// The spans as indented lines, with every field they recorded.
+    fn lines(&self) -> Vec<String> {
+        self.entered.iter().map(|&(depth, span)| format!("{}{}", "  ".repeat(depth), self.spans[span])).collect()
+    }
+}
+
+/// A subscriber that records spans.
+#[derive(Default, Clone)]
+struct Recorder(Arc<Mutex<Spans>>);
+
+struct Fields<'a>(&'a mut String);
+
+impl<'a> Visit for Fields<'a> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.push_str(&format!(" {}={:?}", field.name(), value));
+    }
+}
+
+fn index(span: &Id) -> usize {
+    span.into_u64() as usize - 1
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes) -> Id {
+        let mut name = span.metadata().name().to_owned();
+        span.record(&mut Fields(&mut name));
+
+        let mut spans = self.0.lock().unwrap();
+        spans.spans.push(name);
+
+        Id::from_u64(spans.spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record) {
+        values.record(&mut Fields(&mut self.0.lock().unwrap().spans[index(span)]));
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event) {}
+
+    fn enter(&self, span: &Id) {
+        let mut spans = self.0.lock().unwrap();
+
+        let depth = spans.depth;
+        spans.entered.push((depth, index(span)));
+        spans.depth += 1;
+    }
+
+    fn exit(&self, _: &Id) {
+        self.0.lock().unwrap().depth -= 1;
+    }
+}
+
+struct Clock;
+impl<C> Resolvable<C> for Clock {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Clock
+    }
+}
+
+struct Dispatcher;
+impl Resolvable<Scoped> for Dispatcher {
+    type Dependency = (Rc<Clock>, Rc<Clock>);
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Dispatcher
+    }
+}
+
+#[test]
+fn spans_are_nested_like_dependencies() {
+    let recorder = Recorder::default();
+
+    tracing::subscriber::with_default(recorder.clone(), || {
+        Scoped::new().resolve::<_, Dispatcher>();
+    });
+
+    assert_eq!(
+        vec![
+            "resolve type_name=\"Dispatcher\"",
+            "  get_or_add type_name=\"Clock\" cached=false",
+            "  get_or_add type_name=\"Clock\" cached=true",
+        ],
+        recorder.0.lock().unwrap().lines()
+    );
+}
@@ -0,0 +1,121 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+use ioc::{InTransaction, Level, MemoryLogger, Pool, Transactional};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type Events = Rc<RefCell<Vec<&'static str>>>;
+
+struct Connection {
+    events: Events,
+    fail_commit: bool,
+}
+
+impl Transactional for Connection {
+    type Error = &'static str;
+
+    fn begin(&mut self) -> Result<(), Self::Error> {
+        self.events.borrow_mut().push("begin");
+        Ok(())
+    }
+
+    fn commit(&mut self) -> Result<(), Self::Error> {
+        if self.fail_commit {
+            return Err("connection reset");
+        }
+
+        self.events.borrow_mut().push("commit");
+        Ok(())
+    }
+
+    fn rollback(&mut self) -> Result<(), Self::Error> {
+        self.events.borrow_mut().push("rollback");
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct DbPool {
+    events: Events,
+    fail_commit: bool,
+}
+
+impl Pool for DbPool {
+    type Connection = Connection;
+    type Error = &'static str;
+
+    fn check_out(&self) -> Result<Connection, Self::Error> {
+        Ok(Connection {
+            events: self.events.clone(),
+            fail_commit: self.fail_commit,
+        })
+    }
+
+    fn check_in(&self, _: Connection) {
+        self.events.borrow_mut().push("check in");
+    }
+}
+
+fn scope(pool: DbPool, log: Option<MemoryLogger>) -> Scoped {
+    let mut registry = Registry::new();
+    registry.bind_pool(pool);
+
+    if let Some(log) = log {
+        registry.bind_logger(log);
+    }
+
+    Scoped::new().with_registry(Rc::new(registry))
+}
+
+#[test]
+fn ok_commits_the_transaction() {
+    let events = Events::default();
+    let scope = scope(DbPool { events: events.clone(), ..DbPool::default() }, None);
+
+    let result: Result<(), ()> = scope.try_run(|scope| {
+        scope.get_or_add::<InTransaction<DbPool>, _>();
+
+        Ok(())
+    });
+
+    assert!(result.is_ok());
+    assert_eq!(vec!["begin", "commit", "check in"], *events.borrow());
+}
+
+#[test]
+fn err_rolls_back_the_transaction() {
+    let events = Events::default();
+    let scope = scope(DbPool { events: events.clone(), ..DbPool::default() }, None);
+
+    let result: Result<(), &str> = scope.try_run(|scope| {
+        scope.get_or_add::<InTransaction<DbPool>, _>();
+
+        Err("rename failed")
+    });
+
+    assert_eq!(Err("rename failed"), result);
+    assert_eq!(vec!["begin", "rollback", "check in"], *events.borrow());
+}
+
+#[test]
+fn failed_commit_is_logged_instead_of_panicking() {
+    let events = Events::default();
+    let log = MemoryLogger::new();
+    let scope = scope(DbPool { events: events.clone(), fail_commit: true }, Some(log.clone()));
+
+    let result: Result<(), ()> = scope.try_run(|scope| {
+        scope.get_or_add::<InTransaction<DbPool>, _>();
+
+        Ok(())
+    });
+
+    assert!(result.is_ok());
+    assert_eq!(vec!["begin", "check in"], *events.borrow());
+
+    let records = log.records();
+    assert_eq!(1, records.len());
+    assert_eq!(Level::Error, records[0].level);
+    assert_eq!(Some("connection reset"), records[0].field("error"));
+}
@@ -0,0 +1,103 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+thread_local!(static CREATED: Cell<usize> = const { Cell::new(0) });
+
+struct Config;
+
+impl<C> Resolvable<C> for Config {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Config
+    }
+}
+
+struct Rng {
+    config: Rc<Config>,
+    state: u64,
+}
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.state += 1;
+        self.state
+    }
+}
+
+impl Resolvable<Scoped> for Rng {
+    type Dependency = Rc<Config>;
+
+    fn resolve(config: Self::Dependency) -> Self {
+        CREATED.with(|created| created.set(created.get() + 1));
+
+        Rng { config, state: 0 }
+    }
+}
+
+struct Handler {
+    a: Transient<Rng>,
+    b: Transient<Rng>,
+}
+
+impl Resolvable<Scoped> for Handler {
+    type Dependency = (Transient<Rng>, Transient<Rng>);
+
+    fn resolve((a, b): Self::Dependency) -> Self {
+        Handler { a, b }
+    }
+}
+
+#[test]
+fn transient_dependencies_are_new_each_time() {
+    CREATED.with(|created| created.set(0));
+
+    let scope = Scoped::new();
+    let mut handler = scope.resolve::<_, Handler>();
+    scope.resolve::<_, Handler>();
+
+    assert_eq!(4, CREATED.with(Cell::get));
+
+    // each one has its own state
+    handler.a.next();
+    assert_eq!(2, handler.a.next());
+    assert_eq!(1, handler.b.next());
+}
+
+#[test]
+fn transient_dependencies_still_share_their_dependencies() {
+    let scope = Scoped::new();
+    let handler = scope.resolve::<_, Handler>();
+
+    assert!(Rc::ptr_eq(&handler.a.config, &handler.b.config));
+    assert!(Rc::ptr_eq(&handler.a.config, &scope.get_or_add::<Config, _>()));
+}
+
+#[test]
+fn transient_dependencies_arent_cached() {
+    let scope = Scoped::new();
+    scope.resolve::<_, Handler>();
+
+    assert!(scope.get_weak::<Rng>().upgrade().is_none());
+    assert!(scope.get_weak::<Config>().upgrade().is_some());
+}
+
+#[test]
+fn transient_dependencies_can_be_converted() {
+    fn advance<R: AsMut<Rng>>(mut rng: R) -> u64 {
+        rng.as_mut().next()
+    }
+
+    let scope = Scoped::new();
+
+    let mut rng: Transient<Rng> = Transient::from(Rng { config: scope.get_or_add(), state: 41 });
+
+    assert!(Rc::ptr_eq(&rng.as_ref().config, &scope.get_or_add::<Config, _>()));
+    assert_eq!(42, rng.as_mut().next());
+    assert_eq!(43, advance(&mut rng));
+    assert_eq!(44, rng.into_inner().next());
+}
@@ -0,0 +1,251 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq)]
+struct V1;
+impl<C> Resolvable<C> for V1 {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        V1
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct V2;
+impl<C> Resolvable<C> for V2 {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        V2
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct V3;
+impl<C> Resolvable<C> for V3 {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        V3
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct V4;
+impl<C> Resolvable<C> for V4 {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        V4
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct V5;
+impl<C> Resolvable<C> for V5 {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        V5
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct V6;
+impl<C> Resolvable<C> for V6 {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        V6
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct V7;
+impl<C> Resolvable<C> for V7 {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        V7
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct V8;
+impl<C> Resolvable<C> for V8 {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        V8
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct V9;
+impl<C> Resolvable<C> for V9 {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        V9
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct V10;
+impl<C> Resolvable<C> for V10 {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        V10
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct V11;
+impl<C> Resolvable<C> for V11 {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        V11
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct V12;
+impl<C> Resolvable<C> for V12 {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        V12
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct V13;
+impl<C> Resolvable<C> for V13 {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        V13
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct V14;
+impl<C> Resolvable<C> for V14 {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        V14
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct V15;
+impl<C> Resolvable<C> for V15 {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        V15
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct V16;
+impl<C> Resolvable<C> for V16 {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        V16
+    }
+}
+
+type Sixteen = (V1, V2, V3, V4, V5, V6, V7, V8, V9, V10, V11, V12, V13, V14, V15, V16);
+type OwnedSixteen = (RefCell<V1>, RefCell<V2>, RefCell<V3>, RefCell<V4>, RefCell<V5>, RefCell<V6>, RefCell<V7>, RefCell<V8>, RefCell<V9>, RefCell<V10>, RefCell<V11>, RefCell<V12>, RefCell<V13>, RefCell<V14>, RefCell<V15>, RefCell<V16>);
+type SharedSixteen = (Rc<V1>, Rc<V2>, Rc<V3>, Rc<V4>, Rc<V5>, Rc<V6>, Rc<V7>, Rc<V8>, Rc<V9>, Rc<V10>, Rc<V11>, Rc<V12>, Rc<V13>, Rc<V14>, Rc<V15>, Rc<V16>);
+
+struct Wrapped(Sixteen);
+impl<C> Resolvable<C> for Wrapped {
+    type Dependency = OwnedSixteen;
+
+    fn resolve(dependency: Self::Dependency) -> Self {
+        let (v1, v2, v3, v4, v5, v6, v7, v8, v9, v10, v11, v12, v13, v14, v15, v16) = dependency;
+
+        Wrapped((v1.into_inner(), v2.into_inner(), v3.into_inner(), v4.into_inner(), v5.into_inner(), v6.into_inner(), v7.into_inner(), v8.into_inner(), v9.into_inner(), v10.into_inner(), v11.into_inner(), v12.into_inner(), v13.into_inner(), v14.into_inner(), v15.into_inner(), v16.into_inner()))
+    }
+}
+
+// std only implements `PartialEq` and `Debug` for tuples of up to 12 elements
+fn assert_sixteen((v1, v2, v3, v4, v5, v6, v7, v8, v9, v10, v11, v12, v13, v14, v15, v16): Sixteen) {
+    assert_eq!(V1, v1);
+    assert_eq!(V2, v2);
+    assert_eq!(V3, v3);
+    assert_eq!(V4, v4);
+    assert_eq!(V5, v5);
+    assert_eq!(V6, v6);
+    assert_eq!(V7, v7);
+    assert_eq!(V8, v8);
+    assert_eq!(V9, v9);
+    assert_eq!(V10, v10);
+    assert_eq!(V11, v11);
+    assert_eq!(V12, v12);
+    assert_eq!(V13, v13);
+    assert_eq!(V14, v14);
+    assert_eq!(V15, v15);
+    assert_eq!(V16, v16);
+}
+
+#[test]
+fn resolve_sixteen_owned_dependencies() {
+    let sixteen: Wrapped = BasicContainer.resolve();
+
+    assert_sixteen(sixteen.0);
+}
+
+#[test]
+fn resolve_sixteen_tuple() {
+    let sixteen: Sixteen = BasicContainer.resolve();
+
+    assert_sixteen(sixteen);
+}
+
+#[test]
+fn resolve_six_tuple() {
+    let six: (V1, V2, V3, V4, V5, V6) = BasicContainer.resolve();
+
+    assert_eq!((V1, V2, V3, V4, V5, V6), six);
+}
+
+#[test]
+fn resolve_sixteen_shared_dependencies() {
+    BasicContainer.scope(|scope| {
+        let first: SharedSixteen = scope.invoke(|shared: SharedSixteen| shared);
+        let second: SharedSixteen = scope.invoke(|shared: SharedSixteen| shared);
+
+        assert!(Rc::ptr_eq(&first.0, &second.0));
+        assert!(Rc::ptr_eq(&first.15, &second.15));
+    });
+}
+
+#[test]
+fn invoke_with_sixteen_arguments() {
+    let invoked = BasicContainer.invoke(|v1: RefCell<V1>, v2: RefCell<V2>, v3: RefCell<V3>, v4: RefCell<V4>, v5: RefCell<V5>, v6: RefCell<V6>, v7: RefCell<V7>, v8: RefCell<V8>, v9: RefCell<V9>, v10: RefCell<V10>, v11: RefCell<V11>, v12: RefCell<V12>, v13: RefCell<V13>, v14: RefCell<V14>, v15: RefCell<V15>, v16: RefCell<V16>| {
+        (v1.into_inner(), v2.into_inner(), v3.into_inner(), v4.into_inner(), v5.into_inner(), v6.into_inner(), v7.into_inner(), v8.into_inner(), v9.into_inner(), v10.into_inner(), v11.into_inner(), v12.into_inner(), v13.into_inner(), v14.into_inner(), v15.into_inner(), v16.into_inner())
+    });
+
+    assert_sixteen(invoked);
+}
+
+#[test]
+fn detach_sixteen_shared_dependencies() {
+    BasicContainer.scope(|scope| {
+        let detached: Sixteen = scope.detach();
+
+        assert_sixteen(detached);
+    });
+}
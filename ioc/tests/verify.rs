@@ -0,0 +1,166 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+trait Store {}
+trait Cache {}
+
+thread_local!(static CONSTRUCTED: Cell<usize> = const { Cell::new(0) });
+
+struct FileStore;
+impl Store for FileStore {}
+impl<C> Resolvable<C> for FileStore {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        CONSTRUCTED.with(|constructed| constructed.set(constructed.get() + 1));
+
+        FileStore
+    }
+}
+
+/// A cache that's backed by the store.
+struct StoreCache;
+impl Cache for StoreCache {}
+impl Resolvable<Scoped> for StoreCache {
+    type Dependency = Bound<dyn Store>;
+
+    fn resolve(_: Self::Dependency) -> Self {
+        StoreCache
+    }
+}
+
+/// A store that's backed by the cache.
+struct CachedStore;
+impl Store for CachedStore {}
+impl Resolvable<Scoped> for CachedStore {
+    type Dependency = Bound<dyn Cache>;
+
+    fn resolve(_: Self::Dependency) -> Self {
+        CachedStore
+    }
+}
+
+/// A store that looks up the cache when it's first used.
+struct LazyStore;
+impl Store for LazyStore {}
+impl Resolvable<Scoped> for LazyStore {
+    type Dependency = Lazy<StoreCache>;
+
+    fn resolve(_: Self::Dependency) -> Self {
+        LazyStore
+    }
+}
+
+struct Service;
+impl Resolvable<Scoped> for Service {
+    type Dependency = (Rc<FileStore>, Bound<dyn Cache>);
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Service
+    }
+}
+
+fn scope(f: impl FnOnce(&mut Registry<Scoped>)) -> Scoped {
+    let mut registry = Registry::new();
+    f(&mut registry);
+
+    Scoped::new().with_registry(Rc::new(registry))
+}
+
+#[test]
+fn verify_passes_when_every_binding_is_there() {
+    let scope = scope(|registry| {
+        registry
+            .bind_to::<dyn Store, FileStore, _, _>(|store| Rc::new(store))
+            .bind_to::<dyn Cache, StoreCache, _, _>(|cache| Rc::new(cache));
+    });
+
+    CONSTRUCTED.with(|constructed| constructed.set(0));
+
+    assert_eq!(Ok(()), scope.verify::<_, Service>());
+    assert_eq!(Ok(()), scope.verify_all());
+
+    // nothing is constructed while verifying
+    assert_eq!(0, CONSTRUCTED.with(Cell::get));
+}
+
+#[test]
+fn verify_finds_missing_bindings() {
+    let scope = scope(|registry| {
+        registry.bind_to::<dyn Cache, StoreCache, _, _>(|cache| Rc::new(cache));
+    });
+
+    match scope.verify::<_, Service>() {
+        Err(errors) => assert_eq!(
+            vec![VerifyError::MissingBinding {
+                type_name: "dyn verify::Store",
+                path: vec![
+                    "verify::Service",
+                    "ioc::container::registry::Bound<dyn verify::Cache>",
+                    "verify::StoreCache",
+                    "ioc::container::registry::Bound<dyn verify::Store>",
+                ],
+            }],
+            errors
+        ),
+        Ok(()) => panic!("expected a missing binding"),
+    }
+
+    assert!(scope.verify_all().is_err());
+}
+
+#[test]
+fn verify_finds_cycles_through_bindings() {
+    let scope = scope(|registry| {
+        registry
+            .bind_to::<dyn Store, CachedStore, _, _>(|store| Rc::new(store))
+            .bind_to::<dyn Cache, StoreCache, _, _>(|cache| Rc::new(cache));
+    });
+
+    match scope.verify_all() {
+        Err(errors) => {
+            assert!(!errors.is_empty());
+            assert!(errors.iter().all(|err| matches!(err, VerifyError::Cycle { .. })), "{:?}", errors);
+            assert!(errors[0].to_string().starts_with("there's a dependency cycle: "));
+        }
+        Ok(()) => panic!("expected a cycle"),
+    }
+}
+
+#[test]
+fn deferred_dependencies_dont_make_cycles() {
+    let scope = scope(|registry| {
+        registry
+            .bind_to::<dyn Store, LazyStore, _, _>(|store| Rc::new(store))
+            .bind_to::<dyn Cache, StoreCache, _, _>(|cache| Rc::new(cache));
+    });
+
+    assert_eq!(Ok(()), scope.verify_all());
+}
+
+struct Startup;
+impl Resolvable<Scoped> for Startup {
+    type Dependency = Lazy<StoreCache>;
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Startup
+    }
+}
+
+#[test]
+fn cycles_within_deferred_dependencies_are_found() {
+    let scope = scope(|registry| {
+        registry
+            .bind_to::<dyn Store, CachedStore, _, _>(|store| Rc::new(store))
+            .bind_to::<dyn Cache, StoreCache, _, _>(|cache| Rc::new(cache));
+    });
+
+    match scope.verify::<_, Startup>() {
+        Err(errors) => assert!(matches!(errors[..], [VerifyError::Cycle { .. }]), "{:?}", errors),
+        Ok(()) => panic!("expected a cycle"),
+    }
+}
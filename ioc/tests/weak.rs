@@ -0,0 +1,68 @@
+extern crate ioc;
+
+use ioc::prelude::*;
+
+use std::rc::{Rc, Weak};
+
+struct Parent {
+    child: Rc<Child>,
+}
+
+impl Resolvable<Scoped> for Parent {
+    type Dependency = Rc<Child>;
+
+    fn resolve(child: Self::Dependency) -> Self {
+        Parent { child }
+    }
+}
+
+struct Child {
+    parent: Weak<Parent>,
+}
+
+impl Resolvable<Scoped> for Child {
+    type Dependency = Weak<Parent>;
+
+    fn resolve(parent: Self::Dependency) -> Self {
+        Child { parent }
+    }
+}
+
+#[test]
+fn weak_refers_to_the_value_being_constructed() {
+    let scope = Scoped::new();
+
+    let parent = scope.get_or_add::<Parent, _>();
+    let back = parent.child.parent.upgrade().expect("the parent is alive");
+
+    assert!(Rc::ptr_eq(&parent, &back));
+}
+
+#[test]
+fn weak_refers_to_the_cached_value() {
+    let scope = Scoped::new();
+    let parent = scope.get_or_add::<Parent, _>();
+
+    let weak = scope.invoke(|weak: Weak<Parent>| weak);
+
+    assert!(Rc::ptr_eq(&parent, &weak.upgrade().unwrap()));
+}
+
+#[test]
+fn weak_is_empty_when_nothing_is_constructed() {
+    let scope = Scoped::new();
+
+    let child = scope.get_or_add::<Child, _>();
+
+    assert!(child.parent.upgrade().is_none());
+}
+
+#[test]
+fn back_references_dont_keep_values_alive() {
+    let scope = Scoped::new();
+    let parent = Rc::downgrade(&scope.get_or_add::<Parent, _>());
+
+    drop(scope);
+
+    assert!(parent.upgrade().is_none());
+}
@@ -0,0 +1,111 @@
+//! A scope for each request served by a tower service
+//!
+//! `ScopeLayer` is a tower `Layer` that wraps services so each request is
+//! handled in a new `Scoped`:
+//!
+//! ```
+//! # use std::convert::Infallible;
+//! # use std::rc::Rc;
+//! # use ioc::prelude::*;
+//! # use ioc_tower::{current_scope, Request, ScopeLayer};
+//! # use tower::{service_fn, ServiceBuilder};
+//! # struct OrderHandler;
+//! # impl Resolvable<Scoped> for OrderHandler {
+//! #     type Dependency = ();
+//! #     fn resolve(_: ()) -> Self { OrderHandler }
+//! # }
+//! # let registry = Rc::new(Registry::new());
+//! # let router = service_fn(|_: Request<()>| async {
+//! // anywhere inside the router
+//! let handler: OrderHandler = current_scope().expect("missing scope").resolve();
+//! # Ok::<_, Infallible>(())
+//! # });
+//!
+//! let service = ServiceBuilder::new()
+//!     .layer(ScopeLayer::new().with_registry(registry))
+//!     .service(router);
+//! ```
+//!
+//! Scopes are created the same way as `ioc::hyper_scope::ScopeLayer`'s.
+//! They're seeded with the request's head, use the `x-correlation-id`
+//! header as their correlation id, and are the current scope while the
+//! inner service is called and while its future is polled.
+//!
+//! Requests are `ioc::hyper_scope::Request`, which is re-exported here, so
+//! the version of `http` always matches the one scopes are created for.
+//!
+//! Scopes aren't `Send`, so neither are the services or their futures.
+
+extern crate ioc;
+extern crate tower;
+
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+use ioc::hyper_scope::{self, in_scope, InScope};
+use ioc::{Registry, Scoped};
+
+pub use ioc::hyper_scope::{current_scope, Request, RequestHead};
+
+/// Wraps tower `Service`s so they're called in a new scope for each request.
+#[derive(Default, Clone)]
+pub struct ScopeLayer {
+    layer: hyper_scope::ScopeLayer,
+}
+
+impl ScopeLayer {
+    pub fn new() -> Self {
+        ScopeLayer::default()
+    }
+
+    /// Use a registry of runtime bindings for each request's scope.
+    pub fn with_registry(self, registry: Rc<Registry<Scoped>>) -> Self {
+        ScopeLayer {
+            layer: self.layer.with_registry(registry),
+        }
+    }
+}
+
+impl<S> tower::Layer<S> for ScopeLayer {
+    type Service = Scoping<S>;
+
+    fn layer(&self, inner: S) -> Scoping<S> {
+        Scoping {
+            inner,
+            layer: self.layer.clone(),
+        }
+    }
+}
+
+/// A tower `Service` that calls an inner service in a new scope for each
+/// request.
+#[derive(Clone)]
+pub struct Scoping<S> {
+    inner: S,
+    layer: hyper_scope::ScopeLayer,
+}
+
+impl<S> Scoping<S> {
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, ReqBody> tower::Service<Request<ReqBody>> for Scoping<S>
+    where S: tower::Service<Request<ReqBody>>
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = InScope<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let (scope, req) = self.layer.scope_request(req);
+        let future = in_scope(&scope, || self.inner.call(req));
+
+        InScope::new(scope, future)
+    }
+}
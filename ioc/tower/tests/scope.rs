@@ -0,0 +1,81 @@
+extern crate ioc;
+extern crate ioc_tower;
+extern crate tokio;
+extern crate tower;
+
+use std::convert::Infallible;
+use std::rc::Rc;
+
+use ioc::prelude::*;
+use ioc_tower::{current_scope, Request, RequestHead, ScopeLayer};
+use tower::{service_fn, Layer, ServiceExt};
+
+struct Greeting(&'static str);
+
+impl<C> Resolvable<C> for Greeting {
+    type Dependency = ();
+
+    fn resolve(_: Self::Dependency) -> Self {
+        Greeting("hello")
+    }
+}
+
+async fn handle(_: Request<()>) -> Result<String, Infallible> {
+    let scope = current_scope().expect("missing scope");
+    let head = scope.get_or_add::<RequestHead, _>();
+    let greeting = scope.get_or_add::<Greeting, _>();
+
+    Ok(format!("{} {} {}", greeting.0, scope.correlation_id(), head.uri))
+}
+
+#[tokio::test]
+async fn service_is_called_in_a_new_scope() {
+    let service = ScopeLayer::new().layer(service_fn(handle));
+
+    let req = Request::builder()
+        .uri("/orders")
+        .header("x-correlation-id", "req-1")
+        .body(())
+        .unwrap();
+
+    assert_eq!("hello req-1 /orders", service.oneshot(req).await.unwrap());
+    assert!(current_scope().is_none());
+}
+
+#[tokio::test]
+async fn each_request_has_its_own_scope() {
+    let service = ScopeLayer::new().layer(service_fn(|_: Request<()>| async {
+        Ok::<_, Infallible>(current_scope().expect("missing scope"))
+    }));
+
+    let first = service.clone().oneshot(Request::new(())).await.unwrap();
+    let second = service.oneshot(Request::new(())).await.unwrap();
+
+    assert!(!Rc::ptr_eq(&first, &second));
+}
+
+#[tokio::test]
+async fn scopes_use_the_registry() {
+    trait Store {
+        fn name(&self) -> &'static str;
+    }
+
+    struct FileStore;
+
+    impl Store for FileStore {
+        fn name(&self) -> &'static str {
+            "file"
+        }
+    }
+
+    let mut registry = Registry::<Scoped>::new();
+    registry.bind::<dyn Store, _>(|_| Rc::new(FileStore));
+
+    let service = ScopeLayer::new().with_registry(Rc::new(registry)).layer(service_fn(|_: Request<()>| async {
+        let scope = current_scope().expect("missing scope");
+
+        Ok::<_, Infallible>(Bound::<dyn Store>::resolve_from_container(&*scope).name())
+    }));
+
+    assert_eq!("file", service.oneshot(Request::new(())).await.unwrap());
+}